@@ -1,9 +1,14 @@
-/// gen-rii-index: reads rii.db and writes a flat JSON array of material keys.
+/// gen-rii-index: reads rii.db and writes a JSON index of the materials.
 ///
 /// Usage: gen-rii-index --output <path>
 ///
-/// The output file contains one JSON array of "shelf:book:page" strings,
-/// matching the format expected by MaterialIndex::build_from_keys.
+/// The output file contains one JSON array with an object per material: its
+/// "shelf:book:page" key, and the nd, vd, wavelength_range and aliases that
+/// the material browser filters by, matching the format expected by
+/// MaterialIndex::from_json.
+use cherry_rs::{RefractiveIndexSpec, abbe_number, nd};
+use serde_json::json;
+
 fn main() -> anyhow::Result<()> {
     let mut args = std::env::args().skip(1);
     let output_path = match args.next().as_deref() {
@@ -23,6 +28,23 @@ fn main() -> anyhow::Result<()> {
 
     let mut keys: Vec<String> = store.keys().cloned().collect();
     keys.sort();
+    let entries: Vec<serde_json::Value> = keys
+        .iter()
+        .filter_map(|key| {
+            let mat = store.get(key)?;
+            let aliases: Vec<&String> = [&mat.shelf_divider, &mat.book_divider]
+                .into_iter()
+                .flatten()
+                .collect();
+            Some(json!({
+                "key": key,
+                "nd": nd(mat).ok(),
+                "vd": abbe_number(mat).ok(),
+                "wavelength_range": mat.wavelength_range(),
+                "aliases": aliases,
+            }))
+        })
+        .collect();
 
     if let Some(parent) = std::path::Path::new(&output_path).parent()
         && !parent.as_os_str().is_empty()
@@ -32,7 +54,7 @@ fn main() -> anyhow::Result<()> {
 
     let file = std::fs::File::create(&output_path)
         .map_err(|e| anyhow::anyhow!("Cannot create {output_path}: {e}"))?;
-    serde_json::to_writer(file, &keys)?;
+    serde_json::to_writer(file, &entries)?;
 
     println!("Wrote {} keys to {output_path}", keys.len());
     Ok(())
@@ -8,18 +8,25 @@ use crate::core::{Float, PI, math::vec3::Vec3, sequential_model::placement::Plac
 /// # Attributes
 /// - pos: Position of the ray
 /// - dir: Direction of the ray (direction cosines)
+/// - intensity: Fraction of the ray's initial intensity that has not been
+///   absorbed
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct Ray {
     pos: Vec3,
     dir: Vec3,
+    intensity: Float,
 }
 
 impl Ray {
     pub fn new(pos: Vec3, dir: Vec3) -> Self {
         // We no longer require the direction vector to be normalized since this led to
         // difficulties due to floating point errors
-        Self { pos, dir }
+        Self {
+            pos,
+            dir,
+            intensity: 1.0,
+        }
     }
 
     /// Create a bundle of rays with default values.
@@ -28,6 +35,7 @@ impl Ray {
             Self {
                 pos: Vec3::new(0.0, 0.0, 0.0),
                 dir: Vec3::new(0.0, 0.0, 1.0),
+                intensity: 1.0,
             };
             num
         ]
@@ -43,6 +51,22 @@ impl Ray {
         self.dir = dir;
     }
 
+    /// Attenuate the ray by absorption over a path through a medium.
+    ///
+    /// The intensity falls as `exp(-4π k L / λ)` by the Beer-Lambert law.
+    ///
+    /// # Arguments
+    /// * `k`: Extinction coefficient of the medium.
+    /// * `path_length`: Geometric length of the path, in mm.
+    /// * `wavelength`: Vacuum wavelength, in µm.
+    pub(crate) fn attenuate(&mut self, k: Float, path_length: Float, wavelength: Float) {
+        if k == 0.0 {
+            return;
+        }
+        // The 1e3 converts the path length from mm to µm.
+        self.intensity *= (-4.0 * PI * k * path_length * 1e3 / wavelength).exp();
+    }
+
     /// Transform a ray into the local coordinate system of a surface from the
     /// global system.
     pub fn transform(&mut self, placement: &Placement) {
@@ -72,6 +96,12 @@ impl Ray {
         self.dir
     }
 
+    /// Returns the fraction of the ray's initial intensity that has not been
+    /// absorbed.
+    pub fn intensity(&self) -> Float {
+        self.intensity
+    }
+
     // Return the x-coordinate of the ray position
     pub fn x(&self) -> Float {
         self.pos.x()
@@ -2,7 +2,7 @@
 use anyhow::{Result, anyhow};
 
 use crate::core::{Float, math::Complex};
-use crate::specs::gaps::RefractiveIndexSpec;
+use crate::specs::{environment::Environment, gaps::RefractiveIndexSpec};
 
 #[derive(Debug, Clone, Copy)]
 pub struct RefractiveIndex {
@@ -24,8 +24,18 @@ impl RefractiveIndex {
         self.eta.imag
     }
 
-    pub(crate) fn try_from_spec(spec: &dyn RefractiveIndexSpec, wavelength: Float) -> Result<Self> {
-        let n = spec.n(wavelength)?;
+    /// Evaluates a refractive index spec at a wavelength.
+    ///
+    /// With an environment, the spec's absolute index in it is used.
+    pub(crate) fn try_from_spec(
+        spec: &dyn RefractiveIndexSpec,
+        wavelength: Float,
+        environment: Option<&Environment>,
+    ) -> Result<Self> {
+        let n = match environment {
+            Some(environment) => spec.n_in(wavelength, environment)?,
+            None => spec.n(wavelength)?,
+        };
         let k = spec.k(wavelength)?;
         if n < 1.0 {
             return Err(anyhow!(
@@ -3,7 +3,7 @@ use anyhow::{Result, anyhow};
 
 #[cfg(feature = "serde")]
 use crate::core::surfaces::SurfaceRegistry;
-use crate::specs::{gaps::GapSpec, surfaces::SurfaceSpec};
+use crate::specs::{environment::Environment, gaps::GapSpec, surfaces::SurfaceSpec};
 
 use super::SequentialModel;
 use super::solves::Solve;
@@ -30,6 +30,7 @@ pub struct SequentialModelBuilder {
     stop_surface: Option<usize>,
     wavelengths: Option<Vec<f64>>,
     solves: Vec<Box<dyn Solve>>,
+    environment: Option<Environment>,
     #[cfg(feature = "serde")]
     registry: Option<SurfaceRegistry>,
 }
@@ -49,6 +50,7 @@ impl SequentialModelBuilder {
             stop_surface: None,
             wavelengths: None,
             solves: Vec::new(),
+            environment: None,
             #[cfg(feature = "serde")]
             registry: None,
         }
@@ -63,25 +65,30 @@ impl SequentialModelBuilder {
         let wavelengths = self.wavelengths.unwrap();
         let stop_surface = self.stop_surface;
         let solves = self.solves;
+        let environment = self.environment;
         #[cfg(feature = "serde")]
         let registry = self.registry;
 
         let build = |gap_specs: &[GapSpec], surface_specs: &[SurfaceSpec]| {
             #[cfg(feature = "serde")]
-            return SequentialModel::from_surface_specs_with_registry(
+            let model = SequentialModel::from_surface_specs_with_registry(
                 gap_specs,
                 surface_specs,
                 &wavelengths,
                 stop_surface,
                 registry.as_ref(),
-            );
+            )?;
             #[cfg(not(feature = "serde"))]
-            SequentialModel::from_surface_specs(
+            let model = SequentialModel::from_surface_specs(
                 gap_specs,
                 surface_specs,
                 &wavelengths,
                 stop_surface,
-            )
+            )?;
+            match environment {
+                Some(environment) => model.with_environment(gap_specs, environment),
+                None => Ok(model),
+            }
         };
 
         let mut model = build(&gap_specs, &surface_specs)?;
@@ -125,6 +132,18 @@ impl SequentialModelBuilder {
         self
     }
 
+    /// Sets the temperature and pressure in which the system is modeled.
+    ///
+    /// Gap indices are then the absolute indices of their materials in the
+    /// environment; see [`RefractiveIndexSpec::n_in`]. Without an
+    /// environment, the materials' nominal indices are used.
+    ///
+    /// [`RefractiveIndexSpec::n_in`]: crate::RefractiveIndexSpec::n_in
+    pub fn environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
     /// Sets the [`SurfaceRegistry`] used to resolve [`SurfaceSpec::Custom`]
     /// variants. Required when the system contains custom surfaces.
     #[cfg(feature = "serde")]
@@ -545,4 +564,53 @@ mod tests {
         let thickness = model.submodel(0).unwrap().gaps()[1].thickness;
         assert_eq!(thickness, 77.0);
     }
+
+    #[test]
+    fn environment_sets_gap_indices() {
+        use crate::{Air, RefractiveIndexSpec};
+
+        let gaps = || {
+            vec![
+                GapSpec {
+                    thickness: f64::INFINITY,
+                    refractive_index: n!(1.0),
+                },
+                GapSpec {
+                    thickness: 10.0,
+                    refractive_index: std::rc::Rc::new(Air),
+                },
+            ]
+        };
+        let builder = || {
+            SequentialModelBuilder::new()
+                .gap_specs(gaps())
+                .surface_specs(lens_surfaces())
+                .wavelengths(vec![0.587])
+        };
+        let gap_n =
+            |model: &SequentialModel| model.submodel(0).unwrap().gaps()[1].refractive_index.n();
+
+        let nominal = builder().build().unwrap().model;
+        assert!(nominal.environment().is_none());
+        assert_eq!(gap_n(&nominal), Air.n(0.587).unwrap());
+
+        // The environment outlives the rebuilds of the solves.
+        let environment = Environment::new(40.0, 90_000.0);
+        let model = builder()
+            .environment(environment)
+            .solves(vec![Box::new(SetThickness {
+                gap_index: 1,
+                surface_index: 1,
+                value: 42.0,
+            })])
+            .build()
+            .unwrap()
+            .model;
+        assert_eq!(model.environment(), Some(&environment));
+        assert_eq!(gap_n(&model), Air.n_in(0.587, &environment).unwrap());
+        assert!(gap_n(&model) < gap_n(&nominal));
+
+        let result = builder().environment(Environment::new(20.0, -1.0)).build();
+        assert!(result.is_err());
+    }
 }
@@ -16,9 +16,14 @@ use crate::core::{
     Float,
     math::{linalg::mat3x3::Mat3x3, vec3::Vec3},
     refractive_index::RefractiveIndex,
-    surfaces::{Conic, Image, Iris, Object, Probe, Sphere, Surface, SurfaceKind},
+    surfaces::{
+        Axicon, Biconic, CardinalLens, Conic, Diffractive, EvenAsphere, ForbesAsphere, Fresnel,
+        Grating, GridSag, Image, Iris, LensletArray, Masked, Mounted, Object, ParaxialLens, Probe,
+        Sphere, Surface, SurfaceKind, Toroid, XYPolynomial, ZernikeSag,
+    },
 };
 use crate::specs::{
+    environment::Environment,
     gaps::GapSpec,
     surfaces::{BoundaryKind, SurfaceSpec},
 };
@@ -52,6 +57,10 @@ pub struct SequentialModel {
 
     /// User-specified aperture stop surface index, or `None` for auto-derived.
     stop_surface: Option<usize>,
+
+    /// Temperature and pressure at which the gap indices are evaluated, or
+    /// `None` for the materials' nominal indices.
+    environment: Option<Environment>,
 }
 
 /// A submodel of a sequential optical system.
@@ -125,6 +134,9 @@ pub trait SequentialSubModel {
     fn gaps(&self) -> &[Gap];
     fn is_obj_at_inf(&self) -> bool;
 
+    /// Returns the vacuum wavelength of the submodel, in micrometers.
+    fn wavelength(&self) -> Float;
+
     fn is_empty(&self) -> bool {
         self.gaps().is_empty()
     }
@@ -140,6 +152,7 @@ pub trait SequentialSubModel {
     fn slice(&self, idx: Range<usize>) -> SequentialSubModelSlice<'_> {
         SequentialSubModelSlice {
             gaps: &self.gaps()[idx],
+            wavelength: self.wavelength(),
         }
     }
 }
@@ -147,6 +160,7 @@ pub trait SequentialSubModel {
 #[derive(Debug)]
 pub struct SequentialSubModelBase {
     gaps: Vec<Gap>,
+    wavelength: Float,
 }
 
 /// A view of a single submodel in a sequential system.
@@ -155,6 +169,7 @@ pub struct SequentialSubModelBase {
 #[derive(Debug)]
 pub struct SequentialSubModelSlice<'a> {
     gaps: &'a [Gap],
+    wavelength: Float,
 }
 
 /// An iterator over the surfaces and gaps in a submodel.
@@ -249,15 +264,22 @@ pub fn reversed_surface_id(num_surfaces: usize, surf_id: usize) -> usize {
 }
 
 impl Gap {
-    pub(crate) fn try_from_spec(spec: &GapSpec, wavelength: Float) -> Result<Self> {
+    pub(crate) fn try_from_spec(
+        spec: &GapSpec,
+        wavelength: Float,
+        environment: Option<&Environment>,
+    ) -> Result<Self> {
         let thickness = spec.thickness;
         if thickness < 0.0 {
             return Err(anyhow!(
                 "gap thickness must be non-negative, got {thickness}"
             ));
         }
-        let refractive_index =
-            RefractiveIndex::try_from_spec(spec.refractive_index.as_ref(), wavelength)?;
+        let refractive_index = RefractiveIndex::try_from_spec(
+            spec.refractive_index.as_ref(),
+            wavelength,
+            environment,
+        )?;
         Ok(Self {
             thickness,
             refractive_index,
@@ -300,8 +322,8 @@ impl SequentialModel {
             }
             let mut models: Vec<SequentialSubModelBase> = Vec::new();
             for &wavelength in wavelengths.iter() {
-                let gaps = Self::gap_specs_to_gaps(gap_specs, wavelength)?;
-                models.push(SequentialSubModelBase::new(gaps));
+                let gaps = Self::gap_specs_to_gaps(gap_specs, wavelength, None)?;
+                models.push(SequentialSubModelBase::new(gaps, wavelength));
             }
             Ok(Self {
                 surfaces,
@@ -310,6 +332,7 @@ impl SequentialModel {
                 wavelengths: wavelengths.to_vec(),
                 axis_directions,
                 stop_surface,
+                environment: None,
             })
         }
     }
@@ -334,8 +357,8 @@ impl SequentialModel {
         }
         let mut models: Vec<SequentialSubModelBase> = Vec::new();
         for &wavelength in wavelengths.iter() {
-            let gaps = Self::gap_specs_to_gaps(gap_specs, wavelength)?;
-            models.push(SequentialSubModelBase::new(gaps));
+            let gaps = Self::gap_specs_to_gaps(gap_specs, wavelength, None)?;
+            models.push(SequentialSubModelBase::new(gaps, wavelength));
         }
         Ok(Self {
             surfaces,
@@ -344,6 +367,7 @@ impl SequentialModel {
             wavelengths: wavelengths.to_vec(),
             axis_directions,
             stop_surface,
+            environment: None,
         })
     }
 
@@ -385,8 +409,8 @@ impl SequentialModel {
 
         let mut models: Vec<SequentialSubModelBase> = Vec::new();
         for &wavelength in wavelengths.iter() {
-            let gaps = Self::gap_specs_to_gaps(gap_specs, wavelength)?;
-            models.push(SequentialSubModelBase::new(gaps));
+            let gaps = Self::gap_specs_to_gaps(gap_specs, wavelength, None)?;
+            models.push(SequentialSubModelBase::new(gaps, wavelength));
         }
 
         Ok(Self {
@@ -396,6 +420,7 @@ impl SequentialModel {
             wavelengths: wavelengths.to_vec(),
             axis_directions,
             stop_surface,
+            environment: None,
         })
     }
 
@@ -410,10 +435,28 @@ impl SequentialModel {
             ));
         }
         match surfaces[i].surface_kind() {
-            SurfaceKind::Conic | SurfaceKind::Sphere | SurfaceKind::Iris => Ok(()),
+            SurfaceKind::Axicon
+            | SurfaceKind::Biconic
+            | SurfaceKind::CardinalLens
+            | SurfaceKind::Conic
+            | SurfaceKind::Diffractive
+            | SurfaceKind::EvenAsphere
+            | SurfaceKind::ForbesAsphere
+            | SurfaceKind::Fresnel
+            | SurfaceKind::Grating
+            | SurfaceKind::GridSag
+            | SurfaceKind::LensletArray
+            | SurfaceKind::ParaxialLens
+            | SurfaceKind::Sphere
+            | SurfaceKind::Toroid
+            | SurfaceKind::XYPolynomial
+            | SurfaceKind::ZernikeSag
+            | SurfaceKind::Iris => Ok(()),
             kind => Err(anyhow!(
                 "surface {i} ({kind:?}) is not eligible as the aperture stop; \
-                 only Conic and Iris surfaces are allowed"
+                 only Conic, Sphere, Biconic, EvenAsphere, ForbesAsphere, Fresnel, Axicon, \
+                 Grating, Diffractive, ParaxialLens, CardinalLens, Toroid, XYPolynomial, \
+                 ZernikeSag, GridSag, LensletArray and Iris surfaces are allowed"
             )),
         }
     }
@@ -483,10 +526,38 @@ impl SequentialModel {
         &self.axis_directions
     }
 
-    fn gap_specs_to_gaps(gap_specs: &[GapSpec], wavelength: Float) -> Result<Vec<Gap>> {
+    /// Returns the environment in which the gap indices are evaluated, or
+    /// `None` if the materials' nominal indices are used.
+    pub fn environment(&self) -> Option<&Environment> {
+        self.environment.as_ref()
+    }
+
+    /// Re-evaluates the gap indices of the model in an environment.
+    ///
+    /// `gap_specs` must be the specs from which the model was built.
+    pub(crate) fn with_environment(
+        mut self,
+        gap_specs: &[GapSpec],
+        environment: Environment,
+    ) -> Result<Self> {
+        environment.validate()?;
+        for submodel in self.submodels.iter_mut() {
+            let wavelength = submodel.wavelength;
+            let gaps = Self::gap_specs_to_gaps(gap_specs, wavelength, Some(&environment))?;
+            *submodel = SequentialSubModelBase::new(gaps, wavelength);
+        }
+        self.environment = Some(environment);
+        Ok(self)
+    }
+
+    fn gap_specs_to_gaps(
+        gap_specs: &[GapSpec],
+        wavelength: Float,
+        environment: Option<&Environment>,
+    ) -> Result<Vec<Gap>> {
         let mut gaps = Vec::new();
         for gap_spec in gap_specs.iter() {
-            let gap = Gap::try_from_spec(gap_spec, wavelength)?;
+            let gap = Gap::try_from_spec(gap_spec, wavelength, environment)?;
             gaps.push(gap);
         }
         Ok(gaps)
@@ -547,6 +618,10 @@ impl SequentialModel {
             }
 
             placements.push(placement);
+
+            // Surfaces that move rays along their axis, such as cardinal lenses,
+            // measure the following gap from the displaced plane.
+            cursor.advance(surf.axial_displacement());
             cursor.advance(gap_spec.thickness);
         }
 
@@ -633,8 +708,8 @@ impl SequentialModel {
 }
 
 impl SequentialSubModelBase {
-    pub(crate) fn new(gaps: Vec<Gap>) -> Self {
-        Self { gaps }
+    pub(crate) fn new(gaps: Vec<Gap>, wavelength: Float) -> Self {
+        Self { gaps, wavelength }
     }
 }
 
@@ -651,6 +726,10 @@ impl SequentialSubModel for SequentialSubModelBase {
             .is_infinite()
     }
 
+    fn wavelength(&self) -> Float {
+        self.wavelength
+    }
+
     fn try_iter<'a>(
         &'a self,
         surfaces: &'a [Box<dyn Surface>],
@@ -673,6 +752,10 @@ impl SequentialSubModel for SequentialSubModelSlice<'_> {
             .is_infinite()
     }
 
+    fn wavelength(&self) -> Float {
+        self.wavelength
+    }
+
     fn try_iter<'b>(
         &'b self,
         surfaces: &'b [Box<dyn Surface>],
@@ -808,6 +891,159 @@ pub(crate) fn surface_from_spec(
             *conic_constant,
             *surf_kind,
         ))),
+        SurfaceSpec::EvenAsphere {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            aspheric_coefficients,
+            surf_kind,
+            ..
+        } => Ok(Box::new(EvenAsphere::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            aspheric_coefficients,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::ForbesAsphere {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            q_type,
+            q_coefficients,
+            surf_kind,
+            ..
+        } => Ok(Box::new(ForbesAsphere::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            *normalization_radius,
+            *q_type,
+            q_coefficients,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::Fresnel {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            aspheric_coefficients,
+            groove_pitch,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Fresnel::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            aspheric_coefficients,
+            *groove_pitch,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::LensletArray {
+            semi_diameter,
+            pitch,
+            radius_of_curvature,
+            conic_constant,
+            packing,
+            surf_kind,
+            ..
+        } => Ok(Box::new(LensletArray::new(
+            *semi_diameter,
+            *pitch,
+            *radius_of_curvature,
+            *conic_constant,
+            *packing,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::Diffractive {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            phase_coefficients,
+            diffraction_order,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Diffractive::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            *normalization_radius,
+            phase_coefficients,
+            *diffraction_order,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::Biconic {
+            semi_diameter,
+            radius_of_curvature_x,
+            radius_of_curvature_y,
+            conic_constant_x,
+            conic_constant_y,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Biconic::new(
+            *semi_diameter,
+            *radius_of_curvature_x,
+            *radius_of_curvature_y,
+            *conic_constant_x,
+            *conic_constant_y,
+            *surf_kind,
+        ))),
+        SurfaceSpec::Grating {
+            semi_diameter,
+            radius_of_curvature,
+            line_density,
+            line_orientation,
+            diffraction_order,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Grating::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *line_density,
+            *line_orientation,
+            *diffraction_order,
+            *surf_kind,
+        ))),
+        SurfaceSpec::Axicon {
+            semi_diameter,
+            cone_angle,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Axicon::new(
+            *semi_diameter,
+            *cone_angle,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::GridSag {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            grid,
+            surf_kind,
+            ..
+        } => Ok(Box::new(GridSag::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            grid.clone(),
+            *surf_kind,
+        )?)),
+        SurfaceSpec::ParaxialLens {
+            semi_diameter,
+            focal_length,
+            ..
+        } => Ok(Box::new(ParaxialLens::new(*semi_diameter, *focal_length)?)),
+        SurfaceSpec::CardinalLens {
+            semi_diameter,
+            focal_length,
+            principal_plane_separation,
+            ..
+        } => Ok(Box::new(CardinalLens::new(
+            *semi_diameter,
+            *focal_length,
+            *principal_plane_separation,
+        )?)),
         SurfaceSpec::Sphere {
             semi_diameter,
             radius_of_curvature,
@@ -828,6 +1064,84 @@ pub(crate) fn surface_from_spec(
                 )
             })?
             .build(type_id, params),
+        SurfaceSpec::Toroid {
+            semi_diameter,
+            radius_of_curvature_x,
+            radius_of_curvature_y,
+            conic_constant,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Toroid::new(
+            *semi_diameter,
+            *radius_of_curvature_x,
+            *radius_of_curvature_y,
+            *conic_constant,
+            *surf_kind,
+        ))),
+        SurfaceSpec::Cylinder {
+            semi_diameter,
+            radius_of_curvature,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Toroid::cylinder(
+            *semi_diameter,
+            *radius_of_curvature,
+            *surf_kind,
+        ))),
+        SurfaceSpec::XYPolynomial {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            order,
+            xy_coefficients,
+            surf_kind,
+            ..
+        } => Ok(Box::new(XYPolynomial::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            *normalization_radius,
+            *order,
+            xy_coefficients,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::ZernikeSag {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            ordering,
+            zernike_coefficients,
+            surf_kind,
+            ..
+        } => Ok(Box::new(ZernikeSag::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            *normalization_radius,
+            *ordering,
+            zernike_coefficients,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::Masked { mask, surface } => match **surface {
+            SurfaceSpec::Object | SurfaceSpec::Image { .. } | SurfaceSpec::Probe { .. } => Err(
+                anyhow!("Object, Image and Probe surfaces have no clear aperture to mask"),
+            ),
+            _ => Ok(Box::new(Masked::new(
+                surface_from_spec(surface, registry)?,
+                mask.clone(),
+            )?)),
+        },
+        SurfaceSpec::Mounted {
+            mechanical_semi_diameter,
+            flat_width,
+            surface,
+        } => Ok(Box::new(Mounted::new(
+            surface_from_spec(surface, registry)?,
+            *mechanical_semi_diameter,
+            *flat_width,
+        )?)),
         SurfaceSpec::Image { .. } => Ok(Box::new(Image::new())),
         SurfaceSpec::Object => Ok(Box::new(Object::new())),
         SurfaceSpec::Probe { .. } => Ok(Box::new(Probe::new())),
@@ -851,6 +1165,159 @@ pub(crate) fn surface_from_spec(spec: &SurfaceSpec) -> Result<Box<dyn Surface>>
             *conic_constant,
             *surf_kind,
         ))),
+        SurfaceSpec::EvenAsphere {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            aspheric_coefficients,
+            surf_kind,
+            ..
+        } => Ok(Box::new(EvenAsphere::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            aspheric_coefficients,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::ForbesAsphere {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            q_type,
+            q_coefficients,
+            surf_kind,
+            ..
+        } => Ok(Box::new(ForbesAsphere::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            *normalization_radius,
+            *q_type,
+            q_coefficients,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::Fresnel {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            aspheric_coefficients,
+            groove_pitch,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Fresnel::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            aspheric_coefficients,
+            *groove_pitch,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::LensletArray {
+            semi_diameter,
+            pitch,
+            radius_of_curvature,
+            conic_constant,
+            packing,
+            surf_kind,
+            ..
+        } => Ok(Box::new(LensletArray::new(
+            *semi_diameter,
+            *pitch,
+            *radius_of_curvature,
+            *conic_constant,
+            *packing,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::Diffractive {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            phase_coefficients,
+            diffraction_order,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Diffractive::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            *normalization_radius,
+            phase_coefficients,
+            *diffraction_order,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::Biconic {
+            semi_diameter,
+            radius_of_curvature_x,
+            radius_of_curvature_y,
+            conic_constant_x,
+            conic_constant_y,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Biconic::new(
+            *semi_diameter,
+            *radius_of_curvature_x,
+            *radius_of_curvature_y,
+            *conic_constant_x,
+            *conic_constant_y,
+            *surf_kind,
+        ))),
+        SurfaceSpec::Grating {
+            semi_diameter,
+            radius_of_curvature,
+            line_density,
+            line_orientation,
+            diffraction_order,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Grating::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *line_density,
+            *line_orientation,
+            *diffraction_order,
+            *surf_kind,
+        ))),
+        SurfaceSpec::Axicon {
+            semi_diameter,
+            cone_angle,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Axicon::new(
+            *semi_diameter,
+            *cone_angle,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::GridSag {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            grid,
+            surf_kind,
+            ..
+        } => Ok(Box::new(GridSag::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            grid.clone(),
+            *surf_kind,
+        )?)),
+        SurfaceSpec::ParaxialLens {
+            semi_diameter,
+            focal_length,
+            ..
+        } => Ok(Box::new(ParaxialLens::new(*semi_diameter, *focal_length)?)),
+        SurfaceSpec::CardinalLens {
+            semi_diameter,
+            focal_length,
+            principal_plane_separation,
+            ..
+        } => Ok(Box::new(CardinalLens::new(
+            *semi_diameter,
+            *focal_length,
+            *principal_plane_separation,
+        )?)),
         SurfaceSpec::Sphere {
             semi_diameter,
             radius_of_curvature,
@@ -861,6 +1328,84 @@ pub(crate) fn surface_from_spec(spec: &SurfaceSpec) -> Result<Box<dyn Surface>>
             *radius_of_curvature,
             *surf_kind,
         ))),
+        SurfaceSpec::Toroid {
+            semi_diameter,
+            radius_of_curvature_x,
+            radius_of_curvature_y,
+            conic_constant,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Toroid::new(
+            *semi_diameter,
+            *radius_of_curvature_x,
+            *radius_of_curvature_y,
+            *conic_constant,
+            *surf_kind,
+        ))),
+        SurfaceSpec::Cylinder {
+            semi_diameter,
+            radius_of_curvature,
+            surf_kind,
+            ..
+        } => Ok(Box::new(Toroid::cylinder(
+            *semi_diameter,
+            *radius_of_curvature,
+            *surf_kind,
+        ))),
+        SurfaceSpec::XYPolynomial {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            order,
+            xy_coefficients,
+            surf_kind,
+            ..
+        } => Ok(Box::new(XYPolynomial::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            *normalization_radius,
+            *order,
+            xy_coefficients,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::ZernikeSag {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            ordering,
+            zernike_coefficients,
+            surf_kind,
+            ..
+        } => Ok(Box::new(ZernikeSag::new(
+            *semi_diameter,
+            *radius_of_curvature,
+            *conic_constant,
+            *normalization_radius,
+            *ordering,
+            zernike_coefficients,
+            *surf_kind,
+        )?)),
+        SurfaceSpec::Masked { mask, surface } => match **surface {
+            SurfaceSpec::Object | SurfaceSpec::Image { .. } | SurfaceSpec::Probe { .. } => Err(
+                anyhow!("Object, Image and Probe surfaces have no clear aperture to mask"),
+            ),
+            _ => Ok(Box::new(Masked::new(
+                surface_from_spec(surface)?,
+                mask.clone(),
+            )?)),
+        },
+        SurfaceSpec::Mounted {
+            mechanical_semi_diameter,
+            flat_width,
+            surface,
+        } => Ok(Box::new(Mounted::new(
+            surface_from_spec(surface)?,
+            *mechanical_semi_diameter,
+            *flat_width,
+        )?)),
         SurfaceSpec::Image { .. } => Ok(Box::new(Image::new())),
         SurfaceSpec::Object => Ok(Box::new(Object::new())),
         SurfaceSpec::Probe { .. } => Ok(Box::new(Probe::new())),
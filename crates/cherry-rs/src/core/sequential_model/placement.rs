@@ -160,6 +160,22 @@ impl Placement {
         self.cursor_rotation_matrix.transpose() * Vec3::new(0.0, 0.0, 1.0)
     }
 
+    /// Returns the direction `(x, y)` in the surface's local frame of the line
+    /// along which the tangential plane defined by `v` cuts the surface.
+    ///
+    /// `v` is interpreted as in
+    /// [`projected_semi_diameter`](Self::projected_semi_diameter). The
+    /// direction is not normalized; it vanishes if the surface is parallel to
+    /// the tangential plane.
+    pub fn local_meridian(&self, v: Vec3) -> (Float, Float) {
+        let r_surf = self.rotation_matrix * self.cursor_rotation_matrix.transpose();
+
+        // The normal of the tangential plane in the local frame. Its cross
+        // product with the surface normal (0, 0, 1) lies in both.
+        let m = r_surf * Vec3::new(v.y(), -v.x(), 0.0);
+        (-m.y(), m.x())
+    }
+
     /// Returns the semi-diameter as seen by a paraxial ray travelling along
     /// the cursor axis in the tangential plane defined by `v`.
     ///
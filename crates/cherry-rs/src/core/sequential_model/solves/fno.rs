@@ -97,7 +97,7 @@ impl Solve for FNumberSolve {
             ));
         }
 
-        match &mut surface_specs[self.surface_index] {
+        match surface_specs[self.surface_index].unwrapped_mut() {
             SurfaceSpec::Sphere {
                 radius_of_curvature,
                 ..
@@ -105,12 +105,40 @@ impl Solve for FNumberSolve {
             | SurfaceSpec::Conic {
                 radius_of_curvature,
                 ..
+            }
+            | SurfaceSpec::EvenAsphere {
+                radius_of_curvature,
+                ..
+            }
+            | SurfaceSpec::ForbesAsphere {
+                radius_of_curvature,
+                ..
+            }
+            | SurfaceSpec::Fresnel {
+                radius_of_curvature,
+                ..
+            }
+            | SurfaceSpec::XYPolynomial {
+                radius_of_curvature,
+                ..
+            }
+            | SurfaceSpec::ZernikeSag {
+                radius_of_curvature,
+                ..
+            }
+            | SurfaceSpec::LensletArray {
+                radius_of_curvature,
+                ..
+            }
+            | SurfaceSpec::GridSag {
+                radius_of_curvature,
+                ..
             } => {
                 *radius_of_curvature = roc;
                 Ok(())
             }
             _ => Err(anyhow!(
-                "surface {} is not a Sphere or Conic; cannot set radius of curvature",
+                "surface {} does not have a base radius of curvature; cannot set radius of curvature",
                 self.surface_index
             )),
         }
@@ -0,0 +1,247 @@
+use anyhow::{Result, anyhow, bail};
+use tracing::error;
+
+use crate::{
+    core::{Float, PI, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{
+    Surface, SurfaceKind,
+    solvers::{TOL, flat_surface},
+};
+
+/// A conical surface with its apex on the local z-axis.
+///
+/// The sag grows linearly with the radial distance from the axis,
+///
+/// ```text
+/// z = r tan α
+/// ```
+///
+/// where `α` is the cone angle between the surface and the local xy-plane.
+/// A positive angle points the apex towards negative z, like a surface with a
+/// positive radius of curvature. A cone angle of zero gives a flat surface.
+///
+/// The surface has a kink at the apex, where the normal is undefined. The
+/// normal there is taken to lie along the axis. Newton-Raphson iteration
+/// converges poorly across the kink, so rays are intersected with the cone
+/// analytically instead.
+///
+/// An axicon has no paraxial power and no focal point; it maps a collimated
+/// beam onto a line focus. Paraxial analysis treats it as a flat surface and
+/// logs a warning, so paraxial results for systems that contain one are not
+/// meaningful.
+#[derive(Debug, Clone)]
+pub struct Axicon {
+    /// Angle between the cone and the local xy-plane, in radians.
+    pub cone_angle: Float,
+    pub boundary_kind: BoundaryKind,
+    mask: Mask,
+}
+
+impl Axicon {
+    /// Creates a new axicon.
+    ///
+    /// Returns an error if the cone angle is not finite or if its magnitude
+    /// is not less than π/2.
+    pub fn new(
+        semi_diameter: Float,
+        cone_angle: Float,
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        if !cone_angle.is_finite() || cone_angle.abs() >= PI / 2.0 {
+            return Err(anyhow!(
+                "axicon cone angle must lie strictly between -π/2 and π/2, got {cone_angle}"
+            ));
+        }
+        Ok(Self {
+            cone_angle,
+            boundary_kind,
+            mask: Mask::Circular { semi_diameter },
+        })
+    }
+}
+
+impl Surface for Axicon {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.boundary_kind
+    }
+
+    /// Intersects the ray with the cone analytically.
+    ///
+    /// Squaring `z = r tan α` gives a quadratic in the distance along the ray
+    /// whose roots lie on both nappes of the double cone. Roots on the wrong
+    /// nappe are discarded, and of the remaining ones the root closest to the
+    /// ray's crossing of the apex plane is returned.
+    fn intersect(&self, ray: &Ray, _max_iter: usize) -> Result<(Vec3, Vec3)> {
+        let t = self.cone_angle.tan();
+        if t == 0.0 {
+            return flat_surface(ray, self, 0);
+        }
+
+        let p = ray.pos();
+        let d = ray.dir();
+        let t_sq = t * t;
+
+        let a = d.z() * d.z() - t_sq * (d.x() * d.x() + d.y() * d.y());
+        let b = p.z() * d.z() - t_sq * (p.x() * d.x() + p.y() * d.y());
+        let c = p.z() * p.z() - t_sq * (p.x() * p.x() + p.y() * p.y());
+
+        let mut roots = Vec::with_capacity(2);
+        if a.abs() <= TOL * (d.z() * d.z() + t_sq * (d.x() * d.x() + d.y() * d.y())) {
+            // The ray is parallel to a generator of the cone.
+            if b != 0.0 {
+                roots.push(-c / (2.0 * b));
+            }
+        } else {
+            let discriminant = b * b - a * c;
+            // A ray through the apex is tangent to the double cone, which
+            // rounding can push slightly below zero.
+            if discriminant < -TOL * b * b {
+                error!(a, b, c, discriminant, "Ray does not intersect the axicon");
+                bail!("Ray does not intersect the axicon");
+            }
+            let q = -(b + b.signum() * discriminant.max(0.0).sqrt());
+            if q == 0.0 {
+                // b and the discriminant are both zero, so c is too.
+                roots.push(0.0);
+            } else {
+                roots.push(q / a);
+                roots.push(c / q);
+            }
+        }
+
+        let s_0 = -p.z() / d.z();
+        let s_0 = if s_0.is_finite() { s_0 } else { 0.0 };
+        let Some(s) = roots
+            .into_iter()
+            .filter(|s| s.is_finite() && (p.z() + s * d.z()) * t >= 0.0)
+            .min_by(|s_1, s_2| (s_1 - s_0).abs().total_cmp(&(s_2 - s_0).abs()))
+        else {
+            error!(a, b, c, "Ray only meets the opposite nappe of the axicon");
+            bail!("Ray does not intersect the axicon");
+        };
+
+        let r = ray.pos_at(s);
+        let norm = self.norm(r);
+        Ok((r, norm))
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        let r = pos.x().hypot(pos.y());
+        if r == 0.0 {
+            return Vec3::new(0.0, 0.0, 1.0);
+        }
+
+        let t = self.cone_angle.tan();
+        Vec3::new(-t * pos.x() / r, -t * pos.y() / r, 1.0)
+    }
+
+    fn profile_breakpoints(&self) -> Vec<Float> {
+        vec![0.0]
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        self.cone_angle.tan() * pos.x().hypot(pos.y())
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::Axicon
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn axicon(cone_angle: Float) -> Axicon {
+        Axicon::new(10.0, cone_angle, BoundaryKind::Refracting).unwrap()
+    }
+
+    #[test]
+    fn invalid_cone_angles_are_errors() {
+        assert!(Axicon::new(10.0, PI / 2.0, BoundaryKind::Refracting).is_err());
+        assert!(Axicon::new(10.0, -2.0, BoundaryKind::Refracting).is_err());
+        assert!(Axicon::new(10.0, Float::NAN, BoundaryKind::Refracting).is_err());
+    }
+
+    #[test]
+    fn sag_and_norm_are_defined_at_the_apex() {
+        let surf = axicon(0.1);
+        let apex = Vec3::new(0.0, 0.0, 0.0);
+        assert_eq!(surf.sag(apex), 0.0);
+        assert_eq!(surf.norm(apex), Vec3::new(0.0, 0.0, 1.0));
+
+        let pos = Vec3::new(3.0, 4.0, 0.0);
+        let t = Float::tan(0.1);
+        assert_abs_diff_eq!(surf.sag(pos), 5.0 * t, epsilon = 1e-15);
+        assert!(
+            surf.norm(pos)
+                .approx_eq(&Vec3::new(-0.6 * t, -0.8 * t, 1.0), 1e-15)
+        );
+        assert!(surf.roc(0.0).is_infinite());
+    }
+
+    #[test]
+    fn intersection_lies_on_the_cone() {
+        let surf = axicon(0.2);
+        for (y, m) in [(5.0, 0.0), (-3.0, 0.1), (1e-9, 0.0), (0.5, -0.3)] {
+            let dir = Vec3::new(0.02, m, 1.0).normalize();
+            let ray = Ray::new(Vec3::new(0.3, y, -5.0), dir);
+            let (pos, norm) = surf.intersect(&ray, 0).unwrap();
+            assert_abs_diff_eq!(pos.z(), surf.sag(pos), epsilon = 1e-12);
+            assert_eq!(norm, surf.norm(pos));
+        }
+    }
+
+    #[test]
+    fn axial_ray_hits_the_apex() {
+        for angle in [0.3, -0.3] {
+            let surf = axicon(angle);
+            let ray = Ray::new(Vec3::new(0.0, 0.0, -2.0), Vec3::new(0.0, 0.0, 1.0));
+            let (pos, norm) = surf.intersect(&ray, 0).unwrap();
+            assert!(pos.approx_eq(&Vec3::new(0.0, 0.0, 0.0), 1e-12));
+            assert_eq!(norm, Vec3::new(0.0, 0.0, 1.0));
+        }
+    }
+
+    #[test]
+    fn ray_crossing_the_axis_hits_the_near_nappe() {
+        // A ray heading down through the axis meets the cone on the side it
+        // starts from when the cone opens towards it.
+        let surf = axicon(0.4);
+        let ray = Ray::new(
+            Vec3::new(0.0, 2.0, -1.0),
+            Vec3::new(0.0, -0.5, 1.0).normalize(),
+        );
+        let (pos, _) = surf.intersect(&ray, 0).unwrap();
+        assert!(pos.y() > 0.0);
+        assert_abs_diff_eq!(pos.z(), surf.sag(pos), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn collimated_beam_is_deflected_towards_the_axis() {
+        // Thin-prism deviation: (n - 1) α for small cone angles.
+        let alpha = 0.01;
+        let surf = axicon(-alpha);
+        let n = 1.5;
+        for y in [-4.0, 4.0] {
+            let ray = Ray::new(Vec3::new(0.0, y, -1.0), Vec3::new(0.0, 0.0, 1.0));
+            let (pos, norm) = surf.intersect(&ray, 0).unwrap();
+            let mut ray = Ray::new(pos, ray.dir());
+            surf.interact(&mut ray, n, 1.0, norm, 0.5876);
+            let dir = ray.dir();
+            assert_abs_diff_eq!(
+                dir.y() / dir.z(),
+                -y.signum() * (n - 1.0) * alpha,
+                epsilon = 1e-5
+            );
+        }
+    }
+}
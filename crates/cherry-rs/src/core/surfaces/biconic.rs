@@ -0,0 +1,207 @@
+use crate::{
+    core::{Float, math::vec3::Vec3},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Surface, SurfaceKind};
+
+/// A biconic surface with independent radii and conic constants in the local x
+/// and y meridians.
+///
+/// ```text
+/// z(x, y) = (c_x x² + c_y y²) / (1 + sqrt(1 - (1 + k_x) c_x² x² - (1 + k_y) c_y² y²))
+/// ```
+///
+/// Equal radii and conic constants reduce to a [`Conic`](super::Conic).
+#[derive(Debug, Clone)]
+pub struct Biconic {
+    pub radius_of_curvature_x: Float,
+    pub radius_of_curvature_y: Float,
+    pub conic_constant_x: Float,
+    pub conic_constant_y: Float,
+    pub boundary_kind: BoundaryKind,
+    mask: Mask,
+}
+
+impl Biconic {
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature_x: Float,
+        radius_of_curvature_y: Float,
+        conic_constant_x: Float,
+        conic_constant_y: Float,
+        boundary_kind: BoundaryKind,
+    ) -> Self {
+        Self {
+            radius_of_curvature_x,
+            radius_of_curvature_y,
+            conic_constant_x,
+            conic_constant_y,
+            boundary_kind,
+            mask: Mask::Circular { semi_diameter },
+        }
+    }
+
+    /// Returns the curvatures (c_x, c_y). An infinite radius gives zero
+    /// curvature.
+    fn curvatures(&self) -> (Float, Float) {
+        (
+            1.0 / self.radius_of_curvature_x,
+            1.0 / self.radius_of_curvature_y,
+        )
+    }
+}
+
+impl Surface for Biconic {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.boundary_kind
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        let (c_x, c_y) = self.curvatures();
+        let (x, y) = (pos.x(), pos.y());
+        let a_x = (1.0 + self.conic_constant_x) * c_x * c_x;
+        let a_y = (1.0 + self.conic_constant_y) * c_y * c_y;
+
+        // z = num / den with den = 1 + s
+        let num = c_x * x * x + c_y * y * y;
+        let s = (1.0 - a_x * x * x - a_y * y * y).sqrt();
+        let den = 1.0 + s;
+
+        let dzdx = (2.0 * c_x * x * den + num * a_x * x / s) / (den * den);
+        let dzdy = (2.0 * c_y * y * den + num * a_y * y / s) / (den * den);
+
+        // Not normalized — magnitude matters for Newton-Raphson
+        Vec3::new(-dzdx, -dzdy, 1.0)
+    }
+
+    /// Returns the radius of curvature at the vertex along the given azimuth.
+    ///
+    /// The normal curvature follows Euler's theorem, `c(φ) = c_x cos²φ + c_y
+    /// sin²φ`, where φ is measured from the local x-axis.
+    fn roc(&self, azimuth_rad: Float) -> Float {
+        let (c_x, c_y) = self.curvatures();
+        let c = c_x * azimuth_rad.cos().powi(2) + c_y * azimuth_rad.sin().powi(2);
+        if c == 0.0 { Float::INFINITY } else { 1.0 / c }
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        let (c_x, c_y) = self.curvatures();
+        let (x, y) = (pos.x(), pos.y());
+        let num = c_x * x * x + c_y * y * y;
+        let s = (1.0
+            - (1.0 + self.conic_constant_x) * (c_x * x).powi(2)
+            - (1.0 + self.conic_constant_y) * (c_y * y).powi(2))
+        .sqrt();
+        num / (1.0 + s)
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::Biconic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        PI,
+        ray::Ray,
+        surfaces::{Conic, solvers::newton_raphson},
+    };
+    use approx::assert_abs_diff_eq;
+
+    fn biconic(r_x: Float, r_y: Float, k_x: Float, k_y: Float) -> Biconic {
+        Biconic::new(10.0, r_x, r_y, k_x, k_y, BoundaryKind::Refracting)
+    }
+
+    #[test]
+    fn equal_meridians_match_conic() {
+        let b = biconic(40.0, 40.0, -0.5, -0.5);
+        let c = Conic::new(10.0, 40.0, -0.5, BoundaryKind::Refracting);
+        for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-3.0, 4.0), (5.0, -6.0)] {
+            let pos = Vec3::new(x, y, 0.0);
+            assert_abs_diff_eq!(b.sag(pos), c.sag(pos), epsilon = 1e-12);
+            let nb = b.norm(pos);
+            let nc = c.norm(pos);
+            assert_abs_diff_eq!(nb.x(), nc.x(), epsilon = 1e-12);
+            assert_abs_diff_eq!(nb.y(), nc.y(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn principal_sections_are_conics() {
+        let b = biconic(30.0, -50.0, -1.0, 0.3);
+        let cx = Conic::new(10.0, 30.0, -1.0, BoundaryKind::Refracting);
+        let cy = Conic::new(10.0, -50.0, 0.3, BoundaryKind::Refracting);
+        for t in [-6.0, -2.5, 0.0, 4.0, 7.5] {
+            assert_abs_diff_eq!(
+                b.sag(Vec3::new(t, 0.0, 0.0)),
+                cx.sag(Vec3::new(t, 0.0, 0.0)),
+                epsilon = 1e-12
+            );
+            assert_abs_diff_eq!(
+                b.sag(Vec3::new(0.0, t, 0.0)),
+                cy.sag(Vec3::new(0.0, t, 0.0)),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn flat_meridian_has_no_sag() {
+        let b = biconic(Float::INFINITY, 25.0, 0.0, 0.0);
+        assert_abs_diff_eq!(b.sag(Vec3::new(7.0, 0.0, 0.0)), 0.0);
+        let n = b.norm(Vec3::new(7.0, 0.0, 0.0));
+        assert_abs_diff_eq!(n.x(), 0.0);
+        assert_abs_diff_eq!(n.y(), 0.0);
+    }
+
+    #[test]
+    fn norm_matches_finite_difference_of_sag() {
+        for b in [
+            biconic(40.0, -60.0, 0.0, 0.0),
+            biconic(-30.0, 80.0, -1.0, 2.0),
+            biconic(Float::INFINITY, 35.0, 0.0, -0.5),
+            biconic(25.0, 45.0, -2.0, -0.7),
+        ] {
+            let h = 1e-6;
+            for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-3.0, 4.0), (5.0, -6.0)] {
+                let dzdx =
+                    (b.sag(Vec3::new(x + h, y, 0.0)) - b.sag(Vec3::new(x - h, y, 0.0))) / (2.0 * h);
+                let dzdy =
+                    (b.sag(Vec3::new(x, y + h, 0.0)) - b.sag(Vec3::new(x, y - h, 0.0))) / (2.0 * h);
+                let norm = b.norm(Vec3::new(x, y, 0.0));
+                assert_abs_diff_eq!(norm.x(), -dzdx, epsilon = 1e-7);
+                assert_abs_diff_eq!(norm.y(), -dzdy, epsilon = 1e-7);
+            }
+        }
+    }
+
+    #[test]
+    fn roc_depends_on_azimuth() {
+        let b = biconic(40.0, 20.0, -1.0, 0.5);
+        assert_abs_diff_eq!(b.roc(0.0), 40.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(b.roc(PI / 2.0), 20.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(b.roc(PI), 40.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(
+            b.roc(PI / 4.0),
+            2.0 / (1.0 / 40.0 + 1.0 / 20.0),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn newton_raphson_converges_onto_surface() {
+        let b = biconic(30.0, -45.0, -0.8, 0.4);
+        let ray = Ray::new(Vec3::new(3.0, 5.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let (p, _) = newton_raphson(&ray, &b, 100).unwrap();
+        assert_abs_diff_eq!(p.x(), 3.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.y(), 5.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.z(), b.sag(p), epsilon = 1e-10);
+    }
+}
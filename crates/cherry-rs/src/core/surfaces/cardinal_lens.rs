@@ -0,0 +1,124 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{ParaxialLens, Surface, SurfaceKind, solvers::flat_surface};
+
+/// An ideal lens described only by its focal length and principal planes.
+///
+/// The surface lies in the front principal plane. A ray that reaches it is
+/// moved to the back principal plane, a distance `principal_plane_separation`
+/// further along the local z-axis, without changing its transverse position.
+/// It is then deflected like a [`ParaxialLens`] of the same focal length.
+///
+/// The gap that follows the surface is measured from the back principal plane,
+/// so an objective can be modeled from the data sheet alone: place the surface
+/// at the front principal plane and measure the next gap from the back one. A
+/// negative separation puts the back principal plane before the front one.
+///
+/// See <https://opg.optica.org/ao/fulltext.cfm?uri=ao-63-4-1110>.
+#[derive(Debug, Clone)]
+pub struct CardinalLens {
+    pub principal_plane_separation: Float,
+    base: ParaxialLens,
+}
+
+impl CardinalLens {
+    /// Creates a new cardinal lens.
+    ///
+    /// Returns an error if the focal length is zero or NaN, or if the
+    /// principal plane separation is not finite.
+    pub fn new(
+        semi_diameter: Float,
+        focal_length: Float,
+        principal_plane_separation: Float,
+    ) -> Result<Self> {
+        if !principal_plane_separation.is_finite() {
+            return Err(anyhow!(
+                "cardinal lens principal plane separation must be finite, got \
+                 {principal_plane_separation}"
+            ));
+        }
+        Ok(Self {
+            principal_plane_separation,
+            base: ParaxialLens::new(semi_diameter, focal_length)?,
+        })
+    }
+
+    pub fn focal_length(&self) -> Float {
+        self.base.focal_length
+    }
+}
+
+impl Surface for CardinalLens {
+    fn axial_displacement(&self) -> Float {
+        self.principal_plane_separation
+    }
+
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.base.boundary_kind()
+    }
+
+    fn intersect(&self, ray: &Ray, _max_iter: usize) -> Result<(Vec3, Vec3)> {
+        flat_surface(ray, self, 0)
+    }
+
+    fn mask(&self) -> &Mask {
+        self.base.mask()
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        self.base.norm(pos)
+    }
+
+    fn paraxial_focal_length(&self) -> Option<Float> {
+        self.base.paraxial_focal_length()
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        self.base.sag(pos)
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::CardinalLens
+    }
+
+    /// Moves the ray to the back principal plane and deflects it there.
+    fn interact(&self, ray: &mut Ray, n_0: Float, n_1: Float, norm: Vec3, wavelength: Float) {
+        let pos = ray.pos();
+        ray.displace(Vec3::new(
+            pos.x(),
+            pos.y(),
+            pos.z() + self.principal_plane_separation,
+        ));
+        self.base.interact(ray, n_0, n_1, norm, wavelength);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn ray_is_moved_to_the_back_principal_plane() {
+        let lens = CardinalLens::new(10.0, 20.0, -7.5).unwrap();
+        let mut ray = Ray::new(Vec3::new(1.0, 2.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        lens.interact(&mut ray, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), 0.5876);
+
+        assert!(ray.pos().approx_eq(&Vec3::new(1.0, 2.0, -7.5), 1e-15));
+        let dir = ray.dir();
+        assert_abs_diff_eq!(dir.x() / dir.z(), -1.0 / 20.0, epsilon = 1e-15);
+        assert_abs_diff_eq!(dir.y() / dir.z(), -2.0 / 20.0, epsilon = 1e-15);
+    }
+
+    #[test]
+    fn invalid_inputs_are_errors() {
+        assert!(CardinalLens::new(10.0, 0.0, 1.0).is_err());
+        assert!(CardinalLens::new(10.0, 20.0, Float::INFINITY).is_err());
+        assert!(CardinalLens::new(10.0, 20.0, Float::NAN).is_err());
+    }
+}
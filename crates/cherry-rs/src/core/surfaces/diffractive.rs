@@ -0,0 +1,252 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, PI, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Conic, Surface, SurfaceKind};
+
+/// A rotationally symmetric diffractive surface on a conic base.
+///
+/// The surface adds the phase
+///
+/// ```text
+/// Φ(ρ) = M Σ A_i ρ^(2i),   i = 1, 2, ...
+/// ```
+///
+/// in radians, where `M` is the diffraction order, `A_i` the phase
+/// coefficients and `ρ` the radial distance from the axis divided by the
+/// normalization radius. This is the "Binary 2" convention of common lens
+/// design programs.
+///
+/// Instead of Snell's law, rays are redirected with
+///
+/// ```text
+/// n' r'_t = n r_t + λ / 2π ∇Φ_t
+/// ```
+///
+/// where `r_t` is the component of the ray direction tangent to the surface
+/// and `∇Φ_t` the tangential component of the phase gradient. A negative `A_1`
+/// gives positive power. The paraxial power of the phase,
+///
+/// ```text
+/// φ = -M A_1 λ / (π R²),
+/// ```
+///
+/// is proportional to wavelength, so a diffractive surface has an Abbe number
+/// of about -3.45 in the visible.
+#[derive(Debug, Clone)]
+pub struct Diffractive {
+    /// Coefficients of ρ², ρ⁴, ... in radians.
+    pub phase_coefficients: Vec<Float>,
+    pub normalization_radius: Float,
+    pub diffraction_order: i32,
+    base: Conic,
+}
+
+impl Diffractive {
+    /// Creates a new diffractive surface.
+    ///
+    /// Returns an error if the normalization radius is not positive and
+    /// finite.
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        normalization_radius: Float,
+        phase_coefficients: &[Float],
+        diffraction_order: i32,
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        if !(normalization_radius.is_finite() && normalization_radius > 0.0) {
+            return Err(anyhow!(
+                "diffractive normalization radius must be positive and finite, got \
+                 {normalization_radius}"
+            ));
+        }
+        Ok(Self {
+            phase_coefficients: phase_coefficients.to_vec(),
+            normalization_radius,
+            diffraction_order,
+            base: Conic::new(
+                semi_diameter,
+                radius_of_curvature,
+                conic_constant,
+                boundary_kind,
+            ),
+        })
+    }
+
+    /// Returns the phase added at a position in the local xy-plane, in
+    /// radians.
+    pub fn phase(&self, pos: Vec3) -> Float {
+        let rho_sq = (pos.x().powi(2) + pos.y().powi(2)) / self.normalization_radius.powi(2);
+        let poly = self
+            .phase_coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, a| (acc + a) * rho_sq);
+        self.diffraction_order as Float * poly
+    }
+
+    /// Returns the gradient of the phase in the local xy-plane, in radians per
+    /// mm.
+    pub fn phase_gradient(&self, pos: Vec3) -> Vec3 {
+        let r_norm_sq = self.normalization_radius.powi(2);
+        let rho_sq = (pos.x().powi(2) + pos.y().powi(2)) / r_norm_sq;
+
+        // dΦ/dr / r = M Σ 2i A_i ρ^(2i - 2) / R²
+        let slope_over_r = self
+            .phase_coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .fold(0.0, |acc, (i, a)| acc * rho_sq + 2.0 * (i + 1) as Float * a)
+            * self.diffraction_order as Float
+            / r_norm_sq;
+        Vec3::new(slope_over_r * pos.x(), slope_over_r * pos.y(), 0.0)
+    }
+}
+
+impl Surface for Diffractive {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.base.boundary_kind
+    }
+
+    fn diffractive_power(&self, wavelength: Float) -> Float {
+        let a_1 = self.phase_coefficients.first().copied().unwrap_or(0.0);
+        // The 1e-3 converts the wavelength from µm to mm.
+        -(self.diffraction_order as Float) * a_1 * wavelength * 1e-3
+            / (PI * self.normalization_radius.powi(2))
+    }
+
+    fn mask(&self) -> &Mask {
+        self.base.mask()
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        self.base.norm(pos)
+    }
+
+    fn roc(&self, azimuth_rad: Float) -> Float {
+        self.base.roc(azimuth_rad)
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        self.base.sag(pos)
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::Diffractive
+    }
+
+    /// Refracts or reflects the ray and adds the phase gradient at the
+    /// intersection point.
+    ///
+    /// `wavelength` is in micrometers.
+    fn interact(&self, ray: &mut Ray, n_0: Float, n_1: Float, norm: Vec3, wavelength: Float) {
+        let n_1 = match self.boundary_kind() {
+            BoundaryKind::Refracting => n_1,
+            BoundaryKind::Reflecting => n_0,
+            BoundaryKind::NoOp => return,
+        };
+
+        let p = norm.normalize();
+        let dir = ray.dir();
+        let cos_theta_0 = dir.dot(&p);
+
+        // Tangential component of the optical direction cosines after
+        // diffraction. The 1e-3 converts the wavelength from µm to mm.
+        let grad = self.phase_gradient(ray.pos());
+        let grad_t = grad - p * grad.dot(&p);
+        let tangential = (dir - p * cos_theta_0) * n_0 + grad_t * (wavelength * 1e-3 / (2.0 * PI));
+
+        // NaN for evanescent orders
+        let normal = (n_1 * n_1 - tangential.length_squared()).sqrt();
+        let normal = match self.boundary_kind() {
+            BoundaryKind::Reflecting => -normal * cos_theta_0.signum(),
+            _ => normal * cos_theta_0.signum(),
+        };
+
+        ray.set_dir((tangential + p * normal) * (1.0 / n_1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn flat(coefficients: &[Float], order: i32) -> Diffractive {
+        Diffractive::new(
+            10.0,
+            Float::INFINITY,
+            0.0,
+            5.0,
+            coefficients,
+            order,
+            BoundaryKind::Refracting,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn invalid_normalization_radius_is_error() {
+        for r in [0.0, -1.0, Float::INFINITY] {
+            assert!(
+                Diffractive::new(10.0, 50.0, 0.0, r, &[], 1, BoundaryKind::Refracting).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn phase_gradient_matches_finite_difference() {
+        let surf = flat(&[-100.0, 20.0, -3.0], 2);
+        let pos = Vec3::new(1.5, -2.5, 0.0);
+        let h = 1e-6;
+        let grad = surf.phase_gradient(pos);
+        let dx = (surf.phase(pos + Vec3::new(h, 0.0, 0.0))
+            - surf.phase(pos - Vec3::new(h, 0.0, 0.0)))
+            / (2.0 * h);
+        let dy = (surf.phase(pos + Vec3::new(0.0, h, 0.0))
+            - surf.phase(pos - Vec3::new(0.0, h, 0.0)))
+            / (2.0 * h);
+        assert_abs_diff_eq!(grad.x(), dx, epsilon = 1e-6);
+        assert_abs_diff_eq!(grad.y(), dy, epsilon = 1e-6);
+        assert_eq!(grad.z(), 0.0);
+    }
+
+    #[test]
+    fn zeroth_order_matches_refraction() {
+        let surf =
+            Diffractive::new(10.0, 40.0, 0.0, 5.0, &[-100.0], 0, BoundaryKind::Refracting).unwrap();
+        let conic = Conic::new(10.0, 40.0, 0.0, BoundaryKind::Refracting);
+        let pos = Vec3::new(0.0, 3.0, conic.sag(Vec3::new(0.0, 3.0, 0.0)));
+        let mut r_d = Ray::new(pos, Vec3::new(0.0, 0.1, 1.0).normalize());
+        let mut r_c = r_d.clone();
+        surf.interact(&mut r_d, 1.0, 1.5, surf.norm(pos), 0.5876);
+        conic.interact(&mut r_c, 1.0, 1.5, conic.norm(pos), 0.5876);
+        assert!(r_d.dir().approx_eq(&r_c.dir(), 1e-12));
+    }
+
+    #[test]
+    fn collimated_ray_is_bent_by_the_paraxial_power() {
+        let surf = flat(&[-1000.0], 1);
+        let wavelength = 0.55;
+        let phi = surf.diffractive_power(wavelength);
+        assert_abs_diff_eq!(phi, 1000.0 * 0.55e-3 / (PI * 25.0), epsilon = 1e-15);
+
+        let y = 1e-3;
+        let mut ray = Ray::new(Vec3::new(0.0, y, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        surf.interact(&mut ray, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), wavelength);
+        assert_abs_diff_eq!(ray.dir().y() / ray.dir().z(), -y * phi, epsilon = 1e-12);
+
+        // Power is proportional to wavelength.
+        assert_abs_diff_eq!(
+            surf.diffractive_power(2.0 * wavelength),
+            2.0 * phi,
+            epsilon = 1e-15
+        );
+    }
+}
@@ -0,0 +1,219 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, math::vec3::Vec3},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Surface, SurfaceKind};
+
+/// The maximum number of even-order polynomial coefficients (A4 through A20).
+pub const MAX_ASPHERIC_COEFFICIENTS: usize = 9;
+
+/// An even-order asphere: a conic base plus a polynomial in even powers of the
+/// radial coordinate.
+///
+/// The sag is
+///
+/// ```text
+/// z(r) = c r² / (1 + sqrt(1 - (1 + k) c² r²)) + A4 r⁴ + A6 r⁶ + ... + A20 r²⁰
+/// ```
+///
+/// where `c = 1 / R` is the base curvature and `k` is the conic constant.
+/// `aspheric_coefficients[0]` is A4, `aspheric_coefficients[1]` is A6, and so
+/// on.
+#[derive(Debug, Clone)]
+pub struct EvenAsphere {
+    pub radius_of_curvature: Float,
+    pub conic_constant: Float,
+    pub aspheric_coefficients: Vec<Float>,
+    pub boundary_kind: BoundaryKind,
+    mask: Mask,
+}
+
+impl EvenAsphere {
+    /// Creates a new even asphere.
+    ///
+    /// Returns an error if more than [`MAX_ASPHERIC_COEFFICIENTS`]
+    /// coefficients are given.
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        coefficients: &[Float],
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        if coefficients.len() > MAX_ASPHERIC_COEFFICIENTS {
+            return Err(anyhow!(
+                "an even asphere supports at most {MAX_ASPHERIC_COEFFICIENTS} coefficients \
+                 (A4 to A20), got {}",
+                coefficients.len()
+            ));
+        }
+
+        Ok(Self {
+            radius_of_curvature,
+            conic_constant,
+            aspheric_coefficients: coefficients.to_vec(),
+            boundary_kind,
+            mask: Mask::Circular { semi_diameter },
+        })
+    }
+
+    /// Returns the polynomial departure from the base conic at r².
+    fn poly_sag(&self, r_sq: Float) -> Float {
+        // Horner's scheme in r²: r⁴ (A4 + r² (A6 + r² (A8 + ...)))
+        let sum = self
+            .aspheric_coefficients
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &a| acc * r_sq + a);
+        sum * r_sq * r_sq
+    }
+
+    /// Returns (1 / r) dz/dr of the polynomial departure at r².
+    ///
+    /// Dividing by r avoids a singularity at the vertex; dz/dx is then simply
+    /// `x` times this value.
+    fn poly_slope_over_r(&self, r_sq: Float) -> Float {
+        // d/dr (A_{2i} r^{2i}) / r = 2i A_{2i} r^{2i - 2}
+        let sum = self
+            .aspheric_coefficients
+            .iter()
+            .enumerate()
+            .rev()
+            .fold(0.0, |acc, (i, &a)| acc * r_sq + (2 * i + 4) as Float * a);
+        sum * r_sq
+    }
+}
+
+impl Surface for EvenAsphere {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.boundary_kind
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        let r_sq = pos.x().powi(2) + pos.y().powi(2);
+
+        let base = if self.radius_of_curvature.is_infinite() {
+            0.0
+        } else {
+            let c = 1.0 / self.radius_of_curvature;
+            c / (1.0 - (1.0 + self.conic_constant) * c * c * r_sq).sqrt()
+        };
+        let slope_over_r = base + self.poly_slope_over_r(r_sq);
+
+        // Not normalized — magnitude matters for Newton-Raphson
+        Vec3::new(-pos.x() * slope_over_r, -pos.y() * slope_over_r, 1.0)
+    }
+
+    fn roc(&self, _azimuth_rad: Float) -> Float {
+        self.radius_of_curvature
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        let r_sq = pos.x().powi(2) + pos.y().powi(2);
+
+        let base = if self.radius_of_curvature.is_infinite() {
+            0.0
+        } else {
+            let a = r_sq / self.radius_of_curvature;
+            a / (1.0 + (1.0 - (1.0 + self.conic_constant) * a / self.radius_of_curvature).sqrt())
+        };
+
+        base + self.poly_sag(r_sq)
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::EvenAsphere
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        ray::Ray,
+        surfaces::{Conic, solvers::newton_raphson},
+    };
+    use approx::assert_abs_diff_eq;
+
+    fn asphere(roc: Float, k: Float, coefficients: &[Float]) -> EvenAsphere {
+        EvenAsphere::new(10.0, roc, k, coefficients, BoundaryKind::Refracting).unwrap()
+    }
+
+    #[test]
+    fn too_many_coefficients_is_error() {
+        let coefficients = [0.0; MAX_ASPHERIC_COEFFICIENTS + 1];
+        let result = EvenAsphere::new(10.0, 50.0, 0.0, &coefficients, BoundaryKind::Refracting);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn no_coefficients_matches_conic() {
+        let a = asphere(50.0, -0.5, &[]);
+        let c = Conic::new(10.0, 50.0, -0.5, BoundaryKind::Refracting);
+        for &r in &[0.0, 1.0, 3.0, 5.0, 8.0] {
+            let pos = Vec3::new(0.6 * r, 0.8 * r, 0.0);
+            assert_abs_diff_eq!(a.sag(pos), c.sag(pos), epsilon = 1e-12);
+            let na = a.norm(pos);
+            let nc = c.norm(pos);
+            assert_abs_diff_eq!(na.x(), nc.x(), epsilon = 1e-12);
+            assert_abs_diff_eq!(na.y(), nc.y(), epsilon = 1e-12);
+            assert_abs_diff_eq!(na.z(), nc.z(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn flat_base_sag_is_polynomial() {
+        let a4 = 1e-4;
+        let a6 = -2e-6;
+        let a = asphere(Float::INFINITY, 0.0, &[a4, a6]);
+        let r: Float = 3.0;
+        let expected = a4 * r.powi(4) + a6 * r.powi(6);
+        assert_abs_diff_eq!(a.sag(Vec3::new(r, 0.0, 0.0)), expected, epsilon = 1e-14);
+        assert_abs_diff_eq!(a.sag(Vec3::new(0.0, r, 0.0)), expected, epsilon = 1e-14);
+    }
+
+    #[test]
+    fn norm_matches_finite_difference_of_sag() {
+        let a = asphere(40.0, -1.2, &[2e-5, -3e-7, 1e-9]);
+        let h = 1e-6;
+        for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-3.0, 4.0), (5.0, -6.0)] {
+            let dzdx =
+                (a.sag(Vec3::new(x + h, y, 0.0)) - a.sag(Vec3::new(x - h, y, 0.0))) / (2.0 * h);
+            let dzdy =
+                (a.sag(Vec3::new(x, y + h, 0.0)) - a.sag(Vec3::new(x, y - h, 0.0))) / (2.0 * h);
+            let norm = a.norm(Vec3::new(x, y, 0.0));
+            assert_abs_diff_eq!(norm.x(), -dzdx, epsilon = 1e-7);
+            assert_abs_diff_eq!(norm.y(), -dzdy, epsilon = 1e-7);
+            assert_abs_diff_eq!(norm.z(), 1.0);
+        }
+    }
+
+    #[test]
+    fn roc_is_base_radius() {
+        let a = asphere(77.3, -1.0, &[1e-4]);
+        assert_abs_diff_eq!(a.roc(0.0), 77.3);
+        assert_abs_diff_eq!(a.roc(1.23), 77.3);
+    }
+
+    #[test]
+    fn newton_raphson_converges_onto_surface() {
+        let a = asphere(30.0, -0.8, &[5e-5, -1e-7]);
+        let ray = Ray::new(Vec3::new(0.0, 6.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let (p, _) = newton_raphson(&ray, &a, 100).unwrap();
+        assert_abs_diff_eq!(p.y(), 6.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.z(), a.sag(p), epsilon = 1e-10);
+    }
+
+    #[test]
+    fn surface_kind_is_even_asphere() {
+        let a = asphere(30.0, 0.0, &[]);
+        assert!(matches!(a.surface_kind(), SurfaceKind::EvenAsphere));
+    }
+}
@@ -0,0 +1,609 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, math::vec3::Vec3},
+    specs::surfaces::{BoundaryKind, ForbesQType, Mask},
+};
+
+use super::{Surface, SurfaceKind, even_asphere::MAX_ASPHERIC_COEFFICIENTS};
+
+/// An asphere described by Forbes Q-polynomials.
+///
+/// For [`ForbesQType::Qbfs`] the base is a sphere and the sag is
+///
+/// ```text
+/// z(r) = c r² / (1 + sqrt(1 - c² r²)) + u² (1 - u²) / sqrt(1 - c² r²) Σ a_m Q_m^bfs(u²)
+/// ```
+///
+/// so the departure vanishes on axis and at the normalization radius, and the
+/// mean square slope of the departure is `Σ a_m²`. For [`ForbesQType::Qcon`]
+/// the base is a conic and
+///
+/// ```text
+/// z(r) = c r² / (1 + sqrt(1 - (1 + k) c² r²)) + u⁴ Σ a_m Q_m^con(u²)
+/// ```
+///
+/// where `Q_m^con(x) = P_m^(0,4)(2x - 1)` are Jacobi polynomials. In both
+/// cases `u = r / normalization_radius` and `coefficients[0]` is `a_0`.
+///
+/// The polynomials are evaluated with their three-term recurrences rather than
+/// as power series, which keeps high-order terms accurate. See G. W. Forbes,
+/// "Shape specification for axially symmetric optical surfaces," Opt. Express
+/// 15, 5218 (2007).
+#[derive(Debug, Clone)]
+pub struct ForbesAsphere {
+    pub radius_of_curvature: Float,
+    pub conic_constant: Float,
+    pub normalization_radius: Float,
+    pub q_type: ForbesQType,
+    pub coefficients: Vec<Float>,
+    pub boundary_kind: BoundaryKind,
+    mask: Mask,
+    bfs: QbfsRecurrence,
+}
+
+impl ForbesAsphere {
+    /// Creates a new Forbes asphere.
+    ///
+    /// Returns an error if the normalization radius is not positive and
+    /// finite, or if a Qbfs surface is given a non-zero conic constant.
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        normalization_radius: Float,
+        q_type: ForbesQType,
+        coefficients: &[Float],
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        if !(normalization_radius.is_finite() && normalization_radius > 0.0) {
+            return Err(anyhow!(
+                "Forbes normalization radius must be positive and finite, got \
+                 {normalization_radius}"
+            ));
+        }
+        if q_type == ForbesQType::Qbfs && conic_constant != 0.0 {
+            return Err(anyhow!(
+                "a Qbfs surface has a spherical base; got conic constant {conic_constant}"
+            ));
+        }
+
+        let bfs = match q_type {
+            ForbesQType::Qbfs => QbfsRecurrence::new(coefficients.len()),
+            ForbesQType::Qcon => QbfsRecurrence::new(0),
+        };
+
+        Ok(Self {
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            q_type,
+            coefficients: coefficients.to_vec(),
+            boundary_kind,
+            mask: Mask::Circular { semi_diameter },
+            bfs,
+        })
+    }
+
+    /// Creates a Qcon surface with the same sag as an even asphere.
+    ///
+    /// The conversion is exact. `aspheric_coefficients` holds A4, A6, ... as
+    /// for [`EvenAsphere`](super::EvenAsphere).
+    pub fn qcon_from_even_asphere(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        aspheric_coefficients: &[Float],
+        normalization_radius: Float,
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        // A_{4 + 2k} r^{4 + 2k} = (A_{4 + 2k} R^{4 + 2k}) u⁴ x^k
+        let power_series: Vec<Float> = aspheric_coefficients
+            .iter()
+            .enumerate()
+            .map(|(k, a)| a * normalization_radius.powi(4 + 2 * k as i32))
+            .collect();
+        Self::new(
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            ForbesQType::Qcon,
+            &power_series_to_q(ForbesQType::Qcon, &power_series),
+            boundary_kind,
+        )
+    }
+
+    /// Returns the even asphere coefficients A4, A6, ... with the same sag as
+    /// a Qcon surface.
+    ///
+    /// Returns an error for Qbfs surfaces, whose departure is not a power
+    /// series in r, and if the result needs more than
+    /// [`MAX_ASPHERIC_COEFFICIENTS`] terms.
+    pub fn even_asphere_coefficients(&self) -> Result<Vec<Float>> {
+        if self.q_type != ForbesQType::Qcon {
+            return Err(anyhow!(
+                "only Qcon surfaces have an exact even asphere equivalent"
+            ));
+        }
+        if self.coefficients.len() > MAX_ASPHERIC_COEFFICIENTS {
+            return Err(anyhow!(
+                "an even asphere supports at most {MAX_ASPHERIC_COEFFICIENTS} coefficients, \
+                 but the surface has {} Qcon terms",
+                self.coefficients.len()
+            ));
+        }
+        Ok(q_to_power_series(ForbesQType::Qcon, &self.coefficients)
+            .iter()
+            .enumerate()
+            .map(|(k, b)| b / self.normalization_radius.powi(4 + 2 * k as i32))
+            .collect())
+    }
+
+    /// Returns the base curvature `c` and `1 - (1 + k) c² r²`.
+    fn base_root_arg(&self, r_sq: Float) -> (Float, Float) {
+        if self.radius_of_curvature.is_infinite() {
+            return (0.0, 1.0);
+        }
+        let c = 1.0 / self.radius_of_curvature;
+        (c, 1.0 - (1.0 + self.conic_constant) * c * c * r_sq)
+    }
+
+    /// Returns the sag and `(1 / r) dz/dr` at r².
+    ///
+    /// Dividing by r avoids a singularity at the vertex; dz/dx is then simply
+    /// `x` times the second value.
+    fn sag_and_slope_over_r(&self, r_sq: Float) -> (Float, Float) {
+        let (c, arg) = self.base_root_arg(r_sq);
+        let root = arg.sqrt();
+        let base = c * r_sq / (1.0 + root);
+        let base_slope = c / root;
+
+        let norm_sq = self.normalization_radius * self.normalization_radius;
+        let x = r_sq / norm_sq;
+        let (s, ds) = match self.q_type {
+            ForbesQType::Qbfs => self.bfs.sum(&self.coefficients, x),
+            ForbesQType::Qcon => qcon_sum(&self.coefficients, x),
+        };
+
+        // d/dr = (2 r / R²) d/dx
+        let (departure, departure_slope) = match self.q_type {
+            ForbesQType::Qbfs => {
+                let a = x * (1.0 - x) * s;
+                let da = (1.0 - 2.0 * x) * s + x * (1.0 - x) * ds;
+                (
+                    a / root,
+                    2.0 * da / (norm_sq * root) + a * c * c / (root * root * root),
+                )
+            }
+            ForbesQType::Qcon => (x * x * s, 2.0 * (2.0 * x * s + x * x * ds) / norm_sq),
+        };
+
+        (base + departure, base_slope + departure_slope)
+    }
+}
+
+/// Recurrence coefficients of the Qbfs polynomials.
+///
+/// The polynomials follow from `P_0 = 2`, `P_1 = 6 - 8x` and
+/// `P_m = (2 - 4x) P_{m-1} - P_{m-2}` through
+/// `Q_m = (P_m - g_{m-1} Q_{m-1} - h_{m-2} Q_{m-2}) / f_m`.
+#[derive(Debug, Clone)]
+struct QbfsRecurrence {
+    f: Vec<Float>,
+    g: Vec<Float>,
+    h: Vec<Float>,
+}
+
+impl QbfsRecurrence {
+    /// Precomputes the coefficients for the first `n_terms` polynomials.
+    fn new(n_terms: usize) -> Self {
+        let mut f: Vec<Float> = vec![2.0, (19.0 as Float).sqrt() / 2.0];
+        let mut g: Vec<Float> = vec![-0.5];
+        let mut h: Vec<Float> = Vec::new();
+        for m in 2..n_terms {
+            let m_f = m as Float;
+            let h_m2 = -m_f * (m_f - 1.0) / (2.0 * f[m - 2]);
+            let g_m1 = -(1.0 + g[m - 2] * h_m2) / f[m - 1];
+            f.push((m_f * (m_f + 1.0) + 3.0 - g_m1 * g_m1 - h_m2 * h_m2).sqrt());
+            g.push(g_m1);
+            h.push(h_m2);
+        }
+        Self { f, g, h }
+    }
+
+    /// Returns `Σ a_m Q_m(x)` and its derivative with respect to `x`.
+    fn sum(&self, coefficients: &[Float], x: Float) -> (Float, Float) {
+        let mut s = 0.0;
+        let mut ds = 0.0;
+
+        // (P_{m-2}, P_{m-1}) and (Q_{m-2}, Q_{m-1}), with derivatives
+        let (mut p_2, mut p_1) = (0.0, 0.0);
+        let (mut dp_2, mut dp_1) = (0.0, 0.0);
+        let (mut q_2, mut q_1) = (0.0, 0.0);
+        let (mut dq_2, mut dq_1) = (0.0, 0.0);
+        for (m, &a) in coefficients.iter().enumerate() {
+            let (p, dp) = match m {
+                0 => (2.0, 0.0),
+                1 => (6.0 - 8.0 * x, -8.0),
+                _ => (
+                    (2.0 - 4.0 * x) * p_1 - p_2,
+                    -4.0 * p_1 + (2.0 - 4.0 * x) * dp_1 - dp_2,
+                ),
+            };
+            let g = if m >= 1 { self.g[m - 1] } else { 0.0 };
+            let h = if m >= 2 { self.h[m - 2] } else { 0.0 };
+            let q = (p - g * q_1 - h * q_2) / self.f[m];
+            let dq = (dp - g * dq_1 - h * dq_2) / self.f[m];
+
+            s += a * q;
+            ds += a * dq;
+            (p_2, p_1) = (p_1, p);
+            (dp_2, dp_1) = (dp_1, dp);
+            (q_2, q_1) = (q_1, q);
+            (dq_2, dq_1) = (dq_1, dq);
+        }
+        (s, ds)
+    }
+}
+
+/// Returns the coefficients `(A_n, B_n, C_n)` of the Qcon recurrence
+/// `Q_n = (A_n + B_n x) Q_{n-1} - C_n Q_{n-2}` for `n >= 1`.
+///
+/// This is the Jacobi recurrence for `P_n^(0,4)(t)` with `t = 2x - 1`.
+fn qcon_recurrence(n: usize) -> (Float, Float, Float) {
+    let n = n as Float;
+    let denom = 2.0 * n * (n + 4.0) * (2.0 * n + 2.0);
+    let slope = (2.0 * n + 3.0) * (2.0 * n + 4.0) * (2.0 * n + 2.0) / denom;
+    let a = -slope - (2.0 * n + 3.0) * 16.0 / denom;
+    let c = 2.0 * (n - 1.0) * (n + 3.0) * (2.0 * n + 4.0) / denom;
+    (a, 2.0 * slope, c)
+}
+
+/// Returns `Σ a_m Q_m^con(x)` and its derivative with respect to `x`.
+fn qcon_sum(coefficients: &[Float], x: Float) -> (Float, Float) {
+    let mut s = 0.0;
+    let mut ds = 0.0;
+    let (mut q_2, mut q_1) = (0.0, 0.0);
+    let (mut dq_2, mut dq_1) = (0.0, 0.0);
+    for (m, &a) in coefficients.iter().enumerate() {
+        let (q, dq) = if m == 0 {
+            (1.0, 0.0)
+        } else {
+            let (a_n, b_n, c_n) = qcon_recurrence(m);
+            (
+                (a_n + b_n * x) * q_1 - c_n * q_2,
+                b_n * q_1 + (a_n + b_n * x) * dq_1 - c_n * dq_2,
+            )
+        };
+        s += a * q;
+        ds += a * dq;
+        (q_2, q_1) = (q_1, q);
+        (dq_2, dq_1) = (dq_1, dq);
+    }
+    (s, ds)
+}
+
+/// Returns the monomial coefficients of `Q_0` through `Q_{n_terms - 1}`.
+///
+/// Element `k` of each polynomial multiplies `x^k`.
+fn q_polynomials(q_type: ForbesQType, n_terms: usize) -> Vec<Vec<Float>> {
+    // Returns (α + β x) p as a polynomial of one degree higher.
+    let affine_times = |alpha: Float, beta: Float, p: &[Float]| {
+        let mut out = vec![0.0; p.len() + 1];
+        for (k, &c) in p.iter().enumerate() {
+            out[k] += alpha * c;
+            out[k + 1] += beta * c;
+        }
+        out
+    };
+    // Returns p - scale q, padding the shorter polynomial with zeros.
+    let minus = |p: &[Float], scale: Float, q: &[Float]| {
+        let mut out = p.to_vec();
+        out.resize(p.len().max(q.len()), 0.0);
+        for (k, &c) in q.iter().enumerate() {
+            out[k] -= scale * c;
+        }
+        out
+    };
+
+    let mut qs: Vec<Vec<Float>> = Vec::with_capacity(n_terms);
+    match q_type {
+        ForbesQType::Qbfs => {
+            let rec = QbfsRecurrence::new(n_terms);
+            let mut ps: Vec<Vec<Float>> = Vec::with_capacity(n_terms);
+            for m in 0..n_terms {
+                let p = match m {
+                    0 => vec![2.0],
+                    1 => vec![6.0, -8.0],
+                    _ => minus(&affine_times(2.0, -4.0, &ps[m - 1]), 1.0, &ps[m - 2]),
+                };
+                let mut q = p.clone();
+                if m >= 1 {
+                    q = minus(&q, rec.g[m - 1], &qs[m - 1]);
+                }
+                if m >= 2 {
+                    q = minus(&q, rec.h[m - 2], &qs[m - 2]);
+                }
+                qs.push(q.iter().map(|c| c / rec.f[m]).collect());
+                ps.push(p);
+            }
+        }
+        ForbesQType::Qcon => {
+            for m in 0..n_terms {
+                let q = if m == 0 {
+                    vec![1.0]
+                } else {
+                    let (a_n, b_n, c_n) = qcon_recurrence(m);
+                    let next = affine_times(a_n, b_n, &qs[m - 1]);
+                    if m >= 2 {
+                        minus(&next, c_n, &qs[m - 2])
+                    } else {
+                        next
+                    }
+                };
+                qs.push(q);
+            }
+        }
+    }
+    qs
+}
+
+/// Converts Forbes coefficients to the power series in `x = u²` of the same
+/// polynomial sum.
+///
+/// The result `b` satisfies `Σ a_m Q_m(x) = Σ b_k x^k`. The departure of the
+/// surface then follows from the prefactor of its [`ForbesQType`]; for Qcon it
+/// is `Σ b_k u^(4 + 2k)`. Precision is lost for many terms because the
+/// monomial coefficients grow quickly and alternate in sign.
+pub fn q_to_power_series(q_type: ForbesQType, coefficients: &[Float]) -> Vec<Float> {
+    let mut power_series = vec![0.0; coefficients.len()];
+    for (a, q) in coefficients
+        .iter()
+        .zip(q_polynomials(q_type, coefficients.len()))
+    {
+        for (b, c) in power_series.iter_mut().zip(q) {
+            *b += a * c;
+        }
+    }
+    power_series
+}
+
+/// Converts a power series in `x = u²` to Forbes coefficients of the same
+/// polynomial sum.
+///
+/// This is the inverse of [`q_to_power_series`].
+pub fn power_series_to_q(q_type: ForbesQType, power_series: &[Float]) -> Vec<Float> {
+    let qs = q_polynomials(q_type, power_series.len());
+    let mut remainder = power_series.to_vec();
+    let mut coefficients = vec![0.0; power_series.len()];
+
+    // Q_m has degree m, so peel off terms from the highest degree down.
+    for m in (0..power_series.len()).rev() {
+        let a = remainder[m] / qs[m][m];
+        for (r, c) in remainder.iter_mut().zip(&qs[m]) {
+            *r -= a * c;
+        }
+        coefficients[m] = a;
+    }
+    coefficients
+}
+
+impl Surface for ForbesAsphere {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.boundary_kind
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        let r_sq = pos.x().powi(2) + pos.y().powi(2);
+        let (_, slope_over_r) = self.sag_and_slope_over_r(r_sq);
+
+        // Not normalized — magnitude matters for Newton-Raphson
+        Vec3::new(-pos.x() * slope_over_r, -pos.y() * slope_over_r, 1.0)
+    }
+
+    fn roc(&self, _azimuth_rad: Float) -> Float {
+        self.radius_of_curvature
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        let r_sq = pos.x().powi(2) + pos.y().powi(2);
+        self.sag_and_slope_over_r(r_sq).0
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::ForbesAsphere
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{PI, surfaces::EvenAsphere};
+    use approx::assert_abs_diff_eq;
+
+    fn qbfs(roc: Float, coefficients: &[Float]) -> ForbesAsphere {
+        ForbesAsphere::new(
+            10.0,
+            roc,
+            0.0,
+            10.0,
+            ForbesQType::Qbfs,
+            coefficients,
+            BoundaryKind::Refracting,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn invalid_inputs_are_errors() {
+        for r in [0.0, -1.0, Float::INFINITY, Float::NAN] {
+            let result = ForbesAsphere::new(
+                10.0,
+                50.0,
+                0.0,
+                r,
+                ForbesQType::Qcon,
+                &[],
+                BoundaryKind::Refracting,
+            );
+            assert!(result.is_err(), "radius {r} should be rejected");
+        }
+        let result = ForbesAsphere::new(
+            10.0,
+            50.0,
+            -1.0,
+            10.0,
+            ForbesQType::Qbfs,
+            &[],
+            BoundaryKind::Refracting,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn low_order_polynomials_match_closed_forms() {
+        let x = 0.3;
+        let (q1_bfs, _) = QbfsRecurrence::new(2).sum(&[0.0, 1.0], x);
+        assert_abs_diff_eq!(
+            q1_bfs,
+            (13.0 - 16.0 * x) / (19.0 as Float).sqrt(),
+            epsilon = 1e-15
+        );
+
+        let (q1_con, _) = qcon_sum(&[0.0, 1.0], x);
+        let (q2_con, _) = qcon_sum(&[0.0, 0.0, 1.0], x);
+        assert_abs_diff_eq!(q1_con, 6.0 * x - 5.0, epsilon = 1e-15);
+        assert_abs_diff_eq!(q2_con, 15.0 - 14.0 * x * (3.0 - 2.0 * x), epsilon = 1e-14);
+
+        // P_m^(0,4)(1) = 1
+        for m in 0..12 {
+            let mut a = vec![0.0; m + 1];
+            a[m] = 1.0;
+            assert_abs_diff_eq!(qcon_sum(&a, 1.0).0, 1.0, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn qbfs_departures_have_orthonormal_slopes() {
+        // <dδ_m/du dδ_n/du> = δ_mn with δ_m = u² (1 - u²) Q_m(u²), averaged
+        // with the weight 2 / (π sqrt(1 - u²)). Substituting u = sin θ removes
+        // the endpoint singularity.
+        let n_terms = 6;
+        let rec = QbfsRecurrence::new(n_terms);
+        let slope = |m: usize, u: Float| {
+            let mut a = vec![0.0; n_terms];
+            a[m] = 1.0;
+            let x = u * u;
+            let (q, dq) = rec.sum(&a, x);
+            2.0 * u * ((1.0 - 2.0 * x) * q + x * (1.0 - x) * dq)
+        };
+
+        let n = 2000;
+        for m in 0..n_terms {
+            for k in 0..=m {
+                let sum: Float = (0..n)
+                    .map(|i| {
+                        let u = ((i as Float + 0.5) / n as Float * PI / 2.0).sin();
+                        slope(m, u) * slope(k, u)
+                    })
+                    .sum();
+                let mean = sum / n as Float;
+                let expected = if m == k { 1.0 } else { 0.0 };
+                assert_abs_diff_eq!(mean, expected, epsilon = 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn qbfs_departure_vanishes_at_normalization_radius() {
+        let surf = qbfs(-40.0, &[1e-3, -2e-4, 5e-5]);
+        let sphere = qbfs(-40.0, &[]);
+        let edge = Vec3::new(6.0, 8.0, 0.0);
+        assert_abs_diff_eq!(surf.sag(edge), sphere.sag(edge), epsilon = 1e-15);
+        assert_abs_diff_eq!(surf.sag(Vec3::new(0.0, 0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn norm_matches_finite_difference_of_sag() {
+        let h = 1e-6;
+        for surf in [
+            qbfs(60.0, &[2e-3, 1e-3, -4e-4, 1e-4]),
+            ForbesAsphere::new(
+                10.0,
+                -35.0,
+                -1.5,
+                9.0,
+                ForbesQType::Qcon,
+                &[1e-4, -3e-5, 2e-5, 1e-6],
+                BoundaryKind::Refracting,
+            )
+            .unwrap(),
+        ] {
+            for &(x, y) in &[(0.0, 0.0), (1.5, -3.0), (-6.0, 7.0)] {
+                let dzdx = (surf.sag(Vec3::new(x + h, y, 0.0))
+                    - surf.sag(Vec3::new(x - h, y, 0.0)))
+                    / (2.0 * h);
+                let dzdy = (surf.sag(Vec3::new(x, y + h, 0.0))
+                    - surf.sag(Vec3::new(x, y - h, 0.0)))
+                    / (2.0 * h);
+                let n = surf.norm(Vec3::new(x, y, 0.0));
+                assert_abs_diff_eq!(n.x(), -dzdx, epsilon = 1e-8);
+                assert_abs_diff_eq!(n.y(), -dzdy, epsilon = 1e-8);
+            }
+        }
+    }
+
+    #[test]
+    fn power_series_round_trips() {
+        for q_type in [ForbesQType::Qbfs, ForbesQType::Qcon] {
+            let a = [0.3, -0.2, 0.05, 0.01, -0.004, 0.002];
+            let b = q_to_power_series(q_type, &a);
+            for x in [0.0, 0.25, 0.7, 1.0] {
+                let (s, _) = match q_type {
+                    ForbesQType::Qbfs => QbfsRecurrence::new(a.len()).sum(&a, x),
+                    ForbesQType::Qcon => qcon_sum(&a, x),
+                };
+                let p: Float = b.iter().rev().fold(0.0, |acc, c| acc * x + c);
+                assert_abs_diff_eq!(s, p, epsilon = 1e-12);
+            }
+
+            let back = power_series_to_q(q_type, &b);
+            for (a, back) in a.iter().zip(&back) {
+                assert_abs_diff_eq!(a, back, epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn qcon_converts_exactly_to_and_from_even_asphere() {
+        let coefficients = [2.0e-5, -3.0e-8, 4.0e-11, -1.0e-13];
+        let asphere =
+            EvenAsphere::new(12.0, 45.0, -0.8, &coefficients, BoundaryKind::Refracting).unwrap();
+        let forbes = ForbesAsphere::qcon_from_even_asphere(
+            12.0,
+            45.0,
+            -0.8,
+            &coefficients,
+            12.0,
+            BoundaryKind::Refracting,
+        )
+        .unwrap();
+
+        for &(x, y) in &[(0.0, 0.0), (3.0, 4.0), (-8.0, 8.0), (0.0, 12.0)] {
+            let pos = Vec3::new(x, y, 0.0);
+            assert_abs_diff_eq!(forbes.sag(pos), asphere.sag(pos), epsilon = 1e-13);
+            assert!(forbes.norm(pos).approx_eq(&asphere.norm(pos), 1e-13));
+        }
+
+        let back = forbes.even_asphere_coefficients().unwrap();
+        for (a, back) in coefficients.iter().zip(&back) {
+            assert_abs_diff_eq!(a, back, epsilon = 1e-12 * a.abs());
+        }
+        assert!(qbfs(50.0, &[1e-3]).even_asphere_coefficients().is_err());
+    }
+}
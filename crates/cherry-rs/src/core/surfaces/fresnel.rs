@@ -0,0 +1,176 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{EvenAsphere, Surface, SurfaceKind, solvers::flat_surface};
+
+/// A Fresnel lens: a flat carrier with facets that follow an aspheric profile.
+///
+/// Rays intersect the flat carrier in the local xy-plane, but are refracted or
+/// reflected by the facet normal, which is the normal of the base profile at
+/// the same height. The base profile is an even asphere, so a conic is the
+/// special case without polynomial terms. The facet heights are ignored, which
+/// is accurate when the grooves are much finer than the lens.
+///
+/// `roc()` reports the base radius, so paraxial analysis sees the power of the
+/// underlying profile. Cross-section views draw the sawtooth relief obtained by
+/// restarting the base profile at every multiple of `groove_pitch`. The pitch
+/// has no effect on ray tracing, so it may be chosen for legibility.
+#[derive(Debug, Clone)]
+pub struct Fresnel {
+    pub groove_pitch: Float,
+    base: EvenAsphere,
+}
+
+impl Fresnel {
+    /// Creates a new Fresnel surface.
+    ///
+    /// Returns an error if the groove pitch is not positive and finite, or if
+    /// the base profile is invalid.
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        aspheric_coefficients: &[Float],
+        groove_pitch: Float,
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        if !(groove_pitch.is_finite() && groove_pitch > 0.0) {
+            return Err(anyhow!(
+                "Fresnel groove pitch must be positive and finite, got {groove_pitch}"
+            ));
+        }
+        Ok(Self {
+            groove_pitch,
+            base: EvenAsphere::new(
+                semi_diameter,
+                radius_of_curvature,
+                conic_constant,
+                aspheric_coefficients,
+                boundary_kind,
+            )?,
+        })
+    }
+
+    /// Returns the even asphere that the facets follow.
+    pub fn base(&self) -> &EvenAsphere {
+        &self.base
+    }
+}
+
+impl Surface for Fresnel {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.base.boundary_kind
+    }
+
+    fn intersect(&self, ray: &Ray, _max_iter: usize) -> Result<(Vec3, Vec3)> {
+        flat_surface(ray, self, 0)
+    }
+
+    fn mask(&self) -> &Mask {
+        self.base.mask()
+    }
+
+    fn roc(&self, azimuth_rad: Float) -> Float {
+        self.base.roc(azimuth_rad)
+    }
+
+    /// The flat carrier.
+    fn sag(&self, _pos: Vec3) -> Float {
+        0.0
+    }
+
+    /// The facet normal, i.e. the normal of the base profile.
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        self.base.norm(pos)
+    }
+
+    /// The sawtooth relief: the base profile measured from the start of the
+    /// groove that contains `pos`.
+    fn profile_sag(&self, pos: Vec3) -> Float {
+        let r = pos.x().hypot(pos.y());
+        let groove_start = (r / self.groove_pitch).floor() * self.groove_pitch;
+        let start = Vec3::new(groove_start, 0.0, 0.0);
+        self.base.sag(pos) - self.base.sag(start)
+    }
+
+    fn profile_breakpoints(&self) -> Vec<Float> {
+        let sd = self.mask().semi_diameter();
+        let n_grooves = (sd / self.groove_pitch).ceil() as usize;
+        (1..n_grooves)
+            .map(|k| k as Float * self.groove_pitch)
+            .collect()
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::Fresnel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn fresnel(pitch: Float) -> Fresnel {
+        Fresnel::new(10.0, 25.0, -1.0, &[1e-6], pitch, BoundaryKind::Refracting).unwrap()
+    }
+
+    #[test]
+    fn invalid_groove_pitch_is_error() {
+        for pitch in [0.0, -1.0, Float::INFINITY, Float::NAN] {
+            let result = Fresnel::new(10.0, 25.0, 0.0, &[], pitch, BoundaryKind::Refracting);
+            assert!(result.is_err(), "pitch {pitch} should be rejected");
+        }
+    }
+
+    #[test]
+    fn carrier_is_flat_and_facets_follow_base() {
+        let surf = fresnel(1.0);
+        let base = surf.base();
+        for &(x, y) in &[(0.0, 0.0), (2.5, -1.0), (-6.0, 7.0)] {
+            let pos = Vec3::new(x, y, 0.0);
+            assert_eq!(surf.sag(pos), 0.0);
+            assert_eq!(surf.norm(pos), base.norm(pos));
+        }
+        assert_eq!(surf.roc(0.0), 25.0);
+
+        let ray = Ray::new(
+            Vec3::new(1.0, 3.0, -2.0),
+            Vec3::new(0.0, 0.1, 1.0).normalize(),
+        );
+        let (pos, norm) = surf.intersect(&ray, 0).unwrap();
+        assert_abs_diff_eq!(pos.z(), 0.0);
+        assert_eq!(norm, base.norm(pos));
+    }
+
+    #[test]
+    fn relief_restarts_at_every_groove() {
+        let surf = fresnel(2.0);
+        let base = surf.base();
+        let at = |r: Float| Vec3::new(0.0, r, 0.0);
+
+        assert_eq!(surf.profile_breakpoints(), vec![2.0, 4.0, 6.0, 8.0]);
+        assert_abs_diff_eq!(
+            surf.profile_sag(at(1.5)),
+            base.sag(at(1.5)),
+            epsilon = 1e-15
+        );
+        assert_abs_diff_eq!(surf.profile_sag(at(4.0)), 0.0, epsilon = 1e-15);
+        assert_abs_diff_eq!(
+            surf.profile_sag(at(5.0)),
+            base.sag(at(5.0)) - base.sag(at(4.0)),
+            epsilon = 1e-15
+        );
+
+        // No groove is taller than the step of the outermost one.
+        let max_step = base.sag(at(10.0)) - base.sag(at(8.0));
+        for i in 0..100 {
+            let h = surf.profile_sag(at(i as Float * 0.1));
+            assert!((0.0..=max_step + 1e-12).contains(&h));
+        }
+    }
+}
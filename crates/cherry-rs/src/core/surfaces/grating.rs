@@ -0,0 +1,213 @@
+use crate::{
+    core::{Float, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Conic, Surface, SurfaceKind};
+
+/// A ruled diffraction grating on a spherical base.
+///
+/// The rulings are straight and equally spaced when projected onto the local
+/// xy-plane. `line_orientation` is the angle of the rulings measured from the
+/// local x-axis, so an orientation of zero gives rulings parallel to x that
+/// disperse light in the local yz-plane. Positive orders are diffracted towards
+/// the grating vector `(-sin θ, cos θ, 0)`.
+///
+/// Instead of Snell's law, rays are redirected with the vector grating
+/// equation
+///
+/// ```text
+/// n' r'_t = n r_t + m λ ν (g - (g · p) p)
+/// ```
+///
+/// where `r_t` is the component of the ray direction tangent to the surface,
+/// `p` the unit surface normal, `g` the grating vector, `ν` the line density
+/// and `m` the diffraction order. A refracting grating transmits the order and
+/// a reflecting grating reflects it. Orders that do not propagate leave the ray
+/// with an undefined direction, which terminates it.
+///
+/// Paraxial analysis and the model's optical axis follow the zeroth order;
+/// rotate downstream surfaces to follow a diffracted order.
+#[derive(Debug, Clone)]
+pub struct Grating {
+    /// Lines per mm.
+    pub line_density: Float,
+    /// Angle of the rulings from the local x-axis, in radians.
+    pub line_orientation: Float,
+    pub diffraction_order: i32,
+    base: Conic,
+}
+
+impl Grating {
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        line_density: Float,
+        line_orientation: Float,
+        diffraction_order: i32,
+        boundary_kind: BoundaryKind,
+    ) -> Self {
+        Self {
+            line_density,
+            line_orientation,
+            diffraction_order,
+            base: Conic::new(semi_diameter, radius_of_curvature, 0.0, boundary_kind),
+        }
+    }
+
+    /// Returns the unit grating vector in the local xy-plane.
+    fn grating_vector(&self) -> Vec3 {
+        Vec3::new(
+            -self.line_orientation.sin(),
+            self.line_orientation.cos(),
+            0.0,
+        )
+    }
+}
+
+impl Surface for Grating {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.base.boundary_kind
+    }
+
+    fn mask(&self) -> &Mask {
+        self.base.mask()
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        self.base.norm(pos)
+    }
+
+    fn roc(&self, azimuth_rad: Float) -> Float {
+        self.base.roc(azimuth_rad)
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        self.base.sag(pos)
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::Grating
+    }
+
+    /// Applies the vector grating equation at the ray's wavelength.
+    ///
+    /// `wavelength` is in micrometers and the line density in lines per mm.
+    fn interact(&self, ray: &mut Ray, n_0: Float, n_1: Float, norm: Vec3, wavelength: Float) {
+        let n_1 = match self.boundary_kind() {
+            BoundaryKind::Refracting => n_1,
+            BoundaryKind::Reflecting => n_0,
+            BoundaryKind::NoOp => return,
+        };
+
+        let p = norm.normalize();
+        let dir = ray.dir();
+        let cos_theta_0 = dir.dot(&p);
+
+        // Tangential component of the optical direction cosines after
+        // diffraction. The 1e-3 converts µm × lines/mm to a dimensionless value.
+        let g = self.grating_vector();
+        let g_t = g - p * g.dot(&p);
+        let kick = self.diffraction_order as Float * wavelength * self.line_density * 1e-3;
+        let tangential = (dir - p * cos_theta_0) * n_0 + g_t * kick;
+
+        // NaN for evanescent orders
+        let normal = (n_1 * n_1 - tangential.length_squared()).sqrt();
+        let normal = match self.boundary_kind() {
+            BoundaryKind::Reflecting => -normal * cos_theta_0.signum(),
+            _ => normal * cos_theta_0.signum(),
+        };
+
+        ray.set_dir((tangential + p * normal) * (1.0 / n_1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn flat_grating(order: i32, boundary_kind: BoundaryKind) -> Grating {
+        // 600 lines/mm, rulings parallel to x
+        Grating::new(10.0, Float::INFINITY, 600.0, 0.0, order, boundary_kind)
+    }
+
+    fn incident(theta: Float) -> Ray {
+        Ray::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(0.0, theta.sin(), theta.cos()),
+        )
+    }
+
+    #[test]
+    fn zeroth_order_matches_snell_and_reflection() {
+        for kind in [BoundaryKind::Refracting, BoundaryKind::Reflecting] {
+            let grating = flat_grating(0, kind);
+            let conic = Conic::new(10.0, Float::INFINITY, 0.0, kind);
+            let mut r_g = incident(0.3);
+            let mut r_c = incident(0.3);
+            let norm = Vec3::new(0.0, 0.0, 1.0);
+            grating.interact(&mut r_g, 1.0, 1.5, norm, 0.5876);
+            conic.interact(&mut r_c, 1.0, 1.5, norm, 0.5876);
+            assert!(r_g.dir().approx_eq(&r_c.dir(), 1e-12));
+        }
+    }
+
+    #[test]
+    fn transmitted_order_satisfies_grating_equation() {
+        // sin θ_m = sin θ_i + m λ / d
+        let grating = flat_grating(1, BoundaryKind::Refracting);
+        let theta_i: Float = 0.1;
+        let wavelength = 0.55;
+        let mut ray = incident(theta_i);
+        grating.interact(&mut ray, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), wavelength);
+
+        let expected = theta_i.sin() + wavelength * 600.0 * 1e-3;
+        assert_abs_diff_eq!(ray.dir().y(), expected, epsilon = 1e-12);
+        assert_abs_diff_eq!(ray.dir().x(), 0.0);
+        assert!(ray.dir().z() > 0.0);
+        assert_abs_diff_eq!(ray.dir().length(), 1.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn reflected_order_disperses_by_wavelength() {
+        // Littrow: the first order retraces the incident ray when 2 sin θ = λ ν.
+        let grating = flat_grating(-1, BoundaryKind::Reflecting);
+        let wavelength = 0.6;
+        let theta_littrow = (wavelength * 600.0 * 1e-3 / 2.0 as Float).asin();
+        let mut ray = incident(theta_littrow);
+        let dir_in = ray.dir();
+        grating.interact(&mut ray, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), wavelength);
+        assert!(ray.dir().approx_eq(&(-dir_in), 1e-12));
+
+        // A longer wavelength is diffracted further from the Littrow direction.
+        let mut red = incident(theta_littrow);
+        grating.interact(&mut red, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), 0.7);
+        assert!(red.dir().y() < -dir_in.y());
+    }
+
+    #[test]
+    fn rulings_orientation_rotates_dispersion_plane() {
+        // Rulings parallel to y disperse along x.
+        let grating = Grating::new(
+            10.0,
+            Float::INFINITY,
+            300.0,
+            crate::core::PI / 2.0,
+            1,
+            BoundaryKind::Refracting,
+        );
+        let mut ray = incident(0.0);
+        grating.interact(&mut ray, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), 0.5);
+        assert_abs_diff_eq!(ray.dir().x(), -0.15, epsilon = 1e-12);
+        assert_abs_diff_eq!(ray.dir().y(), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn evanescent_order_has_undefined_direction() {
+        let grating = flat_grating(3, BoundaryKind::Refracting);
+        let mut ray = incident(0.0);
+        grating.interact(&mut ray, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), 0.6);
+        assert!(ray.dir().z().is_nan());
+    }
+}
@@ -0,0 +1,509 @@
+use anyhow::{Result, anyhow};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    core::{Float, math::vec3::Vec3},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Conic, Surface, SurfaceKind};
+
+/// Leading bytes of the binary grid format.
+const GRID_MAGIC: &[u8; 4] = b"GSAG";
+
+/// Size of the binary header: magic, `nx` and `ny` as `u32`, `dx` and `dy` as
+/// `f64`.
+const GRID_HEADER_LEN: usize = 4 + 2 * 4 + 2 * 8;
+
+/// A regular 2D grid of sag departures.
+///
+/// `values` is stored row by row: `values[j * nx + i]` is the departure at
+/// `x = x_0 + i dx`, `y = y_0 + j dy`. The grid is centered on the surface
+/// vertex, so `x_0 = -(nx - 1) dx / 2` and likewise for `y`. Departures are in
+/// the same length units as the rest of the system. NaN values mark missing
+/// data; rays that land near them are lost.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SagGrid {
+    pub nx: usize,
+    pub ny: usize,
+    pub dx: Float,
+    pub dy: Float,
+    #[cfg_attr(feature = "serde", serde(with = "nan_as_null"))]
+    pub values: Vec<Float>,
+}
+
+/// Serializes missing samples as `null`, since JSON has no NaN.
+#[cfg(feature = "serde")]
+mod nan_as_null {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::core::Float;
+
+    pub fn serialize<S: Serializer>(values: &[Float], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(values.iter().map(|v| (!v.is_nan()).then_some(*v)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Float>, D::Error> {
+        let values = Vec::<Option<Float>>::deserialize(deserializer)?;
+        Ok(values
+            .into_iter()
+            .map(|v| v.unwrap_or(Float::NAN))
+            .collect())
+    }
+}
+
+impl SagGrid {
+    /// Creates a new grid.
+    ///
+    /// Returns an error if the grid has fewer than two samples along either
+    /// axis, if a spacing is not positive and finite, or if the number of
+    /// values is not `nx * ny`.
+    pub fn new(nx: usize, ny: usize, dx: Float, dy: Float, values: Vec<Float>) -> Result<Self> {
+        let grid = Self {
+            nx,
+            ny,
+            dx,
+            dy,
+            values,
+        };
+        grid.validate()?;
+        Ok(grid)
+    }
+
+    /// Parses a grid from comma-separated text.
+    ///
+    /// Each line is one row of constant `y`, in order of increasing `y`, and
+    /// each column one value of `x`, in order of increasing `x`. Blank lines
+    /// and lines starting with `#` are skipped. The spacings are not part of
+    /// the file and are given by `dx` and `dy`.
+    pub fn from_csv(text: &str, dx: Float, dy: Float) -> Result<Self> {
+        let mut nx = 0;
+        let mut ny = 0;
+        let mut values = Vec::new();
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let row = line
+                .split(',')
+                .map(|v| v.trim().parse::<Float>())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| anyhow!("invalid sag value on line {}: {e}", line_no + 1))?;
+            if ny == 0 {
+                nx = row.len();
+            } else if row.len() != nx {
+                return Err(anyhow!(
+                    "line {} has {} values, expected {nx}",
+                    line_no + 1,
+                    row.len()
+                ));
+            }
+            values.extend(row);
+            ny += 1;
+        }
+
+        Self::new(nx, ny, dx, dy, values)
+    }
+
+    /// Parses a grid from the binary format written by
+    /// [`to_bytes`](SagGrid::to_bytes).
+    ///
+    /// All numbers are little-endian. The data is the magic bytes `GSAG`,
+    /// `nx` and `ny` as `u32`, `dx` and `dy` as `f64`, and then the
+    /// `nx * ny` values as `f64` in row order.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < GRID_HEADER_LEN || &bytes[..4] != GRID_MAGIC {
+            return Err(anyhow!("not a binary sag grid"));
+        }
+        let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap()) as usize;
+        let f64_at = |i: usize| Float::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+
+        let nx = u32_at(4);
+        let ny = u32_at(8);
+        let dx = f64_at(12);
+        let dy = f64_at(20);
+
+        let data = &bytes[GRID_HEADER_LEN..];
+        if Some(data.len()) != nx.checked_mul(ny).and_then(|n| n.checked_mul(8)) {
+            return Err(anyhow!(
+                "binary sag grid has {} bytes of data, expected {nx} x {ny} values",
+                data.len()
+            ));
+        }
+        let values = data
+            .chunks_exact(8)
+            .map(|c| Float::from_le_bytes(c.try_into().unwrap()))
+            .collect();
+
+        Self::new(nx, ny, dx, dy, values)
+    }
+
+    /// Encodes the grid in the binary format read by
+    /// [`from_bytes`](SagGrid::from_bytes).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(GRID_HEADER_LEN + 8 * self.values.len());
+        bytes.extend_from_slice(GRID_MAGIC);
+        bytes.extend_from_slice(&(self.nx as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.ny as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.dx.to_le_bytes());
+        bytes.extend_from_slice(&self.dy.to_le_bytes());
+        for &v in &self.values {
+            bytes.extend_from_slice(&v.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Returns the half-widths of the grid along x and y.
+    pub fn half_extent(&self) -> (Float, Float) {
+        (
+            0.5 * (self.nx - 1) as Float * self.dx,
+            0.5 * (self.ny - 1) as Float * self.dy,
+        )
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.nx < 2 || self.ny < 2 {
+            return Err(anyhow!(
+                "sag grid needs at least 2 x 2 samples, got {} x {}",
+                self.nx,
+                self.ny
+            ));
+        }
+        if !(self.dx.is_finite() && self.dx > 0.0 && self.dy.is_finite() && self.dy > 0.0) {
+            return Err(anyhow!(
+                "sag grid spacing must be positive and finite, got {} x {}",
+                self.dx,
+                self.dy
+            ));
+        }
+        if self.values.len() != self.nx * self.ny {
+            return Err(anyhow!(
+                "sag grid has {} values, expected {} x {}",
+                self.values.len(),
+                self.nx,
+                self.ny
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns the sample at `(i, j)`, extrapolating linearly one sample
+    /// beyond each edge.
+    fn sample(&self, i: isize, j: isize) -> Float {
+        let (nx, ny) = (self.nx as isize, self.ny as isize);
+        if i < 0 {
+            2.0 * self.sample(0, j) - self.sample(1, j)
+        } else if i >= nx {
+            2.0 * self.sample(nx - 1, j) - self.sample(nx - 2, j)
+        } else if j < 0 {
+            2.0 * self.sample(i, 0) - self.sample(i, 1)
+        } else if j >= ny {
+            2.0 * self.sample(i, ny - 1) - self.sample(i, ny - 2)
+        } else {
+            self.values[j as usize * self.nx + i as usize]
+        }
+    }
+
+    /// Returns the interpolated departure and its gradient at `(x, y)`.
+    ///
+    /// Interpolation uses the bicubic convolution kernel of Keys, which has a
+    /// continuous first derivative. Points outside the grid give NaN.
+    fn interpolate(&self, x: Float, y: Float) -> (Float, Float, Float) {
+        let (hx, hy) = self.half_extent();
+        let u = (x + hx) / self.dx;
+        let v = (y + hy) / self.dy;
+        let (u_max, v_max) = ((self.nx - 1) as Float, (self.ny - 1) as Float);
+        if !(0.0..=u_max).contains(&u) || !(0.0..=v_max).contains(&v) {
+            return (Float::NAN, Float::NAN, Float::NAN);
+        }
+
+        let i = (u.floor() as isize).min(self.nx as isize - 2);
+        let j = (v.floor() as isize).min(self.ny as isize - 2);
+        let (wx, dwx) = keys_weights(u - i as Float);
+        let (wy, dwy) = keys_weights(v - j as Float);
+
+        let mut z = 0.0;
+        let mut dzdu = 0.0;
+        let mut dzdv = 0.0;
+        for (b, (&wy_b, &dwy_b)) in wy.iter().zip(dwy.iter()).enumerate() {
+            for (a, (&wx_a, &dwx_a)) in wx.iter().zip(dwx.iter()).enumerate() {
+                let p = self.sample(i + a as isize - 1, j + b as isize - 1);
+                z += wx_a * wy_b * p;
+                dzdu += dwx_a * wy_b * p;
+                dzdv += wx_a * dwy_b * p;
+            }
+        }
+        (z, dzdu / self.dx, dzdv / self.dy)
+    }
+}
+
+/// Returns the cubic convolution weights and their derivatives for the four
+/// samples around a point a fraction `t` of the way between the middle two.
+fn keys_weights(t: Float) -> ([Float; 4], [Float; 4]) {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    (
+        [
+            -0.5 * t + t2 - 0.5 * t3,
+            1.0 - 2.5 * t2 + 1.5 * t3,
+            0.5 * t + 2.0 * t2 - 1.5 * t3,
+            -0.5 * t2 + 0.5 * t3,
+        ],
+        [
+            -0.5 + 2.0 * t - 1.5 * t2,
+            -5.0 * t + 4.5 * t2,
+            0.5 + 4.0 * t - 4.5 * t2,
+            -t + 1.5 * t2,
+        ],
+    )
+}
+
+/// A conic base plus a tabulated grid of sag departures.
+///
+/// The sag is the base conic plus the departure interpolated from a
+/// [`SagGrid`], for example a surface figure measured with an interferometer.
+/// Bicubic interpolation keeps the surface normal continuous, which the
+/// Newton-Raphson intersection relies on.
+///
+/// The grid must cover the clear aperture. `roc()` reports the base radius, so
+/// paraxial analysis treats the surface as its base conic.
+#[derive(Debug, Clone)]
+pub struct GridSag {
+    pub grid: SagGrid,
+    base: Conic,
+}
+
+impl GridSag {
+    /// Creates a new grid sag surface.
+    ///
+    /// Returns an error if the grid is invalid or does not cover the clear
+    /// aperture.
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        grid: SagGrid,
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        grid.validate()?;
+        let (hx, hy) = grid.half_extent();
+        if hx < semi_diameter || hy < semi_diameter {
+            return Err(anyhow!(
+                "sag grid spans ±{hx} x ±{hy}, which does not cover the semi-diameter \
+                 {semi_diameter}"
+            ));
+        }
+        Ok(Self {
+            grid,
+            base: Conic::new(
+                semi_diameter,
+                radius_of_curvature,
+                conic_constant,
+                boundary_kind,
+            ),
+        })
+    }
+
+    /// Builds a grid sag surface from JSON parameters.
+    ///
+    /// The parameters have the same fields as [`SurfaceSpec::GridSag`]
+    /// without the placement, so this can be registered with a
+    /// [`SurfaceRegistry`](super::SurfaceRegistry) as a
+    /// [`SurfaceConstructor`](super::SurfaceConstructor).
+    ///
+    /// [`SurfaceSpec::GridSag`]: crate::specs::surfaces::SurfaceSpec::GridSag
+    #[cfg(feature = "serde")]
+    pub fn from_params(params: &serde_json::Value) -> Result<Box<dyn Surface>> {
+        #[derive(Deserialize)]
+        struct Params {
+            semi_diameter: Float,
+            radius_of_curvature: Float,
+            #[serde(default)]
+            conic_constant: Float,
+            grid: SagGrid,
+            surf_kind: BoundaryKind,
+        }
+
+        let p = Params::deserialize(params)?;
+        Ok(Box::new(Self::new(
+            p.semi_diameter,
+            p.radius_of_curvature,
+            p.conic_constant,
+            p.grid,
+            p.surf_kind,
+        )?))
+    }
+
+    pub fn radius_of_curvature(&self) -> Float {
+        self.base.radius_of_curvature
+    }
+
+    pub fn conic_constant(&self) -> Float {
+        self.base.conic_constant
+    }
+}
+
+impl Surface for GridSag {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.base.boundary_kind
+    }
+
+    fn mask(&self) -> &Mask {
+        self.base.mask()
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        let (_, dzdx, dzdy) = self.grid.interpolate(pos.x(), pos.y());
+
+        // Not normalized — magnitude matters for Newton-Raphson
+        self.base.norm(pos) - Vec3::new(dzdx, dzdy, 0.0)
+    }
+
+    fn roc(&self, azimuth_rad: Float) -> Float {
+        self.base.roc(azimuth_rad)
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        let (z, _, _) = self.grid.interpolate(pos.x(), pos.y());
+        self.base.sag(pos) + z
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::GridSag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{ray::Ray, surfaces::solvers::newton_raphson};
+    use approx::assert_abs_diff_eq;
+
+    /// Samples `f` on an `n x n` grid spanning ±`half_width`.
+    fn sampled(n: usize, half_width: Float, f: impl Fn(Float, Float) -> Float) -> SagGrid {
+        let d = 2.0 * half_width / (n - 1) as Float;
+        let mut values = Vec::with_capacity(n * n);
+        for j in 0..n {
+            for i in 0..n {
+                values.push(f(
+                    -half_width + i as Float * d,
+                    -half_width + j as Float * d,
+                ));
+            }
+        }
+        SagGrid::new(n, n, d, d, values).unwrap()
+    }
+
+    #[test]
+    fn invalid_grids_are_errors() {
+        assert!(SagGrid::new(1, 3, 1.0, 1.0, vec![0.0; 3]).is_err());
+        assert!(SagGrid::new(2, 2, 0.0, 1.0, vec![0.0; 4]).is_err());
+        assert!(SagGrid::new(2, 2, 1.0, 1.0, vec![0.0; 3]).is_err());
+
+        let grid = sampled(5, 2.0, |_, _| 0.0);
+        assert!(GridSag::new(3.0, 50.0, 0.0, grid, BoundaryKind::Refracting).is_err());
+    }
+
+    #[test]
+    fn csv_rows_are_rows_of_constant_y() {
+        let text = "# measured figure\n0, 1, 2\n\n3, 4, 5\n";
+        let grid = SagGrid::from_csv(text, 0.5, 2.0).unwrap();
+        assert_eq!((grid.nx, grid.ny), (3, 2));
+        assert_eq!(grid.values, vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(grid.half_extent(), (0.5, 1.0));
+
+        assert!(SagGrid::from_csv("0, 1\n2\n", 1.0, 1.0).is_err());
+        assert!(SagGrid::from_csv("0, x\n2, 3\n", 1.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn binary_format_round_trips() {
+        let grid = sampled(4, 1.5, |x, y| x * y);
+        let bytes = grid.to_bytes();
+        assert_eq!(SagGrid::from_bytes(&bytes).unwrap(), grid);
+
+        assert!(SagGrid::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(SagGrid::from_bytes(b"nope").is_err());
+    }
+
+    #[test]
+    fn interpolation_reproduces_samples_and_planes() {
+        // The kernel is exact for linear functions, edges included.
+        let grid = sampled(6, 5.0, |x, y| 0.01 * x - 0.02 * y + 0.3);
+        for &(x, y) in &[(0.0, 0.0), (-5.0, 5.0), (4.9, -2.2), (1.3, 3.7)] {
+            let (z, dzdx, dzdy) = grid.interpolate(x, y);
+            assert_abs_diff_eq!(z, 0.01 * x - 0.02 * y + 0.3, epsilon = 1e-14);
+            assert_abs_diff_eq!(dzdx, 0.01, epsilon = 1e-14);
+            assert_abs_diff_eq!(dzdy, -0.02, epsilon = 1e-14);
+        }
+        assert!(grid.interpolate(5.1, 0.0).0.is_nan());
+
+        let grid = sampled(5, 2.0, |x, y| (x * y).sin());
+        let d = grid.dx;
+        assert_abs_diff_eq!(grid.interpolate(d, -d).0, (-d * d).sin(), epsilon = 1e-14);
+    }
+
+    #[test]
+    fn gradient_is_continuous_across_cells() {
+        let grid = sampled(9, 4.0, |x, y| 1e-3 * (x * x - y * y * y));
+        let edge = grid.dx;
+        let h = 1e-9;
+        let (_, gx_0, gy_0) = grid.interpolate(edge - h, 0.3);
+        let (_, gx_1, gy_1) = grid.interpolate(edge + h, 0.3);
+        assert_abs_diff_eq!(gx_0, gx_1, epsilon = 1e-8);
+        assert_abs_diff_eq!(gy_0, gy_1, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn norm_matches_finite_difference_of_sag() {
+        let grid = sampled(21, 10.0, |x, y| 1e-4 * (x * x * y + 3.0 * y));
+        let surf = GridSag::new(10.0, 80.0, -0.5, grid, BoundaryKind::Refracting).unwrap();
+        let h = 1e-6;
+        for &(x, y) in &[(0.0, 0.0), (3.3, -2.1), (-6.0, 6.5)] {
+            let p = Vec3::new(x, y, 0.0);
+            let dzdx = (surf.sag(Vec3::new(x + h, y, 0.0)) - surf.sag(Vec3::new(x - h, y, 0.0)))
+                / (2.0 * h);
+            let dzdy = (surf.sag(Vec3::new(x, y + h, 0.0)) - surf.sag(Vec3::new(x, y - h, 0.0)))
+                / (2.0 * h);
+            let n = surf.norm(p);
+            assert_abs_diff_eq!(n.x(), -dzdx, epsilon = 1e-7);
+            assert_abs_diff_eq!(n.y(), -dzdy, epsilon = 1e-7);
+        }
+    }
+
+    #[test]
+    fn flat_grid_traces_like_its_base_conic() {
+        let grid = sampled(11, 10.0, |_, _| 0.0);
+        let surf = GridSag::new(10.0, 40.0, 0.0, grid, BoundaryKind::Refracting).unwrap();
+        let conic = Conic::new(10.0, 40.0, 0.0, BoundaryKind::Refracting);
+        let ray = Ray::new(
+            Vec3::new(0.5, 4.0, -3.0),
+            Vec3::new(0.0, 0.05, 1.0).normalize(),
+        );
+        let (p_grid, n_grid) = newton_raphson(&ray, &surf, 100).unwrap();
+        let (p_conic, n_conic) = newton_raphson(&ray, &conic, 100).unwrap();
+        assert!(p_grid.approx_eq(&p_conic, 1e-12));
+        assert!(n_grid.approx_eq(&n_conic, 1e-12));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn builds_from_registry_params() {
+        let grid = sampled(3, 6.0, |x, _| 1e-3 * x);
+        let params = serde_json::json!({
+            "semi_diameter": 5.0,
+            "radius_of_curvature": -200.0,
+            "grid": grid,
+            "surf_kind": "Reflecting",
+        });
+        let surf = GridSag::from_params(&params).unwrap();
+        assert_eq!(surf.surface_kind(), SurfaceKind::GridSag);
+        let pos = Vec3::new(2.0, 1.0, 0.0);
+        let conic = Conic::new(5.0, -200.0, 0.0, BoundaryKind::Reflecting);
+        assert_abs_diff_eq!(surf.sag(pos), conic.sag(pos) + 2e-3, epsilon = 1e-15);
+    }
+}
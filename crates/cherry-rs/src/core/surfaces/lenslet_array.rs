@@ -0,0 +1,318 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, LensletPacking, Mask},
+};
+
+use super::{Conic, Surface, SurfaceKind, solvers::newton_raphson};
+
+/// Maximum number of times a ray may move to a neighbouring cell during
+/// intersection before falling back to iterating on the whole array.
+const MAX_CELL_HOPS: usize = 4;
+
+/// A periodic array of identical conic lenslets.
+///
+/// The lenslets sit on a square or hexagonal lattice with one lenslet
+/// centered on the local z-axis. Each lenslet fills the cell of points that
+/// are closer to its center than to any other, so the sag is continuous
+/// across cell boundaries with a kink between neighbouring lenslets. In the
+/// hexagonal packing, rows of lenslets run along the local x-axis with
+/// neighbours `pitch` apart.
+///
+/// A ray is intersected with the lenslet of the cell it lands in. The
+/// paraxial model only sees the lenslet on the axis, so paraxial results
+/// describe a single lenslet.
+#[derive(Debug, Clone)]
+pub struct LensletArray {
+    /// Distance between the centers of neighbouring lenslets.
+    pub pitch: Float,
+    pub packing: LensletPacking,
+    lenslet: Conic,
+    mask: Mask,
+}
+
+impl LensletArray {
+    /// Creates a new lenslet array.
+    ///
+    /// Returns an error if the pitch is not positive and finite, or if the
+    /// lenslet profile is undefined at the corners of its cell.
+    pub fn new(
+        semi_diameter: Float,
+        pitch: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        packing: LensletPacking,
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        if !(pitch.is_finite() && pitch > 0.0) {
+            return Err(anyhow!(
+                "lenslet pitch must be positive and finite, got {pitch}"
+            ));
+        }
+        let lenslet = Conic::new(
+            Float::INFINITY,
+            radius_of_curvature,
+            conic_constant,
+            boundary_kind,
+        );
+        let corner = match packing {
+            LensletPacking::Square => pitch / Float::sqrt(2.0),
+            LensletPacking::Hexagonal => pitch / Float::sqrt(3.0),
+        };
+        if !lenslet.sag(Vec3::new(corner, 0.0, 0.0)).is_finite() {
+            return Err(anyhow!(
+                "lenslet profile with radius of curvature {radius_of_curvature} and conic \
+                 constant {conic_constant} does not cover a cell of pitch {pitch}"
+            ));
+        }
+        Ok(Self {
+            pitch,
+            packing,
+            lenslet,
+            mask: Mask::Circular { semi_diameter },
+        })
+    }
+
+    pub fn radius_of_curvature(&self) -> Float {
+        self.lenslet.radius_of_curvature
+    }
+
+    pub fn conic_constant(&self) -> Float {
+        self.lenslet.conic_constant
+    }
+
+    /// Returns the center of the lenslet whose cell contains `pos`, in the
+    /// local xy-plane.
+    pub fn cell_center(&self, pos: Vec3) -> Vec3 {
+        let p = self.pitch;
+        match self.packing {
+            LensletPacking::Square => {
+                Vec3::new((pos.x() / p).round() * p, (pos.y() / p).round() * p, 0.0)
+            }
+            LensletPacking::Hexagonal => {
+                // The hexagonal lattice is the union of two rectangular ones
+                // of size p x √3 p, offset by half a cell in each direction.
+                let h = Float::sqrt(3.0) * p;
+                let nearest = |x0: Float, y0: Float| {
+                    Vec3::new(
+                        ((pos.x() - x0) / p).round() * p + x0,
+                        ((pos.y() - y0) / h).round() * h + y0,
+                        0.0,
+                    )
+                };
+                let a = nearest(0.0, 0.0);
+                let b = nearest(0.5 * p, 0.5 * h);
+                let dist_sq = |c: Vec3| (pos.x() - c.x()).powi(2) + (pos.y() - c.y()).powi(2);
+                if dist_sq(a) <= dist_sq(b) { a } else { b }
+            }
+        }
+    }
+}
+
+impl Surface for LensletArray {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.lenslet.boundary_kind
+    }
+
+    /// Intersects the ray with the lenslet of the cell it lands in.
+    ///
+    /// The first guess is the cell where the ray crosses the local xy-plane.
+    /// If the intersection with that lenslet lies in another cell, the ray is
+    /// intersected with the lenslet of that cell instead.
+    fn intersect(&self, ray: &Ray, max_iter: usize) -> Result<(Vec3, Vec3)> {
+        let mut center = self.cell_center(ray.pos_at(-ray.z() / ray.n()));
+        for _ in 0..MAX_CELL_HOPS {
+            let local = Ray::new(ray.pos() - center, ray.dir());
+            let (pos, norm) = newton_raphson(&local, &self.lenslet, max_iter)?;
+            let pos = pos + center;
+            let next = self.cell_center(pos);
+            if next == center {
+                return Ok((pos, norm));
+            }
+            center = next;
+        }
+
+        // The ray grazes a cell boundary and the lenslets on either side of
+        // it disagree. The sag is continuous, so iterate on the whole array.
+        newton_raphson(ray, self, max_iter)
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        self.lenslet.norm(pos - self.cell_center(pos))
+    }
+
+    /// The cell boundaries along the local x- and y-axes.
+    fn profile_breakpoints(&self) -> Vec<Float> {
+        let sd = self.mask.semi_diameter();
+        let p = self.pitch;
+        let mut breakpoints: Vec<Float> = (0..)
+            .map(|k| (k as Float + 0.5) * p)
+            .take_while(|&r| r < sd)
+            .collect();
+        if self.packing == LensletPacking::Hexagonal {
+            let h = Float::sqrt(3.0) * p;
+            let offset = p / Float::sqrt(3.0);
+            breakpoints.extend(
+                (0..)
+                    .flat_map(|m| [m as Float * h - offset, m as Float * h + offset])
+                    .skip(1)
+                    .take_while(|&r| r < sd),
+            );
+        }
+        breakpoints
+    }
+
+    fn roc(&self, _azimuth_rad: Float) -> Float {
+        self.lenslet.radius_of_curvature
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        self.lenslet.sag(pos - self.cell_center(pos))
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::LensletArray
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn array(packing: LensletPacking) -> LensletArray {
+        LensletArray::new(5.0, 1.0, 2.0, 0.0, packing, BoundaryKind::Refracting).unwrap()
+    }
+
+    #[test]
+    fn invalid_parameters_are_errors() {
+        for pitch in [0.0, -1.0, Float::NAN] {
+            assert!(
+                LensletArray::new(
+                    5.0,
+                    pitch,
+                    2.0,
+                    0.0,
+                    LensletPacking::Square,
+                    BoundaryKind::Refracting
+                )
+                .is_err()
+            );
+        }
+        // A hemisphere of radius 0.6 is too small to fill a unit square cell.
+        assert!(
+            LensletArray::new(
+                5.0,
+                1.0,
+                0.6,
+                0.0,
+                LensletPacking::Square,
+                BoundaryKind::Refracting
+            )
+            .is_err()
+        );
+        assert!(
+            LensletArray::new(
+                5.0,
+                1.0,
+                0.6,
+                0.0,
+                LensletPacking::Hexagonal,
+                BoundaryKind::Refracting
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn cell_centers() {
+        let square = array(LensletPacking::Square);
+        assert_eq!(
+            square.cell_center(Vec3::new(1.4, -2.6, 0.0)),
+            Vec3::new(1.0, -3.0, 0.0)
+        );
+
+        let hex = array(LensletPacking::Hexagonal);
+        let h = Float::sqrt(3.0);
+        assert_eq!(
+            hex.cell_center(Vec3::new(0.1, 0.2, 0.0)),
+            Vec3::new(0.0, 0.0, 0.0)
+        );
+        assert_eq!(
+            hex.cell_center(Vec3::new(0.6, 0.8, 0.0)),
+            Vec3::new(0.5, 0.5 * h, 0.0)
+        );
+        assert_eq!(
+            hex.cell_center(Vec3::new(-1.1, h - 0.1, 0.0)),
+            Vec3::new(-1.0, h, 0.0)
+        );
+    }
+
+    #[test]
+    fn every_lenslet_has_the_same_profile() {
+        let surf = array(LensletPacking::Hexagonal);
+        let lenslet = Conic::new(1.0, 2.0, 0.0, BoundaryKind::Refracting);
+        let offset = Vec3::new(0.2, -0.1, 0.0);
+        for center in [
+            Vec3::new(0.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(-1.5, 1.5 * Float::sqrt(3.0), 0.0),
+        ] {
+            let pos = center + offset;
+            assert_abs_diff_eq!(surf.sag(pos), lenslet.sag(offset), epsilon = 1e-15);
+            assert_eq!(surf.norm(pos), lenslet.norm(offset));
+        }
+    }
+
+    #[test]
+    fn sag_is_continuous_across_cell_boundaries() {
+        let surf = array(LensletPacking::Square);
+        let below = surf.sag(Vec3::new(0.5 - 1e-12, 0.3, 0.0));
+        let above = surf.sag(Vec3::new(0.5 + 1e-12, 0.3, 0.0));
+        assert_abs_diff_eq!(below, above, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn ray_intersects_the_lenslet_it_lands_in() {
+        for packing in [LensletPacking::Square, LensletPacking::Hexagonal] {
+            let surf = array(packing);
+            // Steep rays that cross the z = 0 plane in one cell but meet the
+            // surface in a neighbouring one.
+            for (x, y, l, m) in [
+                (2.3, 1.1, 0.0, 0.0),
+                (0.45, 0.0, 0.5, 0.0),
+                (-0.1, 0.55, -0.3, 0.6),
+            ] {
+                let ray = Ray::new(Vec3::new(x, y, -1.0), Vec3::new(l, m, 1.0).normalize());
+                let (pos, norm) = surf.intersect(&ray, 1000).unwrap();
+                assert_abs_diff_eq!(pos.z(), surf.sag(pos), epsilon = 1e-12);
+                assert_eq!(norm, surf.norm(pos));
+            }
+        }
+    }
+
+    #[test]
+    fn breakpoints_are_cell_boundaries() {
+        let square = array(LensletPacking::Square);
+        assert_eq!(square.profile_breakpoints(), vec![0.5, 1.5, 2.5, 3.5, 4.5]);
+
+        let hex = array(LensletPacking::Hexagonal);
+        let s = Float::sqrt(3.0);
+        let breakpoints = hex.profile_breakpoints();
+        for r in [1.0 / s, 2.0 / s, 4.0 / s, 5.0 / s] {
+            assert!(breakpoints.iter().any(|b| (b - r).abs() < 1e-12), "{r}");
+        }
+        // Points either side of a breakpoint on the y-axis lie in different
+        // cells.
+        let r = 2.0 / s;
+        assert_ne!(
+            hex.cell_center(Vec3::new(0.0, r - 1e-9, 0.0)),
+            hex.cell_center(Vec3::new(0.0, r + 1e-9, 0.0))
+        );
+    }
+}
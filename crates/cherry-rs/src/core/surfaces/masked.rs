@@ -0,0 +1,133 @@
+use anyhow::Result;
+
+use crate::{
+    core::{Float, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Surface, SurfaceKind};
+
+/// A surface with a clear aperture other than its own.
+///
+/// Every method except [`mask`](Surface::mask) is forwarded to the wrapped
+/// surface, so a masked surface traces, draws and reports its kind exactly
+/// like the original. Use it to give any surface a rectangular, elliptical,
+/// polygonal, decentered or obscured aperture.
+#[derive(Debug)]
+pub struct Masked {
+    surface: Box<dyn Surface>,
+    mask: Mask,
+}
+
+impl Masked {
+    /// Creates a new masked surface.
+    ///
+    /// Returns an error if the mask is invalid.
+    pub fn new(surface: Box<dyn Surface>, mask: Mask) -> Result<Self> {
+        mask.validate()?;
+        Ok(Self { surface, mask })
+    }
+
+    /// Returns the wrapped surface.
+    pub fn surface(&self) -> &dyn Surface {
+        self.surface.as_ref()
+    }
+}
+
+impl Surface for Masked {
+    fn axial_displacement(&self) -> Float {
+        self.surface.axial_displacement()
+    }
+
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.surface.boundary_kind()
+    }
+
+    fn intersect(&self, ray: &Ray, max_iter: usize) -> Result<(Vec3, Vec3)> {
+        self.surface.intersect(ray, max_iter)
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn mechanical_semi_diameter(&self) -> Option<Float> {
+        self.surface.mechanical_semi_diameter()
+    }
+
+    fn flat_width(&self) -> Float {
+        self.surface.flat_width()
+    }
+
+    fn roc(&self, azimuth_rad: Float) -> Float {
+        self.surface.roc(azimuth_rad)
+    }
+
+    fn paraxial_focal_length(&self) -> Option<Float> {
+        self.surface.paraxial_focal_length()
+    }
+
+    fn diffractive_power(&self, wavelength: Float) -> Float {
+        self.surface.diffractive_power(wavelength)
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        self.surface.sag(pos)
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        self.surface.norm(pos)
+    }
+
+    fn profile_sag(&self, pos: Vec3) -> Float {
+        self.surface.profile_sag(pos)
+    }
+
+    fn profile_breakpoints(&self) -> Vec<Float> {
+        self.surface.profile_breakpoints()
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        self.surface.surface_kind()
+    }
+
+    fn interact(&self, ray: &mut Ray, n_0: Float, n_1: Float, norm: Vec3, wavelength: Float) {
+        self.surface.interact(ray, n_0, n_1, norm, wavelength)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::surfaces::{Conic, Iris};
+
+    #[test]
+    fn invalid_mask_is_error() {
+        let mask = Mask::Polygon {
+            vertices: vec![[0.0, 0.0], [1.0, 0.0]],
+        };
+        assert!(Masked::new(Box::new(Iris::new(5.0)), mask).is_err());
+    }
+
+    #[test]
+    fn everything_but_the_mask_is_forwarded() {
+        let conic = Conic::new(10.0, 25.0, -0.5, BoundaryKind::Reflecting);
+        let mask = Mask::Rectangular {
+            half_width_x: 8.0,
+            half_width_y: 2.0,
+        };
+        let surf = Masked::new(Box::new(conic.clone()), mask.clone()).unwrap();
+
+        let pos = Vec3::new(1.0, -3.0, 0.0);
+        assert_eq!(surf.sag(pos), conic.sag(pos));
+        assert_eq!(surf.norm(pos), conic.norm(pos));
+        assert_eq!(surf.roc(0.0), 25.0);
+        assert_eq!(surf.surface_kind(), SurfaceKind::Conic);
+        assert!(matches!(surf.boundary_kind(), BoundaryKind::Reflecting));
+        assert_eq!(surf.mask(), &mask);
+
+        // The conic alone would pass this ray; the slit does not.
+        assert!(!conic.mask().outside_clear_aperture(pos));
+        assert!(surf.mask().outside_clear_aperture(pos));
+    }
+}
@@ -5,24 +5,56 @@ use crate::core::{Float, math::vec3::Vec3, ray::Ray};
 
 use crate::specs::surfaces::{BoundaryKind, Mask};
 
+pub mod axicon;
+pub mod biconic;
+pub mod cardinal_lens;
 pub mod conic;
+pub mod diffractive;
+pub mod even_asphere;
+pub mod forbes_asphere;
+pub mod fresnel;
+pub mod grating;
+pub mod grid_sag;
 pub mod image;
 pub mod iris;
+pub mod lenslet_array;
+pub mod masked;
+pub mod mounted;
 pub mod object;
+pub mod paraxial_lens;
 pub mod probe;
 pub mod solvers;
 pub mod sphere;
 #[cfg(feature = "serde")]
 pub mod surface_registry;
+pub mod toroid;
+pub mod xy_polynomial;
+pub mod zernike_sag;
 
+pub use axicon::Axicon;
+pub use biconic::Biconic;
+pub use cardinal_lens::CardinalLens;
 pub use conic::Conic;
+pub use diffractive::Diffractive;
+pub use even_asphere::EvenAsphere;
+pub use forbes_asphere::ForbesAsphere;
+pub use fresnel::Fresnel;
+pub use grating::Grating;
+pub use grid_sag::{GridSag, SagGrid};
 pub use image::Image;
 pub use iris::Iris;
+pub use lenslet_array::LensletArray;
+pub use masked::Masked;
+pub use mounted::Mounted;
 pub use object::Object;
+pub use paraxial_lens::ParaxialLens;
 pub use probe::Probe;
 pub use sphere::Sphere;
 #[cfg(feature = "serde")]
 pub use surface_registry::{SurfaceConstructor, SurfaceRegistry};
+pub use toroid::Toroid;
+pub use xy_polynomial::XYPolynomial;
+pub use zernike_sag::ZernikeSag;
 
 /// The role of a surface in the optical system.
 ///
@@ -34,12 +66,26 @@ pub use surface_registry::{SurfaceConstructor, SurfaceRegistry};
 /// should return [`SurfaceKind::Custom`] (the default).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SurfaceKind {
+    Axicon,
+    Biconic,
+    CardinalLens,
     Conic,
+    Diffractive,
+    EvenAsphere,
+    ForbesAsphere,
+    Fresnel,
+    Grating,
+    GridSag,
     Image,
     Iris,
+    LensletArray,
     Object,
+    ParaxialLens,
     Probe,
     Sphere,
+    Toroid,
+    XYPolynomial,
+    ZernikeSag,
     Custom,
 }
 
@@ -51,6 +97,18 @@ pub enum SurfaceKind {
 /// vector. By convention, the vertex of a curved surface lies at the origin of
 /// its local coordinate system. A flat surface lies in the local xy-plane.
 pub trait Surface: std::fmt::Debug + Send + Sync {
+    /// Returns the distance along the local z-axis by which
+    /// [`interact`](Surface::interact) moves rays.
+    ///
+    /// The gap that follows the surface is measured from the displaced plane,
+    /// so the sequential model advances its cursor by this distance before the
+    /// gap. Paraxial analysis treats the displacement as a unit-magnification
+    /// jump, like the one between the principal planes of a lens. The default
+    /// is zero.
+    fn axial_displacement(&self) -> Float {
+        0.0
+    }
+
     /// Returns the surface boundary type (refracting, reflecting, etc.).
     fn boundary_kind(&self) -> BoundaryKind;
 
@@ -75,6 +133,24 @@ pub trait Surface: std::fmt::Debug + Send + Sync {
     /// Returns a reference to the surface's clear-aperture mask.
     fn mask(&self) -> &Mask;
 
+    /// Returns the semi-diameter of the surface's mechanical edge, if it lies
+    /// outside the clear aperture.
+    ///
+    /// Cross-section views draw the surface out to this edge. Rays are only
+    /// ever clipped by the [`mask`](Surface::mask). The default is `None`.
+    fn mechanical_semi_diameter(&self) -> Option<Float> {
+        None
+    }
+
+    /// Returns the width of the flat at the mechanical edge of the surface.
+    ///
+    /// Cross-section views draw the surface flat, normal to the local z-axis,
+    /// beyond the mechanical semi-diameter less this width. The default is
+    /// zero.
+    fn flat_width(&self) -> Float {
+        0.0
+    }
+
     /// Returns the radius of curvature of the base sphere of the surface.
     ///
     /// `azimuth_rad` is the angle in the surface's **local** xy-plane,
@@ -92,6 +168,27 @@ pub trait Surface: std::fmt::Debug + Send + Sync {
         Float::INFINITY
     }
 
+    /// Returns the focal length of an ideal thin lens that replaces the
+    /// surface in paraxial analysis.
+    ///
+    /// Surfaces that bend light by something other than their curvature
+    /// return `Some`; paraxial ray transfer matrices then use this focal length
+    /// and ignore [`roc`](Surface::roc). The default is `None`.
+    fn paraxial_focal_length(&self) -> Option<Float> {
+        None
+    }
+
+    /// Returns the optical power, in inverse mm, that the surface adds by
+    /// diffraction at a given vacuum wavelength in micrometers.
+    ///
+    /// Paraxial ray transfer matrices add this to the power of the surface's
+    /// curvature. Unlike refractive power it is proportional to wavelength,
+    /// which gives diffractive surfaces their strong negative dispersion. The
+    /// default is zero.
+    fn diffractive_power(&self, _wavelength: Float) -> Float {
+        0.0
+    }
+
     /// Returns the surface sag at a given position in local coordinates.
     fn sag(&self, pos: Vec3) -> Float;
 
@@ -102,6 +199,25 @@ pub trait Surface: std::fmt::Debug + Send + Sync {
     /// Newton-Raphson ray tracing calculations.
     fn norm(&self, pos: Vec3) -> Vec3;
 
+    /// Returns the height of the relief drawn for the surface in cross-section
+    /// views, at a given position in local coordinates.
+    ///
+    /// Surfaces whose physical relief differs from the sag used for ray
+    /// tracing, such as Fresnel lenses, override this. The default is
+    /// [`sag`](Surface::sag).
+    fn profile_sag(&self, pos: Vec3) -> Float {
+        self.sag(pos)
+    }
+
+    /// Returns the radial distances from the local z-axis at which the drawn
+    /// relief has a kink or a step.
+    ///
+    /// Cross-section views sample the relief on both sides of each of these
+    /// so that it is drawn sharply. The default is none.
+    fn profile_breakpoints(&self) -> Vec<Float> {
+        Vec::new()
+    }
+
     /// Returns the role of this surface in the optical system.
     ///
     /// Used by rendering and analysis code to distinguish Object, Image, Probe,
@@ -118,7 +234,8 @@ pub trait Surface: std::fmt::Debug + Send + Sync {
     /// the law of reflection for reflecting surfaces, and is a no-op for NoOp
     /// surfaces. Custom surface implementations may override this method to
     /// also displace the ray (e.g., a cardinal lens that displaces rays between
-    /// principal planes).
+    /// principal planes) or to redirect it in a wavelength-dependent way (e.g.,
+    /// a diffraction grating).
     ///
     /// All vectors are in the surface's **local** coordinate system.
     ///
@@ -128,7 +245,8 @@ pub trait Surface: std::fmt::Debug + Send + Sync {
     /// - `n_1`: Refractive index of the medium after the surface
     /// - `norm`: Surface normal at the intersection point (need not be
     ///   normalized)
-    fn interact(&self, ray: &mut Ray, n_0: Float, n_1: Float, norm: Vec3) {
+    /// - `wavelength`: Vacuum wavelength of the ray, in micrometers
+    fn interact(&self, ray: &mut Ray, n_0: Float, n_1: Float, norm: Vec3, _wavelength: Float) {
         let norm = norm.normalize();
         match self.boundary_kind() {
             BoundaryKind::Refracting => {
@@ -164,7 +282,7 @@ mod tests {
         let dir = Vec3::new(0.0, 0.0, 1.0);
         let mut ray = make_ray(dir);
         let norm = Vec3::new(0.0, 0.0, 1.0);
-        surf.interact(&mut ray, 1.0, 1.5, norm);
+        surf.interact(&mut ray, 1.0, 1.5, norm, 0.5876);
         assert_eq!(ray.dir(), dir);
     }
 
@@ -175,7 +293,7 @@ mod tests {
         let dir = Vec3::new(0.0, 0.0, 1.0);
         let mut ray = make_ray(dir);
         let norm = Vec3::new(0.0, 0.0, 1.0);
-        surf.interact(&mut ray, 1.0, 1.5, norm);
+        surf.interact(&mut ray, 1.0, 1.5, norm, 0.5876);
         assert!((ray.dir().x() - 0.0).abs() < 1e-10);
         assert!((ray.dir().y() - 0.0).abs() < 1e-10);
         assert!((ray.dir().z() - 1.0).abs() < 1e-10);
@@ -188,7 +306,7 @@ mod tests {
         let dir = Vec3::new(0.0, 0.0, 1.0);
         let mut ray = make_ray(dir);
         let norm = Vec3::new(0.0, 0.0, 1.0);
-        surf.interact(&mut ray, 1.0, 1.0, norm);
+        surf.interact(&mut ray, 1.0, 1.0, norm, 0.5876);
         assert!((ray.dir().z() - (-1.0)).abs() < 1e-10);
     }
 }
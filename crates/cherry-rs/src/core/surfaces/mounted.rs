@@ -0,0 +1,173 @@
+use anyhow::{Result, bail};
+
+use crate::{
+    core::{Float, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Surface, SurfaceKind};
+
+/// A surface with a mechanical edge outside its clear aperture.
+///
+/// Every method except [`mechanical_semi_diameter`] and [`flat_width`] is
+/// forwarded to the wrapped surface, so a mounted surface traces and clips
+/// rays exactly like the original. Only cross-section views see the
+/// difference.
+///
+/// [`mechanical_semi_diameter`]: Surface::mechanical_semi_diameter
+/// [`flat_width`]: Surface::flat_width
+#[derive(Debug)]
+pub struct Mounted {
+    surface: Box<dyn Surface>,
+    mechanical_semi_diameter: Float,
+    flat_width: Float,
+}
+
+impl Mounted {
+    /// Creates a new mounted surface.
+    ///
+    /// Returns an error if the surface neither refracts nor reflects, if the
+    /// mechanical semi-diameter is not finite or is smaller than the clear
+    /// aperture, or if the flat is negative or wider than the mechanical
+    /// semi-diameter.
+    pub fn new(
+        surface: Box<dyn Surface>,
+        mechanical_semi_diameter: Float,
+        flat_width: Float,
+    ) -> Result<Self> {
+        if matches!(surface.boundary_kind(), BoundaryKind::NoOp) {
+            bail!("only refracting and reflecting surfaces have a mechanical edge");
+        }
+        let clear_semi_diameter = surface.mask().semi_diameter();
+        if !mechanical_semi_diameter.is_finite() || mechanical_semi_diameter < clear_semi_diameter {
+            bail!(
+                "mechanical semi-diameter must be finite and at least the clear semi-diameter \
+                 {clear_semi_diameter}, got {mechanical_semi_diameter}"
+            );
+        }
+        if !(0.0..mechanical_semi_diameter).contains(&flat_width) {
+            bail!(
+                "flat width must be non-negative and less than the mechanical semi-diameter \
+                 {mechanical_semi_diameter}, got {flat_width}"
+            );
+        }
+        Ok(Self {
+            surface,
+            mechanical_semi_diameter,
+            flat_width,
+        })
+    }
+
+    /// Returns the wrapped surface.
+    pub fn surface(&self) -> &dyn Surface {
+        self.surface.as_ref()
+    }
+}
+
+impl Surface for Mounted {
+    fn axial_displacement(&self) -> Float {
+        self.surface.axial_displacement()
+    }
+
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.surface.boundary_kind()
+    }
+
+    fn intersect(&self, ray: &Ray, max_iter: usize) -> Result<(Vec3, Vec3)> {
+        self.surface.intersect(ray, max_iter)
+    }
+
+    fn mask(&self) -> &Mask {
+        self.surface.mask()
+    }
+
+    fn mechanical_semi_diameter(&self) -> Option<Float> {
+        Some(self.mechanical_semi_diameter)
+    }
+
+    fn flat_width(&self) -> Float {
+        self.flat_width
+    }
+
+    fn roc(&self, azimuth_rad: Float) -> Float {
+        self.surface.roc(azimuth_rad)
+    }
+
+    fn paraxial_focal_length(&self) -> Option<Float> {
+        self.surface.paraxial_focal_length()
+    }
+
+    fn diffractive_power(&self, wavelength: Float) -> Float {
+        self.surface.diffractive_power(wavelength)
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        self.surface.sag(pos)
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        self.surface.norm(pos)
+    }
+
+    fn profile_sag(&self, pos: Vec3) -> Float {
+        self.surface.profile_sag(pos)
+    }
+
+    fn profile_breakpoints(&self) -> Vec<Float> {
+        self.surface.profile_breakpoints()
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        self.surface.surface_kind()
+    }
+
+    fn interact(&self, ray: &mut Ray, n_0: Float, n_1: Float, norm: Vec3, wavelength: Float) {
+        self.surface.interact(ray, n_0, n_1, norm, wavelength)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::surfaces::{Conic, Iris, Masked};
+
+    fn lens() -> Box<dyn Surface> {
+        Box::new(Conic::new(10.0, 25.0, 0.0, BoundaryKind::Refracting))
+    }
+
+    #[test]
+    fn invalid_edges_are_errors() {
+        assert!(Mounted::new(Box::new(Iris::new(5.0)), 8.0, 0.0).is_err());
+        assert!(Mounted::new(lens(), 9.0, 0.0).is_err());
+        assert!(Mounted::new(lens(), Float::INFINITY, 0.0).is_err());
+        assert!(Mounted::new(lens(), 12.0, -1.0).is_err());
+        assert!(Mounted::new(lens(), 12.0, 12.0).is_err());
+        assert!(Mounted::new(lens(), 10.0, 0.0).is_ok());
+    }
+
+    #[test]
+    fn clear_aperture_is_unchanged() {
+        let surf = Mounted::new(lens(), 12.0, 1.5).unwrap();
+        assert_eq!(
+            surf.mask(),
+            &Mask::Circular {
+                semi_diameter: 10.0
+            }
+        );
+        assert_eq!(surf.mechanical_semi_diameter(), Some(12.0));
+        assert_eq!(surf.flat_width(), 1.5);
+        assert!(
+            surf.mask()
+                .outside_clear_aperture(Vec3::new(0.0, 11.0, 0.0))
+        );
+
+        // The edge survives a mask wrapped around it.
+        let mask = Mask::Rectangular {
+            half_width_x: 8.0,
+            half_width_y: 4.0,
+        };
+        let masked = Masked::new(Box::new(surf), mask).unwrap();
+        assert_eq!(masked.mechanical_semi_diameter(), Some(12.0));
+        assert_eq!(masked.flat_width(), 1.5);
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, math::vec3::Vec3, ray::Ray},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Surface, SurfaceKind, solvers::flat_surface};
+
+/// An ideal thin lens that focuses every ray perfectly.
+///
+/// The surface is flat. Instead of Snell's law, a ray crossing it at height
+/// `h` is deflected so that
+///
+/// ```text
+/// n' tan θ' = n tan θ - n' h / f
+/// ```
+///
+/// independently in the local x and y directions. `f` is the image-space
+/// focal length, so all rays of a collimated bundle meet in the plane a
+/// distance `f` after the lens regardless of their height or field angle.
+/// In a homogeneous medium this reduces to `tan θ' = tan θ - h / f`.
+///
+/// Paraxial analysis uses [`Surface::paraxial_focal_length`] to model the
+/// surface as a thin lens of the same focal length.
+#[derive(Debug, Clone)]
+pub struct ParaxialLens {
+    pub focal_length: Float,
+    mask: Mask,
+}
+
+impl ParaxialLens {
+    /// Creates a new paraxial lens.
+    ///
+    /// Returns an error if the focal length is zero or NaN. An infinite focal
+    /// length gives a lens with no power.
+    pub fn new(semi_diameter: Float, focal_length: Float) -> Result<Self> {
+        if focal_length == 0.0 || focal_length.is_nan() {
+            return Err(anyhow!(
+                "paraxial lens focal length must be non-zero, got {focal_length}"
+            ));
+        }
+        Ok(Self {
+            focal_length,
+            mask: Mask::Circular { semi_diameter },
+        })
+    }
+}
+
+impl Surface for ParaxialLens {
+    fn boundary_kind(&self) -> BoundaryKind {
+        BoundaryKind::Refracting
+    }
+
+    fn intersect(&self, ray: &Ray, _max_iter: usize) -> Result<(Vec3, Vec3)> {
+        flat_surface(ray, self, 0)
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, _pos: Vec3) -> Vec3 {
+        Vec3::new(0.0, 0.0, 1.0)
+    }
+
+    fn paraxial_focal_length(&self) -> Option<Float> {
+        Some(self.focal_length)
+    }
+
+    fn sag(&self, _pos: Vec3) -> Float {
+        0.0
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::ParaxialLens
+    }
+
+    /// Deflects the ray by the ideal thin-lens equation at its intersection
+    /// height. The surface normal is ignored.
+    fn interact(&self, ray: &mut Ray, n_0: Float, n_1: Float, _norm: Vec3, _wavelength: Float) {
+        let pos = ray.pos();
+        let dir = ray.dir();
+        let forward = dir.z().signum();
+
+        let mu = n_0 / n_1;
+        let tan_x = mu * dir.x() / dir.z().abs() - pos.x() / self.focal_length;
+        let tan_y = mu * dir.y() / dir.z().abs() - pos.y() / self.focal_length;
+
+        ray.set_dir(Vec3::new(tan_x, tan_y, forward).normalize());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn ray_at(x: Float, y: Float, dir: Vec3) -> Ray {
+        Ray::new(Vec3::new(x, y, 0.0), dir.normalize())
+    }
+
+    #[test]
+    fn invalid_focal_lengths_are_errors() {
+        assert!(ParaxialLens::new(10.0, 0.0).is_err());
+        assert!(ParaxialLens::new(10.0, Float::NAN).is_err());
+        assert!(ParaxialLens::new(10.0, Float::INFINITY).is_ok());
+    }
+
+    #[test]
+    fn collimated_rays_meet_at_the_focal_point() {
+        let f = 50.0;
+        let lens = ParaxialLens::new(10.0, f).unwrap();
+        for &(x, y) in &[(0.0, 5.0), (3.0, -4.0), (-8.0, 1.0)] {
+            let mut ray = ray_at(x, y, Vec3::new(0.0, 0.0, 1.0));
+            lens.interact(&mut ray, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), 0.5876);
+            let dir = ray.dir();
+            let s = f / dir.z();
+            assert_abs_diff_eq!(x + s * dir.x(), 0.0, epsilon = 1e-12);
+            assert_abs_diff_eq!(y + s * dir.y(), 0.0, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn oblique_bundle_focuses_without_aberration() {
+        // Even at a large field angle every ray meets at y = f tan θ.
+        let f = 40.0;
+        let lens = ParaxialLens::new(20.0, f).unwrap();
+        let theta: Float = 0.4;
+        for y in [-15.0, -5.0, 0.0, 7.5, 15.0] {
+            let mut ray = ray_at(0.0, y, Vec3::new(0.0, theta.sin(), theta.cos()));
+            lens.interact(&mut ray, 1.0, 1.0, Vec3::new(0.0, 0.0, 1.0), 0.5876);
+            let dir = ray.dir();
+            assert_abs_diff_eq!(dir.length(), 1.0, epsilon = 1e-12);
+            assert_abs_diff_eq!(y + f * dir.y() / dir.z(), f * theta.tan(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn index_change_scales_incident_slope() {
+        let lens = ParaxialLens::new(10.0, 100.0).unwrap();
+        let mut ray = ray_at(2.0, 0.0, Vec3::new(0.1, 0.0, 1.0));
+        lens.interact(&mut ray, 1.0, 1.5, Vec3::new(0.0, 0.0, 1.0), 0.5876);
+        let dir = ray.dir();
+        assert_abs_diff_eq!(dir.x() / dir.z(), 0.1 / 1.5 - 0.02, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn reports_focal_length_for_paraxial_analysis() {
+        let lens = ParaxialLens::new(10.0, -25.0).unwrap();
+        assert_eq!(lens.paraxial_focal_length(), Some(-25.0));
+        assert!(lens.roc(0.3).is_infinite());
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    core::{Float, math::vec3::Vec3},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Surface, SurfaceKind};
+
+/// A toroidal surface with different radii of curvature in the local x and y
+/// meridians.
+///
+/// The surface is generated by a conic profile in the local yz-plane,
+///
+/// ```text
+/// z_y(y) = c_y y² / (1 + sqrt(1 - (1 + k) c_y² y²))
+/// ```
+///
+/// swept about an axis parallel to y at a distance `radius_of_curvature_x`
+/// from the vertex:
+///
+/// ```text
+/// z(x, y) = R_x - sign(R_x - z_y) sqrt((R_x - z_y)² - x²)
+/// ```
+///
+/// An infinite `radius_of_curvature_x` gives a cylinder with power in y only;
+/// an infinite `radius_of_curvature_y` gives a cylinder with power in x only.
+#[derive(Debug, Clone)]
+pub struct Toroid {
+    pub radius_of_curvature_x: Float,
+    pub radius_of_curvature_y: Float,
+    pub conic_constant: Float,
+    pub boundary_kind: BoundaryKind,
+    mask: Mask,
+}
+
+impl Toroid {
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature_x: Float,
+        radius_of_curvature_y: Float,
+        conic_constant: Float,
+        boundary_kind: BoundaryKind,
+    ) -> Self {
+        Self {
+            radius_of_curvature_x,
+            radius_of_curvature_y,
+            conic_constant,
+            boundary_kind,
+            mask: Mask::Circular { semi_diameter },
+        }
+    }
+
+    /// Creates a cylinder with power in the local y meridian. The cylinder axis
+    /// is parallel to the local x-axis.
+    pub fn cylinder(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        boundary_kind: BoundaryKind,
+    ) -> Self {
+        Self::new(
+            semi_diameter,
+            Float::INFINITY,
+            radius_of_curvature,
+            0.0,
+            boundary_kind,
+        )
+    }
+
+    /// Returns the sag of the yz-profile and its derivative with respect to y.
+    fn profile_y(&self, y: Float) -> (Float, Float) {
+        if self.radius_of_curvature_y.is_infinite() {
+            return (0.0, 0.0);
+        }
+        let c = 1.0 / self.radius_of_curvature_y;
+        let root = (1.0 - (1.0 + self.conic_constant) * c * c * y * y).sqrt();
+        (c * y * y / (1.0 + root), c * y / root)
+    }
+}
+
+impl Surface for Toroid {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.boundary_kind
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        let (z_y, dz_y) = self.profile_y(pos.y());
+        if self.radius_of_curvature_x.is_infinite() {
+            return Vec3::new(0.0, -dz_y, 1.0);
+        }
+
+        let d = self.radius_of_curvature_x - z_y;
+        let s = d.signum() * (d * d - pos.x() * pos.x()).sqrt();
+        let dzdx = pos.x() / s;
+        let dzdy = dz_y * d / s;
+
+        // Not normalized — magnitude matters for Newton-Raphson
+        Vec3::new(-dzdx, -dzdy, 1.0)
+    }
+
+    /// Returns the radius of curvature at the vertex along the given azimuth.
+    ///
+    /// The normal curvature follows Euler's theorem, `c(φ) = c_x cos²φ + c_y
+    /// sin²φ`, where φ is measured from the local x-axis.
+    fn roc(&self, azimuth_rad: Float) -> Float {
+        let c_x = 1.0 / self.radius_of_curvature_x;
+        let c_y = 1.0 / self.radius_of_curvature_y;
+        let c = c_x * azimuth_rad.cos().powi(2) + c_y * azimuth_rad.sin().powi(2);
+        if c == 0.0 { Float::INFINITY } else { 1.0 / c }
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        let (z_y, _) = self.profile_y(pos.y());
+        if self.radius_of_curvature_x.is_infinite() {
+            return z_y;
+        }
+
+        let d = self.radius_of_curvature_x - z_y;
+        self.radius_of_curvature_x - d.signum() * (d * d - pos.x() * pos.x()).sqrt()
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::Toroid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        ray::Ray,
+        surfaces::{Conic, solvers::newton_raphson},
+    };
+    use approx::assert_abs_diff_eq;
+    use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+    fn toroid(r_x: Float, r_y: Float) -> Toroid {
+        Toroid::new(10.0, r_x, r_y, 0.0, BoundaryKind::Refracting)
+    }
+
+    #[test]
+    fn equal_radii_matches_sphere() {
+        let t = toroid(40.0, 40.0);
+        let c = Conic::new(10.0, 40.0, 0.0, BoundaryKind::Refracting);
+        for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-3.0, 4.0), (5.0, -6.0)] {
+            let pos = Vec3::new(x, y, 0.0);
+            assert_abs_diff_eq!(t.sag(pos), c.sag(pos), epsilon = 1e-12);
+            let nt = t.norm(pos);
+            let nc = c.norm(pos);
+            assert_abs_diff_eq!(nt.x(), nc.x(), epsilon = 1e-12);
+            assert_abs_diff_eq!(nt.y(), nc.y(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn cylinder_has_no_sag_along_axis() {
+        let t = Toroid::cylinder(10.0, 25.0, BoundaryKind::Refracting);
+        assert_abs_diff_eq!(t.sag(Vec3::new(7.0, 0.0, 0.0)), 0.0);
+        let c = Conic::new(10.0, 25.0, 0.0, BoundaryKind::Refracting);
+        assert_abs_diff_eq!(
+            t.sag(Vec3::new(7.0, 3.0, 0.0)),
+            c.sag(Vec3::new(0.0, 3.0, 0.0)),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn x_cylinder_has_no_sag_along_y() {
+        let t = toroid(25.0, Float::INFINITY);
+        assert_abs_diff_eq!(t.sag(Vec3::new(0.0, 7.0, 0.0)), 0.0);
+        let c = Conic::new(10.0, 25.0, 0.0, BoundaryKind::Refracting);
+        assert_abs_diff_eq!(
+            t.sag(Vec3::new(3.0, 7.0, 0.0)),
+            c.sag(Vec3::new(3.0, 0.0, 0.0)),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn norm_matches_finite_difference_of_sag() {
+        for t in [
+            toroid(-60.0, 40.0),
+            toroid(80.0, -30.0),
+            toroid(35.0, Float::INFINITY),
+            Toroid::new(10.0, 50.0, 30.0, -0.7, BoundaryKind::Refracting),
+        ] {
+            let h = 1e-6;
+            for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-3.0, 4.0), (5.0, -6.0)] {
+                let dzdx =
+                    (t.sag(Vec3::new(x + h, y, 0.0)) - t.sag(Vec3::new(x - h, y, 0.0))) / (2.0 * h);
+                let dzdy =
+                    (t.sag(Vec3::new(x, y + h, 0.0)) - t.sag(Vec3::new(x, y - h, 0.0))) / (2.0 * h);
+                let norm = t.norm(Vec3::new(x, y, 0.0));
+                assert_abs_diff_eq!(norm.x(), -dzdx, epsilon = 1e-7);
+                assert_abs_diff_eq!(norm.y(), -dzdy, epsilon = 1e-7);
+            }
+        }
+    }
+
+    #[test]
+    fn roc_depends_on_azimuth() {
+        let t = toroid(40.0, 20.0);
+        assert_abs_diff_eq!(t.roc(0.0), 40.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(t.roc(FRAC_PI_2), 20.0, epsilon = 1e-12);
+        // c(45°) = (1/40 + 1/20) / 2
+        assert_abs_diff_eq!(
+            t.roc(FRAC_PI_4),
+            2.0 / (1.0 / 40.0 + 1.0 / 20.0),
+            epsilon = 1e-12
+        );
+
+        let cyl = Toroid::cylinder(10.0, 25.0, BoundaryKind::Refracting);
+        assert!(cyl.roc(0.0).is_infinite());
+        assert_abs_diff_eq!(cyl.roc(FRAC_PI_2), 25.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn newton_raphson_converges_onto_surface() {
+        let t = toroid(-45.0, 30.0);
+        let ray = Ray::new(Vec3::new(3.0, 5.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let (p, _) = newton_raphson(&ray, &t, 100).unwrap();
+        assert_abs_diff_eq!(p.x(), 3.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.y(), 5.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.z(), t.sag(p), epsilon = 1e-10);
+    }
+}
@@ -0,0 +1,325 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, math::vec3::Vec3},
+    specs::surfaces::{BoundaryKind, Mask},
+};
+
+use super::{Surface, SurfaceKind};
+
+/// The highest total polynomial order (`i + j`) supported.
+pub const MAX_XY_ORDER: usize = 20;
+
+/// Returns the number of terms in an XY polynomial of the given order.
+///
+/// Terms of every total order from 1 to `order` are counted; there is no
+/// constant term.
+pub fn xy_term_count(order: usize) -> usize {
+    order * (order + 3) / 2
+}
+
+/// Returns the 0-based coefficient index of the term `x^i y^j`.
+///
+/// Terms are ordered by total order `n = i + j` and, within an order, by
+/// increasing power of `y`: `x, y, x², xy, y², x³, x²y, ...`. This matches the
+/// Zemax "Extended Polynomial" convention. `i + j` must be at least 1.
+pub fn xy_term_index(i: usize, j: usize) -> usize {
+    let n = i + j;
+    n * (n + 1) / 2 - 1 + j
+}
+
+/// Returns the powers `(i, j)` of the term at a 0-based coefficient index.
+pub fn xy_term_powers(index: usize) -> (usize, usize) {
+    let mut n = 1;
+    while xy_term_count(n) <= index {
+        n += 1;
+    }
+    let j = index - xy_term_count(n - 1);
+    (n - j, j)
+}
+
+/// A conic base plus a polynomial in `x` and `y`.
+///
+/// The sag is
+///
+/// ```text
+/// z(x, y) = c r² / (1 + sqrt(1 - (1 + k) c² r²)) + Σ c_ij X^i Y^j
+/// ```
+///
+/// where `X = x / normalization_radius` and `Y = y / normalization_radius`.
+/// The sum runs over all terms with `1 <= i + j <= order`, and
+/// `coefficients[xy_term_index(i, j)]` is `c_ij`.
+///
+/// Like [`ZernikeSag`](super::ZernikeSag), `roc()` reports the base radius so
+/// that paraxial analysis treats the surface as its base conic.
+#[derive(Debug, Clone)]
+pub struct XYPolynomial {
+    pub radius_of_curvature: Float,
+    pub conic_constant: Float,
+    pub normalization_radius: Float,
+    pub order: usize,
+    pub coefficients: Vec<Float>,
+    pub boundary_kind: BoundaryKind,
+    mask: Mask,
+    /// Non-zero terms as `(i, j, c_ij)`.
+    terms: Vec<(usize, usize, Float)>,
+}
+
+impl XYPolynomial {
+    /// Creates a new XY polynomial surface.
+    ///
+    /// Returns an error if the normalization radius is not positive and
+    /// finite, if `order` exceeds [`MAX_XY_ORDER`], or if there are more
+    /// coefficients than terms of that order. Trailing coefficients may be
+    /// omitted.
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        normalization_radius: Float,
+        order: usize,
+        coefficients: &[Float],
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        if !(normalization_radius.is_finite() && normalization_radius > 0.0) {
+            return Err(anyhow!(
+                "XY polynomial normalization radius must be positive and finite, got \
+                 {normalization_radius}"
+            ));
+        }
+        if order > MAX_XY_ORDER {
+            return Err(anyhow!(
+                "XY polynomial order must be at most {MAX_XY_ORDER}, got {order}"
+            ));
+        }
+        if coefficients.len() > xy_term_count(order) {
+            return Err(anyhow!(
+                "an XY polynomial of order {order} has {} terms, got {} coefficients",
+                xy_term_count(order),
+                coefficients.len()
+            ));
+        }
+
+        let terms = coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c != 0.0)
+            .map(|(k, &c)| {
+                let (i, j) = xy_term_powers(k);
+                (i, j, c)
+            })
+            .collect();
+
+        Ok(Self {
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            order,
+            coefficients: coefficients.to_vec(),
+            boundary_kind,
+            mask: Mask::Circular { semi_diameter },
+            terms,
+        })
+    }
+
+    /// Returns the polynomial departure and its gradient with respect to the
+    /// normalized coordinates `(X, Y)`.
+    fn polynomial(&self, x: Float, y: Float) -> (Float, Float, Float) {
+        let xn = x / self.normalization_radius;
+        let yn = y / self.normalization_radius;
+
+        let mut z = 0.0;
+        let mut dzdx = 0.0;
+        let mut dzdy = 0.0;
+        for &(i, j, c) in &self.terms {
+            let x_i = xn.powi(i as i32);
+            let y_j = yn.powi(j as i32);
+            z += c * x_i * y_j;
+            if i > 0 {
+                dzdx += c * i as Float * xn.powi(i as i32 - 1) * y_j;
+            }
+            if j > 0 {
+                dzdy += c * j as Float * x_i * yn.powi(j as i32 - 1);
+            }
+        }
+        (z, dzdx, dzdy)
+    }
+}
+
+impl Surface for XYPolynomial {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.boundary_kind
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        let r_sq = pos.x().powi(2) + pos.y().powi(2);
+
+        let base = if self.radius_of_curvature.is_infinite() {
+            0.0
+        } else {
+            let c = 1.0 / self.radius_of_curvature;
+            c / (1.0 - (1.0 + self.conic_constant) * c * c * r_sq).sqrt()
+        };
+        let (_, dzdx, dzdy) = self.polynomial(pos.x(), pos.y());
+
+        // Not normalized — magnitude matters for Newton-Raphson
+        Vec3::new(
+            -pos.x() * base - dzdx / self.normalization_radius,
+            -pos.y() * base - dzdy / self.normalization_radius,
+            1.0,
+        )
+    }
+
+    fn roc(&self, _azimuth_rad: Float) -> Float {
+        self.radius_of_curvature
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        let r_sq = pos.x().powi(2) + pos.y().powi(2);
+
+        let base = if self.radius_of_curvature.is_infinite() {
+            0.0
+        } else {
+            let a = r_sq / self.radius_of_curvature;
+            a / (1.0 + (1.0 - (1.0 + self.conic_constant) * a / self.radius_of_curvature).sqrt())
+        };
+        let (z, _, _) = self.polynomial(pos.x(), pos.y());
+
+        base + z
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::XYPolynomial
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        ray::Ray,
+        surfaces::{Conic, solvers::newton_raphson},
+    };
+    use approx::assert_abs_diff_eq;
+
+    fn xy(roc: Float, order: usize, coefficients: &[Float]) -> XYPolynomial {
+        XYPolynomial::new(
+            10.0,
+            roc,
+            -0.5,
+            10.0,
+            order,
+            coefficients,
+            BoundaryKind::Refracting,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn term_indices_follow_extended_polynomial_order() {
+        let expected = [(1, 0), (0, 1), (2, 0), (1, 1), (0, 2), (3, 0), (2, 1)];
+        for (k, &(i, j)) in expected.iter().enumerate() {
+            assert_eq!(xy_term_powers(k), (i, j));
+            assert_eq!(xy_term_index(i, j), k);
+        }
+        assert_eq!(xy_term_count(1), 2);
+        assert_eq!(xy_term_count(4), 14);
+        assert_eq!(
+            xy_term_powers(xy_term_count(MAX_XY_ORDER) - 1),
+            (0, MAX_XY_ORDER)
+        );
+    }
+
+    #[test]
+    fn invalid_inputs_are_errors() {
+        for r in [0.0, -1.0, Float::INFINITY, Float::NAN] {
+            let result = XYPolynomial::new(10.0, 50.0, 0.0, r, 2, &[], BoundaryKind::Refracting);
+            assert!(result.is_err(), "radius {r} should be rejected");
+        }
+        let too_high = XYPolynomial::new(
+            10.0,
+            50.0,
+            0.0,
+            10.0,
+            MAX_XY_ORDER + 1,
+            &[],
+            BoundaryKind::Refracting,
+        );
+        assert!(too_high.is_err());
+        let too_many = XYPolynomial::new(
+            10.0,
+            50.0,
+            0.0,
+            10.0,
+            2,
+            &[0.0; 6],
+            BoundaryKind::Refracting,
+        );
+        assert!(too_many.is_err());
+    }
+
+    #[test]
+    fn zero_coefficients_match_conic() {
+        let s = xy(40.0, 4, &[0.0; 14]);
+        let c = Conic::new(10.0, 40.0, -0.5, BoundaryKind::Refracting);
+        for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-3.0, 4.0), (5.0, -6.0)] {
+            let pos = Vec3::new(x, y, 0.0);
+            assert_abs_diff_eq!(s.sag(pos), c.sag(pos), epsilon = 1e-12);
+            let ns = s.norm(pos);
+            let nc = c.norm(pos);
+            assert_abs_diff_eq!(ns.x(), nc.x(), epsilon = 1e-12);
+            assert_abs_diff_eq!(ns.y(), nc.y(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn single_term_sag() {
+        // c_21 x² y on a flat base, normalized to 10 mm
+        let mut coefficients = vec![0.0; xy_term_count(3)];
+        coefficients[xy_term_index(2, 1)] = 0.2;
+        let s = xy(Float::INFINITY, 3, &coefficients);
+
+        let pos = Vec3::new(5.0, -4.0, 0.0);
+        assert_abs_diff_eq!(s.sag(pos), 0.2 * 0.25 * -0.4, epsilon = 1e-15);
+        assert_abs_diff_eq!(s.sag(Vec3::new(5.0, 0.0, 0.0)), 0.0);
+    }
+
+    #[test]
+    fn norm_matches_finite_difference_of_sag() {
+        let coefficients: Vec<Float> = (0..xy_term_count(5))
+            .map(|k| 1e-3 * (k as Float + 1.0) * if k % 3 == 0 { -1.0 } else { 1.0 })
+            .collect();
+        let s = xy(45.0, 5, &coefficients);
+        let h = 1e-6;
+        for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-3.0, 4.0), (5.0, -6.0)] {
+            let dzdx =
+                (s.sag(Vec3::new(x + h, y, 0.0)) - s.sag(Vec3::new(x - h, y, 0.0))) / (2.0 * h);
+            let dzdy =
+                (s.sag(Vec3::new(x, y + h, 0.0)) - s.sag(Vec3::new(x, y - h, 0.0))) / (2.0 * h);
+            let norm = s.norm(Vec3::new(x, y, 0.0));
+            assert_abs_diff_eq!(norm.x(), -dzdx, epsilon = 1e-7);
+            assert_abs_diff_eq!(norm.y(), -dzdy, epsilon = 1e-7);
+        }
+    }
+
+    #[test]
+    fn roc_is_base_radius() {
+        let s = xy(77.3, 2, &[0.0, 0.0, 1e-2, 0.0, -1e-2]);
+        assert_abs_diff_eq!(s.roc(0.0), 77.3);
+        assert_abs_diff_eq!(s.roc(1.23), 77.3);
+    }
+
+    #[test]
+    fn newton_raphson_converges_onto_surface() {
+        let s = xy(30.0, 3, &[0.0, 0.0, 1e-2, 5e-3, -2e-2, 1e-3]);
+        let ray = Ray::new(Vec3::new(3.0, 5.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let (p, _) = newton_raphson(&ray, &s, 100).unwrap();
+        assert_abs_diff_eq!(p.x(), 3.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.y(), 5.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.z(), s.sag(p), epsilon = 1e-10);
+    }
+}
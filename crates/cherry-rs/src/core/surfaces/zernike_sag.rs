@@ -0,0 +1,490 @@
+use anyhow::{Result, anyhow};
+
+use crate::{
+    core::{Float, math::vec3::Vec3},
+    specs::surfaces::{BoundaryKind, Mask, ZernikeOrdering},
+};
+
+use super::{Surface, SurfaceKind};
+
+/// The number of terms in the Fringe set.
+pub const MAX_FRINGE_TERMS: usize = 37;
+
+/// A conic base plus a Zernike polynomial expansion of the sag.
+///
+/// The sag is
+///
+/// ```text
+/// z(x, y) = c r² / (1 + sqrt(1 - (1 + k) c² r²)) + Σ_j C_j Z_j(ρ, θ)
+/// ```
+///
+/// where `ρ = r / normalization_radius` and `θ` is measured from the local
+/// x-axis. `coefficients[0]` is the coefficient of the first term (piston) in
+/// the chosen [`ZernikeOrdering`].
+///
+/// The surface is not rotationally symmetric in general, but `roc()` reports
+/// the base radius so that paraxial analysis treats it as its base conic.
+#[derive(Debug, Clone)]
+pub struct ZernikeSag {
+    pub radius_of_curvature: Float,
+    pub conic_constant: Float,
+    pub normalization_radius: Float,
+    pub ordering: ZernikeOrdering,
+    pub coefficients: Vec<Float>,
+    pub boundary_kind: BoundaryKind,
+    mask: Mask,
+    terms: Vec<ZernikeTerm>,
+}
+
+/// A single non-zero term, pre-expanded for evaluation in Cartesian form.
+///
+/// The term is `scale * Q(ρ²) * P(X, Y)` where `Q` is the radial polynomial
+/// with `ρ^m` factored out and `P` is the real or imaginary part of
+/// `(X + iY)^m`. Written this way every term is a polynomial in `X` and `Y`,
+/// so the gradient has no singularity at the vertex.
+#[derive(Debug, Clone)]
+struct ZernikeTerm {
+    scale: Float,
+    m: u32,
+    sin: bool,
+    /// `(a_s, k_s)` pairs such that `Q(ρ²) = Σ a_s (ρ²)^k_s`.
+    radial: Vec<(Float, i32)>,
+}
+
+impl ZernikeSag {
+    /// Creates a new Zernike sag surface.
+    ///
+    /// Returns an error if the normalization radius is not positive and
+    /// finite, or if more than [`MAX_FRINGE_TERMS`] coefficients are given
+    /// with [`ZernikeOrdering::Fringe`].
+    pub fn new(
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        normalization_radius: Float,
+        ordering: ZernikeOrdering,
+        coefficients: &[Float],
+        boundary_kind: BoundaryKind,
+    ) -> Result<Self> {
+        if !(normalization_radius.is_finite() && normalization_radius > 0.0) {
+            return Err(anyhow!(
+                "Zernike normalization radius must be positive and finite, got \
+                 {normalization_radius}"
+            ));
+        }
+        if matches!(ordering, ZernikeOrdering::Fringe) && coefficients.len() > MAX_FRINGE_TERMS {
+            return Err(anyhow!(
+                "the Fringe Zernike set has {MAX_FRINGE_TERMS} terms, got {} coefficients",
+                coefficients.len()
+            ));
+        }
+
+        let terms = coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c != 0.0)
+            .map(|(i, &c)| ZernikeTerm::new(ordering, i + 1, c))
+            .collect();
+
+        Ok(Self {
+            radius_of_curvature,
+            conic_constant,
+            normalization_radius,
+            ordering,
+            coefficients: coefficients.to_vec(),
+            boundary_kind,
+            mask: Mask::Circular { semi_diameter },
+            terms,
+        })
+    }
+
+    /// Returns the Zernike departure and its gradient with respect to the
+    /// normalized coordinates `(X, Y)`.
+    fn zernike(&self, x: Float, y: Float) -> (Float, Float, Float) {
+        let xn = x / self.normalization_radius;
+        let yn = y / self.normalization_radius;
+        let rho_sq = xn * xn + yn * yn;
+
+        let mut z = 0.0;
+        let mut dzdx = 0.0;
+        let mut dzdy = 0.0;
+        for term in &self.terms {
+            let (t, tx, ty) = term.eval(xn, yn, rho_sq);
+            z += t;
+            dzdx += tx;
+            dzdy += ty;
+        }
+        (z, dzdx, dzdy)
+    }
+}
+
+impl ZernikeTerm {
+    fn new(ordering: ZernikeOrdering, j: usize, coefficient: Float) -> Self {
+        let (n, m) = match ordering {
+            ZernikeOrdering::Fringe => fringe_nm(j),
+            ZernikeOrdering::Standard => noll_nm(j),
+        };
+        let m_abs = m.unsigned_abs();
+
+        let norm = match ordering {
+            ZernikeOrdering::Fringe => 1.0,
+            ZernikeOrdering::Standard if m == 0 => ((n + 1) as Float).sqrt(),
+            ZernikeOrdering::Standard => (2.0 * (n + 1) as Float).sqrt(),
+        };
+
+        // R_n^m(ρ) = Σ_s (-1)^s (n - s)! / (s! ((n + m)/2 - s)! ((n - m)/2 - s)!)
+        // ρ^(n - 2s)
+        let half_diff = (n - m_abs) / 2;
+        let half_sum = (n + m_abs) / 2;
+        let radial = (0..=half_diff)
+            .map(|s| {
+                let sign = if s % 2 == 0 { 1.0 } else { -1.0 };
+                let a = sign * factorial(n - s)
+                    / (factorial(s) * factorial(half_sum - s) * factorial(half_diff - s));
+                (a, (half_diff - s) as i32)
+            })
+            .collect();
+
+        Self {
+            scale: coefficient * norm,
+            m: m_abs,
+            sin: m < 0,
+            radial,
+        }
+    }
+
+    /// Evaluates the term and its partial derivatives at normalized `(X, Y)`.
+    fn eval(&self, xn: Float, yn: Float, rho_sq: Float) -> (Float, Float, Float) {
+        let mut q = 0.0;
+        let mut dq = 0.0;
+        for &(a, k) in &self.radial {
+            q += a * rho_sq.powi(k);
+            if k > 0 {
+                dq += a * k as Float * rho_sq.powi(k - 1);
+            }
+        }
+
+        // (X + iY)^(m - 1) and (X + iY)^m
+        let (mut re_1, mut im_1) = (1.0, 0.0);
+        for _ in 1..self.m {
+            (re_1, im_1) = (re_1 * xn - im_1 * yn, re_1 * yn + im_1 * xn);
+        }
+        let (re, im) = if self.m == 0 {
+            (1.0, 0.0)
+        } else {
+            (re_1 * xn - im_1 * yn, re_1 * yn + im_1 * xn)
+        };
+
+        let m = self.m as Float;
+        let (p, dpdx, dpdy) = match (self.m, self.sin) {
+            (0, _) => (1.0, 0.0, 0.0),
+            (_, false) => (re, m * re_1, -m * im_1),
+            (_, true) => (im, m * im_1, m * re_1),
+        };
+
+        (
+            self.scale * q * p,
+            self.scale * (2.0 * xn * dq * p + q * dpdx),
+            self.scale * (2.0 * yn * dq * p + q * dpdy),
+        )
+    }
+}
+
+/// Returns `(n, m)` for the 1-based Fringe (University of Arizona) index `j`.
+///
+/// Positive `m` denotes a cosine term and negative `m` a sine term.
+fn fringe_nm(j: usize) -> (u32, i32) {
+    // The last term of the set is 12th-order spherical, out of the usual
+    // sequence.
+    if j == MAX_FRINGE_TERMS {
+        return (12, 0);
+    }
+    // Group d holds the 2d + 1 terms with n + |m| = 2d, ordered by decreasing
+    // |m| with the cosine term first and ending with the rotationally symmetric
+    // term.
+    let d = ((j as Float).sqrt().ceil() as usize).saturating_sub(1);
+    let i = j - d * d - 1;
+    let m_abs = d - i / 2;
+    let n = 2 * d - m_abs;
+    let m = if m_abs != 0 && i % 2 == 1 {
+        -(m_abs as i32)
+    } else {
+        m_abs as i32
+    };
+    (n as u32, m)
+}
+
+/// Returns `(n, m)` for the 1-based Noll (Standard) index `j`.
+///
+/// Positive `m` denotes a cosine term and negative `m` a sine term.
+fn noll_nm(j: usize) -> (u32, i32) {
+    let mut n = 0;
+    while (n + 1) * (n + 2) / 2 < j {
+        n += 1;
+    }
+    let k = j - n * (n + 1) / 2; // 1-based position within row n
+    let m_abs = if n % 2 == 0 {
+        2 * (k / 2)
+    } else {
+        2 * ((k - 1) / 2) + 1
+    };
+    let m = if m_abs != 0 && j % 2 == 1 {
+        -(m_abs as i32)
+    } else {
+        m_abs as i32
+    };
+    (n as u32, m)
+}
+
+fn factorial(n: u32) -> Float {
+    (1..=n).map(|i| i as Float).product()
+}
+
+impl Surface for ZernikeSag {
+    fn boundary_kind(&self) -> BoundaryKind {
+        self.boundary_kind
+    }
+
+    fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    fn norm(&self, pos: Vec3) -> Vec3 {
+        let r_sq = pos.x().powi(2) + pos.y().powi(2);
+
+        let base = if self.radius_of_curvature.is_infinite() {
+            0.0
+        } else {
+            let c = 1.0 / self.radius_of_curvature;
+            c / (1.0 - (1.0 + self.conic_constant) * c * c * r_sq).sqrt()
+        };
+        let (_, dzdx, dzdy) = self.zernike(pos.x(), pos.y());
+
+        // Not normalized — magnitude matters for Newton-Raphson
+        Vec3::new(
+            -pos.x() * base - dzdx / self.normalization_radius,
+            -pos.y() * base - dzdy / self.normalization_radius,
+            1.0,
+        )
+    }
+
+    fn roc(&self, _azimuth_rad: Float) -> Float {
+        self.radius_of_curvature
+    }
+
+    fn sag(&self, pos: Vec3) -> Float {
+        let r_sq = pos.x().powi(2) + pos.y().powi(2);
+
+        let base = if self.radius_of_curvature.is_infinite() {
+            0.0
+        } else {
+            let a = r_sq / self.radius_of_curvature;
+            a / (1.0 + (1.0 - (1.0 + self.conic_constant) * a / self.radius_of_curvature).sqrt())
+        };
+        let (z, _, _) = self.zernike(pos.x(), pos.y());
+
+        base + z
+    }
+
+    fn surface_kind(&self) -> SurfaceKind {
+        SurfaceKind::ZernikeSag
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        ray::Ray,
+        surfaces::{Conic, solvers::newton_raphson},
+    };
+    use approx::assert_abs_diff_eq;
+
+    fn zernike(roc: Float, ordering: ZernikeOrdering, coefficients: &[Float]) -> ZernikeSag {
+        ZernikeSag::new(
+            10.0,
+            roc,
+            -0.5,
+            10.0,
+            ordering,
+            coefficients,
+            BoundaryKind::Refracting,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn invalid_normalization_radius_is_error() {
+        for r in [0.0, -1.0, Float::INFINITY, Float::NAN] {
+            let result = ZernikeSag::new(
+                10.0,
+                50.0,
+                0.0,
+                r,
+                ZernikeOrdering::Fringe,
+                &[],
+                BoundaryKind::Refracting,
+            );
+            assert!(result.is_err(), "radius {r} should be rejected");
+        }
+    }
+
+    #[test]
+    fn too_many_fringe_terms_is_error() {
+        let coefficients = [0.0; MAX_FRINGE_TERMS + 1];
+        let result = ZernikeSag::new(
+            10.0,
+            50.0,
+            0.0,
+            10.0,
+            ZernikeOrdering::Fringe,
+            &coefficients,
+            BoundaryKind::Refracting,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn fringe_indices() {
+        let expected = [
+            (0, 0),
+            (1, 1),
+            (1, -1),
+            (2, 0),
+            (2, 2),
+            (2, -2),
+            (3, 1),
+            (3, -1),
+            (4, 0),
+            (3, 3),
+            (3, -3),
+            (4, 2),
+            (4, -2),
+            (5, 1),
+            (5, -1),
+            (6, 0),
+        ];
+        for (j, &nm) in expected.iter().enumerate() {
+            assert_eq!(fringe_nm(j + 1), nm, "Fringe Z{}", j + 1);
+        }
+        assert_eq!(fringe_nm(25), (8, 0));
+        assert_eq!(fringe_nm(36), (10, 0));
+        assert_eq!(fringe_nm(37), (12, 0));
+    }
+
+    #[test]
+    fn noll_indices() {
+        let expected = [
+            (0, 0),
+            (1, 1),
+            (1, -1),
+            (2, 0),
+            (2, -2),
+            (2, 2),
+            (3, -1),
+            (3, 1),
+            (3, -3),
+            (3, 3),
+            (4, 0),
+            (4, 2),
+            (4, -2),
+            (4, 4),
+            (4, -4),
+        ];
+        for (j, &nm) in expected.iter().enumerate() {
+            assert_eq!(noll_nm(j + 1), nm, "Noll Z{}", j + 1);
+        }
+    }
+
+    #[test]
+    fn no_coefficients_matches_conic() {
+        let z = zernike(50.0, ZernikeOrdering::Standard, &[]);
+        let c = Conic::new(10.0, 50.0, -0.5, BoundaryKind::Refracting);
+        for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-4.0, 3.0), (6.0, -7.0)] {
+            let pos = Vec3::new(x, y, 0.0);
+            assert_abs_diff_eq!(z.sag(pos), c.sag(pos), epsilon = 1e-12);
+            let nz = z.norm(pos);
+            let nc = c.norm(pos);
+            assert_abs_diff_eq!(nz.x(), nc.x(), epsilon = 1e-12);
+            assert_abs_diff_eq!(nz.y(), nc.y(), epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn defocus_sag_on_flat_base() {
+        let rho: Float = 0.5;
+        let pos = Vec3::new(0.0, rho * 10.0, 0.0);
+
+        // Fringe Z4 = 2ρ² - 1
+        let z = zernike(
+            Float::INFINITY,
+            ZernikeOrdering::Fringe,
+            &[0.0, 0.0, 0.0, 1e-3],
+        );
+        assert_abs_diff_eq!(z.sag(pos), 1e-3 * (2.0 * rho * rho - 1.0), epsilon = 1e-15);
+
+        // Noll Z4 = √3 (2ρ² - 1)
+        let z = zernike(
+            Float::INFINITY,
+            ZernikeOrdering::Standard,
+            &[0.0, 0.0, 0.0, 1e-3],
+        );
+        assert_abs_diff_eq!(
+            z.sag(pos),
+            1e-3 * (3.0 as Float).sqrt() * (2.0 * rho * rho - 1.0),
+            epsilon = 1e-15
+        );
+    }
+
+    #[test]
+    fn astigmatism_differs_between_cut_planes() {
+        // Fringe Z5 = ρ² cos 2θ: positive along x, negative along y.
+        let z = zernike(
+            Float::INFINITY,
+            ZernikeOrdering::Fringe,
+            &[0.0, 0.0, 0.0, 0.0, 1e-3],
+        );
+        assert_abs_diff_eq!(z.sag(Vec3::new(5.0, 0.0, 0.0)), 0.25e-3, epsilon = 1e-15);
+        assert_abs_diff_eq!(z.sag(Vec3::new(0.0, 5.0, 0.0)), -0.25e-3, epsilon = 1e-15);
+    }
+
+    #[test]
+    fn norm_matches_finite_difference_of_sag() {
+        let coefficients: Vec<Float> = (0..37).map(|j| 1e-4 / (j + 1) as Float).collect();
+        for ordering in [ZernikeOrdering::Fringe, ZernikeOrdering::Standard] {
+            let z = zernike(40.0, ordering, &coefficients);
+            let h = 1e-6;
+            for &(x, y) in &[(0.0, 0.0), (1.0, 2.0), (-3.0, 4.0), (5.0, -6.0), (0.0, 9.5)] {
+                let dzdx =
+                    (z.sag(Vec3::new(x + h, y, 0.0)) - z.sag(Vec3::new(x - h, y, 0.0))) / (2.0 * h);
+                let dzdy =
+                    (z.sag(Vec3::new(x, y + h, 0.0)) - z.sag(Vec3::new(x, y - h, 0.0))) / (2.0 * h);
+                let norm = z.norm(Vec3::new(x, y, 0.0));
+                assert_abs_diff_eq!(norm.x(), -dzdx, epsilon = 1e-7);
+                assert_abs_diff_eq!(norm.y(), -dzdy, epsilon = 1e-7);
+                assert_abs_diff_eq!(norm.z(), 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn roc_is_base_radius() {
+        let z = zernike(77.3, ZernikeOrdering::Fringe, &[0.0, 0.0, 0.0, 0.0, 1e-3]);
+        assert_abs_diff_eq!(z.roc(0.0), 77.3);
+        assert_abs_diff_eq!(z.roc(1.0), 77.3);
+    }
+
+    #[test]
+    fn newton_raphson_converges_onto_surface() {
+        let z = zernike(
+            30.0,
+            ZernikeOrdering::Standard,
+            &[0.0, 1e-3, -2e-3, 5e-3, 1e-3, 0.0, 2e-4],
+        );
+        let ray = Ray::new(Vec3::new(2.0, 6.0, -5.0), Vec3::new(0.0, 0.0, 1.0));
+        let (p, _) = newton_raphson(&ray, &z, 100).unwrap();
+        assert_abs_diff_eq!(p.x(), 2.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.y(), 6.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(p.z(), z.sag(p), epsilon = 1e-10);
+    }
+}
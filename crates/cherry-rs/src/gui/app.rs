@@ -3,6 +3,10 @@ use std::sync::mpsc::{Receiver, Sender, channel};
 #[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
 use std::{collections::HashMap, rc::Rc};
 
+#[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+use crate::gui::compute::{GLASS_CATALOG_DIR, load_glass_catalogs, read_material_dir};
+#[cfg(feature = "ri-info")]
+use crate::gui::{compute::custom_material_from_file, model::CustomMaterial};
 use crate::gui::{
     compute::{ComputeRequest, compute_loop, spawn_compute_thread},
     examples,
@@ -10,7 +14,7 @@ use crate::gui::{
     result_package::ResultPackage,
     windows::{
         CrossSectionWindow, ParaxialWindow, RayFanWindow, SpecsWindow, SpotDiagramWindow,
-        SystemWindow, WindowVisibility,
+        SystemWindow, ThermalWindow, WindowVisibility,
     },
 };
 
@@ -20,14 +24,41 @@ use crate::gui::panels;
 use crate::gui::windows::MaterialsWindow;
 
 /// Serialized subset of app state, persisted across sessions.
+///
+/// The specs are persisted as JSON, as in a saved file, since the storage
+/// format cannot read back the flattened fields of the surface rows.
 #[derive(Default, serde::Serialize, serde::Deserialize)]
 #[serde(default)]
 struct AppState {
+    #[serde(with = "json_string")]
     specs: SystemSpecs,
     input_id: u64,
     windows: WindowVisibility,
 }
 
+/// Serializes a value as a string of JSON.
+mod json_string {
+    use serde::{
+        Deserialize, Deserializer, Serialize, Serializer, de::DeserializeOwned, de::Error as _,
+        ser::Error as _,
+    };
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let json = serde_json::to_string(value).map_err(S::Error::custom)?;
+        serializer.serialize_str(&json)
+    }
+
+    pub fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let json = String::deserialize(deserializer)?;
+        serde_json::from_str(&json).map_err(D::Error::custom)
+    }
+}
+
 pub struct CherryApp {
     // Persisted
     specs: SystemSpecs,
@@ -53,11 +84,17 @@ pub struct CherryApp {
     material_index: panels::MaterialIndex,
     #[cfg(feature = "ri-info")]
     material_browser: panels::MaterialBrowserState,
+    #[cfg(feature = "ri-info")]
+    materials_window: MaterialsWindow,
 
     // WASM: async-loaded pending material index (swapped in during update())
     #[cfg(all(feature = "ri-info", target_arch = "wasm32"))]
     pending_material_index: std::sync::Arc<std::sync::Mutex<Option<panels::MaterialIndex>>>,
 
+    // WASM: materials loaded asynchronously from the file open dialog
+    #[cfg(all(feature = "ri-info", target_arch = "wasm32"))]
+    pending_materials: std::sync::Arc<std::sync::Mutex<Option<Vec<CustomMaterial>>>>,
+
     // WASM: pending specs loaded asynchronously from file open dialog
     #[cfg(target_arch = "wasm32")]
     pending_specs: std::sync::Arc<std::sync::Mutex<Option<SystemSpecs>>>,
@@ -124,14 +161,24 @@ impl CherryApp {
             })
             .ok();
 
-        // Native: load materials synchronously from disk.
+        // Native: load materials and glass catalogs synchronously from disk.
         #[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
-        let material_index = match load_material_store() {
-            Ok(mats) => panels::MaterialIndex::build_from_keys(mats.keys()),
-            Err(e) => {
-                log::error!("Failed to load material database: {e}");
-                panels::MaterialIndex::default()
+        let material_index = {
+            let mut index = panels::MaterialIndex::default();
+            match load_material_store() {
+                Ok(mats) => {
+                    for (key, mat) in &mats {
+                        index.insert(key, panels::MaterialInfo::of_rii(mat));
+                    }
+                }
+                Err(e) => log::error!("Failed to load material database: {e}"),
+            }
+            let catalog_dir =
+                std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(GLASS_CATALOG_DIR);
+            for (key, glass) in &load_glass_catalogs(&catalog_dir) {
+                index.insert(key, panels::MaterialInfo::of(glass.as_ref()));
             }
+            index
         };
 
         // WASM: start empty; materials are fetched asynchronously below.
@@ -154,9 +201,8 @@ impl CherryApp {
             wasm_bindgen_futures::spawn_local(async move {
                 // Phase 1: fetch the small index JSON and populate the browser.
                 match fetch_bytes("assets/rii-index.json").await {
-                    Ok(bytes) => match serde_json::from_slice::<Vec<String>>(&bytes) {
-                        Ok(keys) => {
-                            let idx = panels::MaterialIndex::build_from_keys(keys.iter());
+                    Ok(bytes) => match panels::MaterialIndex::from_json(&bytes) {
+                        Ok(idx) => {
                             *pending_index.lock().unwrap() = Some(idx);
                         }
                         Err(e) => {
@@ -179,7 +225,8 @@ impl CherryApp {
             });
         }
 
-        Self {
+        #[cfg_attr(not(feature = "ri-info"), allow(unused_mut))]
+        let mut app = Self {
             specs: state.specs,
             input_id: initial_id,
             windows: state.windows,
@@ -195,11 +242,18 @@ impl CherryApp {
             material_index,
             #[cfg(feature = "ri-info")]
             material_browser: panels::MaterialBrowserState::default(),
+            #[cfg(feature = "ri-info")]
+            materials_window: MaterialsWindow::default(),
             #[cfg(all(feature = "ri-info", target_arch = "wasm32"))]
             pending_material_index,
+            #[cfg(all(feature = "ri-info", target_arch = "wasm32"))]
+            pending_materials: std::sync::Arc::new(std::sync::Mutex::new(None)),
             #[cfg(target_arch = "wasm32")]
             pending_specs,
-        }
+        };
+        #[cfg(feature = "ri-info")]
+        app.sync_material_index();
+        app
     }
 
     /// Increment the input id and dispatch a new compute request.
@@ -215,9 +269,97 @@ impl CherryApp {
 
     fn load_specs(&mut self, specs: SystemSpecs) {
         self.specs = specs;
+        #[cfg(feature = "ri-info")]
+        self.sync_material_index();
         self.bump_input_id();
     }
 
+    /// Show the materials loaded from files in the material browser.
+    #[cfg(feature = "ri-info")]
+    fn sync_material_index(&mut self) {
+        self.material_index
+            .set_custom_materials(&self.specs.custom_materials);
+    }
+
+    /// Add materials loaded from files to the specs and select them.
+    #[cfg(feature = "ri-info")]
+    fn add_custom_materials(&mut self, materials: Vec<CustomMaterial>) {
+        if materials.is_empty() {
+            return;
+        }
+        for material in materials {
+            if !self.specs.selected_materials.contains(&material.key) {
+                self.specs.selected_materials.push(material.key.clone());
+            }
+            self.specs.add_custom_material(material);
+        }
+        self.sync_material_index();
+        self.bump_input_id();
+    }
+
+    /// Load RefractiveIndex.info YAML files. They are filed in the `files`
+    /// book of the user shelf.
+    #[cfg(feature = "ri-info")]
+    fn load_material_files(&mut self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(paths) = rfd::FileDialog::new()
+                .set_title("Load Materials")
+                .add_filter("YAML", &["yml", "yaml"])
+                .pick_files()
+            else {
+                return;
+            };
+            let mut materials = Vec::new();
+            for path in paths {
+                let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+                match std::fs::read(&path)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|bytes| custom_material_from_file("files", file_name, &bytes))
+                {
+                    Ok(material) => materials.push(material),
+                    Err(e) => log::error!("Failed to load material: {e:#}"),
+                }
+            }
+            self.add_custom_materials(materials);
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let pending = std::sync::Arc::clone(&self.pending_materials);
+            wasm_bindgen_futures::spawn_local(async move {
+                let Some(handles) = rfd::AsyncFileDialog::new()
+                    .set_title("Load Materials")
+                    .add_filter("YAML", &["yml", "yaml"])
+                    .pick_files()
+                    .await
+                else {
+                    return;
+                };
+                let mut materials = Vec::new();
+                for handle in handles {
+                    let bytes = handle.read().await;
+                    match custom_material_from_file("files", &handle.file_name(), &bytes) {
+                        Ok(material) => materials.push(material),
+                        Err(e) => log::error!("Failed to load material: {e:#}"),
+                    }
+                }
+                *pending.lock().unwrap() = Some(materials);
+            });
+        }
+    }
+
+    /// Load every RefractiveIndex.info YAML file under a directory.
+    #[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+    fn load_material_dir(&mut self) {
+        if let Some(dir) = rfd::FileDialog::new()
+            .set_title("Load Material Folder")
+            .pick_folder()
+        {
+            self.add_custom_materials(read_material_dir(&dir));
+        }
+    }
+
     fn save_to_file(&self) {
         #[cfg(not(target_arch = "wasm32"))]
         {
@@ -366,6 +508,7 @@ impl CherryApp {
         ui.toggle_value(&mut self.windows.spot_diagram, "Spot Diagram");
         ui.toggle_value(&mut self.windows.cross_section, "Cross Section");
         ui.toggle_value(&mut self.windows.ray_fan, "Ray Fan Plot");
+        ui.toggle_value(&mut self.windows.thermal, "Thermal Analysis");
     }
 }
 
@@ -383,6 +526,7 @@ impl eframe::App for CherryApp {
                 ray_fan: self.windows.ray_fan,
                 system: self.windows.system,
                 lens_overlay: self.windows.lens_overlay,
+                thermal: self.windows.thermal,
             },
         };
         eframe::set_value(storage, eframe::APP_KEY, &state);
@@ -399,6 +543,16 @@ impl eframe::App for CherryApp {
                 .and_then(|mut g| g.take());
             if let Some(idx) = maybe_idx {
                 self.material_index = idx;
+                self.sync_material_index();
+            }
+
+            let maybe_materials = self
+                .pending_materials
+                .try_lock()
+                .ok()
+                .and_then(|mut g| g.take());
+            if let Some(materials) = maybe_materials {
+                self.add_custom_materials(materials);
             }
         }
 
@@ -442,6 +596,19 @@ impl eframe::App for CherryApp {
                         ui.close();
                         self.save_to_file();
                     }
+                    #[cfg(feature = "ri-info")]
+                    {
+                        ui.separator();
+                        if ui.button("Load Materials\u{2026}").clicked() {
+                            ui.close();
+                            self.load_material_files();
+                        }
+                        #[cfg(not(target_arch = "wasm32"))]
+                        if ui.button("Load Material Folder\u{2026}").clicked() {
+                            ui.close();
+                            self.load_material_dir();
+                        }
+                    }
                     ui.separator();
                     let can_export_svg = self
                         .latest_result
@@ -568,14 +735,16 @@ impl eframe::App for CherryApp {
 
         #[cfg(feature = "ri-info")]
         if self.windows.materials {
-            let changed = MaterialsWindow::show(
+            let changed = self.materials_window.show(
                 ctx,
                 &mut self.windows.materials,
                 &mut self.specs,
                 &self.material_index,
                 &mut self.material_browser,
+                self.latest_result.as_ref(),
             );
             if changed {
+                self.sync_material_index();
                 self.bump_input_id();
             }
         }
@@ -620,6 +789,18 @@ impl eframe::App for CherryApp {
                 .show(ctx, &mut self.windows.ray_fan, self.latest_result.as_ref());
         }
 
+        if self.windows.thermal {
+            let changed = ThermalWindow::show(
+                ctx,
+                &mut self.windows.thermal,
+                &mut self.specs,
+                self.latest_result.as_ref(),
+            );
+            if changed {
+                self.bump_input_id();
+            }
+        }
+
         {
             let changed = self.lens_overlay_panel.show(
                 ctx,
@@ -3,20 +3,32 @@ use std::sync::mpsc::{Receiver, Sender};
 #[cfg(feature = "ri-info")]
 use std::{collections::HashMap, rc::Rc};
 
+#[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+use crate::GlassCatalog;
+#[cfg(feature = "ri-info")]
+use crate::RefractiveIndexSpec;
+
 use crate::{
     ParaxialView, SequentialModel, SequentialModelBuilder, components_view, cross_section_view,
-    ray_trace_3d_view,
+    dn_dwavelength, ray_trace_3d_view,
     specs::{fields::PupilSampling, gaps::GapSpec, surfaces::SurfaceSpec},
-    trace_ray_bundle,
+    thermal_view, trace_ray_bundle,
     views::ray_trace_3d::SamplingConfig,
 };
 
+#[cfg(feature = "ri-info")]
+use super::model::{CUSTOM_MATERIAL_SHELF, CustomMaterial};
 use super::{
     convert,
     model::{SolveSpec, SystemSpecs},
-    result_package::{ResultPackage, SolvedValues, SurfaceDesc},
+    result_package::{DispersionCurve, ResultPackage, SolvedValues, SurfaceDesc},
 };
 
+/// Number of wavelengths at which each dispersion curve is sampled.
+const DISPERSION_SAMPLES: usize = 101;
+/// Narrowest wavelength span of the dispersion curves, in µm.
+const MIN_DISPERSION_SPAN: f64 = 0.1;
+
 pub struct ComputeRequest {
     pub id: u64,
     pub specs: SystemSpecs,
@@ -30,9 +42,14 @@ pub fn spawn_compute_thread<F: FnOnce() + Send + 'static>(f: F) {
     wasm_thread::spawn(f);
 }
 
+/// Directory, relative to the crate root, searched for `.agf` and `.seq`
+/// glass catalogs.
+#[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+pub(crate) const GLASS_CATALOG_DIR: &str = "data/catalogs";
+
 /// Deserialize raw bytes into a material map.
 #[cfg(feature = "ri-info")]
-fn deserialize_materials(data: &[u8]) -> HashMap<String, Rc<lib_ria::Material>> {
+fn deserialize_materials(data: &[u8]) -> convert::MaterialsMap {
     let mut store: lib_ria::Store = match bitcode::deserialize(data) {
         Ok(s) => s,
         Err(e) => {
@@ -44,24 +61,154 @@ fn deserialize_materials(data: &[u8]) -> HashMap<String, Rc<lib_ria::Material>>
     let mut materials = HashMap::with_capacity(keys.len());
     for key in keys {
         if let Some(mat) = store.remove(&key) {
-            materials.insert(key, Rc::new(mat));
+            materials.insert(key, Rc::new(mat) as Rc<dyn RefractiveIndexSpec>);
+        }
+    }
+    materials
+}
+
+/// Load every Zemax `.agf` and CODE V `.seq` glass catalog in a directory.
+/// Each glass is keyed `catalog:glass`, where `catalog` is the file name
+/// without its extension. Catalogs that cannot be parsed are logged and
+/// skipped.
+#[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+pub(crate) fn load_glass_catalogs(dir: &std::path::Path) -> convert::MaterialsMap {
+    let mut materials: convert::MaterialsMap = HashMap::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return materials;
+    };
+    let mut paths: Vec<_> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| catalog_reader(ext).is_some())
+        })
+        .collect();
+    paths.sort();
+    for path in paths {
+        let Some(catalog) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let Some(read) = path.extension().and_then(catalog_reader) else {
+            continue;
+        };
+        match read(&path) {
+            Ok(glasses) => {
+                for glass in glasses.glasses {
+                    let key = format!("{catalog}:{}", glass.name);
+                    materials.insert(key, Rc::new(glass));
+                }
+            }
+            Err(e) => log::error!("Cannot load glass catalog: {e:#}"),
+        }
+    }
+    materials
+}
+
+/// Returns the reader of the glass catalogs with a file extension, if any.
+#[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+fn catalog_reader(
+    extension: &std::ffi::OsStr,
+) -> Option<fn(&std::path::Path) -> anyhow::Result<GlassCatalog>> {
+    if extension.eq_ignore_ascii_case("agf") {
+        Some(|path| GlassCatalog::read_agf(path))
+    } else if extension.eq_ignore_ascii_case("seq") {
+        Some(|path| GlassCatalog::read_codev(path))
+    } else {
+        None
+    }
+}
+
+/// Read a RefractiveIndex.info YAML file into a material filed in `book`,
+/// with the file name without its extension as its page. Fails if the file
+/// cannot be parsed.
+#[cfg(feature = "ri-info")]
+pub(crate) fn custom_material_from_file(
+    book: &str,
+    file_name: &str,
+    contents: &[u8],
+) -> anyhow::Result<CustomMaterial> {
+    let page = std::path::Path::new(file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_name);
+    let yaml = std::str::from_utf8(contents)
+        .map_err(|e| anyhow::anyhow!("{file_name} is not UTF-8: {e}"))?;
+    let material = CustomMaterial::new(book, page, yaml.to_string());
+    material
+        .parse()
+        .map_err(|e| anyhow::anyhow!("Cannot load {file_name}: {e}"))?;
+    Ok(material)
+}
+
+/// Read every `.yml` or `.yaml` file under a directory. Each material is
+/// filed in a book named after the directory that holds it. Files that cannot
+/// be parsed are logged and skipped.
+#[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+pub(crate) fn read_material_dir(dir: &std::path::Path) -> Vec<CustomMaterial> {
+    let mut materials = Vec::new();
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return materials;
+    };
+    let mut paths: Vec<_> = entries.filter_map(|e| e.ok().map(|e| e.path())).collect();
+    paths.sort();
+    let book = dir.file_name().and_then(|s| s.to_str()).unwrap_or("files");
+    for path in paths {
+        if path.is_dir() {
+            materials.extend(read_material_dir(&path));
+            continue;
+        }
+        let is_yaml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("yml") || ext.eq_ignore_ascii_case("yaml"));
+        let Some(file_name) = path.file_name().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        if !is_yaml {
+            continue;
+        }
+        match std::fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| custom_material_from_file(book, file_name, &bytes))
+        {
+            Ok(material) => materials.push(material),
+            Err(e) => log::error!("Cannot load material file {}: {e:#}", path.display()),
         }
     }
     materials
 }
 
-/// Load the material store from disk. Returns an empty map on failure.
+/// Replace the user-supplied materials in the map by those of the specs, so
+/// that materials removed from the specs can no longer be resolved.
+#[cfg(feature = "ri-info")]
+fn merge_custom_materials(
+    materials: &mut convert::MaterialsMap,
+    custom: &[CustomMaterial],
+) -> anyhow::Result<()> {
+    let prefix = format!("{CUSTOM_MATERIAL_SHELF}:");
+    materials.retain(|key, _| !key.starts_with(&prefix));
+    for material in custom {
+        let parsed = material.parse()?;
+        materials.insert(material.key.clone(), Rc::new(parsed));
+    }
+    Ok(())
+}
+
+/// Load the material store and the glass catalogs from disk. Returns an empty
+/// map on failure.
 #[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
-fn load_materials() -> HashMap<String, Rc<lib_ria::Material>> {
-    let filename = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("data/rii.db");
-    let data = match std::fs::read(&filename) {
-        Ok(d) => d,
+fn load_materials() -> convert::MaterialsMap {
+    let root = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    let filename = root.join("data/rii.db");
+    let mut materials = match std::fs::read(&filename) {
+        Ok(data) => deserialize_materials(&data),
         Err(e) => {
             log::error!("Compute thread: cannot read {}: {e}", filename.display());
-            return HashMap::new();
+            HashMap::new()
         }
     };
-    deserialize_materials(&data)
+    materials.extend(load_glass_catalogs(&root.join(GLASS_CATALOG_DIR)));
+    materials
 }
 
 /// Background compute loop. Drains the channel and processes only the latest
@@ -74,12 +221,12 @@ pub fn compute_loop(
 ) {
     // Native: load materials from disk at startup.
     #[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
-    let materials = load_materials();
+    let mut materials = load_materials();
 
     // WASM: block until the main thread sends the fetched database bytes, then
     // deserialize. The coordinator runs in a Web Worker so blocking is safe.
     #[cfg(all(feature = "ri-info", target_arch = "wasm32"))]
-    let materials = match materials_rx.recv() {
+    let mut materials = match materials_rx.recv() {
         Ok(bytes) => deserialize_materials(&bytes),
         Err(_) => HashMap::new(),
     };
@@ -100,7 +247,7 @@ pub fn compute_loop(
         let result = run_compute(
             latest,
             #[cfg(feature = "ri-info")]
-            &materials,
+            &mut materials,
         );
 
         if tx.send(result).is_err() {
@@ -111,8 +258,13 @@ pub fn compute_loop(
 
 fn run_compute(
     req: ComputeRequest,
-    #[cfg(feature = "ri-info")] materials: &HashMap<String, Rc<lib_ria::Material>>,
+    #[cfg(feature = "ri-info")] materials: &mut convert::MaterialsMap,
 ) -> ResultPackage {
+    #[cfg(feature = "ri-info")]
+    if let Err(e) = merge_custom_materials(materials, &req.specs.custom_materials) {
+        return ResultPackage::error(req.id, format!("Specs error: {e}"));
+    }
+
     #[cfg(feature = "ri-info")]
     let parsed = convert::convert_specs(&req.specs, materials);
     #[cfg(not(feature = "ri-info"))]
@@ -144,6 +296,27 @@ fn run_compute(
         &build_result.surface_specs,
     );
 
+    let (thermal, thermal_error) = match thermal_view(
+        &build_result.gap_specs,
+        &build_result.surface_specs,
+        &parsed.wavelengths,
+        req.specs.stop_surface,
+        &parsed.fields,
+        &convert::thermal_spec(&req.specs.thermal),
+    ) {
+        Ok(t) => (Some(t), None),
+        Err(e) => (None, Some(format!("{e:#}"))),
+    };
+
+    let dispersion = dispersion_curves(
+        convert::dispersion_materials(
+            &req.specs,
+            #[cfg(feature = "ri-info")]
+            materials,
+        ),
+        &parsed.wavelengths,
+    );
+
     let seq = build_result.model;
 
     let wavelengths = seq.wavelengths().to_vec();
@@ -165,6 +338,9 @@ fn run_compute(
                 error: Some(format!("Paraxial error: {e}")),
                 solved_values,
                 components: Vec::new(),
+                thermal,
+                thermal_error,
+                dispersion,
             };
         }
     };
@@ -217,6 +393,9 @@ fn run_compute(
         error: None,
         solved_values,
         components,
+        thermal,
+        thermal_error,
+        dispersion,
     }
 }
 
@@ -244,7 +423,7 @@ fn extract_solved_values(
 }
 
 fn roc_from_spec(spec: Option<&SurfaceSpec>) -> Option<f64> {
-    match spec? {
+    match spec?.unwrapped() {
         SurfaceSpec::Conic {
             radius_of_curvature,
             ..
@@ -253,10 +432,77 @@ fn roc_from_spec(spec: Option<&SurfaceSpec>) -> Option<f64> {
             radius_of_curvature,
             ..
         } => Some(*radius_of_curvature),
+        SurfaceSpec::EvenAsphere {
+            radius_of_curvature,
+            ..
+        } => Some(*radius_of_curvature),
+        SurfaceSpec::ForbesAsphere {
+            radius_of_curvature,
+            ..
+        } => Some(*radius_of_curvature),
+        SurfaceSpec::Fresnel {
+            radius_of_curvature,
+            ..
+        } => Some(*radius_of_curvature),
+        SurfaceSpec::XYPolynomial {
+            radius_of_curvature,
+            ..
+        } => Some(*radius_of_curvature),
+        SurfaceSpec::ZernikeSag {
+            radius_of_curvature,
+            ..
+        } => Some(*radius_of_curvature),
+        SurfaceSpec::GridSag {
+            radius_of_curvature,
+            ..
+        } => Some(*radius_of_curvature),
+        SurfaceSpec::LensletArray {
+            radius_of_curvature,
+            ..
+        } => Some(*radius_of_curvature),
         _ => None,
     }
 }
 
+/// Sample n and dn/dλ of each material across the span of the system's
+/// wavelengths, widened to at least `MIN_DISPERSION_SPAN`. Samples at which a
+/// material is not defined are left out.
+fn dispersion_curves(
+    materials: Vec<convert::DispersionMaterial>,
+    wavelengths: &[f64],
+) -> Vec<DispersionCurve> {
+    let min = wavelengths.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = wavelengths
+        .iter()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    if !(min.is_finite() && max.is_finite()) {
+        return Vec::new();
+    }
+    let pad = ((MIN_DISPERSION_SPAN - (max - min)) / 2.0).max(0.0);
+    let (min, max) = ((min - pad).max(min / 2.0), max + pad);
+    let step = (max - min) / (DISPERSION_SAMPLES - 1) as f64;
+
+    materials
+        .into_iter()
+        .map(|m| {
+            let samples = (0..DISPERSION_SAMPLES)
+                .map(|i| min + i as f64 * step)
+                .filter_map(|wl| {
+                    let n = m.material.n(wl).ok()?;
+                    let dn = dn_dwavelength(m.material.as_ref(), wl).ok()?;
+                    Some([wl, n, dn])
+                })
+                .collect();
+            DispersionCurve {
+                label: m.label,
+                in_design: m.in_design,
+                samples,
+            }
+        })
+        .collect()
+}
+
 fn build_surface_descs(seq: &SequentialModel) -> Vec<SurfaceDesc> {
     use crate::SurfaceKind;
     seq.surfaces()
@@ -265,12 +511,26 @@ fn build_surface_descs(seq: &SequentialModel) -> Vec<SurfaceDesc> {
         .enumerate()
         .map(|(i, (s, p))| {
             let name = match s.surface_kind() {
+                SurfaceKind::Axicon => "Axicon",
+                SurfaceKind::Biconic => "Biconic",
+                SurfaceKind::CardinalLens => "Cardinal Lens",
                 SurfaceKind::Conic => "Conic",
+                SurfaceKind::Diffractive => "Diffractive",
+                SurfaceKind::EvenAsphere => "Even Asphere",
+                SurfaceKind::ForbesAsphere => "Forbes Asphere",
+                SurfaceKind::Fresnel => "Fresnel",
+                SurfaceKind::Grating => "Grating",
+                SurfaceKind::GridSag => "Grid Sag",
                 SurfaceKind::Image => "Image",
                 SurfaceKind::Object => "Object",
+                SurfaceKind::ParaxialLens => "Paraxial Lens",
                 SurfaceKind::Probe => "Probe",
                 SurfaceKind::Iris => "Iris",
+                SurfaceKind::LensletArray => "Lenslet Array",
                 SurfaceKind::Sphere => "Sphere",
+                SurfaceKind::Toroid => "Toroid",
+                SurfaceKind::XYPolynomial => "XY Polynomial",
+                SurfaceKind::ZernikeSag => "Zernike Sag",
                 SurfaceKind::Custom => "Custom",
             };
             SurfaceDesc {
@@ -343,6 +603,102 @@ mod tests {
         }
     }
 
+    #[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+    #[test]
+    fn glass_catalogs_are_keyed_by_file_name() {
+        let dir = std::env::temp_dir().join(format!("cherry-catalogs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let glass = |name: &str| format!("NM {name} 2 0 1.5 60\nCD 1 0.01\nLD 0.4 0.7\n");
+        std::fs::write(dir.join("VENDOR.agf"), glass("G-1") + &glass("G-2")).unwrap();
+        std::fs::write(dir.join("team.AGF"), glass("G-1")).unwrap();
+        std::fs::write(dir.join("broken.agf"), "NM G-3 99 0 1.5 60\nLD 0.4 0.7\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), glass("G-4")).unwrap();
+        let private = "PRV\nPWL 486.1 656.3\n'G-5' 1.52 1.51\nEND\n";
+        std::fs::write(dir.join("lab.seq"), private).unwrap();
+
+        let materials = load_glass_catalogs(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let mut keys: Vec<&str> = materials.keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec!["VENDOR:G-1", "VENDOR:G-2", "lab:G-5", "team:G-1"]
+        );
+        assert!(materials["team:G-1"].n(0.5).is_ok());
+    }
+
+    #[cfg(all(feature = "ri-info", not(target_arch = "wasm32")))]
+    #[test]
+    fn material_files_are_read_from_nested_directories() {
+        let dir = std::env::temp_dir().join(format!("cherry-rii-{}", std::process::id()));
+        let oils = dir.join("oils");
+        std::fs::create_dir_all(&oils).unwrap();
+        let page = "DATA:\n  - type: tabulated n\n    data: |\n      0.4 1.52\n      0.7 1.51\n";
+        std::fs::write(dir.join("coating.yml"), page).unwrap();
+        std::fs::write(oils.join("type-A.YAML"), page).unwrap();
+        std::fs::write(oils.join("broken.yml"), "COMMENTS: no data\n").unwrap();
+        std::fs::write(oils.join("notes.txt"), page).unwrap();
+
+        let custom = read_material_dir(&dir);
+        let book = dir.file_name().unwrap().to_str().unwrap().to_string();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let keys: Vec<&str> = custom.iter().map(|m| m.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec![format!("user:{book}:coating").as_str(), "user:oils:type-A"]
+        );
+    }
+
+    #[cfg(feature = "ri-info")]
+    #[test]
+    fn custom_materials_replace_previous_ones_in_the_map() {
+        let page = |n: f64| {
+            format!(
+                "DATA:\n  - type: formula 5\n    wavelength_range: 0.4 0.7\n    coefficients: {n}\n"
+            )
+        };
+        let mut materials: convert::MaterialsMap = HashMap::new();
+        let a = custom_material_from_file("lab", "a.yml", page(1.4).as_bytes()).unwrap();
+        let b = custom_material_from_file("lab", "b.yml", page(1.6).as_bytes()).unwrap();
+
+        merge_custom_materials(&mut materials, &[a, b.clone()]).unwrap();
+        assert_eq!(materials.len(), 2);
+        merge_custom_materials(&mut materials, &[b]).unwrap();
+        assert_eq!(materials.len(), 1);
+        assert_eq!(materials["user:lab:b"].n(0.5).unwrap(), 1.6);
+
+        assert!(custom_material_from_file("lab", "c.yml", b"DATA:\n").is_err());
+    }
+
+    #[test]
+    fn dispersion_curves_span_the_system_wavelengths() {
+        use crate::gui::model::ModelGlassRow;
+
+        let mut specs = SystemSpecs::default();
+        specs.surfaces[1].model_glass = Some(ModelGlassRow::new("1.6"));
+        specs.surfaces[2].model_glass = Some(ModelGlassRow::new("1.6"));
+        let materials = convert::dispersion_materials(
+            &specs,
+            #[cfg(feature = "ri-info")]
+            &HashMap::new(),
+        );
+        assert_eq!(materials.len(), 1);
+
+        let curves = dispersion_curves(materials, &[0.55]);
+        let samples = &curves[0].samples;
+        assert_eq!(curves[0].label, "Model glass 1.6 / 50");
+        assert!(curves[0].in_design);
+        assert_eq!(samples.len(), DISPERSION_SAMPLES);
+        approx::assert_abs_diff_eq!(samples[0][0], 0.5, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(samples[DISPERSION_SAMPLES - 1][0], 0.6, epsilon = 1e-12);
+        // Normal dispersion: the index falls with the wavelength.
+        assert!(samples.iter().all(|s| s[2] < 0.0));
+
+        assert!(dispersion_curves(Vec::new(), &[]).is_empty());
+    }
+
     #[test]
     fn field_descs_angle_mode() {
         use crate::FieldSpec;
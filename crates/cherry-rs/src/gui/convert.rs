@@ -1,16 +1,22 @@
-use std::rc::Rc;
+use std::{collections::HashMap, rc::Rc};
 
 use anyhow::{Context, Result, bail};
 
 use crate::{
-    ApertureSpec, BoundaryKind, ConstantRefractiveIndex, EulerAngles, FNumberSolve, FieldSpec,
-    GapSpec, MarginalRaySolve, RefractiveIndexSpec, Rotation3D, SequentialModel, Solve,
-    SurfaceSpec, Vec3,
-    core::math::linalg::mat3x3::Mat3x3,
+    ApertureSpec, BoundaryKind, EulerAngles, FNumberSolve, FieldSpec, GapRefSpec, GapSpec,
+    MarginalRaySolve, Mask, MaterialRef, MaterialResolver, ModelGlass, RefractiveIndexSpec,
+    Rotation3D, SequentialModel, Solve, Spider, SurfaceSpec, ThermalSpec, Vec3,
+    core::{
+        math::linalg::mat3x3::Mat3x3,
+        surfaces::xy_polynomial::{MAX_XY_ORDER, xy_term_count},
+    },
     views::components::{Component, components_view},
 };
 
-use super::model::{FieldMode, SolveSpec, SurfaceKind, SurfaceVariant, SystemSpecs};
+use super::model::{
+    ApertureShape, FieldMode, SolveSpec, SurfaceKind, SurfaceRow, SurfaceVariant, SystemSpecs,
+    ThermalSettings,
+};
 
 /// Parsed core specs ready for model construction.
 pub struct ParsedSpecs {
@@ -36,9 +42,145 @@ fn parse_float(s: &str) -> Result<f64> {
     }
 }
 
-/// Materials map type used when the ri-info feature is enabled.
+/// Parse a row's clear aperture into a mask.
+///
+/// Returns `None` for a circle centered on the vertex without obscuration,
+/// which the surface's semi-diameter already describes.
+fn parse_mask(i: usize, row: &SurfaceRow) -> Result<Option<Mask>> {
+    let aperture = &row.aperture;
+    let parse_or_zero = |s: &str, name: &str| -> Result<f64> {
+        if s.trim().is_empty() {
+            Ok(0.0)
+        } else {
+            parse_float(s).with_context(|| format!("surface {i}: aperture {name}"))
+        }
+    };
+    let offset_x = parse_or_zero(&aperture.offset_x, "offset x")?;
+    let offset_y = parse_or_zero(&aperture.offset_y, "offset y")?;
+    let obscuration = parse_or_zero(&aperture.obscuration, "obscuration")?;
+    let vanes = match aperture.spider_vanes.trim() {
+        "" => 0,
+        s => s
+            .parse::<usize>()
+            .with_context(|| format!("surface {i}: spider vanes"))?,
+    };
+
+    let outline = match aperture.shape {
+        ApertureShape::Circular => {
+            if offset_x == 0.0 && offset_y == 0.0 && obscuration == 0.0 && vanes == 0 {
+                return Ok(None);
+            }
+            Mask::Circular {
+                semi_diameter: parse_float(&row.semi_diameter)
+                    .with_context(|| format!("surface {i}: semi-diameter"))?,
+            }
+        }
+        ApertureShape::Rectangular => Mask::Rectangular {
+            half_width_x: parse_float(&aperture.size_x)
+                .with_context(|| format!("surface {i}: aperture half-width x"))?,
+            half_width_y: parse_float(&aperture.size_y)
+                .with_context(|| format!("surface {i}: aperture half-width y"))?,
+        },
+        ApertureShape::Elliptical => Mask::Elliptical {
+            semi_axis_x: parse_float(&aperture.size_x)
+                .with_context(|| format!("surface {i}: aperture semi-axis x"))?,
+            semi_axis_y: parse_float(&aperture.size_y)
+                .with_context(|| format!("surface {i}: aperture semi-axis y"))?,
+        },
+        ApertureShape::Polygon => Mask::Polygon {
+            vertices: parse_vertices(&aperture.vertices)
+                .with_context(|| format!("surface {i}: aperture vertices"))?,
+        },
+    };
+
+    let mut mask = outline;
+    if obscuration != 0.0 || vanes > 0 {
+        let spider = if vanes > 0 {
+            Some(Spider {
+                vanes,
+                width: parse_float(&aperture.spider_width)
+                    .with_context(|| format!("surface {i}: spider vane width"))?,
+                rotation: 0.0,
+            })
+        } else {
+            None
+        };
+        mask = Mask::Obscured {
+            aperture: Box::new(mask),
+            obscuration: (obscuration != 0.0).then(|| {
+                Box::new(Mask::Circular {
+                    semi_diameter: obscuration,
+                })
+            }),
+            spider,
+        };
+    }
+    if offset_x != 0.0 || offset_y != 0.0 {
+        mask = Mask::Decentered {
+            offset_x,
+            offset_y,
+            mask: Box::new(mask),
+        };
+    }
+    mask.validate()
+        .with_context(|| format!("surface {i}: aperture"))?;
+    Ok(Some(mask))
+}
+
+/// Parse a row's mechanical semi-diameter and flat width.
+///
+/// Returns `None` if the row has neither, in which case the surface ends at
+/// its clear aperture.
+fn parse_mechanical_edge(i: usize, row: &SurfaceRow) -> Result<Option<(f64, f64)>> {
+    let parse_or_zero = |s: &str, name: &str| -> Result<f64> {
+        if s.trim().is_empty() {
+            Ok(0.0)
+        } else {
+            parse_float(s).with_context(|| format!("surface {i}: {name}"))
+        }
+    };
+    let mechanical = parse_or_zero(
+        &row.aperture.mechanical_semi_diameter,
+        "mechanical semi-diameter",
+    )?;
+    let flat_width = parse_or_zero(&row.aperture.flat_width, "flat width")?;
+    if mechanical == 0.0 && flat_width == 0.0 {
+        return Ok(None);
+    }
+    // A flat without a mechanical semi-diameter is cut into the clear
+    // aperture's edge.
+    let mechanical = if mechanical == 0.0 {
+        parse_float(&row.semi_diameter).with_context(|| format!("surface {i}: semi-diameter"))?
+    } else {
+        mechanical
+    };
+    Ok(Some((mechanical, flat_width)))
+}
+
+/// Parse polygon vertices written as `x y` pairs separated by semicolons or
+/// new lines.
+fn parse_vertices(s: &str) -> Result<Vec<[f64; 2]>> {
+    s.split([';', '\n'])
+        .map(str::trim)
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let coords: Vec<&str> = pair
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|c| !c.is_empty())
+                .collect();
+            let [x, y] = coords[..] else {
+                bail!("expected an 'x y' pair, got '{pair}'");
+            };
+            Ok([parse_float(x)?, parse_float(y)?])
+        })
+        .collect()
+}
+
+/// Materials map type used when the ri-info feature is enabled. Keys are
+/// `shelf:book:page` for refractiveindex.info materials and `catalog:glass`
+/// for glasses from AGF and CODE V catalogs.
 #[cfg(feature = "ri-info")]
-pub type MaterialsMap = std::collections::HashMap<String, Rc<lib_ria::Material>>;
+pub type MaterialsMap = std::collections::HashMap<String, Rc<dyn RefractiveIndexSpec>>;
 
 /// Convert GUI `SystemSpecs` into core library specs.
 ///
@@ -54,6 +196,76 @@ pub fn convert_specs(specs: &SystemSpecs) -> Result<ParsedSpecs> {
     convert_specs_inner(specs)
 }
 
+/// Convert the GUI thermal analysis inputs into a core `ThermalSpec`.
+pub fn thermal_spec(settings: &ThermalSettings) -> ThermalSpec {
+    ThermalSpec {
+        temperatures: settings.temperatures(),
+        reference_temperature: settings.reference_temperature,
+        pressure: settings.pressure_kpa * 1e3,
+        mount_expansion: settings.mount_expansion_ppm * 1e-6,
+    }
+}
+
+/// A material to compare in the dispersion plot.
+pub struct DispersionMaterial {
+    pub label: String,
+    /// Whether a surface or the background of the design is made of it.
+    pub in_design: bool,
+    pub material: Rc<dyn RefractiveIndexSpec>,
+}
+
+/// Collect the materials of the dispersion plot: the selected materials, then
+/// the distinct model glasses of the surfaces. Materials that cannot be
+/// resolved are skipped.
+pub fn dispersion_materials(
+    specs: &SystemSpecs,
+    #[cfg(feature = "ri-info")] materials: &MaterialsMap,
+) -> Vec<DispersionMaterial> {
+    let mut result: Vec<DispersionMaterial> = Vec::new();
+
+    #[cfg(feature = "ri-info")]
+    if specs.use_materials {
+        let used = |key: &str| {
+            specs.background_material_key.as_deref() == Some(key)
+                || specs.surfaces.iter().any(|row| {
+                    row.model_glass.is_none() && row.material_key.as_deref() == Some(key)
+                })
+        };
+        for key in &specs.selected_materials {
+            if let Some(material) = materials.get(key) {
+                result.push(DispersionMaterial {
+                    label: key.clone(),
+                    in_design: used(key),
+                    material: Rc::clone(material),
+                });
+            }
+        }
+    }
+
+    for (i, row) in specs.surfaces.iter().enumerate() {
+        if row.model_glass.is_none() {
+            continue;
+        }
+        let Ok(MaterialRef::ModelGlass(glass)) = surface_material(i, row, false) else {
+            continue;
+        };
+        let (nd, vd, delta_pgf) = (glass.nd(), glass.vd(), glass.delta_pgf());
+        let label = if delta_pgf == 0.0 {
+            format!("Model glass {nd} / {vd}")
+        } else {
+            format!("Model glass {nd} / {vd} / {delta_pgf:+}")
+        };
+        if !result.iter().any(|m| m.label == label) {
+            result.push(DispersionMaterial {
+                label,
+                in_design: true,
+                material: Rc::new(glass),
+            });
+        }
+    }
+    result
+}
+
 fn convert_specs_inner(
     specs: &SystemSpecs,
     #[cfg(feature = "ri-info")] materials: Option<&MaterialsMap>,
@@ -70,7 +282,7 @@ fn convert_specs_inner(
     for (i, row) in specs.surfaces.iter().enumerate() {
         let surface = match row.variant {
             SurfaceVariant::Object => SurfaceSpec::Object,
-            SurfaceVariant::Conic => {
+            SurfaceVariant::Conic | SurfaceVariant::EvenAsphere | SurfaceVariant::XYPolynomial => {
                 let semi_diameter = parse_float(&row.semi_diameter)
                     .with_context(|| format!("surface {i}: semi-diameter"))?;
                 let roc = parse_float(&row.radius_of_curvature)
@@ -98,17 +310,68 @@ fn convert_specs_inner(
                 } else {
                     Rotation3D::None
                 };
-                SurfaceSpec::Conic {
-                    semi_diameter,
-                    radius_of_curvature: roc,
-                    conic_constant: conic,
-                    surf_kind,
-                    rotation,
-                    decenter: Vec3::new(0.0, 0.0, 0.0),
-                    rotation_offset: Rotation3D::None,
+                match row.variant {
+                    SurfaceVariant::EvenAsphere => {
+                        let aspheric_coefficients = row
+                            .aspheric_coefficients
+                            .iter()
+                            .enumerate()
+                            .map(|(j, a)| {
+                                parse_float(a)
+                                    .with_context(|| format!("surface {i}: A{}", 2 * j + 4))
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        SurfaceSpec::EvenAsphere {
+                            semi_diameter,
+                            radius_of_curvature: roc,
+                            conic_constant: conic,
+                            aspheric_coefficients,
+                            surf_kind,
+                            rotation,
+                            decenter: Vec3::new(0.0, 0.0, 0.0),
+                            rotation_offset: Rotation3D::None,
+                        }
+                    }
+                    SurfaceVariant::XYPolynomial => {
+                        let normalization_radius = parse_float(&row.normalization_radius)
+                            .with_context(|| format!("surface {i}: normalization radius"))?;
+                        let xy_coefficients = row
+                            .xy_coefficients
+                            .iter()
+                            .enumerate()
+                            .map(|(k, c)| {
+                                parse_float(c)
+                                    .with_context(|| format!("surface {i}: XY term {}", k + 1))
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        let order = (0..=MAX_XY_ORDER)
+                            .find(|&n| xy_term_count(n) >= xy_coefficients.len())
+                            .unwrap_or(MAX_XY_ORDER);
+                        SurfaceSpec::XYPolynomial {
+                            semi_diameter,
+                            radius_of_curvature: roc,
+                            conic_constant: conic,
+                            normalization_radius,
+                            order,
+                            xy_coefficients,
+                            surf_kind,
+                            rotation,
+                            decenter: Vec3::new(0.0, 0.0, 0.0),
+                            rotation_offset: Rotation3D::None,
+                        }
+                    }
+                    _ => SurfaceSpec::Conic {
+                        semi_diameter,
+                        radius_of_curvature: roc,
+                        conic_constant: conic,
+                        surf_kind,
+                        rotation,
+                        decenter: Vec3::new(0.0, 0.0, 0.0),
+                        rotation_offset: Rotation3D::None,
+                    },
                 }
             }
-            SurfaceVariant::Sphere => {
+            SurfaceVariant::Sphere | SurfaceVariant::Grating => {
                 let semi_diameter = parse_float(&row.semi_diameter)
                     .with_context(|| format!("surface {i}: semi-diameter"))?;
                 let roc = parse_float(&row.radius_of_curvature)
@@ -134,11 +397,50 @@ fn convert_specs_inner(
                 } else {
                     Rotation3D::None
                 };
-                SurfaceSpec::Sphere {
+                if row.variant == SurfaceVariant::Grating {
+                    let line_density = parse_float(&row.line_density)
+                        .with_context(|| format!("surface {i}: line density"))?;
+                    let diffraction_order = row
+                        .diffraction_order
+                        .trim()
+                        .parse::<i32>()
+                        .with_context(|| format!("surface {i}: diffraction order"))?;
+                    let line_orientation_deg = parse_float(&row.line_orientation)
+                        .with_context(|| format!("surface {i}: line orientation"))?;
+                    SurfaceSpec::Grating {
+                        semi_diameter,
+                        radius_of_curvature: roc,
+                        line_density,
+                        line_orientation: line_orientation_deg.to_radians(),
+                        diffraction_order,
+                        surf_kind,
+                        rotation,
+                        decenter: Vec3::new(0.0, 0.0, 0.0),
+                        rotation_offset: Rotation3D::None,
+                    }
+                } else {
+                    SurfaceSpec::Sphere {
+                        semi_diameter,
+                        radius_of_curvature: roc,
+                        surf_kind,
+                        rotation,
+                        decenter: Vec3::new(0.0, 0.0, 0.0),
+                        rotation_offset: Rotation3D::None,
+                    }
+                }
+            }
+            SurfaceVariant::CardinalLens => {
+                let semi_diameter = parse_float(&row.semi_diameter)
+                    .with_context(|| format!("surface {i}: semi-diameter"))?;
+                let focal_length = parse_float(&row.focal_length)
+                    .with_context(|| format!("surface {i}: focal length"))?;
+                let principal_plane_separation = parse_float(&row.principal_plane_separation)
+                    .with_context(|| format!("surface {i}: principal plane separation"))?;
+                SurfaceSpec::CardinalLens {
                     semi_diameter,
-                    radius_of_curvature: roc,
-                    surf_kind,
-                    rotation,
+                    focal_length,
+                    principal_plane_separation,
+                    rotation: Rotation3D::None,
                     decenter: Vec3::new(0.0, 0.0, 0.0),
                     rotation_offset: Rotation3D::None,
                 }
@@ -164,23 +466,37 @@ fn convert_specs_inner(
                 rotation_offset: Rotation3D::None,
             },
         };
+        let surface = match row.variant {
+            SurfaceVariant::Object | SurfaceVariant::Probe | SurfaceVariant::Image => surface,
+            _ => {
+                let surface = match parse_mask(i, row)? {
+                    Some(mask) => SurfaceSpec::Masked {
+                        mask,
+                        surface: Box::new(surface),
+                    },
+                    None => surface,
+                };
+                match parse_mechanical_edge(i, row)? {
+                    Some((mechanical_semi_diameter, flat_width)) => SurfaceSpec::Mounted {
+                        mechanical_semi_diameter,
+                        flat_width,
+                        surface: Box::new(surface),
+                    },
+                    None => surface,
+                }
+            }
+        };
         surfaces.push(surface);
 
         // Every surface except the last has a gap after it.
         if i < num_surfaces - 1 {
-            let thickness =
-                parse_float(&row.thickness).with_context(|| format!("surface {i}: thickness"))?;
-            let ri = resolve_refractive_index(
+            let gap = gap_ref_spec(i, row, specs.use_materials)?;
+            gaps.push(resolve_gap(
                 i,
-                row,
-                specs.use_materials,
+                &gap,
                 #[cfg(feature = "ri-info")]
                 materials,
-            )?;
-            gaps.push(GapSpec {
-                thickness,
-                refractive_index: ri,
-            });
+            )?);
         }
     }
 
@@ -313,6 +629,7 @@ fn apply_group_transforms(
                     Component::Element { surf_idxs } => all_surfs.extend(surf_idxs),
                     Component::Iris { stop_idx } => all_surfs.push(*stop_idx),
                     Component::Mirror { surf_idx } => all_surfs.push(*surf_idx),
+                    Component::ParaxialLens { surf_idx } => all_surfs.push(*surf_idx),
                     Component::UnpairedSurface { surf_idx } => all_surfs.push(*surf_idx),
                 }
             }
@@ -378,6 +695,7 @@ fn component_first_idx(c: &Component) -> usize {
         Component::Element { surf_idxs } => *surf_idxs.first().unwrap_or(&usize::MAX),
         Component::Iris { stop_idx } => *stop_idx,
         Component::Mirror { surf_idx } => *surf_idx,
+        Component::ParaxialLens { surf_idx } => *surf_idx,
         Component::UnpairedSurface { surf_idx } => *surf_idx,
     }
 }
@@ -408,17 +726,92 @@ fn set_surface_displacement(
     decenter: Vec3,
     rotation_offset: Rotation3D,
 ) {
-    match &mut surfaces[i] {
+    match surfaces[i].unwrapped_mut() {
         SurfaceSpec::Conic {
             decenter: d,
             rotation_offset: ro,
             ..
         }
+        | SurfaceSpec::Biconic {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::EvenAsphere {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::ForbesAsphere {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::Fresnel {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::Axicon {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::LensletArray {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::Grating {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::Diffractive {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::ParaxialLens {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::CardinalLens {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
         | SurfaceSpec::Sphere {
             decenter: d,
             rotation_offset: ro,
             ..
         }
+        | SurfaceSpec::Toroid {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::Cylinder {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::XYPolynomial {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::GridSag {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
+        | SurfaceSpec::ZernikeSag {
+            decenter: d,
+            rotation_offset: ro,
+            ..
+        }
         | SurfaceSpec::Iris {
             decenter: d,
             rotation_offset: ro,
@@ -437,45 +830,90 @@ fn set_surface_displacement(
             *d = decenter;
             *ro = rotation_offset;
         }
-        SurfaceSpec::Object | SurfaceSpec::Custom { .. } => {}
+        SurfaceSpec::Object
+        | SurfaceSpec::Masked { .. }
+        | SurfaceSpec::Mounted { .. }
+        | SurfaceSpec::Custom { .. } => {}
     }
 }
 
-/// Resolve the background refractive index from SystemSpecs.
-fn resolve_background(
-    specs: &SystemSpecs,
-    #[cfg(feature = "ri-info")] materials: Option<&MaterialsMap>,
-) -> Result<Rc<dyn RefractiveIndexSpec>> {
+impl SystemSpecs {
+    /// Returns the gaps of the design, with their materials as references.
+    ///
+    /// This is how a library user reads a design saved by the GUI: deserialize
+    /// the file into `SystemSpecs`, then resolve these gaps with a
+    /// [`MaterialResolver`] such as a [`GlassCatalog`](crate::GlassCatalog).
+    /// The material of each gap is the one described by [`surface_material`],
+    /// which is also the `material` that the GUI saves with the surface row.
+    pub fn gap_ref_specs(&self) -> Result<Vec<GapRefSpec>> {
+        let num_gaps = self.surfaces.len().saturating_sub(1);
+        self.surfaces[..num_gaps]
+            .iter()
+            .enumerate()
+            .map(|(i, row)| gap_ref_spec(i, row, self.use_materials))
+            .collect()
+    }
+
+    /// Returns the background medium of the design as a material reference.
+    pub fn background_material(&self) -> Result<MaterialRef> {
+        background_material(self)
+    }
+}
+
+/// Describe the gap after a surface with its material as a reference.
+fn gap_ref_spec(surface_idx: usize, row: &SurfaceRow, use_materials: bool) -> Result<GapRefSpec> {
+    let thickness =
+        parse_float(&row.thickness).with_context(|| format!("surface {surface_idx}: thickness"))?;
+    Ok(GapRefSpec {
+        thickness,
+        material: surface_material(surface_idx, row, use_materials)?,
+    })
+}
+
+/// Describe the background medium of SystemSpecs as a material reference.
+pub fn background_material(specs: &SystemSpecs) -> Result<MaterialRef> {
     #[cfg(feature = "ri-info")]
     if specs.use_materials
         && let Some(key) = &specs.background_material_key
     {
-        let materials = materials.ok_or_else(|| anyhow::anyhow!("Material store not loaded"))?;
-        let mat = materials
-            .get(key)
-            .ok_or_else(|| anyhow::anyhow!("background material '{key}' not found in database"))?;
-        return Ok(Rc::clone(mat) as Rc<dyn RefractiveIndexSpec>);
+        return Ok(MaterialRef::Catalog(key.clone()));
     }
 
     let n = parse_float(&specs.background_n).context("background refractive index")?;
-    Ok(Rc::new(ConstantRefractiveIndex::new(n, 0.0)))
+    Ok(MaterialRef::Constant { n, k: 0.0 })
 }
 
-/// Resolve the refractive index for a gap, using either a material key or a
-/// constant n value.
-fn resolve_refractive_index(
+/// Describe the material of the gap after a surface as a material reference.
+///
+/// A row may hold several materials, of which the first that applies is
+/// used:
+///
+/// 1. its model glass, if it has one;
+/// 2. its material key, if the design uses materials;
+/// 3. its constant refractive index.
+///
+/// The others are kept so that the row can switch back to them.
+pub fn surface_material(
     surface_idx: usize,
-    row: &super::model::SurfaceRow,
+    row: &SurfaceRow,
     use_materials: bool,
-    #[cfg(feature = "ri-info")] materials: Option<&MaterialsMap>,
-) -> Result<Rc<dyn RefractiveIndexSpec>> {
+) -> Result<MaterialRef> {
+    if let Some(glass) = &row.model_glass {
+        let parse = |s: &str, name: &str| {
+            parse_float(s).with_context(|| format!("surface {surface_idx}: model glass {name}"))
+        };
+        let glass = ModelGlass::new(
+            parse(&glass.nd, "nd")?,
+            parse(&glass.vd, "Vd")?,
+            parse(&glass.delta_pgf, "ΔPgF")?,
+        )
+        .with_context(|| format!("surface {surface_idx}: model glass"))?;
+        return Ok(MaterialRef::ModelGlass(glass));
+    }
+
     #[cfg(feature = "ri-info")]
     if use_materials && let Some(key) = &row.material_key {
-        let materials = materials.ok_or_else(|| anyhow::anyhow!("Material store not loaded"))?;
-        let mat = materials.get(key).ok_or_else(|| {
-            anyhow::anyhow!("surface {surface_idx}: material '{key}' not found in database")
-        })?;
-        return Ok(Rc::clone(mat) as Rc<dyn RefractiveIndexSpec>);
+        return Ok(MaterialRef::Catalog(key.clone()));
     }
 
     #[cfg(not(feature = "ri-info"))]
@@ -486,7 +924,54 @@ fn resolve_refractive_index(
     // Fall back to constant n.
     let n = parse_float(&row.refractive_index)
         .with_context(|| format!("surface {surface_idx}: refractive index"))?;
-    Ok(Rc::new(ConstantRefractiveIndex::new(n, 0.0)))
+    Ok(MaterialRef::Constant { n, k: 0.0 })
+}
+
+/// Return the resolver of a material reference. Material keys are looked up
+/// in the materials map, which must be loaded.
+fn material_resolver<'a>(
+    material: &MaterialRef,
+    #[cfg(feature = "ri-info")] materials: Option<&'a MaterialsMap>,
+) -> Result<Box<dyn MaterialResolver + 'a>> {
+    #[cfg(feature = "ri-info")]
+    if let Some(materials) = materials {
+        return Ok(Box::new(materials));
+    }
+    if let MaterialRef::Catalog(_) = material {
+        bail!("Material store not loaded");
+    }
+    let no_materials: HashMap<String, Rc<dyn RefractiveIndexSpec>> = HashMap::new();
+    Ok(Box::new(no_materials))
+}
+
+/// Resolve the background refractive index from SystemSpecs.
+fn resolve_background(
+    specs: &SystemSpecs,
+    #[cfg(feature = "ri-info")] materials: Option<&MaterialsMap>,
+) -> Result<Rc<dyn RefractiveIndexSpec>> {
+    let material = background_material(specs)?;
+    material_resolver(
+        &material,
+        #[cfg(feature = "ri-info")]
+        materials,
+    )
+    .and_then(|resolver| resolver.resolve(&material))
+    .map_err(|e| anyhow::anyhow!("background: {e}"))
+}
+
+/// Resolve the material of the gap after a surface.
+fn resolve_gap(
+    surface_idx: usize,
+    gap: &GapRefSpec,
+    #[cfg(feature = "ri-info")] materials: Option<&MaterialsMap>,
+) -> Result<GapSpec> {
+    material_resolver(
+        &gap.material,
+        #[cfg(feature = "ri-info")]
+        materials,
+    )
+    .and_then(|resolver| gap.resolve(resolver.as_ref()))
+    .map_err(|e| anyhow::anyhow!("surface {surface_idx}: {e}"))
 }
 
 #[cfg(test)]
@@ -552,6 +1037,266 @@ mod tests {
         assert_eq!(parsed.solves[0].surface_index(), 1);
     }
 
+    #[test]
+    fn xy_polynomial_row_converts_with_smallest_order() {
+        let specs = SystemSpecs {
+            surfaces: vec![
+                SurfaceRow::new_object("Infinity"),
+                SurfaceRow::new_xy_polynomial(
+                    "12.5",
+                    "50.0",
+                    "-1",
+                    "12.5",
+                    &["0", "0", "1e-3", "0", "2e-3", "0", "5e-4"],
+                    "5.0",
+                    "1.515",
+                ),
+                SurfaceRow::new_sphere("12.5", "Infinity", "46.6", "1.0"),
+                SurfaceRow::new_image(),
+            ],
+            wavelengths: vec!["0.567".into()],
+            ..Default::default()
+        };
+        let parsed = convert(&specs);
+        match &parsed.surfaces[1] {
+            SurfaceSpec::XYPolynomial {
+                conic_constant,
+                normalization_radius,
+                order,
+                xy_coefficients,
+                ..
+            } => {
+                assert_eq!(*conic_constant, -1.0);
+                assert_eq!(*normalization_radius, 12.5);
+                // Seven coefficients need a cubic (nine terms).
+                assert_eq!(*order, 3);
+                assert_eq!(xy_coefficients.len(), 7);
+            }
+            other => panic!("unexpected surface spec: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn cardinal_lens_row_converts() {
+        let specs = SystemSpecs {
+            surfaces: vec![
+                SurfaceRow::new_object("Infinity"),
+                SurfaceRow::new_cardinal_lens("5.0", "9.0", "-15.0", "9.0", "1.0"),
+                SurfaceRow::new_image(),
+            ],
+            wavelengths: vec!["0.5876".into()],
+            ..Default::default()
+        };
+        let parsed = convert(&specs);
+        match &parsed.surfaces[1] {
+            SurfaceSpec::CardinalLens {
+                semi_diameter,
+                focal_length,
+                principal_plane_separation,
+                ..
+            } => {
+                assert_eq!(*semi_diameter, 5.0);
+                assert_eq!(*focal_length, 9.0);
+                assert_eq!(*principal_plane_separation, -15.0);
+            }
+            other => panic!("unexpected surface spec: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn shaped_aperture_row_converts_to_masked_surface() {
+        use crate::gui::model::ApertureShape;
+        let mut specs = thin_singlet_specs("5.0");
+        let aperture = &mut specs.surfaces[1].aperture;
+        aperture.shape = ApertureShape::Rectangular;
+        aperture.size_x = "4".into();
+        aperture.size_y = "1".into();
+        aperture.obscuration = "0.5".into();
+
+        let parsed = convert(&specs);
+        match &parsed.surfaces[1] {
+            SurfaceSpec::Masked { mask, surface } => {
+                assert!(matches!(**surface, SurfaceSpec::Sphere { .. }));
+                let Mask::Obscured {
+                    aperture,
+                    obscuration,
+                    spider,
+                } = mask
+                else {
+                    panic!("unexpected mask: {mask:?}");
+                };
+                assert_eq!(
+                    **aperture,
+                    Mask::Rectangular {
+                        half_width_x: 4.0,
+                        half_width_y: 1.0
+                    }
+                );
+                assert_eq!(
+                    obscuration.as_deref(),
+                    Some(&Mask::Circular { semi_diameter: 0.5 })
+                );
+                assert!(spider.is_none());
+            }
+            other => panic!("unexpected surface spec: {other:?}"),
+        }
+        // A plain circular aperture is left to the surface's semi-diameter.
+        assert!(matches!(parsed.surfaces[2], SurfaceSpec::Sphere { .. }));
+
+        specs.surfaces[1].aperture.vertices = "0 0; 1".into();
+        specs.surfaces[1].aperture.shape = ApertureShape::Polygon;
+        #[cfg(not(feature = "ri-info"))]
+        assert!(convert_specs(&specs).is_err());
+        #[cfg(feature = "ri-info")]
+        assert!(convert_specs(&specs, &Default::default()).is_err());
+    }
+
+    #[test]
+    fn mechanical_edge_wraps_the_masked_surface() {
+        use crate::gui::model::ApertureShape;
+        let mut specs = thin_singlet_specs("5.0");
+        let aperture = &mut specs.surfaces[1].aperture;
+        aperture.shape = ApertureShape::Elliptical;
+        aperture.size_x = "12.5".into();
+        aperture.size_y = "6".into();
+        aperture.mechanical_semi_diameter = "14".into();
+        aperture.flat_width = "1".into();
+        specs.surfaces[2].aperture.flat_width = "0.5".into();
+
+        let parsed = convert(&specs);
+        let SurfaceSpec::Mounted {
+            mechanical_semi_diameter,
+            flat_width,
+            surface,
+        } = &parsed.surfaces[1]
+        else {
+            panic!("unexpected surface spec: {:?}", parsed.surfaces[1]);
+        };
+        assert_eq!((*mechanical_semi_diameter, *flat_width), (14.0, 1.0));
+        assert!(matches!(**surface, SurfaceSpec::Masked { .. }));
+        assert!(matches!(
+            parsed.surfaces[1].unwrapped(),
+            SurfaceSpec::Sphere { .. }
+        ));
+
+        // A flat alone is cut into the edge of the clear aperture.
+        match &parsed.surfaces[2] {
+            SurfaceSpec::Mounted {
+                mechanical_semi_diameter,
+                flat_width,
+                ..
+            } => assert_eq!((*mechanical_semi_diameter, *flat_width), (12.5, 0.5)),
+            other => panic!("unexpected surface spec: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn model_glass_row_converts_to_model_glass() {
+        use crate::gui::model::ModelGlassRow;
+        let mut specs = thin_singlet_specs("5.0");
+        specs.surfaces[1].model_glass = Some(ModelGlassRow {
+            nd: "1.7".into(),
+            vd: "30".into(),
+            delta_pgf: "0.01".into(),
+        });
+
+        let parsed = convert(&specs);
+        let n = &parsed.gaps[1].refractive_index;
+        assert!((n.n(0.5875618).unwrap() - 1.7).abs() < 1e-12);
+        let d_fc = n.n(0.4861327).unwrap() - n.n(0.6562725).unwrap();
+        assert!((0.7 / d_fc - 30.0).abs() < 1e-9);
+
+        specs.surfaces[1].model_glass.as_mut().unwrap().vd = "0".into();
+        #[cfg(not(feature = "ri-info"))]
+        assert!(convert_specs(&specs).is_err());
+        #[cfg(feature = "ri-info")]
+        assert!(convert_specs(&specs, &Default::default()).is_err());
+    }
+
+    #[test]
+    fn rows_describe_their_materials_as_references() {
+        use crate::gui::model::ModelGlassRow;
+        let mut specs = thin_singlet_specs("5.0");
+        specs.surfaces[2].model_glass = Some(ModelGlassRow::new("1.7"));
+
+        assert_eq!(
+            surface_material(1, &specs.surfaces[1], false).unwrap(),
+            MaterialRef::Constant { n: 1.515, k: 0.0 }
+        );
+        assert_eq!(
+            surface_material(2, &specs.surfaces[2], false).unwrap(),
+            MaterialRef::ModelGlass(ModelGlass::new(1.7, 50.0, 0.0).unwrap())
+        );
+        assert_eq!(
+            background_material(&specs).unwrap(),
+            MaterialRef::Constant { n: 1.0, k: 0.0 }
+        );
+
+        #[cfg(feature = "ri-info")]
+        {
+            specs.use_materials = true;
+            specs.surfaces[1].material_key = Some("glass:missing".into());
+            assert_eq!(
+                surface_material(1, &specs.surfaces[1], true).unwrap(),
+                MaterialRef::Catalog("glass:missing".into())
+            );
+            let err = convert_specs(&specs, &Default::default()).err().unwrap();
+            assert!(
+                err.to_string()
+                    .contains("surface 1: Material 'glass:missing'")
+            );
+        }
+
+        let unloaded = material_resolver(
+            &MaterialRef::Catalog("glass:any".into()),
+            #[cfg(feature = "ri-info")]
+            None,
+        );
+        assert_eq!(
+            unloaded.err().unwrap().to_string(),
+            "Material store not loaded"
+        );
+    }
+
+    #[test]
+    fn saved_designs_convert_to_gap_ref_specs() {
+        use std::collections::HashMap;
+
+        use crate::{RefractiveIndexSpec, n};
+
+        // The material key is used only in materials mode.
+        let mut specs = thin_singlet_specs("5.0");
+        specs.use_materials = cfg!(feature = "ri-info");
+        specs.surfaces[1].material_key = Some("lab:glass".into());
+        let json = serde_json::to_string(&specs).unwrap();
+
+        // A library user loads the design and resolves its gaps.
+        let specs: SystemSpecs = serde_json::from_str(&json).unwrap();
+        let gaps = specs.gap_ref_specs().unwrap();
+        assert_eq!(gaps.len(), 3);
+        assert_eq!(gaps[1].thickness, 5.0);
+        assert_eq!(
+            specs.background_material().unwrap(),
+            MaterialRef::Constant { n: 1.0, k: 0.0 }
+        );
+
+        let mut resolver: HashMap<String, Rc<dyn RefractiveIndexSpec>> = HashMap::new();
+        resolver.insert("lab:glass".into(), n!(1.6));
+        let n = |i: usize| {
+            gaps[i]
+                .resolve(&resolver)
+                .unwrap()
+                .refractive_index
+                .n(0.5876)
+                .unwrap()
+        };
+        #[cfg(feature = "ri-info")]
+        assert_eq!(n(1), 1.6);
+        #[cfg(not(feature = "ri-info"))]
+        assert_eq!(n(1), 1.515);
+        assert_eq!(n(2), 1.0);
+    }
+
     // Thin singlet: Object[0] → Sphere[1] (F=0) → Sphere[2] (F=thickness) →
     // Image[3]. Using a thin lens (5 mm thick) in air so surface 2 is at F=5.
     fn thin_singlet_specs(thickness: &str) -> SystemSpecs {
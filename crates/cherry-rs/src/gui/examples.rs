@@ -1,4 +1,7 @@
-use super::model::{FieldMode, FieldRow, SurfaceKind, SurfaceRow, SurfaceVariant, SystemSpecs};
+use super::model::{
+    ApertureRow, FieldMode, FieldRow, SurfaceKind, SurfaceRow, SurfaceVariant, SystemSpecs,
+    ThermalSettings,
+};
 
 /// Figure-Z two-mirror system: two flat mirrors at 30° tilt, separated by 100
 /// mm, returning the beam parallel to the z-axis.
@@ -17,6 +20,16 @@ pub fn mirrors_figure_z() -> SystemSpecs {
                 theta: "30".into(),
                 psi: "0".into(),
                 material_key: None,
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -29,6 +42,16 @@ pub fn mirrors_figure_z() -> SystemSpecs {
                 theta: "30".into(),
                 psi: "0".into(),
                 material_key: None,
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow::new_image(),
         ],
@@ -50,6 +73,9 @@ pub fn mirrors_figure_z() -> SystemSpecs {
         stop_surface: None,
         solves: Vec::new(),
         lens_groups: Vec::new(),
+        show_aperture_details: false,
+        custom_materials: Vec::new(),
+        thermal: ThermalSettings::default(),
     }
 }
 
@@ -94,6 +120,9 @@ pub fn petzval_lens() -> SystemSpecs {
         stop_surface: None,
         solves: Vec::new(),
         lens_groups: Vec::new(),
+        show_aperture_details: false,
+        custom_materials: Vec::new(),
+        thermal: ThermalSettings::default(),
     }
 }
 
@@ -132,6 +161,9 @@ pub fn biconvex_lens() -> SystemSpecs {
         stop_surface: None,
         solves: Vec::new(),
         lens_groups: Vec::new(),
+        show_aperture_details: false,
+        custom_materials: Vec::new(),
+        thermal: ThermalSettings::default(),
     }
 }
 
@@ -151,6 +183,16 @@ pub fn convexplano_lens_with_materials() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -163,6 +205,16 @@ pub fn convexplano_lens_with_materials() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("popular_glass:BK7:SCHOTT".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -175,6 +227,16 @@ pub fn convexplano_lens_with_materials() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow::new_image(),
         ],
@@ -203,6 +265,9 @@ pub fn convexplano_lens_with_materials() -> SystemSpecs {
         stop_surface: None,
         solves: Vec::new(),
         lens_groups: Vec::new(),
+        show_aperture_details: false,
+        custom_materials: Vec::new(),
+        thermal: ThermalSettings::default(),
     }
 }
 
@@ -224,6 +289,16 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Iris,
@@ -236,6 +311,16 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -248,6 +333,16 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("specs:SCHOTT-optical:N-SF57".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -260,6 +355,16 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -272,6 +377,16 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("specs:SCHOTT-optical:N-SF57".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -284,6 +399,16 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -296,6 +421,16 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("specs:SCHOTT-optical:N-SF57".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -308,6 +443,16 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow::new_image(),
         ],
@@ -332,6 +477,9 @@ pub fn f_theta_scan_lens() -> SystemSpecs {
         stop_surface: None,
         solves: Vec::new(),
         lens_groups: Vec::new(),
+        show_aperture_details: false,
+        custom_materials: Vec::new(),
+        thermal: ThermalSettings::default(),
     }
 }
 
@@ -354,6 +502,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -366,6 +524,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "-45".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -378,6 +546,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("specs:SCHOTT-optical:N-KZFS5".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -390,6 +568,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -402,6 +590,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("specs:SCHOTT-optical:N-PK51".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -414,6 +612,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -426,6 +634,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("specs:OHARA-optical:S-FPM2".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -438,6 +656,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("specs:SCHOTT-optical:N-KZFS11".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow {
                 variant: SurfaceVariant::Sphere,
@@ -450,6 +678,16 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: Some("other:air:Ciddor".into()),
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow::new_image(),
         ],
@@ -482,6 +720,9 @@ pub fn galvo_scan_lens_negrean_mansvelder() -> SystemSpecs {
         stop_surface: Some(1),
         solves: Vec::new(),
         lens_groups: Vec::new(),
+        show_aperture_details: false,
+        custom_materials: Vec::new(),
+        thermal: ThermalSettings::default(),
     }
 }
 
@@ -501,6 +742,16 @@ pub fn concave_mirror() -> SystemSpecs {
                 theta: "0".into(),
                 psi: "0".into(),
                 material_key: None,
+                model_glass: None,
+                aspheric_coefficients: Vec::new(),
+                normalization_radius: String::new(),
+                xy_coefficients: Vec::new(),
+                line_density: String::new(),
+                diffraction_order: String::new(),
+                line_orientation: String::new(),
+                focal_length: String::new(),
+                principal_plane_separation: String::new(),
+                aperture: ApertureRow::default(),
             },
             SurfaceRow::new_image(),
         ],
@@ -529,5 +780,8 @@ pub fn concave_mirror() -> SystemSpecs {
         stop_surface: None,
         solves: Vec::new(),
         lens_groups: Vec::new(),
+        show_aperture_details: false,
+        custom_materials: Vec::new(),
+        thermal: ThermalSettings::default(),
     }
 }
@@ -1,6 +1,13 @@
 use std::fmt;
 
-use serde::{Deserialize, Serialize};
+use anyhow::bail;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+
+use super::convert::surface_material;
+use crate::{
+    MaterialRef,
+    core::surfaces::xy_polynomial::{MAX_XY_ORDER, xy_term_count, xy_term_index, xy_term_powers},
+};
 
 /// Which table parameter a solve controls.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -136,12 +143,133 @@ impl SolvePopupState {
     }
 }
 
+/// Transient UI state for the XY polynomial coefficient editor.
+///
+/// Coefficients are edited as free text so that tables can be pasted from a
+/// spreadsheet or another design program. Each non-empty line holds one term
+/// in one of three forms, with fields separated by whitespace, commas or
+/// semicolons:
+///
+/// - `i j c`: the coefficient `c` of `x^i y^j`
+/// - `k c`: the coefficient `c` of term number `k` (1-based, in the order x, y,
+///   x², xy, y², ...)
+/// - `c`: the coefficient of the term following the previous line's term
+///
+/// Lines starting with `#` and lines whose first field is not a number (e.g. a
+/// header row) are ignored.
+#[derive(Clone, Debug)]
+pub struct XYPolynomialPopupState {
+    pub surface_index: usize,
+    pub normalization_radius: String,
+    pub text: String,
+    pub error: Option<String>,
+}
+
+impl XYPolynomialPopupState {
+    pub fn open(surface_index: usize, row: &SurfaceRow) -> Self {
+        let text = row
+            .xy_coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.trim().parse::<f64>().is_ok_and(|v| v != 0.0))
+            .map(|(k, c)| {
+                let (i, j) = xy_term_powers(k);
+                format!("{i} {j} {}", c.trim())
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            surface_index,
+            normalization_radius: row.normalization_radius.clone(),
+            text,
+            error: None,
+        }
+    }
+
+    /// Parses the coefficient table into the row's flat coefficient list.
+    ///
+    /// Terms that are not listed are zero. Trailing zero terms are dropped.
+    pub fn parse_coefficients(&self) -> Result<Vec<String>, String> {
+        let max_terms = xy_term_count(MAX_XY_ORDER);
+        let mut coefficients = vec![0.0; max_terms];
+        let mut next = 0;
+
+        for (line_no, line) in self.text.lines().enumerate() {
+            let line_no = line_no + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line
+                .split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                .filter(|f| !f.is_empty())
+                .collect();
+            let Some(first) = fields.first() else {
+                continue;
+            };
+            if first.parse::<f64>().is_err() {
+                continue;
+            }
+            let parse_value = |s: &str| {
+                s.parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| format!("line {line_no}: '{s}' is not a number"))
+            };
+            let parse_index = |s: &str| {
+                s.parse::<usize>()
+                    .map_err(|_| format!("line {line_no}: '{s}' is not a non-negative integer"))
+            };
+
+            let (k, value) = match fields.as_slice() {
+                [c] => (next, parse_value(c)?),
+                [k, c] => {
+                    let k = parse_index(k)?;
+                    if k == 0 {
+                        return Err(format!("line {line_no}: term numbers start at 1"));
+                    }
+                    (k - 1, parse_value(c)?)
+                }
+                [i, j, c] => {
+                    let (i, j) = (parse_index(i)?, parse_index(j)?);
+                    if i + j == 0 || i + j > MAX_XY_ORDER {
+                        return Err(format!(
+                            "line {line_no}: x^{i} y^{j} must have a total order between 1 and \
+                             {MAX_XY_ORDER}"
+                        ));
+                    }
+                    (xy_term_index(i, j), parse_value(c)?)
+                }
+                _ => return Err(format!("line {line_no}: expected 1 to 3 fields")),
+            };
+            if k >= max_terms {
+                return Err(format!(
+                    "line {line_no}: term {} is beyond order {MAX_XY_ORDER}",
+                    k + 1
+                ));
+            }
+            coefficients[k] = value;
+            next = k + 1;
+        }
+
+        let len = coefficients
+            .iter()
+            .rposition(|&c| c != 0.0)
+            .map_or(0, |k| k + 1);
+        Ok(coefficients[..len].iter().map(|c| c.to_string()).collect())
+    }
+}
+
 /// Which surface variant this row represents.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum SurfaceVariant {
     Object,
     Sphere,
     Conic,
+    EvenAsphere,
+    XYPolynomial,
+    Grating,
+    CardinalLens,
     Iris,
     Probe,
     Image,
@@ -153,6 +281,10 @@ impl SurfaceVariant {
     pub const SELECTABLE: &[SurfaceVariant] = &[
         SurfaceVariant::Sphere,
         SurfaceVariant::Conic,
+        SurfaceVariant::EvenAsphere,
+        SurfaceVariant::XYPolynomial,
+        SurfaceVariant::Grating,
+        SurfaceVariant::CardinalLens,
         SurfaceVariant::Iris,
         SurfaceVariant::Probe,
     ];
@@ -164,6 +296,10 @@ impl std::fmt::Display for SurfaceVariant {
             SurfaceVariant::Object => write!(f, "Object"),
             SurfaceVariant::Sphere => write!(f, "Sphere"),
             SurfaceVariant::Conic => write!(f, "Conic"),
+            SurfaceVariant::EvenAsphere => write!(f, "Even Asphere"),
+            SurfaceVariant::XYPolynomial => write!(f, "XY Polynomial"),
+            SurfaceVariant::Grating => write!(f, "Grating"),
+            SurfaceVariant::CardinalLens => write!(f, "Cardinal Lens"),
             SurfaceVariant::Iris => write!(f, "Iris"),
             SurfaceVariant::Probe => write!(f, "Probe"),
             SurfaceVariant::Image => write!(f, "Image"),
@@ -193,10 +329,18 @@ fn default_zero() -> String {
 
 /// A single row in the surfaces table. All numeric fields are strings for
 /// editing.
+///
+/// Rows are saved by [`SystemSpecs`] with the material of the gap after them
+/// as a [`MaterialRef`], as in a [`GapRefSpec`](crate::GapRefSpec). It is the
+/// material in use, as described by [`SystemSpecs::gap_ref_specs`]. Entries
+/// that the reference does not carry, such as a material key set aside for a
+/// model glass, are saved next to it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct SurfaceRow {
     pub variant: SurfaceVariant,
     pub surface_kind: SurfaceKind,
+    #[serde(skip)]
     pub refractive_index: String,
     pub thickness: String,
     pub semi_diameter: String,
@@ -212,8 +356,172 @@ pub struct SurfaceRow {
     pub psi: String,
     /// Material key from rii.db (e.g. "glass:BK7:SCHOTT"). Used when
     /// `SystemSpecs::use_materials` is true.
-    #[serde(default)]
+    #[serde(skip)]
     pub material_key: Option<String>,
+    /// Model glass of the medium after the surface. Takes precedence over
+    /// both the refractive index and the material key when set.
+    #[serde(skip)]
+    pub model_glass: Option<ModelGlassRow>,
+    /// Even-order polynomial coefficients A4, A6, ... of an Even Asphere row.
+    /// Empty for all other variants.
+    #[serde(default)]
+    pub aspheric_coefficients: Vec<String>,
+    /// Normalization radius of an XY Polynomial row. Empty for all other
+    /// variants.
+    #[serde(default)]
+    pub normalization_radius: String,
+    /// Coefficients of an XY Polynomial row in the order x, y, x², xy, y², ...
+    /// Empty for all other variants.
+    #[serde(default)]
+    pub xy_coefficients: Vec<String>,
+    /// Line density of a Grating row, in lines per mm. Empty for all other
+    /// variants.
+    #[serde(default)]
+    pub line_density: String,
+    /// Diffraction order of a Grating row. Empty for all other variants.
+    #[serde(default)]
+    pub diffraction_order: String,
+    /// Angle of a Grating row's rulings from the local x-axis, degrees. Empty
+    /// for all other variants.
+    #[serde(default)]
+    pub line_orientation: String,
+    /// Focal length of a Cardinal Lens row. Empty for all other variants.
+    #[serde(default)]
+    pub focal_length: String,
+    /// Distance from the front to the back principal plane of a Cardinal Lens
+    /// row. Empty for all other variants.
+    #[serde(default)]
+    pub principal_plane_separation: String,
+    /// Clear aperture of the surface. Ignored for Object, Probe and Image
+    /// rows.
+    #[serde(default)]
+    pub aperture: ApertureRow,
+}
+
+/// A model glass defined by its index, Abbe number and partial dispersion.
+/// All numeric fields are strings for editing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelGlassRow {
+    pub nd: String,
+    pub vd: String,
+    /// Deviation of the partial dispersion P_g,F from the normal line.
+    #[serde(default = "default_zero")]
+    pub delta_pgf: String,
+}
+
+impl ModelGlassRow {
+    /// Creates a normal model glass with the given index at the d line.
+    pub fn new(nd: &str) -> Self {
+        Self {
+            nd: nd.into(),
+            vd: "50".into(),
+            delta_pgf: "0".into(),
+        }
+    }
+}
+
+/// Outline of a surface's clear aperture.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ApertureShape {
+    /// A circle of the row's semi-diameter.
+    #[default]
+    Circular,
+    Rectangular,
+    Elliptical,
+    Polygon,
+}
+
+impl ApertureShape {
+    pub const ALL: &[ApertureShape] = &[
+        ApertureShape::Circular,
+        ApertureShape::Rectangular,
+        ApertureShape::Elliptical,
+        ApertureShape::Polygon,
+    ];
+}
+
+impl std::fmt::Display for ApertureShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApertureShape::Circular => write!(f, "Circular"),
+            ApertureShape::Rectangular => write!(f, "Rectangular"),
+            ApertureShape::Elliptical => write!(f, "Elliptical"),
+            ApertureShape::Polygon => write!(f, "Polygon"),
+        }
+    }
+}
+
+/// The clear aperture of a row in the surfaces table. All numeric fields are
+/// strings for editing.
+///
+/// The default is a circle of the row's semi-diameter centered on the vertex,
+/// which needs no mask beyond the semi-diameter itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ApertureRow {
+    pub shape: ApertureShape,
+    /// Half-width along x of a Rectangular aperture, or semi-axis along x of
+    /// an Elliptical one.
+    pub size_x: String,
+    /// Half-width along y of a Rectangular aperture, or semi-axis along y of
+    /// an Elliptical one.
+    pub size_y: String,
+    /// Vertices of a Polygon aperture as `x y` pairs separated by semicolons.
+    pub vertices: String,
+    /// Offset of the aperture from the vertex along x.
+    pub offset_x: String,
+    /// Offset of the aperture from the vertex along y.
+    pub offset_y: String,
+    /// Radius of a circular central obscuration. Zero for none.
+    pub obscuration: String,
+    /// Number of spider vanes. Zero for none.
+    pub spider_vanes: String,
+    /// Full width of each spider vane.
+    pub spider_width: String,
+    /// Semi-diameter of the mechanical edge. Zero for an edge at the clear
+    /// aperture.
+    pub mechanical_semi_diameter: String,
+    /// Width of the flat at the mechanical edge. Zero for none.
+    pub flat_width: String,
+}
+
+impl Default for ApertureRow {
+    fn default() -> Self {
+        Self {
+            shape: ApertureShape::Circular,
+            size_x: String::new(),
+            size_y: String::new(),
+            vertices: String::new(),
+            offset_x: "0".into(),
+            offset_y: "0".into(),
+            obscuration: "0".into(),
+            spider_vanes: "0".into(),
+            spider_width: "0".into(),
+            mechanical_semi_diameter: "0".into(),
+            flat_width: "0".into(),
+        }
+    }
+}
+
+impl ApertureRow {
+    /// Returns true if the aperture has an offset, an obscuration, a spider
+    /// or a mechanical edge.
+    pub fn has_details(&self) -> bool {
+        [
+            &self.offset_x,
+            &self.offset_y,
+            &self.obscuration,
+            &self.spider_vanes,
+            &self.mechanical_semi_diameter,
+            &self.flat_width,
+        ]
+        .iter()
+        .any(|s| {
+            s.trim()
+                .parse::<f64>()
+                .map_or(!s.trim().is_empty(), |v| v != 0.0)
+        })
+    }
 }
 
 impl SurfaceRow {
@@ -229,6 +537,16 @@ impl SurfaceRow {
             theta: "0".into(),
             psi: "0".into(),
             material_key: None,
+            model_glass: None,
+            aspheric_coefficients: Vec::new(),
+            normalization_radius: String::new(),
+            xy_coefficients: Vec::new(),
+            line_density: String::new(),
+            diffraction_order: String::new(),
+            line_orientation: String::new(),
+            focal_length: String::new(),
+            principal_plane_separation: String::new(),
+            aperture: ApertureRow::default(),
         }
     }
 
@@ -250,6 +568,144 @@ impl SurfaceRow {
             theta: "0".into(),
             psi: "0".into(),
             material_key: None,
+            model_glass: None,
+            aspheric_coefficients: Vec::new(),
+            normalization_radius: String::new(),
+            xy_coefficients: Vec::new(),
+            line_density: String::new(),
+            diffraction_order: String::new(),
+            line_orientation: String::new(),
+            focal_length: String::new(),
+            principal_plane_separation: String::new(),
+            aperture: ApertureRow::default(),
+        }
+    }
+
+    pub fn new_even_asphere(
+        semi_diameter: &str,
+        radius_of_curvature: &str,
+        conic_constant: &str,
+        aspheric_coefficients: &[&str],
+        thickness: &str,
+        refractive_index: &str,
+    ) -> Self {
+        Self {
+            variant: SurfaceVariant::EvenAsphere,
+            surface_kind: SurfaceKind::Refracting,
+            refractive_index: refractive_index.into(),
+            thickness: thickness.into(),
+            semi_diameter: semi_diameter.into(),
+            radius_of_curvature: radius_of_curvature.into(),
+            conic_constant: conic_constant.into(),
+            theta: "0".into(),
+            psi: "0".into(),
+            material_key: None,
+            model_glass: None,
+            aspheric_coefficients: aspheric_coefficients.iter().map(|&a| a.into()).collect(),
+            normalization_radius: String::new(),
+            xy_coefficients: Vec::new(),
+            line_density: String::new(),
+            diffraction_order: String::new(),
+            line_orientation: String::new(),
+            focal_length: String::new(),
+            principal_plane_separation: String::new(),
+            aperture: ApertureRow::default(),
+        }
+    }
+
+    pub fn new_xy_polynomial(
+        semi_diameter: &str,
+        radius_of_curvature: &str,
+        conic_constant: &str,
+        normalization_radius: &str,
+        xy_coefficients: &[&str],
+        thickness: &str,
+        refractive_index: &str,
+    ) -> Self {
+        Self {
+            variant: SurfaceVariant::XYPolynomial,
+            surface_kind: SurfaceKind::Refracting,
+            refractive_index: refractive_index.into(),
+            thickness: thickness.into(),
+            semi_diameter: semi_diameter.into(),
+            radius_of_curvature: radius_of_curvature.into(),
+            conic_constant: conic_constant.into(),
+            theta: "0".into(),
+            psi: "0".into(),
+            material_key: None,
+            model_glass: None,
+            aspheric_coefficients: Vec::new(),
+            normalization_radius: normalization_radius.into(),
+            xy_coefficients: xy_coefficients.iter().map(|&c| c.into()).collect(),
+            line_density: String::new(),
+            diffraction_order: String::new(),
+            line_orientation: String::new(),
+            focal_length: String::new(),
+            principal_plane_separation: String::new(),
+            aperture: ApertureRow::default(),
+        }
+    }
+
+    pub fn new_grating(
+        semi_diameter: &str,
+        radius_of_curvature: &str,
+        line_density: &str,
+        diffraction_order: &str,
+        thickness: &str,
+        refractive_index: &str,
+    ) -> Self {
+        Self {
+            variant: SurfaceVariant::Grating,
+            surface_kind: SurfaceKind::Refracting,
+            refractive_index: refractive_index.into(),
+            thickness: thickness.into(),
+            semi_diameter: semi_diameter.into(),
+            radius_of_curvature: radius_of_curvature.into(),
+            conic_constant: String::new(),
+            theta: "0".into(),
+            psi: "0".into(),
+            material_key: None,
+            model_glass: None,
+            aspheric_coefficients: Vec::new(),
+            normalization_radius: String::new(),
+            xy_coefficients: Vec::new(),
+            line_density: line_density.into(),
+            diffraction_order: diffraction_order.into(),
+            line_orientation: "0".into(),
+            focal_length: String::new(),
+            principal_plane_separation: String::new(),
+            aperture: ApertureRow::default(),
+        }
+    }
+
+    pub fn new_cardinal_lens(
+        semi_diameter: &str,
+        focal_length: &str,
+        principal_plane_separation: &str,
+        thickness: &str,
+        refractive_index: &str,
+    ) -> Self {
+        Self {
+            variant: SurfaceVariant::CardinalLens,
+            surface_kind: SurfaceKind::Refracting,
+            refractive_index: refractive_index.into(),
+            thickness: thickness.into(),
+            semi_diameter: semi_diameter.into(),
+            radius_of_curvature: String::new(),
+            conic_constant: String::new(),
+            theta: "0".into(),
+            psi: "0".into(),
+            material_key: None,
+            model_glass: None,
+            aspheric_coefficients: Vec::new(),
+            normalization_radius: String::new(),
+            xy_coefficients: Vec::new(),
+            line_density: String::new(),
+            diffraction_order: String::new(),
+            line_orientation: String::new(),
+            focal_length: focal_length.into(),
+            principal_plane_separation: principal_plane_separation.into(),
+            aperture: ApertureRow::default(),
         }
     }
 
@@ -270,6 +726,16 @@ impl SurfaceRow {
             theta: "0".into(),
             psi: "0".into(),
             material_key: None,
+            model_glass: None,
+            aspheric_coefficients: Vec::new(),
+            normalization_radius: String::new(),
+            xy_coefficients: Vec::new(),
+            line_density: String::new(),
+            diffraction_order: String::new(),
+            line_orientation: String::new(),
+            focal_length: String::new(),
+            principal_plane_separation: String::new(),
+            aperture: ApertureRow::default(),
         }
     }
 
@@ -285,6 +751,16 @@ impl SurfaceRow {
             theta: "0".into(),
             psi: "0".into(),
             material_key: None,
+            model_glass: None,
+            aspheric_coefficients: Vec::new(),
+            normalization_radius: String::new(),
+            xy_coefficients: Vec::new(),
+            line_density: String::new(),
+            diffraction_order: String::new(),
+            line_orientation: String::new(),
+            focal_length: String::new(),
+            principal_plane_separation: String::new(),
+            aperture: ApertureRow::default(),
         }
     }
 
@@ -300,6 +776,16 @@ impl SurfaceRow {
             theta: "0".into(),
             psi: "0".into(),
             material_key: None,
+            model_glass: None,
+            aspheric_coefficients: Vec::new(),
+            normalization_radius: String::new(),
+            xy_coefficients: Vec::new(),
+            line_density: String::new(),
+            diffraction_order: String::new(),
+            line_orientation: String::new(),
+            focal_length: String::new(),
+            principal_plane_separation: String::new(),
+            aperture: ApertureRow::default(),
         }
     }
 
@@ -381,9 +867,101 @@ pub enum SpecsTab {
     Wavelengths,
 }
 
+/// Which materials tab is active.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum MaterialsTab {
+    Browse,
+    GlassMap,
+    Dispersion,
+}
+
+/// Shelf of the material keys of user-supplied material files.
+pub const CUSTOM_MATERIAL_SHELF: &str = "user";
+
+/// A material loaded from a RefractiveIndex.info YAML file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomMaterial {
+    /// Material key, `user:book:page`.
+    pub key: String,
+    /// Contents of the file.
+    pub yaml: String,
+}
+
+impl CustomMaterial {
+    /// Files a material under the user shelf. Colons in the book and page are
+    /// replaced by underscores, since they separate the parts of the key.
+    pub fn new(book: &str, page: &str, yaml: String) -> Self {
+        let clean = |s: &str| s.trim().replace(':', "_");
+        Self {
+            key: format!("{CUSTOM_MATERIAL_SHELF}:{}:{}", clean(book), clean(page)),
+            yaml,
+        }
+    }
+
+    /// Parse the file into a material.
+    #[cfg(feature = "ri-info")]
+    pub fn parse(&self) -> anyhow::Result<crate::Material> {
+        let mut parts = self.key.splitn(3, ':');
+        let (Some(shelf), Some(book), Some(page)) = (parts.next(), parts.next(), parts.next())
+        else {
+            anyhow::bail!("Invalid material key '{}'", self.key);
+        };
+        crate::material_from_yaml(&self.yaml, shelf, book, page)
+            .map_err(|e| anyhow::anyhow!("material '{}': {e:#}", self.key))
+    }
+}
+
+/// Inputs of the thermal analysis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThermalSettings {
+    /// Lowest temperature of the analysis, in °C.
+    pub min_temperature: f64,
+    /// Highest temperature of the analysis, in °C.
+    pub max_temperature: f64,
+    /// Number of evenly spaced temperatures from the lowest to the highest.
+    pub n_temperatures: u32,
+    /// Temperature at which the system's lengths are specified, in °C.
+    pub reference_temperature: f64,
+    /// Coefficient of thermal expansion of the mount, in 10⁻⁶/K.
+    pub mount_expansion_ppm: f64,
+    /// Air pressure, in kPa.
+    pub pressure_kpa: f64,
+}
+
+impl ThermalSettings {
+    /// Returns the temperatures of the analysis, in °C.
+    pub fn temperatures(&self) -> Vec<f64> {
+        let n = self.n_temperatures.max(2);
+        let step = (self.max_temperature - self.min_temperature) / (n - 1) as f64;
+        (0..n)
+            .map(|i| self.min_temperature + step * i as f64)
+            .collect()
+    }
+}
+
+impl Default for ThermalSettings {
+    /// -20 to 60 °C in an aluminum mount at sea level.
+    fn default() -> Self {
+        Self {
+            min_temperature: -20.0,
+            max_temperature: 60.0,
+            n_temperatures: 9,
+            reference_temperature: 20.0,
+            mount_expansion_ppm: 23.6,
+            pressure_kpa: 101.325,
+        }
+    }
+}
+
 /// All user-editable input specifications.
+///
+/// Saved designs can be read by library users: see
+/// [`SystemSpecs::gap_ref_specs`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self")]
 pub struct SystemSpecs {
+    #[serde(skip)]
     pub surfaces: Vec<SurfaceRow>,
     pub fields: Vec<FieldRow>,
     pub aperture_semi_diameter: String,
@@ -395,6 +973,10 @@ pub struct SystemSpecs {
     /// Material keys the user has selected for use in the surfaces table.
     #[serde(default)]
     pub selected_materials: Vec<String>,
+    /// Materials loaded from RefractiveIndex.info YAML files. Their contents
+    /// are stored with the design so that it can be shared.
+    #[serde(default)]
+    pub custom_materials: Vec<CustomMaterial>,
     /// Number of rays in the tangential fan for the cross-section view.
     #[serde(default = "default_cross_section_n_rays")]
     pub cross_section_n_rays: u32,
@@ -421,6 +1003,191 @@ pub struct SystemSpecs {
     /// User-defined lens groups for the lens overlay panel.
     #[serde(default)]
     pub lens_groups: Vec<LensGroupSpec>,
+    /// When true, the surfaces table shows aperture offset, obscuration and
+    /// spider columns even if no surface uses them.
+    #[serde(default)]
+    pub show_aperture_details: bool,
+    /// Inputs of the thermal analysis window.
+    #[serde(default)]
+    pub thermal: ThermalSettings,
+}
+
+impl Serialize for SystemSpecs {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Saved<'a> {
+            surfaces: Vec<SavedRow<'a>>,
+            #[serde(flatten, serialize_with = "serialize_fields")]
+            specs: &'a SystemSpecs,
+        }
+
+        fn serialize_fields<S: Serializer>(specs: &&SystemSpecs, s: S) -> Result<S::Ok, S::Error> {
+            SystemSpecs::serialize(specs, s)
+        }
+
+        let surfaces = self
+            .surfaces
+            .iter()
+            .enumerate()
+            .map(|(i, row)| SavedRow::new(row, surface_material(i, row, self.use_materials).ok()))
+            .collect();
+        Saved {
+            surfaces,
+            specs: self,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for SystemSpecs {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Saved {
+            surfaces: Vec<LoadedRow>,
+            #[serde(flatten, with = "SystemSpecs")]
+            specs: SystemSpecs,
+        }
+
+        let Saved {
+            surfaces,
+            mut specs,
+        } = Saved::deserialize(deserializer)?;
+        specs.surfaces = surfaces
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| row.into_row(i, specs.use_materials))
+            .collect::<anyhow::Result<_>>()
+            .map_err(|e| D::Error::custom(format!("{e:#}")))?;
+        Ok(specs)
+    }
+}
+
+/// A row of the surfaces table as it is saved. The material in use is saved
+/// as a reference, or not at all if it does not parse; the other entries are
+/// saved as they are.
+#[derive(Serialize)]
+struct SavedRow<'a> {
+    #[serde(flatten, serialize_with = "serialize_row_fields")]
+    row: &'a SurfaceRow,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    material: Option<MaterialRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    refractive_index: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    material_key: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model_glass: Option<&'a ModelGlassRow>,
+}
+
+fn serialize_row_fields<S: Serializer>(row: &&SurfaceRow, s: S) -> Result<S::Ok, S::Error> {
+    SurfaceRow::serialize(row, s)
+}
+
+impl<'a> SavedRow<'a> {
+    fn new(row: &'a SurfaceRow, material: Option<MaterialRef>) -> Self {
+        Self {
+            row,
+            refractive_index: (!matches!(material, Some(MaterialRef::Constant { .. })))
+                .then_some(row.refractive_index.as_str()),
+            material_key: row
+                .material_key
+                .as_deref()
+                .filter(|_| !matches!(material, Some(MaterialRef::Catalog(_)))),
+            model_glass: row
+                .model_glass
+                .as_ref()
+                .filter(|_| !matches!(material, Some(MaterialRef::ModelGlass(_)))),
+            material,
+        }
+    }
+}
+
+/// A row of the surfaces table as it is loaded. Designs saved before rows had
+/// a `material` carry only the other entries.
+#[derive(Deserialize)]
+struct LoadedRow {
+    #[serde(flatten, with = "SurfaceRow")]
+    row: SurfaceRow,
+    #[serde(default)]
+    material: Option<MaterialRef>,
+    #[serde(default)]
+    refractive_index: Option<String>,
+    #[serde(default)]
+    material_key: Option<String>,
+    #[serde(default)]
+    model_glass: Option<ModelGlassRow>,
+}
+
+impl LoadedRow {
+    /// Returns the row, with its saved material in the entry that holds it.
+    ///
+    /// # Errors
+    /// If the entry is also saved on its own, if the table cannot hold the
+    /// material, or if the row would use another material, so that it is
+    /// unclear which one the design means.
+    fn into_row(self, surface_idx: usize, use_materials: bool) -> anyhow::Result<SurfaceRow> {
+        let Self {
+            mut row,
+            material,
+            refractive_index,
+            material_key,
+            model_glass,
+        } = self;
+        let Some(material) = material else {
+            row.refractive_index = refractive_index.unwrap_or_default();
+            row.material_key = material_key;
+            row.model_glass = model_glass;
+            return Ok(row);
+        };
+
+        let saved_twice = match &material {
+            MaterialRef::Constant { n, k: 0.0 } => {
+                row.refractive_index = n.to_string();
+                row.material_key = material_key;
+                row.model_glass = model_glass;
+                refractive_index.is_some()
+            }
+            MaterialRef::Catalog(key) => {
+                row.refractive_index = refractive_index.unwrap_or_default();
+                row.material_key = Some(key.clone());
+                row.model_glass = model_glass;
+                material_key.is_some()
+            }
+            MaterialRef::ModelGlass(glass) => {
+                row.refractive_index = refractive_index.unwrap_or_default();
+                row.material_key = material_key;
+                row.model_glass = Some(ModelGlassRow {
+                    nd: glass.nd().to_string(),
+                    vd: glass.vd().to_string(),
+                    delta_pgf: glass.delta_pgf().to_string(),
+                });
+                model_glass.is_some()
+            }
+            other => {
+                bail!(
+                    "surface {surface_idx}: the surfaces table cannot hold the material {other:?}"
+                )
+            }
+        };
+        if saved_twice {
+            bail!("surface {surface_idx}: the material {material:?} is saved twice");
+        }
+
+        // A design in materials mode cannot be converted without the ri-info
+        // feature, so its materials are taken as they are saved.
+        #[cfg(not(feature = "ri-info"))]
+        if use_materials {
+            return Ok(row);
+        }
+        let in_use = surface_material(surface_idx, &row, use_materials)?;
+        if in_use != material {
+            bail!(
+                "surface {surface_idx}: the saved material {material:?} is not the one in use, \
+                 {in_use:?}"
+            );
+        }
+        Ok(row)
+    }
 }
 
 impl SystemSpecs {
@@ -479,6 +1246,32 @@ impl SystemSpecs {
             .retain(|g| !g.component_first_surfs.is_empty());
     }
 
+    /// Add a material loaded from a file, replacing any with the same key.
+    pub fn add_custom_material(&mut self, material: CustomMaterial) {
+        match self
+            .custom_materials
+            .iter_mut()
+            .find(|m| m.key == material.key)
+        {
+            Some(existing) => *existing = material,
+            None => self.custom_materials.push(material),
+        }
+    }
+
+    /// Remove a material loaded from a file and every reference to it.
+    pub fn remove_custom_material(&mut self, key: &str) {
+        self.custom_materials.retain(|m| m.key != key);
+        self.selected_materials.retain(|k| k != key);
+        if self.background_material_key.as_deref() == Some(key) {
+            self.background_material_key = None;
+        }
+        for surf in &mut self.surfaces {
+            if surf.material_key.as_deref() == Some(key) {
+                surf.material_key = None;
+            }
+        }
+    }
+
     /// Return the active solve for a given cell, if any.
     pub fn solve_for(&self, surface_index: usize, parameter: SolveParameter) -> Option<&SolveSpec> {
         self.solves
@@ -523,6 +1316,7 @@ impl Default for SystemSpecs {
             field_mode: FieldMode::Angle,
             use_materials: false,
             selected_materials: Vec::new(),
+            custom_materials: Vec::new(),
             cross_section_n_rays: 3,
             full_pupil_spacing: "0.1".into(),
             n_fan_rays: 65,
@@ -531,6 +1325,8 @@ impl Default for SystemSpecs {
             stop_surface: None,
             solves: Vec::new(),
             lens_groups: Vec::new(),
+            show_aperture_details: false,
+            thermal: ThermalSettings::default(),
         }
     }
 }
@@ -794,4 +1590,225 @@ mod tests {
         // Wrong index
         assert!(specs.solve_for(3, SolveParameter::Thickness).is_none());
     }
+
+    // --- XYPolynomialPopupState ---
+
+    fn xy_popup(text: &str) -> XYPolynomialPopupState {
+        XYPolynomialPopupState {
+            surface_index: 1,
+            normalization_radius: "10".into(),
+            text: text.into(),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn xy_popup_parses_mixed_line_forms() {
+        // Header row, comment, "i j c" with tabs, "term c" with a comma, then a
+        // bare value continuing from the previous term.
+        let popup = xy_popup("i\tj\tcoef\n# comment\n2\t0\t1e-3\n4, -2e-4\n5e-4\n");
+        let coefficients = popup.parse_coefficients().unwrap();
+        assert_eq!(coefficients, vec!["0", "0", "0.001", "-0.0002", "0.0005"]);
+    }
+
+    #[test]
+    fn xy_popup_rejects_out_of_range_terms() {
+        assert!(xy_popup("0 0 1.0").parse_coefficients().is_err());
+        assert!(xy_popup("15 6 1.0").parse_coefficients().is_err());
+        assert!(xy_popup("0 1.0").parse_coefficients().is_err());
+        assert!(xy_popup("1 0 abc").parse_coefficients().is_err());
+    }
+
+    #[test]
+    fn xy_popup_skips_separator_only_lines() {
+        let popup = xy_popup("1, 0.5\n,,\n;\n");
+        assert_eq!(popup.parse_coefficients().unwrap(), vec!["0.5"]);
+    }
+
+    // --- Saved materials ---
+
+    /// Saves a design whose first row is `row`, and returns that row.
+    fn saved(row: &SurfaceRow, use_materials: bool) -> serde_json::Value {
+        let mut specs = SystemSpecs {
+            use_materials,
+            ..SystemSpecs::default()
+        };
+        specs.surfaces[1] = row.clone();
+        serde_json::to_value(specs).unwrap()["surfaces"][1].take()
+    }
+
+    /// Loads a design whose first row is saved as `row`, and returns that row.
+    fn loaded(row: serde_json::Value, use_materials: bool) -> Result<SurfaceRow, String> {
+        let mut specs = serde_json::to_value(SystemSpecs {
+            use_materials,
+            ..SystemSpecs::default()
+        })
+        .unwrap();
+        specs["surfaces"][1] = row;
+        serde_json::from_value::<SystemSpecs>(specs)
+            .map(|specs| specs.surfaces[1].clone())
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn rows_save_their_material_as_a_reference() {
+        let row = SurfaceRow::new_sphere("12.5", "50.0", "5.0", "1.515");
+        let json = saved(&row, false);
+        assert_eq!(
+            json["material"],
+            serde_json::json!({"Constant": {"n": 1.515, "k": 0.0}})
+        );
+        assert!(json.get("refractive_index").is_none());
+        let back = loaded(json, false).unwrap();
+        assert_eq!(back.refractive_index, "1.515");
+
+        // A model glass is saved as the reference; the material key and index
+        // it replaces are kept beside it.
+        let mut row = row;
+        row.material_key = Some("SCHOTT:N-BK7".into());
+        row.model_glass = Some(ModelGlassRow::new("1.7"));
+        let json = saved(&row, true);
+        assert_eq!(
+            json["material"],
+            serde_json::json!({"ModelGlass": {"nd": 1.7, "vd": 50.0, "delta_pgf": 0.0}})
+        );
+        assert_eq!(json["material_key"], "SCHOTT:N-BK7");
+        assert_eq!(json["refractive_index"], "1.515");
+        assert!(json.get("model_glass").is_none());
+        let back = loaded(json, true).unwrap();
+        assert_eq!(back.model_glass, Some(ModelGlassRow::new("1.7")));
+        assert_eq!(back.material_key.as_deref(), Some("SCHOTT:N-BK7"));
+        assert_eq!(back.refractive_index, "1.515");
+
+        // Text that does not parse is saved as it is.
+        row.model_glass = Some(ModelGlassRow::new("1.7x"));
+        let json = saved(&row, false);
+        assert!(json.get("material").is_none());
+        let back = loaded(json, false).unwrap();
+        assert_eq!(back.model_glass, Some(ModelGlassRow::new("1.7x")));
+    }
+
+    #[test]
+    fn rows_save_the_material_in_use() {
+        // Without materials, the constant index is used and the material key
+        // is kept beside it.
+        let mut row = SurfaceRow::new_sphere("12.5", "50.0", "5.0", "1.515");
+        row.material_key = Some("SCHOTT:N-BK7".into());
+        let json = saved(&row, false);
+        assert_eq!(
+            json["material"],
+            serde_json::json!({"Constant": {"n": 1.515, "k": 0.0}})
+        );
+        assert_eq!(json["material_key"], "SCHOTT:N-BK7");
+        let back = loaded(json, false).unwrap();
+        assert_eq!(back.material_key.as_deref(), Some("SCHOTT:N-BK7"));
+
+        #[cfg(feature = "ri-info")]
+        {
+            let json = saved(&row, true);
+            assert_eq!(
+                json["material"],
+                serde_json::json!({"Catalog": "SCHOTT:N-BK7"})
+            );
+            assert_eq!(json["refractive_index"], "1.515");
+            let back = loaded(json, true).unwrap();
+            assert_eq!(back.refractive_index, "1.515");
+        }
+    }
+
+    #[test]
+    fn rows_with_ambiguous_materials_are_rejected() {
+        let json = saved(
+            &SurfaceRow::new_sphere("12.5", "50.0", "5.0", "1.515"),
+            false,
+        );
+
+        // The index is saved twice.
+        let mut twice = json.clone();
+        twice["refractive_index"] = "1.6".into();
+        let err = loaded(twice, false).unwrap_err();
+        assert!(err.contains("saved twice"), "{err}");
+
+        // A material key is saved as the material, but the constant index
+        // would be used.
+        let mut not_in_use = json;
+        not_in_use["material"] = serde_json::json!({"Catalog": "SCHOTT:N-BK7"});
+        not_in_use["refractive_index"] = "1.515".into();
+        let err = loaded(not_in_use, false).unwrap_err();
+        assert!(err.contains("not the one in use"), "{err}");
+    }
+
+    #[test]
+    fn rows_load_earlier_material_fields() {
+        let mut json = saved(
+            &SurfaceRow::new_sphere("12.5", "50.0", "5.0", "1.515"),
+            false,
+        );
+        let row = json.as_object_mut().unwrap();
+        row.remove("material");
+        row.insert("refractive_index".into(), "1.6".into());
+        row.insert("material_key".into(), "SCHOTT:N-BK7".into());
+        let back = loaded(json.clone(), false).unwrap();
+        assert_eq!(back.refractive_index, "1.6");
+        assert_eq!(back.material_key.as_deref(), Some("SCHOTT:N-BK7"));
+
+        json["material"] = serde_json::json!("Air");
+        let err = loaded(json, false).unwrap_err();
+        assert!(err.contains("cannot hold the material"), "{err}");
+    }
+
+    #[test]
+    fn xy_popup_round_trips_row_coefficients() {
+        let row = SurfaceRow::new_xy_polynomial(
+            "10",
+            "50",
+            "0",
+            "10",
+            &["0", "0", "0.001", "0", "-0.002"],
+            "5",
+            "1.5",
+        );
+        let popup = XYPolynomialPopupState::open(1, &row);
+        assert_eq!(popup.text, "2 0 0.001\n0 2 -0.002");
+        assert_eq!(
+            popup.parse_coefficients().unwrap(),
+            vec!["0", "0", "0.001", "0", "-0.002"]
+        );
+    }
+
+    #[test]
+    fn thermal_temperatures_are_evenly_spaced() {
+        let settings = ThermalSettings::default();
+        assert_eq!(
+            settings.temperatures(),
+            vec![-20.0, -10.0, 0.0, 10.0, 20.0, 30.0, 40.0, 50.0, 60.0]
+        );
+
+        let single = ThermalSettings {
+            n_temperatures: 1,
+            ..Default::default()
+        };
+        assert_eq!(single.temperatures(), vec![-20.0, 60.0]);
+    }
+
+    #[test]
+    fn custom_materials_are_replaced_and_removed() {
+        let mut specs = SystemSpecs::default();
+        let oil = CustomMaterial::new("oils", "type: A", "DATA: old".into());
+        assert_eq!(oil.key, "user:oils:type_ A");
+
+        specs.add_custom_material(oil.clone());
+        specs.add_custom_material(CustomMaterial::new("oils", "type: A", "DATA: new".into()));
+        assert_eq!(specs.custom_materials.len(), 1);
+        assert_eq!(specs.custom_materials[0].yaml, "DATA: new");
+
+        specs.selected_materials.push(oil.key.clone());
+        specs.background_material_key = Some(oil.key.clone());
+        specs.surfaces[1].material_key = Some(oil.key.clone());
+        specs.remove_custom_material(&oil.key);
+        assert!(specs.custom_materials.is_empty());
+        assert!(specs.selected_materials.is_empty());
+        assert_eq!(specs.background_material_key, None);
+        assert_eq!(specs.surfaces[1].material_key, None);
+    }
 }
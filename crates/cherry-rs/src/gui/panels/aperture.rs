@@ -34,7 +34,13 @@ pub fn aperture_panel(ui: &mut egui::Ui, specs: &mut SystemSpecs) -> bool {
         .filter(|(_, row)| {
             matches!(
                 row.variant,
-                SurfaceVariant::Conic | SurfaceVariant::Sphere | SurfaceVariant::Iris
+                SurfaceVariant::Conic
+                    | SurfaceVariant::EvenAsphere
+                    | SurfaceVariant::Sphere
+                    | SurfaceVariant::XYPolynomial
+                    | SurfaceVariant::Grating
+                    | SurfaceVariant::CardinalLens
+                    | SurfaceVariant::Iris
             )
         })
         .map(|(i, _)| i)
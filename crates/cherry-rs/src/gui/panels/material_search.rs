@@ -0,0 +1,232 @@
+//! Fuzzy search over the materials of a `MaterialIndex`.
+use std::cmp::Reverse;
+
+use super::materials::{MaterialIndex, MaterialInfo};
+
+/// Score of each matched character.
+const MATCH: i32 = 16;
+/// Bonus for a character that follows the previous match directly.
+const CONSECUTIVE: i32 = 12;
+/// Bonus for a match at the start of a word.
+const WORD_START: i32 = 10;
+/// Penalty per unmatched character between two matches, up to `MAX_GAP`.
+const GAP: i32 = 2;
+const MAX_GAP: usize = 8;
+/// Bonus for a candidate that starts with the query, or equals it.
+const PREFIX: i32 = 25;
+const EXACT: i32 = 100;
+
+/// Limits on the properties of the materials that a search returns.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MaterialFilter {
+    /// Shelf of the refractiveindex.info database, or glass catalog.
+    pub shelf: Option<String>,
+    /// Range of the refractive index at the d line.
+    pub nd: Option<[f64; 2]>,
+    /// Range of the Abbe number.
+    pub vd: Option<[f64; 2]>,
+    /// Wavelengths, in µm, at which the material must be defined.
+    pub wavelengths: Option<[f64; 2]>,
+}
+
+impl MaterialFilter {
+    /// Returns true if the material passes every active limit. Materials
+    /// whose properties are unknown fail the limits on them.
+    pub fn accepts(&self, key: &str, info: &MaterialInfo) -> bool {
+        let in_range = |value: Option<f64>, range: Option<[f64; 2]>| match range {
+            None => true,
+            Some([min, max]) => value.is_some_and(|v| (min..=max).contains(&v)),
+        };
+
+        if let Some(shelf) = &self.shelf
+            && key.split(':').next() != Some(shelf.as_str())
+        {
+            return false;
+        }
+        if !in_range(info.nd, self.nd) || !in_range(info.vd, self.vd) {
+            return false;
+        }
+        match (self.wavelengths, info.wavelength_range) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some([min, max]), Some([lo, hi])) => lo <= min && max <= hi,
+        }
+    }
+}
+
+/// Scores how well a query matches a candidate, ignoring case.
+///
+/// The characters of the query must appear in the candidate in order, but not
+/// necessarily next to each other. Consecutive matches, matches at the start
+/// of words and matches near the start of the candidate score higher. Returns
+/// `None` if the candidate does not contain the query.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    let chars: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let first = *query.first()?;
+
+    let best = (0..lower.len())
+        .filter(|&start| lower[start] == first)
+        .filter_map(|start| score_from(&query, &chars, &lower, start))
+        .max()?;
+
+    let bonus = if lower == query {
+        EXACT
+    } else if lower.starts_with(&query) {
+        PREFIX
+    } else {
+        0
+    };
+    Some(best + bonus)
+}
+
+/// Scores the greedy match of the query that starts at `start`.
+fn score_from(query: &[char], chars: &[char], lower: &[char], start: usize) -> Option<i32> {
+    let mut score = -(start.min(MAX_GAP) as i32);
+    let mut previous: Option<usize> = None;
+    let mut i = start;
+    for &q in query {
+        i += lower[i..].iter().position(|&c| c == q)?;
+        score += MATCH;
+        match previous {
+            Some(p) if p + 1 == i => score += CONSECUTIVE,
+            Some(p) => score -= (i - p - 1).min(MAX_GAP) as i32 * GAP,
+            None => {}
+        }
+        if is_word_start(chars, i) {
+            score += WORD_START;
+        }
+        previous = Some(i);
+        i += 1;
+    }
+    Some(score)
+}
+
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    i == 0
+        || !chars[i - 1].is_alphanumeric()
+        || chars[i - 1].is_lowercase() && chars[i].is_uppercase()
+}
+
+/// Returns the names under which a material can be found: the parts of its
+/// key, the key itself and its aliases.
+fn names<'a>(key: &'a str, info: &'a MaterialInfo) -> impl Iterator<Item = &'a str> {
+    key.split(':')
+        .skip(1)
+        .chain(std::iter::once(key))
+        .chain(info.aliases.iter().map(String::as_str))
+}
+
+/// Scores a material against every word of a query. Each word is matched
+/// against the best of the material's names.
+fn score(words: &[&str], key: &str, info: &MaterialInfo) -> Option<i32> {
+    words.iter().try_fold(0, |total, word| {
+        names(key, info)
+            .filter_map(|name| fuzzy_score(word, name))
+            .max()
+            .map(|s| total + s)
+    })
+}
+
+/// Finds the materials that match a query and pass a filter, best first.
+///
+/// An empty query matches every material that passes the filter, in key
+/// order. At most `limit` keys are returned.
+pub fn search<'a>(
+    index: &'a MaterialIndex,
+    query: &str,
+    filter: &MaterialFilter,
+    limit: usize,
+) -> Vec<&'a str> {
+    let words: Vec<&str> = query.split_whitespace().collect();
+    let mut hits: Vec<(i32, &str)> = index
+        .info
+        .iter()
+        .filter(|(key, info)| filter.accepts(key, info))
+        .filter_map(|(key, info)| score(&words, key, info).map(|s| (s, key.as_str())))
+        .collect();
+    if !words.is_empty() {
+        hits.sort_by_key(|&(s, key)| (Reverse(s), key.len(), key));
+    }
+    hits.into_iter().take(limit).map(|(_, key)| key).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> MaterialIndex {
+        let mut index = MaterialIndex::default();
+        let glass = |nd, vd| MaterialInfo {
+            nd: Some(nd),
+            vd: Some(vd),
+            wavelength_range: Some([0.3, 2.5]),
+            aliases: Vec::new(),
+        };
+        index.insert("SCHOTT:N-BK7", glass(1.5168, 64.17));
+        index.insert("SCHOTT:N-BAK1", glass(1.5725, 57.55));
+        index.insert("SCHOTT:N-SF6", glass(1.8052, 25.36));
+        index.insert("popular_glass:BK7:SCHOTT", glass(1.5168, 64.17));
+        index.insert(
+            "main:Ag:Johnson",
+            MaterialInfo {
+                aliases: vec!["Ag - Silver".into()],
+                ..Default::default()
+            },
+        );
+        index
+    }
+
+    #[test]
+    fn closer_matches_score_higher() {
+        let exact = fuzzy_score("n-bk7", "N-BK7").unwrap();
+        let prefix = fuzzy_score("n-bk", "N-BK7").unwrap();
+        let scattered = fuzzy_score("nbk7", "N-BK7").unwrap();
+        let later = fuzzy_score("bk7", "SCHOTT N-BK7").unwrap();
+        assert!(exact > prefix);
+        assert!(fuzzy_score("bk7", "BK7").unwrap() > later);
+        assert!(scattered > 0);
+        assert_eq!(fuzzy_score("nbk7", "N-BAK1"), None);
+        assert_eq!(fuzzy_score("", "N-BK7"), None);
+    }
+
+    #[test]
+    fn search_ranks_and_filters() {
+        let index = index();
+        let all = MaterialFilter::default();
+
+        assert_eq!(
+            search(&index, "bk7", &all, 10),
+            vec!["popular_glass:BK7:SCHOTT", "SCHOTT:N-BK7"]
+        );
+        assert_eq!(search(&index, "silver", &all, 10), vec!["main:Ag:Johnson"]);
+        assert_eq!(search(&index, "bk7 schott", &all, 1).len(), 1);
+
+        let flint = MaterialFilter {
+            nd: Some([1.6, 2.0]),
+            vd: Some([20.0, 30.0]),
+            ..Default::default()
+        };
+        assert_eq!(search(&index, "", &flint, 10), vec!["SCHOTT:N-SF6"]);
+
+        let schott = MaterialFilter {
+            shelf: Some("SCHOTT".into()),
+            wavelengths: Some([0.4, 0.7]),
+            ..Default::default()
+        };
+        assert_eq!(
+            search(&index, "", &schott, 10),
+            vec!["SCHOTT:N-BAK1", "SCHOTT:N-BK7", "SCHOTT:N-SF6"]
+        );
+
+        let infrared = MaterialFilter {
+            wavelengths: Some([1.0, 3.0]),
+            ..Default::default()
+        };
+        assert!(search(&index, "", &infrared, 10).is_empty());
+    }
+}
@@ -1,36 +1,139 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use super::super::model::SystemSpecs;
+use serde::Deserialize;
 
-/// Pre-computed shelf → book → page hierarchy from the material store keys.
+use super::super::model::{CUSTOM_MATERIAL_SHELF, CustomMaterial, SystemSpecs};
+use super::material_search::{MaterialFilter, search};
+use crate::{Material, RefractiveIndexSpec, abbe_number, nd};
+
+/// Properties of a material by which searches are filtered.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct MaterialInfo {
+    /// Refractive index at the d line.
+    pub nd: Option<f64>,
+    /// Abbe number at the d line.
+    pub vd: Option<f64>,
+    /// Shortest and longest wavelengths at which the index is defined, in µm.
+    pub wavelength_range: Option<[f64; 2]>,
+    /// Other names of the material, such as its element or compound name.
+    pub aliases: Vec<String>,
+}
+
+impl MaterialInfo {
+    /// Compute the properties of a material. Those it cannot provide, for
+    /// example because the d, F or C line lies outside its data, are `None`.
+    pub fn of(material: &dyn RefractiveIndexSpec) -> Self {
+        Self {
+            nd: nd(material).ok(),
+            vd: abbe_number(material).ok(),
+            wavelength_range: material.wavelength_range(),
+            aliases: Vec::new(),
+        }
+    }
+
+    /// Compute the properties of a refractiveindex.info material, whose
+    /// aliases are the database's divider labels, e.g. "Ag - Silver".
+    pub fn of_rii(material: &Material) -> Self {
+        Self {
+            aliases: [&material.shelf_divider, &material.book_divider]
+                .into_iter()
+                .flatten()
+                .cloned()
+                .collect(),
+            ..Self::of(material)
+        }
+    }
+}
+
+/// An entry of the material index file: either a bare key or a key with the
+/// material's properties.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum IndexEntry {
+    Key(String),
+    Material {
+        key: String,
+        #[serde(flatten)]
+        info: MaterialInfo,
+    },
+}
+
+/// Pre-computed shelf → book → page hierarchy from the material store keys,
+/// and catalog → glass hierarchy from the glass catalog keys.
 #[derive(Default, Clone)]
 pub struct MaterialIndex {
     /// shelf -> set of books on that shelf
     pub shelves: BTreeMap<String, BTreeSet<String>>,
     /// (shelf, book) -> set of pages in that book
     pub pages: BTreeMap<(String, String), BTreeSet<String>>,
+    /// glass catalog -> set of glasses in that catalog
+    pub catalogs: BTreeMap<String, BTreeSet<String>>,
+    /// key -> properties of every material, for searching
+    pub info: BTreeMap<String, MaterialInfo>,
 }
 
 impl MaterialIndex {
-    /// Build the index by parsing store keys of the form "shelf:book:page".
+    /// Build the index by parsing store keys of the form "shelf:book:page"
+    /// and glass catalog keys of the form "catalog:glass".
     pub fn build_from_keys<'a>(keys: impl Iterator<Item = &'a String>) -> Self {
         let mut idx = Self::default();
         for key in keys {
-            let parts: Vec<&str> = key.splitn(3, ':').collect();
-            if parts.len() == 3 {
-                let (shelf, book, page) = (parts[0], parts[1], parts[2]);
-                idx.shelves
-                    .entry(shelf.to_string())
-                    .or_default()
-                    .insert(book.to_string());
-                idx.pages
-                    .entry((shelf.to_string(), book.to_string()))
-                    .or_default()
-                    .insert(page.to_string());
-            }
+            idx.insert(key, MaterialInfo::default());
         }
         idx
     }
+
+    /// Build the index from the JSON written by `gen-rii-index`: an array of
+    /// keys, or of objects with a `key` and the fields of `MaterialInfo`.
+    pub fn from_json(bytes: &[u8]) -> serde_json::Result<Self> {
+        let entries: Vec<IndexEntry> = serde_json::from_slice(bytes)?;
+        let mut idx = Self::default();
+        for entry in entries {
+            match entry {
+                IndexEntry::Key(key) => idx.insert(&key, MaterialInfo::default()),
+                IndexEntry::Material { key, info } => idx.insert(&key, info),
+            }
+        }
+        Ok(idx)
+    }
+
+    /// Add a material to the index.
+    pub fn insert(&mut self, key: &str, info: MaterialInfo) {
+        self.info.insert(key.to_string(), info);
+        let parts: Vec<&str> = key.splitn(3, ':').collect();
+        if let [catalog, glass] = parts[..] {
+            self.catalogs
+                .entry(catalog.to_string())
+                .or_default()
+                .insert(glass.to_string());
+        } else if let [shelf, book, page] = parts[..] {
+            self.shelves
+                .entry(shelf.to_string())
+                .or_default()
+                .insert(book.to_string());
+            self.pages
+                .entry((shelf.to_string(), book.to_string()))
+                .or_default()
+                .insert(page.to_string());
+        }
+    }
+
+    /// Replace the materials on the user shelf by those loaded from files.
+    pub fn set_custom_materials(&mut self, materials: &[CustomMaterial]) {
+        let prefix = format!("{CUSTOM_MATERIAL_SHELF}:");
+        self.shelves.remove(CUSTOM_MATERIAL_SHELF);
+        self.pages
+            .retain(|(shelf, _), _| shelf != CUSTOM_MATERIAL_SHELF);
+        self.info.retain(|key, _| !key.starts_with(&prefix));
+        for material in materials {
+            let info = material
+                .parse()
+                .map(|m| MaterialInfo::of_rii(&m))
+                .unwrap_or_default();
+            self.insert(&material.key, info);
+        }
+    }
 }
 
 /// Transient UI state for the material browser dropdowns (not serialized).
@@ -39,8 +142,18 @@ pub struct MaterialBrowserState {
     pub selected_shelf: Option<String>,
     pub selected_book: Option<String>,
     pub selected_page: Option<String>,
+    pub selected_catalog: Option<String>,
+    pub selected_glass: Option<String>,
+    /// Text of the search box.
+    pub query: String,
+    pub filter: MaterialFilter,
+    /// Position of the highlighted search result.
+    pub highlighted: usize,
 }
 
+/// Maximum number of search results shown.
+const MAX_RESULTS: usize = 50;
+
 /// Draw the materials browser panel. Returns true if specs were modified.
 pub fn materials_panel(
     ui: &mut egui::Ui,
@@ -73,6 +186,9 @@ pub fn materials_panel(
         return changed;
     }
 
+    changed |= search_ui(ui, specs, index, browser);
+    ui.separator();
+
     // Browser section
     ui.heading("Browse Materials");
     ui.add_space(4.0);
@@ -164,6 +280,11 @@ pub fn materials_panel(
         }
     }
 
+    if !index.catalogs.is_empty() {
+        ui.separator();
+        changed |= glass_catalog_browser(ui, specs, index, browser);
+    }
+
     ui.separator();
     ui.heading("Selected Materials");
     ui.add_space(4.0);
@@ -193,5 +314,388 @@ pub fn materials_panel(
         ui.label("No materials selected. Browse above to add.");
     }
 
+    if !specs.custom_materials.is_empty() {
+        ui.separator();
+        ui.heading("Loaded Files");
+        ui.add_space(4.0);
+
+        let mut remove_key = None;
+        for material in &specs.custom_materials {
+            ui.horizontal(|ui| {
+                ui.label(&material.key);
+                if ui.small_button("Unload").clicked() {
+                    remove_key = Some(material.key.clone());
+                }
+            });
+        }
+        if let Some(key) = remove_key {
+            specs.remove_custom_material(&key);
+            changed = true;
+        }
+    }
+
     changed
 }
+
+/// Add a material key to the selected materials. Returns true if it was not
+/// already selected.
+pub fn select_material(specs: &mut SystemSpecs, key: &str) -> bool {
+    if specs.selected_materials.iter().any(|k| k == key) {
+        return false;
+    }
+    specs.selected_materials.push(key.to_string());
+    true
+}
+
+/// Draw the search box, its filters and results. The arrow keys move the
+/// highlight through the results and Enter selects the highlighted one.
+/// Returns true if a material was selected.
+fn search_ui(
+    ui: &mut egui::Ui,
+    specs: &mut SystemSpecs,
+    index: &MaterialIndex,
+    browser: &mut MaterialBrowserState,
+) -> bool {
+    let mut changed = false;
+
+    ui.heading("Search Materials");
+    ui.add_space(4.0);
+
+    let response = ui.add(
+        egui::TextEdit::singleline(&mut browser.query)
+            .hint_text("Name, e.g. N-BK7")
+            .desired_width(240.0),
+    );
+    if response.changed() {
+        browser.highlighted = 0;
+    }
+
+    egui::CollapsingHeader::new("Filters")
+        .id_salt("material_filters")
+        .show(ui, |ui| filters_ui(ui, specs, index, &mut browser.filter));
+
+    let show_results = !browser.query.trim().is_empty() || browser.filter != Default::default();
+    if !show_results {
+        return false;
+    }
+    let results = search(index, &browser.query, &browser.filter, MAX_RESULTS);
+    browser.highlighted = browser.highlighted.min(results.len().saturating_sub(1));
+
+    // Keyboard navigation while the search box has focus. Enter makes the
+    // single-line edit lose focus, so focus is requested back after it.
+    let (down, up, enter) = ui.input(|i| {
+        (
+            i.key_pressed(egui::Key::ArrowDown),
+            i.key_pressed(egui::Key::ArrowUp),
+            i.key_pressed(egui::Key::Enter),
+        )
+    });
+    if response.has_focus() {
+        if down {
+            browser.highlighted = (browser.highlighted + 1).min(results.len().saturating_sub(1));
+        }
+        if up {
+            browser.highlighted = browser.highlighted.saturating_sub(1);
+        }
+    }
+    if response.lost_focus() && enter {
+        if let Some(key) = results.get(browser.highlighted) {
+            changed |= select_material(specs, key);
+        }
+        response.request_focus();
+    }
+
+    if results.is_empty() {
+        ui.label("No matching materials.");
+        return changed;
+    }
+
+    egui::ScrollArea::vertical()
+        .id_salt("material_search_results")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            for (i, key) in results.iter().enumerate() {
+                let info = &index.info[*key];
+                let mut text = (*key).to_string();
+                if let Some(nd) = info.nd {
+                    text += &format!("  nd {nd:.4}");
+                }
+                if let Some(vd) = info.vd {
+                    text += &format!("  Vd {vd:.2}");
+                }
+                let selected = specs.selected_materials.iter().any(|k| k == key);
+                let label = ui.selectable_label(i == browser.highlighted, text);
+                if i == browser.highlighted && (up || down) {
+                    label.scroll_to_me(None);
+                }
+                if label
+                    .on_hover_text(if selected {
+                        "Already selected"
+                    } else {
+                        "Click to add to selected"
+                    })
+                    .clicked()
+                {
+                    browser.highlighted = i;
+                    changed |= select_material(specs, key);
+                }
+            }
+        });
+
+    changed
+}
+
+/// Draw the editors of the search filters.
+fn filters_ui(
+    ui: &mut egui::Ui,
+    specs: &SystemSpecs,
+    index: &MaterialIndex,
+    filter: &mut MaterialFilter,
+) {
+    let shelves: BTreeSet<&String> = index.shelves.keys().chain(index.catalogs.keys()).collect();
+    egui::ComboBox::from_label("Shelf or catalog")
+        .selected_text(filter.shelf.as_deref().unwrap_or("All"))
+        .width(200.0)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut filter.shelf, None, "All");
+            for shelf in shelves {
+                ui.selectable_value(&mut filter.shelf, Some(shelf.clone()), shelf);
+            }
+        });
+
+    range_filter(ui, "nd", &mut filter.nd, [1.4, 1.8], 0.001, 1.0..=5.0);
+    range_filter(ui, "Vd", &mut filter.vd, [20.0, 80.0], 0.1, 0.0..=200.0);
+
+    // Default to the wavelengths of the system, if they parse.
+    let system: Vec<f64> = specs
+        .wavelengths
+        .iter()
+        .filter_map(|w| w.trim().parse().ok())
+        .collect();
+    let default = match (
+        system.iter().copied().reduce(f64::min),
+        system.iter().copied().reduce(f64::max),
+    ) {
+        (Some(min), Some(max)) => [min, max],
+        _ => [0.4, 0.7],
+    };
+    range_filter(
+        ui,
+        "Defined from (\u{00b5}m)",
+        &mut filter.wavelengths,
+        default,
+        0.001,
+        0.0..=100.0,
+    );
+}
+
+/// Draw a checkbox that enables a range filter and the editors of its bounds.
+fn range_filter(
+    ui: &mut egui::Ui,
+    label: &str,
+    range: &mut Option<[f64; 2]>,
+    default: [f64; 2],
+    speed: f64,
+    limits: std::ops::RangeInclusive<f64>,
+) {
+    ui.horizontal(|ui| {
+        let mut enabled = range.is_some();
+        if ui.checkbox(&mut enabled, label).changed() {
+            *range = enabled.then_some(default);
+        }
+        if let Some([min, max]) = range {
+            ui.add(
+                egui::DragValue::new(min)
+                    .range(*limits.start()..=*max)
+                    .speed(speed),
+            );
+            ui.label("to");
+            ui.add(
+                egui::DragValue::new(max)
+                    .range(*min..=*limits.end())
+                    .speed(speed),
+            );
+        }
+    });
+}
+
+/// Draw the catalog and glass dropdowns for the glasses loaded from glass
+/// catalogs. Returns true if a glass was added to the selected materials.
+fn glass_catalog_browser(
+    ui: &mut egui::Ui,
+    specs: &mut SystemSpecs,
+    index: &MaterialIndex,
+    browser: &mut MaterialBrowserState,
+) -> bool {
+    ui.heading("Glass Catalogs");
+    ui.add_space(4.0);
+
+    let catalog_label = browser
+        .selected_catalog
+        .as_deref()
+        .unwrap_or("Select catalog...");
+    egui::ComboBox::from_label("Catalog")
+        .selected_text(catalog_label)
+        .width(200.0)
+        .show_ui(ui, |ui| {
+            for catalog in index.catalogs.keys() {
+                if ui
+                    .selectable_label(
+                        browser.selected_catalog.as_deref() == Some(catalog),
+                        catalog,
+                    )
+                    .clicked()
+                {
+                    browser.selected_catalog = Some(catalog.clone());
+                    browser.selected_glass = None;
+                }
+            }
+        });
+
+    if let Some(glasses) = browser
+        .selected_catalog
+        .as_ref()
+        .and_then(|catalog| index.catalogs.get(catalog))
+    {
+        let glass_label = browser
+            .selected_glass
+            .as_deref()
+            .unwrap_or("Select glass...");
+        egui::ComboBox::from_label("Glass")
+            .selected_text(glass_label)
+            .width(200.0)
+            .show_ui(ui, |ui| {
+                for glass in glasses {
+                    if ui
+                        .selectable_label(browser.selected_glass.as_deref() == Some(glass), glass)
+                        .clicked()
+                    {
+                        browser.selected_glass = Some(glass.clone());
+                    }
+                }
+            });
+    }
+
+    ui.add_space(4.0);
+    let can_add = browser.selected_catalog.is_some() && browser.selected_glass.is_some();
+    if ui
+        .add_enabled(can_add, egui::Button::new("Add glass to selected"))
+        .clicked()
+        && let (Some(catalog), Some(glass)) = (&browser.selected_catalog, &browser.selected_glass)
+    {
+        let key = format!("{catalog}:{glass}");
+        if !specs.selected_materials.contains(&key) {
+            specs.selected_materials.push(key);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_separates_database_and_catalog_keys() {
+        let keys: Vec<String> = ["glass:BK7:SCHOTT", "SCHOTT:N-BK7", "SCHOTT:N-SF6", "lonely"]
+            .iter()
+            .map(|k| k.to_string())
+            .collect();
+        let index = MaterialIndex::build_from_keys(keys.iter());
+
+        assert_eq!(index.shelves.len(), 1);
+        assert!(index.pages[&("glass".into(), "BK7".into())].contains("SCHOTT"));
+        let glasses: Vec<&String> = index.catalogs["SCHOTT"].iter().collect();
+        assert_eq!(glasses, ["N-BK7", "N-SF6"]);
+    }
+
+    #[test]
+    fn custom_materials_replace_the_user_shelf() {
+        let keys = ["glass:BK7:SCHOTT".to_string()];
+        let mut index = MaterialIndex::build_from_keys(keys.iter());
+
+        let oil = CustomMaterial::new("oils", "type-A", String::new());
+        let coating = CustomMaterial::new("coatings", "MgF2", String::new());
+        index.set_custom_materials(&[oil.clone(), coating]);
+        assert_eq!(index.shelves["user"].len(), 2);
+
+        index.set_custom_materials(&[oil]);
+        assert_eq!(index.shelves.len(), 2);
+        assert!(index.pages[&("user".into(), "oils".into())].contains("type-A"));
+        assert!(
+            !index
+                .pages
+                .contains_key(&("user".into(), "coatings".into()))
+        );
+
+        index.set_custom_materials(&[]);
+        assert_eq!(index.shelves.len(), 1);
+        assert_eq!(index.info.len(), 1);
+    }
+
+    #[test]
+    fn index_json_has_keys_with_optional_properties() {
+        let json = br#"["glass:BK7:SCHOTT",
+            {"key": "main:Ag:Johnson", "aliases": ["Ag - Silver"]},
+            {"key": "SCHOTT:N-BK7", "nd": 1.5168, "vd": 64.17, "wavelength_range": [0.3, 2.5]}]"#;
+        let index = MaterialIndex::from_json(json).unwrap();
+
+        assert_eq!(index.info.len(), 3);
+        assert_eq!(index.info["glass:BK7:SCHOTT"], MaterialInfo::default());
+        assert_eq!(index.info["main:Ag:Johnson"].aliases, ["Ag - Silver"]);
+        assert_eq!(index.info["SCHOTT:N-BK7"].nd, Some(1.5168));
+        assert!(index.catalogs["SCHOTT"].contains("N-BK7"));
+        assert!(MaterialIndex::from_json(b"[1]").is_err());
+    }
+
+    #[test]
+    fn material_info_has_the_abbe_number() {
+        let glass = crate::ModelGlass::new(1.5168, 64.17, 0.0).unwrap();
+        let info = MaterialInfo::of(&glass);
+
+        approx::assert_abs_diff_eq!(info.nd.unwrap(), 1.5168, epsilon = 1e-9);
+        approx::assert_abs_diff_eq!(info.vd.unwrap(), 64.17, epsilon = 1e-6);
+        assert_eq!(info.wavelength_range, Some([0.35, 2.5]));
+
+        let constant = crate::ConstantRefractiveIndex::new(1.5, 0.0);
+        let info = MaterialInfo::of(&constant);
+        assert_eq!((info.nd, info.vd), (Some(1.5), None));
+    }
+
+    #[test]
+    fn search_results_are_added_from_the_keyboard() {
+        use egui_kittest::{Harness, kittest::Queryable};
+
+        let keys = ["SCHOTT:N-BK7".to_string(), "SCHOTT:N-SF6".to_string()];
+        let index = MaterialIndex::build_from_keys(keys.iter());
+        let specs = SystemSpecs {
+            use_materials: true,
+            selected_materials: Vec::new(),
+            ..Default::default()
+        };
+        let mut harness = Harness::new_state(
+            |ctx, (specs, browser): &mut (SystemSpecs, MaterialBrowserState)| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    materials_panel(ui, specs, &index, browser);
+                });
+            },
+            (specs, MaterialBrowserState::default()),
+        );
+
+        harness
+            .get_by_role(egui::accesskit::Role::TextInput)
+            .focus();
+        harness.step();
+        harness
+            .get_by_role(egui::accesskit::Role::TextInput)
+            .type_text("sf");
+        harness.run();
+        harness.get_by_label("SCHOTT:N-SF6");
+        assert!(harness.query_by_label("SCHOTT:N-BK7").is_none());
+
+        harness.key_press(egui::Key::Enter);
+        harness.run();
+        assert_eq!(harness.state().0.selected_materials, ["SCHOTT:N-SF6"]);
+    }
+}
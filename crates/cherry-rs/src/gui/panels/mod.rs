@@ -2,6 +2,8 @@ mod aperture;
 mod fields;
 mod lens_overlay;
 #[cfg(feature = "ri-info")]
+mod material_search;
+#[cfg(feature = "ri-info")]
 mod materials;
 mod surfaces;
 mod system;
@@ -11,7 +13,11 @@ pub use aperture::aperture_panel;
 pub use fields::fields_panel;
 pub use lens_overlay::LensOverlayPanel;
 #[cfg(feature = "ri-info")]
-pub use materials::{MaterialBrowserState, MaterialIndex, materials_panel};
+pub use material_search::{MaterialFilter, fuzzy_score, search};
+#[cfg(feature = "ri-info")]
+pub use materials::{
+    MaterialBrowserState, MaterialIndex, MaterialInfo, materials_panel, select_material,
+};
 pub use surfaces::surfaces_panel;
 pub use system::system_panel;
 pub use wavelengths::wavelengths_panel;
@@ -1,9 +1,11 @@
 use egui_extras::{Column, TableBuilder};
 
 use super::super::model::{
-    SolveParameter, SolvePopupState, SurfaceKind, SurfaceVariant, SystemSpecs,
+    ApertureShape, ModelGlassRow, SolveParameter, SolvePopupState, SurfaceKind, SurfaceRow,
+    SurfaceVariant, SystemSpecs, XYPolynomialPopupState,
 };
 use super::{format_display_float, inf_formatter, inf_parser, parse_display_float};
+use crate::core::surfaces::even_asphere::MAX_ASPHERIC_COEFFICIENTS;
 use crate::gui::result_package::SolvedValues;
 
 /// Draw the surfaces editor panel. Returns true if any spec was modified.
@@ -12,6 +14,7 @@ pub fn surfaces_panel(
     specs: &mut SystemSpecs,
     solved_values: Option<&SolvedValues>,
     solve_popup: &mut Option<SolvePopupState>,
+    xy_popup: &mut Option<XYPolynomialPopupState>,
 ) -> bool {
     let mut changed = false;
 
@@ -23,16 +26,67 @@ pub fn surfaces_panel(
         Vec::new()
     };
 
-    let n_col_width = if use_materials { 140.0 } else { 80.0 };
+    let n_col_width = if use_materials { 210.0 } else { 150.0 };
 
     let has_reflecting = specs.surfaces.iter().any(|s| {
-        matches!(s.variant, SurfaceVariant::Conic | SurfaceVariant::Sphere)
-            && s.surface_kind == SurfaceKind::Reflecting
+        matches!(
+            s.variant,
+            SurfaceVariant::Conic
+                | SurfaceVariant::EvenAsphere
+                | SurfaceVariant::Sphere
+                | SurfaceVariant::XYPolynomial
+                | SurfaceVariant::Grating
+        ) && s.surface_kind == SurfaceKind::Reflecting
     });
-    let has_conic = specs
+    let has_conic = specs.surfaces.iter().any(|s| {
+        matches!(
+            s.variant,
+            SurfaceVariant::Conic | SurfaceVariant::EvenAsphere | SurfaceVariant::XYPolynomial
+        )
+    });
+    let has_asphere = specs
+        .surfaces
+        .iter()
+        .any(|s| s.variant == SurfaceVariant::EvenAsphere);
+    let has_xy = specs
+        .surfaces
+        .iter()
+        .any(|s| s.variant == SurfaceVariant::XYPolynomial);
+    let has_grating = specs
+        .surfaces
+        .iter()
+        .any(|s| s.variant == SurfaceVariant::Grating);
+    let has_cardinal = specs
+        .surfaces
+        .iter()
+        .any(|s| s.variant == SurfaceVariant::CardinalLens);
+    let has_model_glass = specs
+        .surfaces
+        .iter()
+        .any(|s| s.variant != SurfaceVariant::Image && s.model_glass.is_some());
+    let has_aperture = specs.surfaces.iter().any(has_clear_aperture);
+    let has_sized_aperture = specs.surfaces.iter().any(|s| {
+        has_clear_aperture(s)
+            && matches!(
+                s.aperture.shape,
+                ApertureShape::Rectangular | ApertureShape::Elliptical
+            )
+    });
+    let has_polygon = specs
         .surfaces
         .iter()
-        .any(|s| s.variant == SurfaceVariant::Conic);
+        .any(|s| has_clear_aperture(s) && s.aperture.shape == ApertureShape::Polygon);
+
+    ui.checkbox(
+        &mut specs.show_aperture_details,
+        "Show aperture offsets, obscurations and mechanical edges",
+    );
+    let has_aperture_details = has_aperture
+        && (specs.show_aperture_details
+            || specs
+                .surfaces
+                .iter()
+                .any(|s| has_clear_aperture(s) && s.aperture.has_details()));
 
     egui::ScrollArea::horizontal().show(ui, |ui| {
         let table = TableBuilder::new(ui)
@@ -51,10 +105,86 @@ pub fn surfaces_panel(
             table
         };
 
+        let table = if has_asphere {
+            table.columns(
+                Column::initial(80.0).resizable(true),
+                MAX_ASPHERIC_COEFFICIENTS,
+            ) // A4 ... A20
+        } else {
+            table
+        };
+
+        let table = if has_xy {
+            table
+                .column(Column::initial(80.0).resizable(true)) // Norm. Radius
+                .column(Column::auto().at_least(60.0)) // Terms
+        } else {
+            table
+        };
+
+        let table = if has_grating {
+            table
+                .column(Column::initial(80.0).resizable(true)) // Lines/mm
+                .column(Column::initial(50.0).resizable(true)) // Order
+                .column(Column::initial(80.0).resizable(true)) // Rulings
+        } else {
+            table
+        };
+
+        let table = if has_cardinal {
+            table
+                .column(Column::initial(80.0).resizable(true)) // Focal Length
+                .column(Column::initial(80.0).resizable(true)) // H to H'
+        } else {
+            table
+        };
+
         let table = table
             .column(Column::initial(80.0).resizable(true)) // Thickness
             .column(Column::initial(n_col_width).resizable(true)); // n / Material
 
+        let table = if has_model_glass {
+            table
+                .column(Column::initial(70.0).resizable(true)) // nd
+                .column(Column::initial(70.0).resizable(true)) // Vd
+                .column(Column::initial(70.0).resizable(true)) // ΔPgF
+        } else {
+            table
+        };
+
+        let table = if has_aperture {
+            table.column(Column::auto().at_least(90.0)) // Shape
+        } else {
+            table
+        };
+
+        let table = if has_sized_aperture {
+            table
+                .column(Column::initial(70.0).resizable(true)) // Semi-X
+                .column(Column::initial(70.0).resizable(true)) // Semi-Y
+        } else {
+            table
+        };
+
+        let table = if has_polygon {
+            table.column(Column::initial(160.0).resizable(true)) // Vertices
+        } else {
+            table
+        };
+
+        let table = if has_aperture_details {
+            table
+                .column(Column::initial(70.0).resizable(true)) // Offset X
+                .column(Column::initial(70.0).resizable(true)) // Offset Y
+                .column(Column::initial(80.0).resizable(true)) // Obscuration
+                .column(Column::initial(50.0).resizable(true)) // Vanes
+                .column(Column::initial(70.0).resizable(true)) // Vane Width
+                .column(Column::initial(80.0).resizable(true)) // Mech. Semi-Diam
+                .column(Column::initial(70.0).resizable(true)) // Flat Width
+        } else {
+            table
+        };
+
         let table = if has_reflecting {
             table
                 .column(Column::initial(120.0).resizable(true)) // Nom. Rotation θ — wide enough for "Nominal Rotation" label
@@ -79,8 +209,52 @@ pub fn surfaces_panel(
                 if has_conic {
                     header.col(|ui| header_cell(ui, None, "Conic"));
                 }
+                if has_asphere {
+                    for j in 0..MAX_ASPHERIC_COEFFICIENTS {
+                        let group = (j == 0).then_some("Aspheric Coefficients");
+                        let name = format!("A{}", 2 * j + 4);
+                        header.col(|ui| header_cell(ui, group, &name));
+                    }
+                }
+                if has_xy {
+                    header.col(|ui| header_cell(ui, Some("XY Polynomial"), "Norm. Radius"));
+                    header.col(|ui| header_cell(ui, None, "Terms"));
+                }
+                if has_grating {
+                    header.col(|ui| header_cell(ui, Some("Grating"), "Lines/mm"));
+                    header.col(|ui| header_cell(ui, None, "Order"));
+                    header.col(|ui| header_cell(ui, None, "Rulings (deg)"));
+                }
+                if has_cardinal {
+                    header.col(|ui| header_cell(ui, Some("Cardinal Lens"), "Focal Length"));
+                    header.col(|ui| header_cell(ui, None, "H \u{2192} H'"));
+                }
                 header.col(|ui| header_cell(ui, None, "Thickness"));
                 header.col(|ui| header_cell(ui, None, "n"));
+                if has_model_glass {
+                    header.col(|ui| header_cell(ui, Some("Model Glass"), "nd"));
+                    header.col(|ui| header_cell(ui, None, "Vd"));
+                    header.col(|ui| header_cell(ui, None, "\u{0394}PgF"));
+                }
+                if has_aperture {
+                    header.col(|ui| header_cell(ui, Some("Clear Aperture"), "Shape"));
+                }
+                if has_sized_aperture {
+                    header.col(|ui| header_cell(ui, None, "Semi-X"));
+                    header.col(|ui| header_cell(ui, None, "Semi-Y"));
+                }
+                if has_polygon {
+                    header.col(|ui| header_cell(ui, None, "Vertices"));
+                }
+                if has_aperture_details {
+                    header.col(|ui| header_cell(ui, None, "Offset X"));
+                    header.col(|ui| header_cell(ui, None, "Offset Y"));
+                    header.col(|ui| header_cell(ui, None, "Obscuration"));
+                    header.col(|ui| header_cell(ui, None, "Vanes"));
+                    header.col(|ui| header_cell(ui, None, "Vane Width"));
+                    header.col(|ui| header_cell(ui, Some("Mechanical"), "Semi-Diam"));
+                    header.col(|ui| header_cell(ui, None, "Flat Width"));
+                }
                 if has_reflecting {
                     header.col(|ui| header_cell(ui, Some("Nominal Rotation"), "\u{03b8} (deg)"));
                     header.col(|ui| header_cell(ui, None, "\u{03c8} (deg)"));
@@ -113,8 +287,13 @@ pub fn surfaces_panel(
                         let is_image = surf.variant == SurfaceVariant::Image;
                         let is_conic = surf.variant == SurfaceVariant::Conic;
                         let is_sphere = surf.variant == SurfaceVariant::Sphere;
-                        let is_curved = is_conic || is_sphere;
+                        let is_asphere = surf.variant == SurfaceVariant::EvenAsphere;
+                        let is_xy = surf.variant == SurfaceVariant::XYPolynomial;
+                        let is_grating = surf.variant == SurfaceVariant::Grating;
+                        let is_cardinal = surf.variant == SurfaceVariant::CardinalLens;
+                        let is_curved = is_conic || is_sphere || is_asphere || is_xy || is_grating;
                         let is_locked = is_object || is_image;
+                        let is_apertured = has_clear_aperture(surf);
 
                         // # column
                         row.col(|ui| {
@@ -147,7 +326,11 @@ pub fn surfaces_panel(
                                                     && !matches!(
                                                         v,
                                                         SurfaceVariant::Conic
+                                                            | SurfaceVariant::EvenAsphere
                                                             | SurfaceVariant::Sphere
+                                                            | SurfaceVariant::XYPolynomial
+                                                            | SurfaceVariant::Grating
+                                                            | SurfaceVariant::CardinalLens
                                                             | SurfaceVariant::Iris
                                                     )
                                                 {
@@ -159,7 +342,7 @@ pub fn surfaces_panel(
                             }
                         });
 
-                        // Kind column (Sphere and Conic)
+                        // Kind column (all curved variants)
                         row.col(|ui| {
                             if is_curved {
                                 let id = ui.make_persistent_id(format!("kind_{row_idx}"));
@@ -238,10 +421,11 @@ pub fn surfaces_panel(
                             }
                         });
 
-                        // Conic Constant (only when the system has Conic surfaces)
+                        // Conic Constant (only when the system has Conic, Even
+                        // Asphere or XY Polynomial surfaces)
                         if has_conic {
                             row.col(|ui| {
-                                if is_conic {
+                                if is_conic || is_asphere || is_xy {
                                     // Normalize empty string to "0" so the stored value
                                     // is always in sync with what the widget displays.
                                     if surf.conic_constant.is_empty() {
@@ -260,6 +444,159 @@ pub fn surfaces_panel(
                             });
                         }
 
+                        // Aspheric coefficients (only when the system has Even
+                        // Asphere surfaces)
+                        if has_asphere {
+                            if is_asphere
+                                && surf.aspheric_coefficients.len() < MAX_ASPHERIC_COEFFICIENTS
+                            {
+                                surf.aspheric_coefficients
+                                    .resize(MAX_ASPHERIC_COEFFICIENTS, "0".into());
+                                changed = true;
+                            }
+                            for j in 0..MAX_ASPHERIC_COEFFICIENTS {
+                                row.col(|ui| {
+                                    if is_asphere {
+                                        changed |= text_value(
+                                            ui,
+                                            &mut surf.aspheric_coefficients[j],
+                                            row_idx,
+                                            &format!("a{}", 2 * j + 4),
+                                        );
+                                    }
+                                });
+                            }
+                        }
+
+                        // Normalization radius and coefficient editor (only when the
+                        // system has XY Polynomial surfaces)
+                        if has_xy {
+                            row.col(|ui| {
+                                if is_xy {
+                                    if surf.normalization_radius.is_empty() {
+                                        surf.normalization_radius = surf.semi_diameter.clone();
+                                        changed = true;
+                                    }
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.normalization_radius,
+                                        row_idx,
+                                        "xy_norm",
+                                        0.0..=500.0,
+                                        0.1,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_xy {
+                                    let nonzero = surf
+                                        .xy_coefficients
+                                        .iter()
+                                        .filter(|c| c.trim().parse::<f64>().is_ok_and(|v| v != 0.0))
+                                        .count();
+                                    if ui
+                                        .small_button(format!("Edit… ({nonzero})"))
+                                        .on_hover_text("Edit or paste the polynomial coefficients")
+                                        .clicked()
+                                    {
+                                        *xy_popup =
+                                            Some(XYPolynomialPopupState::open(row_idx, surf));
+                                    }
+                                }
+                            });
+                        }
+
+                        // Grating parameters (only when the system has Grating
+                        // surfaces)
+                        if has_grating {
+                            if is_grating {
+                                for field in [
+                                    &mut surf.line_density,
+                                    &mut surf.diffraction_order,
+                                    &mut surf.line_orientation,
+                                ] {
+                                    if field.is_empty() {
+                                        *field = "0".into();
+                                        changed = true;
+                                    }
+                                }
+                            }
+                            row.col(|ui| {
+                                if is_grating {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.line_density,
+                                        row_idx,
+                                        "lines",
+                                        0.0..=10000.0,
+                                        1.0,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_grating {
+                                    changed |= drag_int(
+                                        ui,
+                                        &mut surf.diffraction_order,
+                                        row_idx,
+                                        "order",
+                                        -20..=20,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_grating {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.line_orientation,
+                                        row_idx,
+                                        "rulings",
+                                        -180.0..=180.0,
+                                        0.5,
+                                    );
+                                }
+                            });
+                        }
+
+                        // Focal length and principal plane separation (only when
+                        // the system has Cardinal Lens surfaces)
+                        if has_cardinal {
+                            if is_cardinal {
+                                if surf.focal_length.is_empty() {
+                                    surf.focal_length = "100".into();
+                                    changed = true;
+                                }
+                                if surf.principal_plane_separation.is_empty() {
+                                    surf.principal_plane_separation = "0".into();
+                                    changed = true;
+                                }
+                            }
+                            row.col(|ui| {
+                                if is_cardinal {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.focal_length,
+                                        row_idx,
+                                        "efl",
+                                        -10000.0..=10000.0,
+                                        0.5,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_cardinal {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.principal_plane_separation,
+                                        row_idx,
+                                        "hh",
+                                        -10000.0..=10000.0,
+                                        0.5,
+                                    );
+                                }
+                            });
+                        }
+
                         // Thickness
                         row.col(|ui| {
                             if !is_image {
@@ -302,7 +639,10 @@ pub fn surfaces_panel(
                         // Refractive Index / Material
                         row.col(|ui| {
                             if !is_image {
-                                if use_materials {
+                                changed |= medium_kind_combo(ui, surf, row_idx, use_materials);
+                                if surf.model_glass.is_some() {
+                                    ui.weak("model glass");
+                                } else if use_materials {
                                     let display = surf.material_key.as_deref().unwrap_or("(none)");
                                     let id = ui.make_persistent_id(format!("mat_{row_idx}"));
                                     egui::ComboBox::from_id_salt(id)
@@ -342,6 +682,223 @@ pub fn surfaces_panel(
                             }
                         });
 
+                        // Model glass (only when the system has model glasses)
+                        if has_model_glass {
+                            let glass = surf.model_glass.as_mut().filter(|_| !is_image);
+                            if let Some(glass) = glass {
+                                for (field, col, range, speed) in [
+                                    (&mut glass.nd, "nd", 1.0..=4.0, 0.001),
+                                    (&mut glass.vd, "vd", 1.0..=150.0, 0.1),
+                                    (&mut glass.delta_pgf, "dpgf", -0.1..=0.1, 0.0001),
+                                ] {
+                                    row.col(|ui| {
+                                        changed |=
+                                            drag_value(ui, field, row_idx, col, range, speed);
+                                    });
+                                }
+                            } else {
+                                for _ in 0..3 {
+                                    row.col(|_| {});
+                                }
+                            }
+                        }
+
+                        // Clear aperture shape (only when the system has surfaces
+                        // with a clear aperture)
+                        if has_aperture {
+                            row.col(|ui| {
+                                if is_apertured {
+                                    let id = ui.make_persistent_id(format!("aperture_{row_idx}"));
+                                    egui::ComboBox::from_id_salt(id)
+                                        .selected_text(surf.aperture.shape.to_string())
+                                        .width(90.0)
+                                        .show_ui(ui, |ui| {
+                                            for &shape in ApertureShape::ALL {
+                                                if ui
+                                                    .selectable_value(
+                                                        &mut surf.aperture.shape,
+                                                        shape,
+                                                        shape.to_string(),
+                                                    )
+                                                    .changed()
+                                                {
+                                                    changed = true;
+                                                }
+                                            }
+                                        });
+                                }
+                            });
+                        }
+
+                        // Half-widths or semi-axes (only when the system has
+                        // Rectangular or Elliptical apertures)
+                        if has_sized_aperture {
+                            let is_sized = is_apertured
+                                && matches!(
+                                    surf.aperture.shape,
+                                    ApertureShape::Rectangular | ApertureShape::Elliptical
+                                );
+                            if is_sized {
+                                for field in [&mut surf.aperture.size_x, &mut surf.aperture.size_y]
+                                {
+                                    if field.is_empty() {
+                                        *field = surf.semi_diameter.clone();
+                                        changed = true;
+                                    }
+                                }
+                            }
+                            row.col(|ui| {
+                                if is_sized {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.aperture.size_x,
+                                        row_idx,
+                                        "ap_x",
+                                        0.0..=500.0,
+                                        0.1,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_sized {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.aperture.size_y,
+                                        row_idx,
+                                        "ap_y",
+                                        0.0..=500.0,
+                                        0.1,
+                                    );
+                                }
+                            });
+                        }
+
+                        // Polygon vertices (only when the system has Polygon
+                        // apertures)
+                        if has_polygon {
+                            let is_polygon =
+                                is_apertured && surf.aperture.shape == ApertureShape::Polygon;
+                            if is_polygon && surf.aperture.vertices.is_empty() {
+                                // Start from the square inscribed in the
+                                // semi-diameter.
+                                let h = parse_display_float(&surf.semi_diameter)
+                                    / std::f64::consts::SQRT_2;
+                                let h = format_display_float(h);
+                                surf.aperture.vertices =
+                                    format!("-{h} -{h}; {h} -{h}; {h} {h}; -{h} {h}");
+                                changed = true;
+                            }
+                            row.col(|ui| {
+                                if is_polygon {
+                                    changed |= ui
+                                        .push_id(format!("cell_{row_idx}_ap_vertices"), |ui| {
+                                            ui.add(
+                                                egui::TextEdit::singleline(
+                                                    &mut surf.aperture.vertices,
+                                                )
+                                                .desired_width(150.0),
+                                            )
+                                            .on_hover_text(
+                                                "x y pairs separated by semicolons, e.g. \
+                                                 -1 -1; 1 -1; 0 1",
+                                            )
+                                        })
+                                        .inner
+                                        .changed();
+                                }
+                            });
+                        }
+
+                        // Offset, central obscuration and spider vanes (only when
+                        // enabled or in use)
+                        if has_aperture_details {
+                            row.col(|ui| {
+                                if is_apertured {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.aperture.offset_x,
+                                        row_idx,
+                                        "ap_dx",
+                                        -500.0..=500.0,
+                                        0.1,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_apertured {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.aperture.offset_y,
+                                        row_idx,
+                                        "ap_dy",
+                                        -500.0..=500.0,
+                                        0.1,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_apertured {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.aperture.obscuration,
+                                        row_idx,
+                                        "obsc",
+                                        0.0..=500.0,
+                                        0.1,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_apertured {
+                                    changed |= drag_int(
+                                        ui,
+                                        &mut surf.aperture.spider_vanes,
+                                        row_idx,
+                                        "vanes",
+                                        0..=12,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_apertured {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.aperture.spider_width,
+                                        row_idx,
+                                        "vane_w",
+                                        0.0..=100.0,
+                                        0.05,
+                                    );
+                                }
+                            });
+                            // An iris has no mechanical edge.
+                            let is_mountable = is_apertured && surf.variant != SurfaceVariant::Iris;
+                            row.col(|ui| {
+                                if is_mountable {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.aperture.mechanical_semi_diameter,
+                                        row_idx,
+                                        "mech_sd",
+                                        0.0..=1000.0,
+                                        0.1,
+                                    );
+                                }
+                            });
+                            row.col(|ui| {
+                                if is_mountable {
+                                    changed |= drag_value(
+                                        ui,
+                                        &mut surf.aperture.flat_width,
+                                        row_idx,
+                                        "flat_w",
+                                        0.0..=100.0,
+                                        0.05,
+                                    );
+                                }
+                            });
+                        }
+
                         // θ / ψ columns (only shown when system has reflecting surfaces)
                         if has_reflecting {
                             let is_reflecting_curved =
@@ -406,6 +963,58 @@ pub fn surfaces_panel(
     changed
 }
 
+/// Renders a small combo box that chooses whether the medium after a surface
+/// is a model glass or a constant index or catalog material, depending on
+/// `use_materials`. Returns true if the choice changed.
+///
+/// Switching to a model glass starts from the row's constant index, and
+/// switching back keeps the model glass's nd as the constant index.
+fn medium_kind_combo(
+    ui: &mut egui::Ui,
+    surf: &mut SurfaceRow,
+    row: usize,
+    use_materials: bool,
+) -> bool {
+    let plain = if use_materials { "Catalog" } else { "n" };
+    let mut is_model = surf.model_glass.is_some();
+    let id = ui.make_persistent_id(format!("medium_{row}"));
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(if is_model { "Model" } else { plain })
+        .width(60.0)
+        .show_ui(ui, |ui| {
+            ui.selectable_value(&mut is_model, false, plain);
+            ui.selectable_value(&mut is_model, true, "Model");
+        });
+
+    match (is_model, surf.model_glass.take()) {
+        (true, None) => {
+            let nd = if parse_display_float(&surf.refractive_index) > 1.0 {
+                surf.refractive_index.as_str()
+            } else {
+                "1.5"
+            };
+            surf.model_glass = Some(ModelGlassRow::new(nd));
+            true
+        }
+        (false, Some(glass)) => {
+            surf.refractive_index = glass.nd;
+            true
+        }
+        (_, glass) => {
+            surf.model_glass = glass;
+            false
+        }
+    }
+}
+
+/// Returns true if the row's surface has a clear aperture that can be shaped.
+fn has_clear_aperture(row: &SurfaceRow) -> bool {
+    !matches!(
+        row.variant,
+        SurfaceVariant::Object | SurfaceVariant::Probe | SurfaceVariant::Image
+    )
+}
+
 /// Renders a header cell with an optional group label above the column name.
 ///
 /// Uses `bottom_up` layout so the column name is always anchored to the
@@ -466,6 +1075,37 @@ fn drag_value(
     }
 }
 
+/// DragValue cell for integer fields such as a diffraction order.
+fn drag_int(
+    ui: &mut egui::Ui,
+    field: &mut String,
+    row: usize,
+    col: &str,
+    range: std::ops::RangeInclusive<i32>,
+) -> bool {
+    let mut val = field.trim().parse::<i32>().unwrap_or(0);
+    let response = ui.push_id(format!("cell_{row}_{col}"), |ui| {
+        ui.add(egui::DragValue::new(&mut val).range(range).speed(0.05))
+    });
+    if response.inner.changed() {
+        *field = val.to_string();
+        true
+    } else {
+        false
+    }
+}
+
+/// Free-text cell for values that span many orders of magnitude (e.g.
+/// aspheric coefficients), where dragging is not useful. Accepts scientific
+/// notation such as `1.2e-5`; parsing is deferred to spec conversion.
+fn text_value(ui: &mut egui::Ui, field: &mut String, row: usize, col: &str) -> bool {
+    ui.push_id(format!("cell_{row}_{col}"), |ui| {
+        ui.add(egui::TextEdit::singleline(field).desired_width(70.0))
+    })
+    .inner
+    .changed()
+}
+
 /// DragValue cell with infinity-aware formatting: displays and accepts
 /// `"Infinity"` as a string value. Use this for fields that legitimately hold
 /// `f64::INFINITY` (e.g. RoC, thickness).
@@ -523,7 +1163,7 @@ mod tests {
     }
 
     fn default_panel(ui: &mut egui::Ui, specs: &mut SystemSpecs) -> bool {
-        surfaces_panel(ui, specs, None, &mut None)
+        surfaces_panel(ui, specs, None, &mut None, &mut None)
     }
 
     /// The object row must have a + button so users can insert surfaces after
@@ -626,6 +1266,139 @@ mod tests {
         harness.get_by_label("Nominal Rotation");
     }
 
+    /// The aspheric coefficient columns appear only when an Even Asphere row
+    /// exists, and the row is padded to the full set of coefficients.
+    #[test]
+    fn aspheric_columns_present_with_even_asphere() {
+        let mut specs = minimal_specs();
+        {
+            let mut harness = Harness::builder()
+                .with_size(egui::vec2(2000.0, 600.0))
+                .build_ui(|ui| {
+                    default_panel(ui, &mut specs);
+                });
+            harness.run();
+            assert!(harness.query_by_label("A4").is_none());
+        }
+
+        specs.surfaces.insert(
+            1,
+            SurfaceRow::new_even_asphere("10.0", "40.0", "-1", &["1e-4"], "5.0", "1.5"),
+        );
+        {
+            let mut harness = Harness::builder()
+                .with_size(egui::vec2(2000.0, 600.0))
+                .build_ui(|ui| {
+                    default_panel(ui, &mut specs);
+                });
+            harness.run();
+            harness.get_by_label("Aspheric Coefficients");
+            harness.get_by_label("A4");
+            harness.get_by_label("A20");
+        }
+        assert_eq!(
+            specs.surfaces[1].aspheric_coefficients.len(),
+            MAX_ASPHERIC_COEFFICIENTS
+        );
+        assert_eq!(specs.surfaces[1].aspheric_coefficients[0], "1e-4");
+    }
+
+    #[test]
+    fn xy_polynomial_row_opens_coefficient_editor() {
+        let mut specs = minimal_specs();
+        specs.surfaces.insert(
+            1,
+            SurfaceRow::new_xy_polynomial(
+                "10.0",
+                "40.0",
+                "0",
+                "10.0",
+                &["0", "1e-3"],
+                "5.0",
+                "1.5",
+            ),
+        );
+        let mut xy_popup = None;
+        {
+            let mut harness = Harness::builder()
+                .with_size(egui::vec2(2000.0, 600.0))
+                .build_ui(|ui| {
+                    surfaces_panel(ui, &mut specs, None, &mut None, &mut xy_popup);
+                });
+            harness.run();
+            harness.get_by_label("XY Polynomial");
+            harness.get_by_label("Norm. Radius");
+            harness.get_by_label("Edit… (1)").click();
+            harness.run();
+        }
+        let popup = xy_popup.expect("clicking Edit… should open the editor");
+        assert_eq!(popup.surface_index, 1);
+        assert_eq!(popup.text, "0 1 1e-3");
+    }
+
+    #[test]
+    fn grating_columns_present_with_grating() {
+        let mut specs = minimal_specs();
+        specs.surfaces.insert(
+            1,
+            SurfaceRow::new_grating("10.0", "Infinity", "600", "1", "5.0", "1.0"),
+        );
+        let mut harness = Harness::builder()
+            .with_size(egui::vec2(2000.0, 600.0))
+            .build_ui(|ui| {
+                default_panel(ui, &mut specs);
+            });
+        harness.run();
+        harness.get_by_label("Lines/mm");
+        harness.get_by_label("Order");
+        harness.get_by_label("Rulings (deg)");
+    }
+
+    #[test]
+    fn cardinal_lens_columns_present_with_cardinal_lens() {
+        let mut specs = minimal_specs();
+        specs.surfaces.insert(
+            1,
+            SurfaceRow::new_cardinal_lens("5.0", "9.0", "-15.0", "50.0", "1.0"),
+        );
+        let mut harness = Harness::builder()
+            .with_size(egui::vec2(2000.0, 600.0))
+            .build_ui(|ui| {
+                default_panel(ui, &mut specs);
+            });
+        harness.run();
+        harness.get_by_label("Focal Length");
+        harness.get_by_label("H \u{2192} H'");
+    }
+
+    #[test]
+    fn model_glass_columns_present_with_model_glass() {
+        let mut specs = minimal_specs();
+        specs
+            .surfaces
+            .insert(1, SurfaceRow::new_sphere("5.0", "Infinity", "10.0", "1.6"));
+        {
+            let mut harness = Harness::builder()
+                .with_size(egui::vec2(2000.0, 600.0))
+                .build_ui(|ui| {
+                    default_panel(ui, &mut specs);
+                });
+            harness.run();
+            assert!(harness.query_by_label("Model Glass").is_none());
+        }
+
+        specs.surfaces[1].model_glass = Some(ModelGlassRow::new("1.6"));
+        let mut harness = Harness::builder()
+            .with_size(egui::vec2(2000.0, 600.0))
+            .build_ui(|ui| {
+                default_panel(ui, &mut specs);
+            });
+        harness.run();
+        harness.get_by_label("Model Glass");
+        harness.get_by_label("Vd");
+        harness.get_by_label("\u{0394}PgF");
+    }
+
     fn lens_specs() -> SystemSpecs {
         SystemSpecs {
             surfaces: vec![
@@ -669,7 +1442,7 @@ mod tests {
         let mut harness = Harness::builder()
             .with_size(egui::vec2(2000.0, 600.0))
             .build_ui(|ui| {
-                surfaces_panel(ui, &mut specs, Some(&sv), &mut None);
+                surfaces_panel(ui, &mut specs, Some(&sv), &mut None, &mut None);
             });
         harness.run();
         harness.get_by_label("[F]");
@@ -687,7 +1460,7 @@ mod tests {
         let mut harness = Harness::builder()
             .with_size(egui::vec2(2000.0, 600.0))
             .build_ui(|ui| {
-                surfaces_panel(ui, &mut specs, None, &mut None);
+                surfaces_panel(ui, &mut specs, None, &mut None, &mut None);
             });
         harness.run();
         harness.get_by_label("—");
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use crate::{
-    CrossSectionView, FieldSpec, ParaxialView, TraceResultsCollection,
+    CrossSectionView, FieldSpec, ParaxialView, ThermalView, TraceResultsCollection,
     core::math::{linalg::mat3x3::Mat3x3, vec3::Vec3},
     views::components::Component,
 };
@@ -34,6 +34,16 @@ pub struct FieldDesc {
     pub label: String,
 }
 
+/// Refractive index of a material sampled across the system's wavelengths.
+pub struct DispersionCurve {
+    /// Material key, or a description of a model glass.
+    pub label: String,
+    /// Whether a surface or the background of the design is made of it.
+    pub in_design: bool,
+    /// Wavelength (µm), n and dn/dλ (1/µm) at each sample.
+    pub samples: Vec<[f64; 3]>,
+}
+
 /// The complete computed output for one version of the system specs.
 pub struct ResultPackage {
     /// Matches the `input_id` of the request that produced this result.
@@ -51,6 +61,12 @@ pub struct ResultPackage {
     pub solved_values: SolvedValues,
     /// Auto-detected optical components from the sequential model.
     pub components: Vec<Component>,
+    /// Change of focus with temperature.
+    pub thermal: Option<ThermalView>,
+    /// Why the thermal analysis failed, if it did.
+    pub thermal_error: Option<String>,
+    /// Dispersion of the selected materials and model glasses.
+    pub dispersion: Vec<DispersionCurve>,
 }
 
 impl ResultPackage {
@@ -68,6 +84,9 @@ impl ResultPackage {
             error: Some(msg),
             solved_values: SolvedValues::default(),
             components: Vec::new(),
+            thermal: None,
+            thermal_error: None,
+            dispersion: Vec::new(),
         }
     }
 }
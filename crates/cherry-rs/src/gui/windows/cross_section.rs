@@ -328,6 +328,9 @@ fn draw_element(
                 w2s,
             );
         }
+        DrawElement::ThinLens { p1, p2, converging } => {
+            draw_thin_lens(painter, *p1, *p2, *converging, w2s, visuals);
+        }
         DrawElement::FlatPlane { p1, p2, kind } => {
             draw_flat_plane(painter, *p1, *p2, *kind, w2s);
         }
@@ -445,6 +448,54 @@ fn draw_stop(
     painter.line_segment([gap_bot, bot_outer], stroke);
 }
 
+/// Returns the two arrowheads of a thin-lens marker as `[wing, tip, wing]`
+/// polylines in world coordinates.
+///
+/// The heads sit at the ends of the marker and point outwards for a converging
+/// lens and inwards for a diverging one.
+fn thin_lens_arrowheads(p1: [f64; 2], p2: [f64; 2], converging: bool) -> [[[f64; 2]; 3]; 2] {
+    let (dz, dt) = (p2[0] - p1[0], p2[1] - p1[1]);
+    let len = (dz * dz + dt * dt).sqrt().max(f64::EPSILON);
+    let (uz, ut) = (dz / len, dt / len);
+    let head = len * 0.06;
+    let back = if converging { head } else { -head };
+
+    let arrow = |tip: [f64; 2], sign: f64| {
+        let base = [tip[0] - sign * uz * back, tip[1] - sign * ut * back];
+        [
+            [base[0] - ut * head, base[1] + uz * head],
+            tip,
+            [base[0] + ut * head, base[1] - uz * head],
+        ]
+    };
+    [arrow(p1, -1.0), arrow(p2, 1.0)]
+}
+
+fn draw_thin_lens(
+    painter: &egui::Painter,
+    p1: [f64; 2],
+    p2: [f64; 2],
+    converging: bool,
+    w2s: &WorldToScreen,
+    visuals: &egui::Visuals,
+) {
+    let color = if visuals.dark_mode {
+        egui::Color32::from_rgb(100, 149, 220)
+    } else {
+        egui::Color32::from_rgb(30, 80, 160)
+    };
+    let stroke = egui::Stroke::new(2.0, color);
+    let to_screen = |[z, t]: [f64; 2]| w2s.map(z as f32, t as f32);
+
+    painter.line_segment([to_screen(p1), to_screen(p2)], stroke);
+    for head in thin_lens_arrowheads(p1, p2, converging) {
+        painter.add(egui::Shape::line(
+            head.iter().copied().map(to_screen).collect(),
+            stroke,
+        ));
+    }
+}
+
 fn draw_flat_plane(
     painter: &egui::Painter,
     p1: [f64; 2],
@@ -634,6 +685,12 @@ fn render_svg(geom: &PlaneGeometry, wavelengths: &[f64], dark_mode: bool) -> Str
                     stop_color,
                 );
             }
+            DrawElement::ThinLens { p1, p2, converging } => {
+                svg_polyline(&mut s, &[*p1, *p2], &w2s, lens_stroke, 2.0);
+                for head in thin_lens_arrowheads(*p1, *p2, *converging) {
+                    svg_polyline(&mut s, &head, &w2s, lens_stroke, 2.0);
+                }
+            }
             DrawElement::FlatPlane { p1, p2, kind } => {
                 svg_flat_plane(&mut s, *p1, *p2, *kind, &w2s);
             }
@@ -894,6 +951,9 @@ mod tests {
             error: None,
             solved_values: Default::default(),
             components: Vec::new(),
+            thermal: None,
+            thermal_error: None,
+            dispersion: Vec::new(),
         };
         let mut harness = Harness::new_state(
             |ctx, (w, r): &mut (CrossSectionWindow, ResultPackage)| {
@@ -1,24 +1,371 @@
-use crate::gui::{model::SystemSpecs, panels};
+use std::collections::BTreeSet;
 
-/// Floating materials browser window (ri-info only).
-pub struct MaterialsWindow;
+use egui_plot::{Legend, Line, MarkerShape, Plot, PlotPoint, PlotPoints, Points, Text, VLine};
+
+use crate::gui::{
+    colors::wavelength_to_color,
+    model::{MaterialsTab, SystemSpecs},
+    panels,
+    result_package::ResultPackage,
+};
+
+const PLOT_HEIGHT: f32 = 220.0;
+/// Largest distance from the pointer, in points, at which a glass on the map
+/// is hovered or clicked.
+const PICK_RADIUS: f32 = 8.0;
+const DESIGN_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 60, 40);
+
+/// Floating materials window (ri-info only) with the materials browser, a
+/// glass map and dispersion curves.
+pub struct MaterialsWindow {
+    pub active_tab: MaterialsTab,
+    /// Catalogs left out of the glass map.
+    hidden_catalogs: BTreeSet<String>,
+}
+
+impl Default for MaterialsWindow {
+    fn default() -> Self {
+        Self {
+            active_tab: MaterialsTab::Browse,
+            hidden_catalogs: BTreeSet::new(),
+        }
+    }
+}
 
 impl MaterialsWindow {
-    /// Show the materials browser window. Returns true if specs were modified.
+    /// Show the materials window. Returns true if specs were modified.
     pub fn show(
+        &mut self,
         ctx: &egui::Context,
         open: &mut bool,
         specs: &mut SystemSpecs,
         material_index: &panels::MaterialIndex,
         material_browser: &mut panels::MaterialBrowserState,
+        result: Option<&ResultPackage>,
     ) -> bool {
         let response = egui::Window::new("Materials")
             .open(open)
             .default_width(400.0)
             .show(ctx, |ui| {
-                panels::materials_panel(ui, specs, material_index, material_browser)
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut self.active_tab, MaterialsTab::Browse, "Browse");
+                    ui.selectable_value(&mut self.active_tab, MaterialsTab::GlassMap, "Glass Map");
+                    ui.selectable_value(
+                        &mut self.active_tab,
+                        MaterialsTab::Dispersion,
+                        "Dispersion",
+                    );
+                });
+                ui.separator();
+
+                match self.active_tab {
+                    MaterialsTab::Browse => {
+                        panels::materials_panel(ui, specs, material_index, material_browser)
+                    }
+                    MaterialsTab::GlassMap => {
+                        glass_map_ui(ui, specs, material_index, &mut self.hidden_catalogs)
+                    }
+                    MaterialsTab::Dispersion => {
+                        dispersion_ui(ui, result);
+                        false
+                    }
+                }
             });
 
         response.and_then(|r| r.inner).unwrap_or(false)
     }
 }
+
+/// A glass on the map, at (-V_d, n_d) so that V_d decreases to the right as
+/// on the usual glass maps.
+struct MapGlass {
+    key: String,
+    point: [f64; 2],
+}
+
+/// Returns the materials of the design that have an index and Abbe number:
+/// the materials of the surfaces and the background, then the model glasses.
+fn design_glasses(specs: &SystemSpecs, index: &panels::MaterialIndex) -> Vec<MapGlass> {
+    let mut glasses: Vec<MapGlass> = Vec::new();
+    let mut push = |key: String, nd: Option<f64>, vd: Option<f64>| {
+        if let (Some(nd), Some(vd)) = (nd, vd)
+            && !glasses.iter().any(|g| g.key == key)
+        {
+            glasses.push(MapGlass {
+                key,
+                point: [-vd, nd],
+            });
+        }
+    };
+
+    if specs.use_materials {
+        let keys = specs
+            .surfaces
+            .iter()
+            .filter(|row| row.model_glass.is_none())
+            .filter_map(|row| row.material_key.as_ref())
+            .chain(&specs.background_material_key);
+        for key in keys {
+            if let Some(info) = index.info.get(key) {
+                push(key.clone(), info.nd, info.vd);
+            }
+        }
+    }
+    for glass in specs
+        .surfaces
+        .iter()
+        .filter_map(|row| row.model_glass.as_ref())
+    {
+        let parse = |s: &str| s.trim().parse::<f64>().ok();
+        let (nd, vd) = (parse(&glass.nd), parse(&glass.vd));
+        if let (Some(nd_value), Some(vd_value)) = (nd, vd) {
+            push(format!("Model glass {nd_value} / {vd_value}"), nd, vd);
+        }
+    }
+    glasses
+}
+
+/// Draw the glass map of the shown catalogs with the materials of the design
+/// highlighted. Clicking a catalog glass adds it to the selected materials.
+/// Returns true if a glass was added.
+fn glass_map_ui(
+    ui: &mut egui::Ui,
+    specs: &mut SystemSpecs,
+    index: &panels::MaterialIndex,
+    hidden_catalogs: &mut BTreeSet<String>,
+) -> bool {
+    if index.catalogs.is_empty() {
+        ui.label("No glass catalogs loaded.");
+    }
+    ui.horizontal_wrapped(|ui| {
+        for catalog in index.catalogs.keys() {
+            let mut shown = !hidden_catalogs.contains(catalog);
+            if ui.checkbox(&mut shown, catalog).changed() {
+                if shown {
+                    hidden_catalogs.remove(catalog);
+                } else {
+                    hidden_catalogs.insert(catalog.clone());
+                }
+            }
+        }
+    });
+
+    let catalogs: Vec<(&String, Vec<MapGlass>)> = index
+        .catalogs
+        .iter()
+        .filter(|(catalog, _)| !hidden_catalogs.contains(*catalog))
+        .map(|(catalog, glasses)| {
+            let glasses = glasses
+                .iter()
+                .filter_map(|glass| {
+                    let key = format!("{catalog}:{glass}");
+                    let info = index.info.get(&key)?;
+                    Some(MapGlass {
+                        point: [-info.vd?, info.nd?],
+                        key,
+                    })
+                })
+                .collect();
+            (catalog, glasses)
+        })
+        .collect();
+    let design = design_glasses(specs, index);
+
+    let response = Plot::new("glass_map")
+        .height(PLOT_HEIGHT * 1.5)
+        .x_axis_label("Abbe number Vd")
+        .y_axis_label("Refractive index nd")
+        .x_axis_formatter(|mark, _| format!("{}", -mark.value))
+        .label_formatter(|_, _| String::new())
+        .legend(Legend::default())
+        .show(ui, |plot_ui| {
+            for (catalog, glasses) in &catalogs {
+                let points: Vec<[f64; 2]> = glasses.iter().map(|g| g.point).collect();
+                plot_ui.points(Points::new(catalog.as_str(), PlotPoints::from(points)).radius(2.0));
+            }
+
+            let points: Vec<[f64; 2]> = design.iter().map(|g| g.point).collect();
+            plot_ui.points(
+                Points::new("Design", PlotPoints::from(points))
+                    .shape(MarkerShape::Diamond)
+                    .color(DESIGN_COLOR)
+                    .radius(5.0),
+            );
+            for glass in &design {
+                let [x, y] = glass.point;
+                plot_ui.text(
+                    Text::new("Design", PlotPoint::new(x, y), format!("  {}", glass.key))
+                        .anchor(egui::Align2::LEFT_BOTTOM)
+                        .color(DESIGN_COLOR),
+                );
+            }
+
+            // The catalog glass nearest to the pointer, if it is close enough.
+            let pointer = plot_ui.pointer_coordinate()?;
+            let pointer = plot_ui.screen_from_plot(pointer);
+            catalogs
+                .iter()
+                .flat_map(|(_, glasses)| glasses)
+                .map(|g| {
+                    let [x, y] = g.point;
+                    let distance = plot_ui
+                        .screen_from_plot(PlotPoint::new(x, y))
+                        .distance(pointer);
+                    (distance, g)
+                })
+                .filter(|(distance, _)| *distance <= PICK_RADIUS)
+                .min_by(|a, b| a.0.total_cmp(&b.0))
+                .map(|(_, g)| (g.key.clone(), g.point))
+        });
+
+    let mut changed = false;
+    if let Some((key, [x, y])) = response.inner {
+        if response.response.clicked() {
+            changed = panels::select_material(specs, &key);
+        }
+        response
+            .response
+            .on_hover_text_at_pointer(format!("{key}\nnd {y:.4}, Vd {:.2}", -x));
+    }
+    ui.label("Click a glass to add it to the selected materials.");
+    changed
+}
+
+/// Draw n and dn/dλ of the selected materials and model glasses across the
+/// system's wavelengths, which are marked by vertical lines.
+fn dispersion_ui(ui: &mut egui::Ui, result: Option<&ResultPackage>) {
+    let Some(r) = result else {
+        ui.label("No data yet.");
+        return;
+    };
+    if r.dispersion.is_empty() {
+        match &r.error {
+            Some(msg) => {
+                ui.colored_label(egui::Color32::RED, format!("Dispersion unavailable: {msg}"))
+            }
+            None => ui.label("Select materials or use model glasses to compare their dispersion."),
+        };
+        return;
+    }
+
+    let plot = |ui: &mut egui::Ui, id: &str, y_label: &str, column: usize| {
+        Plot::new(id)
+            .height(PLOT_HEIGHT)
+            .x_axis_label("Wavelength (\u{00b5}m)")
+            .y_axis_label(y_label)
+            .link_axis("dispersion_plots", [true, false])
+            .legend(Legend::default())
+            .show(ui, |plot_ui| {
+                for &wl in &r.wavelengths {
+                    plot_ui.vline(
+                        VLine::new(format!("{wl:.4} \u{00b5}m"), wl).color(wavelength_to_color(wl)),
+                    );
+                }
+                for curve in &r.dispersion {
+                    let points: Vec<[f64; 2]> = curve
+                        .samples
+                        .iter()
+                        .map(|sample| [sample[0], sample[column]])
+                        .collect();
+                    let width = if curve.in_design { 2.5 } else { 1.5 };
+                    plot_ui.line(
+                        Line::new(curve.label.as_str(), PlotPoints::from(points)).width(width),
+                    );
+                }
+            });
+    };
+    plot(ui, "dispersion_n_plot", "n", 1);
+    ui.add_space(8.0);
+    plot(ui, "dispersion_dn_plot", "dn/d\u{03bb} (1/\u{00b5}m)", 2);
+    ui.label("Thick lines are materials of the design.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui_kittest::{Harness, kittest::Queryable};
+
+    use crate::gui::{
+        model::ModelGlassRow,
+        panels::{MaterialBrowserState, MaterialIndex, MaterialInfo},
+        result_package::DispersionCurve,
+    };
+
+    fn show(
+        ctx: &egui::Context,
+        tab: MaterialsTab,
+        index: &MaterialIndex,
+        result: Option<&ResultPackage>,
+    ) {
+        let mut window = MaterialsWindow {
+            active_tab: tab,
+            ..Default::default()
+        };
+        let mut open = true;
+        let mut specs = SystemSpecs::default();
+        window.show(
+            ctx,
+            &mut open,
+            &mut specs,
+            index,
+            &mut MaterialBrowserState::default(),
+            result,
+        );
+    }
+
+    #[test]
+    fn design_glasses_include_materials_and_model_glasses() {
+        let mut index = MaterialIndex::default();
+        let info = MaterialInfo {
+            nd: Some(1.5168),
+            vd: Some(64.17),
+            ..Default::default()
+        };
+        index.insert("SCHOTT:N-BK7", info);
+        index.insert("main:Ag:Johnson", MaterialInfo::default());
+
+        let mut specs = SystemSpecs {
+            use_materials: true,
+            background_material_key: Some("main:Ag:Johnson".into()),
+            ..Default::default()
+        };
+        specs.surfaces[1].material_key = Some("SCHOTT:N-BK7".into());
+        specs.surfaces[2].model_glass = Some(ModelGlassRow::new("1.7"));
+
+        let glasses = design_glasses(&specs, &index);
+        let keys: Vec<&str> = glasses.iter().map(|g| g.key.as_str()).collect();
+        assert_eq!(keys, ["SCHOTT:N-BK7", "Model glass 1.7 / 50"]);
+        assert_eq!(glasses[0].point, [-64.17, 1.5168]);
+    }
+
+    #[test]
+    fn glass_map_lists_catalogs() {
+        let keys = ["SCHOTT:N-BK7".to_string(), "OHARA:S-BSL7".to_string()];
+        let index = MaterialIndex::build_from_keys(keys.iter());
+        let mut harness = Harness::new(|ctx| show(ctx, MaterialsTab::GlassMap, &index, None));
+        harness.step();
+        harness.get_by_label("SCHOTT");
+        harness.get_by_label("OHARA");
+    }
+
+    #[test]
+    fn dispersion_tab_shows_placeholder_and_curves() {
+        let index = MaterialIndex::default();
+        let mut harness = Harness::new(|ctx| show(ctx, MaterialsTab::Dispersion, &index, None));
+        harness.step();
+        harness.get_by_label("No data yet.");
+
+        let mut result = ResultPackage::error(1, String::new());
+        result.error = None;
+        result.wavelengths = vec![0.5876];
+        result.dispersion = vec![DispersionCurve {
+            label: "SCHOTT:N-BK7".into(),
+            in_design: true,
+            samples: vec![[0.55, 1.5185, -0.05], [0.6, 1.5163, -0.04]],
+        }];
+        let mut harness =
+            Harness::new(|ctx| show(ctx, MaterialsTab::Dispersion, &index, Some(&result)));
+        harness.step();
+        harness.get_by_label("Thick lines are materials of the design.");
+    }
+}
@@ -6,6 +6,7 @@ mod ray_fan;
 mod specs;
 mod spot_diagram;
 mod system;
+mod thermal;
 
 pub use cross_section::{CrossSectionWindow, CuttingPlane};
 #[cfg(feature = "ri-info")]
@@ -15,6 +16,7 @@ pub use ray_fan::RayFanWindow;
 pub use specs::SpecsWindow;
 pub use spot_diagram::SpotDiagramWindow;
 pub use system::SystemWindow;
+pub use thermal::ThermalWindow;
 
 /// Controls which floating windows are currently open.
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -28,6 +30,7 @@ pub struct WindowVisibility {
     pub ray_fan: bool,
     pub system: bool,
     pub lens_overlay: bool,
+    pub thermal: bool,
 }
 
 impl Default for WindowVisibility {
@@ -41,6 +44,7 @@ impl Default for WindowVisibility {
             ray_fan: false,
             system: false,
             lens_overlay: false,
+            thermal: false,
         }
     }
 }
@@ -299,6 +299,9 @@ mod tests {
             error: None,
             solved_values: Default::default(),
             components: Vec::new(),
+            thermal: None,
+            thermal_error: None,
+            dispersion: Vec::new(),
         }
     }
 
@@ -491,6 +491,9 @@ mod tests {
             error: None,
             solved_values: Default::default(),
             components: Vec::new(),
+            thermal: None,
+            thermal_error: None,
+            dispersion: Vec::new(),
         }
     }
 
@@ -535,12 +538,26 @@ mod tests {
             .enumerate()
             .map(|(i, (s, p))| {
                 let name = match s.surface_kind() {
+                    SurfaceKind::Axicon => "Axicon",
+                    SurfaceKind::Biconic => "Biconic",
+                    SurfaceKind::CardinalLens => "Cardinal Lens",
                     SurfaceKind::Conic => "Conic",
+                    SurfaceKind::Diffractive => "Diffractive",
+                    SurfaceKind::EvenAsphere => "Even Asphere",
+                    SurfaceKind::ForbesAsphere => "Forbes Asphere",
+                    SurfaceKind::Fresnel => "Fresnel",
+                    SurfaceKind::Grating => "Grating",
+                    SurfaceKind::GridSag => "Grid Sag",
                     SurfaceKind::Image => "Image",
                     SurfaceKind::Object => "Object",
+                    SurfaceKind::ParaxialLens => "Paraxial Lens",
                     SurfaceKind::Probe => "Probe",
                     SurfaceKind::Iris => "Iris",
+                    SurfaceKind::LensletArray => "Lenslet Array",
                     SurfaceKind::Sphere => "Sphere",
+                    SurfaceKind::Toroid => "Toroid",
+                    SurfaceKind::XYPolynomial => "XY Polynomial",
+                    SurfaceKind::ZernikeSag => "Zernike Sag",
                     SurfaceKind::Custom => "Custom",
                 };
                 SurfaceDesc {
@@ -579,6 +596,9 @@ mod tests {
             error: None,
             solved_values: Default::default(),
             components: Vec::new(),
+            thermal: None,
+            thermal_error: None,
+            dispersion: Vec::new(),
         }
     }
 
@@ -1,5 +1,7 @@
 use crate::gui::{
-    model::{SolveParameter, SolvePopupState, SolveSpec, SpecsTab, SystemSpecs},
+    model::{
+        SolveParameter, SolvePopupState, SolveSpec, SpecsTab, SystemSpecs, XYPolynomialPopupState,
+    },
     panels,
     result_package::{ResultPackage, SolvedValues},
 };
@@ -8,6 +10,7 @@ use crate::gui::{
 pub struct SpecsWindow {
     pub active_tab: SpecsTab,
     solve_popup: Option<SolvePopupState>,
+    xy_popup: Option<XYPolynomialPopupState>,
 }
 
 impl Default for SpecsWindow {
@@ -15,6 +18,7 @@ impl Default for SpecsWindow {
         Self {
             active_tab: SpecsTab::Surfaces,
             solve_popup: None,
+            xy_popup: None,
         }
     }
 }
@@ -44,10 +48,16 @@ impl SpecsWindow {
                 match self.active_tab {
                     SpecsTab::Surfaces => {
                         let solved_values = result.map(|r| &r.solved_values);
-                        let panel_changed =
-                            panels::surfaces_panel(ui, specs, solved_values, &mut self.solve_popup);
+                        let panel_changed = panels::surfaces_panel(
+                            ui,
+                            specs,
+                            solved_values,
+                            &mut self.solve_popup,
+                            &mut self.xy_popup,
+                        );
                         let popup_committed = self.show_solve_popup(ctx, specs, solved_values);
-                        panel_changed || popup_committed
+                        let xy_committed = self.show_xy_polynomial_popup(ctx, specs);
+                        panel_changed || popup_committed || xy_committed
                     }
                     SpecsTab::Fields => panels::fields_panel(ui, specs),
                     SpecsTab::Aperture => panels::aperture_panel(ui, specs),
@@ -190,6 +200,82 @@ impl SpecsWindow {
         mutated
     }
 
+    fn show_xy_polynomial_popup(&mut self, ctx: &egui::Context, specs: &mut SystemSpecs) -> bool {
+        let Some(state) = &mut self.xy_popup else {
+            return false;
+        };
+        let surface_index = state.surface_index;
+
+        let mut open = true;
+        let mut apply = false;
+        let mut cancel = false;
+
+        egui::Window::new(format!("XY Polynomial — surface {surface_index}"))
+            .id(egui::Id::new("xy_polynomial_popup"))
+            .collapsible(false)
+            .default_width(320.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Normalization radius (mm):");
+                    ui.text_edit_singleline(&mut state.normalization_radius);
+                });
+                ui.label("Coefficients:");
+                ui.weak("One term per line: \"i j c\" for c·xⁱyʲ, \"term c\", or \"c\".");
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::TextEdit::multiline(&mut state.text)
+                                .code_editor()
+                                .desired_rows(12)
+                                .desired_width(f32::INFINITY),
+                        );
+                    });
+                if let Some(err) = &state.error {
+                    ui.colored_label(egui::Color32::from_rgb(200, 80, 80), err);
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Apply").clicked() {
+                        apply = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            });
+
+        if cancel || !open || surface_index >= specs.surfaces.len() {
+            self.xy_popup = None;
+            return false;
+        }
+
+        if apply {
+            match state.normalization_radius.trim().parse::<f64>() {
+                Ok(r) if r.is_finite() && r > 0.0 => {}
+                _ => {
+                    state.error =
+                        Some("Normalization radius must be a positive number.".to_owned());
+                    return false;
+                }
+            }
+            match state.parse_coefficients() {
+                Ok(coefficients) => {
+                    let row = &mut specs.surfaces[surface_index];
+                    row.normalization_radius = state.normalization_radius.trim().to_owned();
+                    row.xy_coefficients = coefficients;
+                    self.xy_popup = None;
+                    return true;
+                }
+                Err(err) => state.error = Some(err),
+            }
+        }
+
+        false
+    }
+
     fn write_back_solved_value(
         specs: &mut SystemSpecs,
         surface_index: usize,
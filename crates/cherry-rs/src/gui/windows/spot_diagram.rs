@@ -407,6 +407,9 @@ mod tests {
             error: Some("trace failed".to_string()),
             solved_values: Default::default(),
             components: Vec::new(),
+            thermal: None,
+            thermal_error: None,
+            dispersion: Vec::new(),
         };
 
         let window = SpotDiagramWindow::default();
@@ -421,6 +424,162 @@ mod tests {
         harness.get_by_label_contains("trace failed");
     }
 
+    #[test]
+    fn grating_spots_are_dispersed_by_wavelength() {
+        use crate::gui::{
+            convert,
+            model::{SurfaceRow, SystemSpecs},
+        };
+        use crate::{ParaxialView, SequentialModel, ray_trace_3d_view};
+
+        let specs = SystemSpecs {
+            surfaces: vec![
+                SurfaceRow::new_object("Infinity"),
+                SurfaceRow::new_grating("10.0", "Infinity", "300", "1", "100.0", "1.0"),
+                SurfaceRow::new_image(),
+            ],
+            aperture_semi_diameter: "5.0".into(),
+            wavelengths: vec!["0.45".into(), "0.55".into(), "0.65".into()],
+            ..Default::default()
+        };
+        #[cfg(not(feature = "ri-info"))]
+        let parsed = convert::convert_specs(&specs).expect("convert");
+        #[cfg(feature = "ri-info")]
+        let parsed = convert::convert_specs(&specs, &Default::default()).expect("convert");
+        let seq = SequentialModel::from_surface_specs(
+            &parsed.gaps,
+            &parsed.surfaces,
+            &parsed.wavelengths,
+            None,
+        )
+        .expect("model");
+        let pv = ParaxialView::new(&seq, &parsed.fields, false).expect("paraxial");
+        let trace = ray_trace_3d_view(
+            &parsed.aperture,
+            &parsed.fields,
+            &seq,
+            &pv,
+            crate::views::ray_trace_3d::SamplingConfig {
+                n_fan_rays: 3,
+                full_pupil_spacing: 0.5,
+            },
+        )
+        .expect("trace");
+
+        let image_idx = seq.surfaces().len() - 1;
+        let wavelengths = seq.wavelengths().to_vec();
+        let visible = vec![true; wavelengths.len()];
+        let query = FieldPlotQuery {
+            ray_trace: &trace,
+            wavelengths: &wavelengths,
+            field_id: 0,
+            surface_idx: image_idx,
+            wavelength_visible: &visible,
+            surf_desc: None,
+        };
+        let (_, (y_min, y_max)) = compute_field_axis_range(&query);
+
+        // Longer wavelengths are diffracted further, and every spot is in view.
+        let chief_y: Vec<f64> = (0..wavelengths.len())
+            .map(|wl_id| {
+                let tr = trace.get(0, wl_id).expect("trace results");
+                rays_at_surface(tr.chief_ray(), image_idx, None)
+                    .next()
+                    .expect("chief ray reaches the image")
+                    .1
+            })
+            .collect();
+        assert!(chief_y.windows(2).all(|w| w[1] > w[0] + 1.0), "{chief_y:?}");
+        assert!(chief_y.iter().all(|&y| y > y_min && y < y_max));
+    }
+
+    #[test]
+    fn lenslet_array_spots_form_a_grid() {
+        use crate::{
+            ApertureSpec, BoundaryKind, FieldSpec, GapSpec, LensletPacking, ParaxialView,
+            Rotation3D, SequentialModel, SurfaceSpec, n, ray_trace_3d_view,
+        };
+
+        // A plano-convex lenslet plate with unit pitch, imaged at the back
+        // focal plane of its lenslets.
+        let gaps = vec![
+            GapSpec {
+                thickness: f64::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 1.0,
+                refractive_index: n!(1.5),
+            },
+            GapSpec {
+                thickness: 4.0 - 1.0 / 1.5,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::LensletArray {
+                semi_diameter: 5.0,
+                pitch: 1.0,
+                radius_of_curvature: 2.0,
+                conic_constant: 0.0,
+                packing: LensletPacking::Square,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Conic {
+                semi_diameter: 5.0,
+                radius_of_curvature: f64::INFINITY,
+                conic_constant: 0.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let fields = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let seq =
+            SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).expect("model");
+        let pv = ParaxialView::new(&seq, &fields, false).expect("paraxial");
+        let trace = ray_trace_3d_view(
+            &ApertureSpec::EntrancePupil { semi_diameter: 2.4 },
+            &fields,
+            &seq,
+            &pv,
+            crate::views::ray_trace_3d::SamplingConfig {
+                n_fan_rays: 3,
+                full_pupil_spacing: 0.1,
+            },
+        )
+        .expect("trace");
+
+        // Every ray lands near the focus of the lenslet it passed through,
+        // which lies on the axis of that lenslet.
+        let image_idx = seq.surfaces().len() - 1;
+        let tr = trace.get(0, 0).expect("trace results");
+        let mut spots = std::collections::BTreeSet::new();
+        for (x, y) in rays_at_surface(tr.full_pupil(), image_idx, None) {
+            let (i, j) = (x.round(), y.round());
+            assert!(
+                (x - i).hypot(y - j) < 0.05,
+                "ray lands at ({x}, {y}), between spots"
+            );
+            spots.insert((i as i64, j as i64));
+        }
+        // The 4.8 mm beam covers the central 5 x 5 lenslets.
+        assert_eq!(spots.len(), 25, "{spots:?}");
+    }
+
     #[test]
     fn full_result_shows_field_labels() {
         use crate::gui::{
@@ -491,6 +650,9 @@ mod tests {
             error: None,
             solved_values: Default::default(),
             components: Vec::new(),
+            thermal: None,
+            thermal_error: None,
+            dispersion: Vec::new(),
         };
 
         let window = SpotDiagramWindow::default();
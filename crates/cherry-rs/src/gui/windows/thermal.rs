@@ -0,0 +1,258 @@
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
+
+use crate::gui::{
+    colors::wavelength_to_color,
+    model::{SystemSpecs, ThermalSettings},
+    result_package::ResultPackage,
+};
+
+const PLOT_HEIGHT: f32 = 220.0;
+
+/// Floating thermal analysis window.
+pub struct ThermalWindow;
+
+impl ThermalWindow {
+    /// Show the thermal analysis window. Returns true if the settings changed.
+    pub fn show(
+        ctx: &egui::Context,
+        open: &mut bool,
+        specs: &mut SystemSpecs,
+        result: Option<&ResultPackage>,
+    ) -> bool {
+        let response = egui::Window::new("Thermal Analysis")
+            .open(open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                let changed = settings_ui(ui, &mut specs.thermal);
+                ui.separator();
+                match result {
+                    None => {
+                        ui.label("No data yet.");
+                    }
+                    Some(r) => match (&r.thermal, &r.thermal_error, &r.error) {
+                        (Some(_), _, _) => render_content(ui, r),
+                        (None, Some(msg), _) | (None, None, Some(msg)) => {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                format!("Thermal analysis unavailable: {msg}"),
+                            );
+                        }
+                        (None, None, None) => {
+                            ui.label("No data yet.");
+                        }
+                    },
+                }
+                changed
+            });
+        response.and_then(|r| r.inner).unwrap_or(false)
+    }
+}
+
+/// Draw the editors of the analysis settings. Returns true if any changed.
+fn settings_ui(ui: &mut egui::Ui, settings: &mut ThermalSettings) -> bool {
+    let mut changed = false;
+    egui::Grid::new("thermal_settings")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Temperatures (\u{00b0}C):");
+            ui.horizontal(|ui| {
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut settings.min_temperature)
+                            .range(-273.0..=settings.max_temperature)
+                            .speed(1.0),
+                    )
+                    .changed();
+                ui.label("to");
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut settings.max_temperature)
+                            .range(settings.min_temperature..=1000.0)
+                            .speed(1.0),
+                    )
+                    .changed();
+                ui.label("in");
+                changed |= ui
+                    .add(
+                        egui::DragValue::new(&mut settings.n_temperatures)
+                            .range(2u32..=101u32)
+                            .suffix(" steps"),
+                    )
+                    .changed();
+            });
+            ui.end_row();
+
+            ui.label("Reference (\u{00b0}C):");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut settings.reference_temperature)
+                        .range(-273.0..=1000.0)
+                        .speed(1.0),
+                )
+                .changed();
+            ui.end_row();
+
+            ui.label("Mount CTE (10\u{207b}\u{2076}/K):");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut settings.mount_expansion_ppm)
+                        .range(-10.0..=100.0)
+                        .speed(0.1),
+                )
+                .changed();
+            ui.end_row();
+
+            ui.label("Pressure (kPa):");
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut settings.pressure_kpa)
+                        .range(0.0..=1000.0)
+                        .speed(0.1),
+                )
+                .changed();
+            ui.end_row();
+        });
+    changed
+}
+
+fn render_content(ui: &mut egui::Ui, r: &ResultPackage) {
+    let tv = r.thermal.as_ref().unwrap();
+
+    // Image plane shift vs temperature, one curve per wavelength.
+    Plot::new("thermal_focus_plot")
+        .height(PLOT_HEIGHT)
+        .x_axis_label("Temperature (\u{00b0}C)")
+        .y_axis_label("Image plane shift (\u{00b5}m)")
+        .legend(Legend::default())
+        .show(ui, |plot_ui| {
+            for (wl_id, &wl) in r.wavelengths.iter().enumerate() {
+                let points: Vec<[f64; 2]> = tv
+                    .get_by_wavelength_id(wl_id)
+                    .iter()
+                    .map(|res| [res.temperature, res.image_plane_shift * 1e3])
+                    .collect();
+                let name = format!("{wl:.4} \u{00b5}m");
+                let color = wavelength_to_color(wl);
+                plot_ui
+                    .line(Line::new(name.clone(), PlotPoints::from(points.clone())).color(color));
+                plot_ui.points(
+                    Points::new(name, PlotPoints::from(points))
+                        .color(color)
+                        .radius(2.5),
+                );
+            }
+        });
+
+    ui.add_space(8.0);
+
+    // Table of the primary wavelength.
+    let primary = tv.get_by_wavelength_id(0);
+    if let Some(&wl) = r.wavelengths.first() {
+        ui.label(format!("Wavelength {wl:.4} \u{00b5}m"));
+    }
+    egui::ScrollArea::vertical()
+        .id_salt("thermal_table_scroll")
+        .max_height(200.0)
+        .show(ui, |ui| {
+            egui::Grid::new("thermal_table")
+                .num_columns(5)
+                .striped(true)
+                .show(ui, |ui| {
+                    for header in [
+                        "T (\u{00b0}C)",
+                        "\u{0394}EFL (\u{00b5}m)",
+                        "\u{0394}BFD (\u{00b5}m)",
+                        "\u{0394}Image (\u{00b5}m)",
+                        "Defocus (\u{00b5}m)",
+                    ] {
+                        ui.strong(header);
+                    }
+                    ui.end_row();
+                    for res in primary {
+                        ui.label(format!("{:.1}", res.temperature));
+                        for value in [
+                            res.efl_change,
+                            res.bfd_change,
+                            res.image_plane_shift,
+                            res.defocus,
+                        ] {
+                            ui.label(format_um(value));
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Format a length in mm as µm.
+fn format_um(value_mm: f64) -> String {
+    if value_mm.is_finite() {
+        format!("{:.3}", value_mm * 1e3)
+    } else {
+        "\u{2014}".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui_kittest::{Harness, kittest::Queryable};
+
+    #[test]
+    fn no_result_shows_placeholder() {
+        let mut harness = Harness::new(|ctx| {
+            let mut open = true;
+            let mut specs = SystemSpecs::default();
+            ThermalWindow::show(ctx, &mut open, &mut specs, None);
+        });
+        harness.step();
+        harness.get_by_label("No data yet.");
+        harness.get_by_label("Mount CTE (10\u{207b}\u{2076}/K):");
+    }
+
+    #[test]
+    fn thermal_error_is_shown() {
+        let mut result = ResultPackage::error(1, "unused".to_string());
+        result.error = None;
+        result.thermal_error = Some("bad temperatures".to_string());
+        let mut harness = Harness::new(|ctx| {
+            let mut open = true;
+            let mut specs = SystemSpecs::default();
+            ThermalWindow::show(ctx, &mut open, &mut specs, Some(&result));
+        });
+        harness.step();
+        harness.get_by_label_contains("bad temperatures");
+    }
+
+    #[test]
+    fn computed_result_shows_table() {
+        use crate::{gui::convert, thermal_view};
+
+        let specs = SystemSpecs::default();
+        #[cfg(not(feature = "ri-info"))]
+        let parsed = convert::convert_specs(&specs).expect("convert");
+        #[cfg(feature = "ri-info")]
+        let parsed = convert::convert_specs(&specs, &Default::default()).expect("convert");
+        let tv = thermal_view(
+            &parsed.gaps,
+            &parsed.surfaces,
+            &parsed.wavelengths,
+            specs.stop_surface,
+            &parsed.fields,
+            &convert::thermal_spec(&specs.thermal),
+        )
+        .expect("thermal");
+        let mut result = ResultPackage::error(1, String::new());
+        result.error = None;
+        result.wavelengths = parsed.wavelengths.clone();
+        result.thermal = Some(tv);
+        let mut harness = Harness::new(|ctx| {
+            let mut open = true;
+            let mut specs = specs.clone();
+            ThermalWindow::show(ctx, &mut open, &mut specs, Some(&result));
+        });
+        harness.step();
+        harness.get_by_label("\u{0394}EFL (\u{00b5}m)");
+        harness.get_by_label("20.0");
+    }
+}
@@ -21,6 +21,10 @@
 //! - [RefractiveIndexSpec](trait@RefractiveIndexSpec) - Describes the
 //!   refractive index of a gap. This is a trait so that different material
 //!   databases may be implemented.
+//! - [MaterialRef](enum@MaterialRef) - A serializable reference to a material,
+//!   which a [MaterialResolver](trait@MaterialResolver) turns into a
+//!   `RefractiveIndexSpec`. A [GapRefSpec](struct@GapRefSpec) is a gap whose
+//!   material is given by reference.
 //! - Wavelength - Describes a single wavelength to model.
 //!
 //! The outputs of the system are provided by views, such as:
@@ -33,6 +37,8 @@
 //!   system.
 //! - [ComponentsView](fn@components_view) - A view of the components of the
 //!   system. Used for grouping surfaces into lenses.
+//! - [ThermalView](fn@thermal_view) - The change of focus of the system with
+//!   temperature.
 //!
 //! # Quick Start
 //! ```rust
@@ -152,13 +158,32 @@ pub use core::{
         placement::{Placement, SurfacePlacement},
         solves::{FNumberSolve, MarginalRaySolve, Solve, SolveKind},
     },
-    surfaces::{Conic, Image, Iris, Object, Probe, Sphere, Surface, SurfaceKind},
+    surfaces::{
+        Axicon, Biconic, CardinalLens, Conic, Diffractive, EvenAsphere, ForbesAsphere, Fresnel,
+        Grating, GridSag, Image, Iris, LensletArray, Masked, Mounted, Object, ParaxialLens, Probe,
+        SagGrid, Sphere, Surface, SurfaceKind, Toroid, XYPolynomial, ZernikeSag,
+        forbes_asphere::{power_series_to_q, q_to_power_series},
+        xy_polynomial::{MAX_XY_ORDER, xy_term_count, xy_term_index, xy_term_powers},
+    },
+};
+pub use materials::{
+    agf::{CatalogGlass, GlassCatalog, InternalTransmission, ThermalCoefficients},
+    air::Air,
+    dispersion::{DispersionFormula, FormulaMaterial},
+    model_glass::ModelGlass,
+    properties::{
+        C_LINE, D_LINE, F_LINE, G_LINE, abbe_number, dn_dwavelength, nd, partial_dispersion,
+    },
 };
 pub use specs::{
     aperture::ApertureSpec,
+    environment::Environment,
     fields::{FieldSpec, PupilSampling},
     gaps::{ConstantRefractiveIndex, GapSpec, RefractiveIndexSpec},
-    surfaces::{BoundaryKind, Mask, SurfaceSpec},
+    material_ref::{GapRefSpec, MaterialRef, MaterialResolver},
+    surfaces::{
+        BoundaryKind, ForbesQType, LensletPacking, Mask, Spider, SurfaceSpec, ZernikeOrdering,
+    },
 };
 pub use views::{
     components::{Component, components_view},
@@ -173,8 +198,11 @@ pub use views::{
         RayBundle, SamplingConfig, TraceResults, TraceResultsCollection, ray_trace_3d_view,
         trace_ray_bundle,
     },
+    thermal::{ThermalResult, ThermalSpec, ThermalView, thermal_view},
 };
 
 // Re-exports from dependencies
 #[cfg(feature = "ri-info")]
 pub use lib_ria::Material;
+#[cfg(feature = "ri-info")]
+pub use materials::ri_info::material_from_yaml;
@@ -0,0 +1,662 @@
+//! Glass catalogs in the Zemax AGF format.
+//!
+//! An AGF file is a list of records, one per line, each starting with a
+//! two-letter code. Every glass starts with an `NM` record; the records that
+//! follow it, up to the next `NM`, describe that glass:
+//!
+//! ```text
+//! NM name formula MIL nd vd exclude status melt_frequency
+//! ED TCE(-30/70) TCE(100/300) density ΔPgF ignore_thermal_expansion
+//! CD c_0 c_1 ... c_9
+//! TD D_0 D_1 D_2 E_0 E_1 λ_tk T_ref
+//! LD λ_min λ_max
+//! IT λ τ thickness
+//! ```
+//!
+//! Other records, such as comments (`CC`, `GC`) and ordering data (`OD`), are
+//! ignored.
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use super::{
+    model_glass::{PGF_0, PGF_SLOPE},
+    properties::{self, F_LINE, G_LINE, abbe_number, partial_dispersion},
+};
+use crate::{
+    DispersionFormula, Environment, FormulaMaterial, RefractiveIndexSpec,
+    core::{Float, PI},
+    materials::air::air_index,
+};
+
+/// Reference temperature of glasses whose catalog does not give one, in °C.
+const DEFAULT_REFERENCE_TEMPERATURE: Float = 20.0;
+
+/// Differences of `n_d`, `V_d` and `ΔP_g,F` that count as equally far apart
+/// when searching for the nearest glass.
+const ND_SCALE: Float = 0.01;
+const VD_SCALE: Float = 1.0;
+const DELTA_PGF_SCALE: Float = 0.002;
+
+/// The glasses of a catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlassCatalog {
+    pub glasses: Vec<CatalogGlass>,
+}
+
+/// A glass from a catalog.
+///
+/// The refractive index follows the catalog's dispersion formula inside its
+/// wavelength range. The extinction coefficient is derived from the internal
+/// transmission data, interpolated linearly in wavelength and held constant
+/// beyond the first and last points; it is zero if there is no data.
+///
+/// Catalog indices are relative to air at the reference temperature and
+/// standard pressure. In an [`Environment`],
+/// [`n_in`](RefractiveIndexSpec::n_in) is instead the absolute index at the
+/// environment's temperature, from the thermal coefficients.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogGlass {
+    pub name: String,
+    /// Refractive index at the d line (587.6 nm).
+    pub nd: Float,
+    /// Abbe number at the d line.
+    pub vd: Float,
+    /// Deviation of the partial dispersion `P_g,F` from the normal line.
+    pub delta_pgf: Float,
+    /// Coefficient of thermal expansion from -30 to 70 °C, in 1/K.
+    pub thermal_expansion: Float,
+    pub material: FormulaMaterial,
+    pub thermal: ThermalCoefficients,
+    /// Internal transmission data in order of increasing wavelength.
+    pub transmission: Vec<InternalTransmission>,
+}
+
+/// Coefficients of the Schott formula for the change of the absolute
+/// refractive index with temperature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ThermalCoefficients {
+    pub d_0: Float,
+    pub d_1: Float,
+    pub d_2: Float,
+    pub e_0: Float,
+    pub e_1: Float,
+    /// Effective resonance wavelength, in micrometers.
+    pub lambda_tk: Float,
+    /// Temperature at which the dispersion formula holds, in °C.
+    pub reference_temperature: Float,
+}
+
+impl ThermalCoefficients {
+    /// Returns the change of the absolute index of a glass from the
+    /// reference temperature to `temperature`, in °C.
+    ///
+    /// `n` is the absolute index at the reference temperature and
+    /// `wavelength` is in micrometers. The change follows the Schott formula
+    ///
+    /// ```text
+    /// Δn_abs = (n² - 1) / 2n (D_0 ΔT + D_1 ΔT² + D_2 ΔT³ + (E_0 ΔT + E_1 ΔT²) / (λ² - λ_tk²))
+    /// ```
+    ///
+    /// where the sign of `λ_tk²` is that of `λ_tk`.
+    pub fn delta_n_abs(&self, n: Float, wavelength: Float, temperature: Float) -> Float {
+        let dt = temperature - self.reference_temperature;
+        let lambda_tk_sq = self.lambda_tk.signum() * self.lambda_tk * self.lambda_tk;
+        let resonance =
+            (self.e_0 * dt + self.e_1 * dt * dt) / (wavelength * wavelength - lambda_tk_sq);
+        (n * n - 1.0) / (2.0 * n) * (dt * (self.d_0 + dt * (self.d_1 + dt * self.d_2)) + resonance)
+    }
+}
+
+impl Default for ThermalCoefficients {
+    fn default() -> Self {
+        Self {
+            d_0: 0.0,
+            d_1: 0.0,
+            d_2: 0.0,
+            e_0: 0.0,
+            e_1: 0.0,
+            lambda_tk: 0.0,
+            reference_temperature: DEFAULT_REFERENCE_TEMPERATURE,
+        }
+    }
+}
+
+/// A point of a glass's internal transmission curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InternalTransmission {
+    /// Wavelength in micrometers.
+    pub wavelength: Float,
+    /// Internal transmittance, between 0 and 1.
+    pub transmittance: Float,
+    /// Thickness of the sample, in mm.
+    pub thickness: Float,
+}
+
+impl InternalTransmission {
+    /// Returns the extinction coefficient that gives this transmittance.
+    fn extinction_coefficient(&self) -> Float {
+        // The 1e3 converts the thickness from mm to µm.
+        -self.wavelength * self.transmittance.ln() / (4.0 * PI * self.thickness * 1e3)
+    }
+}
+
+impl GlassCatalog {
+    /// Parses a catalog from the contents of an AGF file.
+    ///
+    /// The contents may be UTF-8, Latin-1, or UTF-16 with a byte order mark,
+    /// as written by Zemax.
+    ///
+    /// # Errors
+    /// If a glass has a malformed record, an unknown formula number or no
+    /// wavelength range, or if its formula is invalid.
+    pub fn from_agf(contents: &[u8]) -> Result<Self> {
+        let mut glasses = Vec::new();
+        let mut glass: Option<PartialGlass> = None;
+        for (i, line) in decode(contents).lines().enumerate() {
+            let mut fields = line.split_whitespace();
+            let Some(code) = fields.next() else {
+                continue;
+            };
+            let fields: Vec<&str> = fields.collect();
+            let line_number = i + 1;
+            let context = || format!("line {line_number}: {code} record");
+
+            if code == "NM" {
+                if let Some(glass) = glass.take() {
+                    glasses.push(glass.finish()?);
+                }
+                glass = Some(PartialGlass::new(&fields).with_context(context)?);
+                continue;
+            }
+            let Some(glass) = glass.as_mut() else {
+                continue;
+            };
+            glass.read(code, &fields).with_context(context)?;
+        }
+        if let Some(glass) = glass {
+            glasses.push(glass.finish()?);
+        }
+        Ok(Self { glasses })
+    }
+
+    /// Reads a catalog from an AGF file.
+    pub fn read_agf(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents =
+            std::fs::read(path).with_context(|| format!("cannot read {}", path.display()))?;
+        Self::from_agf(&contents).with_context(|| format!("cannot parse {}", path.display()))
+    }
+
+    /// Returns the glass with the given name, if any.
+    pub fn get(&self, name: &str) -> Option<&CatalogGlass> {
+        self.glasses.iter().find(|g| g.name == name)
+    }
+
+    /// Returns the glass whose `n_d`, `V_d` and deviation of `P_g,F` from the
+    /// normal line are nearest to the given ones, such as those of a
+    /// [`ModelGlass`](crate::ModelGlass).
+    ///
+    /// The properties of each glass are computed from its dispersion formula.
+    /// Their differences are weighted so that 0.01 in `n_d`, 1 in `V_d` and
+    /// 0.002 in `ΔP_g,F` count equally. Glasses that are not defined at the
+    /// d, F, C and g lines are skipped. Returns `None` if no glass is left.
+    pub fn nearest_glass(&self, nd: Float, vd: Float, delta_pgf: Float) -> Option<&CatalogGlass> {
+        let distance = |glass: &CatalogGlass| -> Result<Float> {
+            let glass_vd = abbe_number(glass)?;
+            let glass_delta_pgf =
+                partial_dispersion(glass, G_LINE, F_LINE)? - (PGF_0 + PGF_SLOPE * glass_vd);
+            Ok(Float::hypot(
+                (properties::nd(glass)? - nd) / ND_SCALE,
+                Float::hypot(
+                    (glass_vd - vd) / VD_SCALE,
+                    (glass_delta_pgf - delta_pgf) / DELTA_PGF_SCALE,
+                ),
+            ))
+        };
+        self.glasses
+            .iter()
+            .filter_map(|glass| Some((glass, distance(glass).ok()?)))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(glass, _)| glass)
+    }
+}
+
+impl RefractiveIndexSpec for CatalogGlass {
+    fn n(&self, wavelength: Float) -> Result<Float> {
+        self.material
+            .n(wavelength)
+            .with_context(|| format!("glass {}", self.name))
+    }
+
+    fn n_in(&self, wavelength: Float, environment: &Environment) -> Result<Float> {
+        let reference = Environment {
+            temperature: self.thermal.reference_temperature,
+            ..Environment::default()
+        };
+        let n_abs = self.n(wavelength)? * air_index(wavelength, &reference);
+        Ok(n_abs
+            + self
+                .thermal
+                .delta_n_abs(n_abs, wavelength, environment.temperature))
+    }
+
+    /// Returns `None` if the catalog gives no coefficient.
+    fn thermal_expansion(&self) -> Option<Float> {
+        (self.thermal_expansion != 0.0).then_some(self.thermal_expansion)
+    }
+
+    fn wavelength_range(&self) -> Option<[Float; 2]> {
+        self.material.wavelength_range()
+    }
+
+    fn k(&self, wavelength: Float) -> Result<Float> {
+        self.n(wavelength)?;
+        let data = &self.transmission;
+        let (Some(first), Some(last)) = (data.first(), data.last()) else {
+            return Ok(0.0);
+        };
+        if wavelength <= first.wavelength {
+            return Ok(first.extinction_coefficient());
+        }
+        if wavelength >= last.wavelength {
+            return Ok(last.extinction_coefficient());
+        }
+        let i = data.partition_point(|t| t.wavelength < wavelength);
+        let (a, b) = (&data[i - 1], &data[i]);
+        let t = (wavelength - a.wavelength) / (b.wavelength - a.wavelength);
+        let (k_a, k_b) = (a.extinction_coefficient(), b.extinction_coefficient());
+        Ok(k_a + t * (k_b - k_a))
+    }
+}
+
+/// A glass whose records are still being read.
+struct PartialGlass {
+    name: String,
+    formula_number: u32,
+    nd: Float,
+    vd: Float,
+    delta_pgf: Float,
+    thermal_expansion: Float,
+    coefficients: Vec<Float>,
+    wavelength_range: Option<[Float; 2]>,
+    thermal: ThermalCoefficients,
+    transmission: Vec<InternalTransmission>,
+}
+
+impl PartialGlass {
+    fn new(fields: &[&str]) -> Result<Self> {
+        let name = fields
+            .first()
+            .ok_or_else(|| anyhow!("missing glass name"))?
+            .to_string();
+        let formula = number(fields, 1)?;
+        if formula.fract() != 0.0 || formula < 0.0 {
+            bail!("invalid formula number {formula}");
+        }
+        Ok(Self {
+            name,
+            formula_number: formula as u32,
+            nd: number(fields, 3)?,
+            vd: number(fields, 4)?,
+            delta_pgf: 0.0,
+            thermal_expansion: 0.0,
+            coefficients: Vec::new(),
+            wavelength_range: None,
+            thermal: ThermalCoefficients::default(),
+            transmission: Vec::new(),
+        })
+    }
+
+    fn read(&mut self, code: &str, fields: &[&str]) -> Result<()> {
+        match code {
+            "ED" => {
+                self.thermal_expansion = number_or_zero(fields, 0)? * 1e-6;
+                self.delta_pgf = number_or_zero(fields, 3)?;
+            }
+            "CD" => {
+                self.coefficients = (0..fields.len())
+                    .map(|i| number(fields, i))
+                    .collect::<Result<_>>()?;
+            }
+            "TD" => {
+                self.thermal = ThermalCoefficients {
+                    d_0: number_or_zero(fields, 0)?,
+                    d_1: number_or_zero(fields, 1)?,
+                    d_2: number_or_zero(fields, 2)?,
+                    e_0: number_or_zero(fields, 3)?,
+                    e_1: number_or_zero(fields, 4)?,
+                    lambda_tk: number_or_zero(fields, 5)?,
+                    reference_temperature: match fields.get(6) {
+                        Some(_) => number(fields, 6)?,
+                        None => DEFAULT_REFERENCE_TEMPERATURE,
+                    },
+                };
+            }
+            "LD" => self.wavelength_range = Some([number(fields, 0)?, number(fields, 1)?]),
+            "IT" => {
+                let point = InternalTransmission {
+                    wavelength: number(fields, 0)?,
+                    transmittance: number(fields, 1)?,
+                    thickness: number_or_zero(fields, 2)?,
+                };
+                // Points without a sample thickness or with no light
+                // transmitted say nothing about the extinction coefficient.
+                if point.thickness > 0.0 && point.transmittance > 0.0 {
+                    self.transmission.push(point);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<CatalogGlass> {
+        let context = || format!("glass {}", self.name);
+        let formula = agf_formula(self.formula_number, &self.coefficients).with_context(context)?;
+        let range = self
+            .wavelength_range
+            .ok_or_else(|| anyhow!("missing LD record"))
+            .with_context(context)?;
+        let material = FormulaMaterial::new(formula, range).with_context(context)?;
+        self.transmission
+            .sort_by(|a, b| a.wavelength.total_cmp(&b.wavelength));
+        Ok(CatalogGlass {
+            name: self.name,
+            nd: self.nd,
+            vd: self.vd,
+            delta_pgf: self.delta_pgf,
+            thermal_expansion: self.thermal_expansion,
+            material,
+            thermal: self.thermal,
+            transmission: self.transmission,
+        })
+    }
+}
+
+/// Returns the dispersion formula with the given AGF formula number.
+///
+/// Coefficients missing from the `CD` record are taken to be zero.
+fn agf_formula(number: u32, coefficients: &[Float]) -> Result<DispersionFormula> {
+    let c = |i: usize| coefficients.get(i).copied().unwrap_or(0.0);
+    fn a<const N: usize>(c: impl Fn(usize) -> Float) -> [Float; N] {
+        std::array::from_fn(c)
+    }
+    // Sellmeier coefficients alternate between b_i and c_i.
+    fn b<const N: usize>(c: impl Fn(usize) -> Float, offset: usize) -> [Float; N] {
+        std::array::from_fn(|i| c(offset + 2 * i))
+    }
+    let formula = match number {
+        1 => DispersionFormula::Schott { a: a(c) },
+        2 => DispersionFormula::Sellmeier1 {
+            b: b(c, 0),
+            c: b(c, 1),
+        },
+        3 => DispersionFormula::Herzberger { a: a(c) },
+        4 => DispersionFormula::Sellmeier2 {
+            a: c(0),
+            b: [c(1), c(3)],
+            lambda: [c(2), c(4)],
+        },
+        5 => DispersionFormula::Conrady {
+            n_0: c(0),
+            a: c(1),
+            b: c(2),
+        },
+        6 => DispersionFormula::Sellmeier3 {
+            b: b(c, 0),
+            c: b(c, 1),
+        },
+        7 => DispersionFormula::HandbookOfOptics1 { a: a(c) },
+        8 => DispersionFormula::HandbookOfOptics2 { a: a(c) },
+        9 => DispersionFormula::Sellmeier4 {
+            a: c(0),
+            b: [c(1), c(3)],
+            c: [c(2), c(4)],
+        },
+        10 => DispersionFormula::Extended1 { a: a(c) },
+        11 => DispersionFormula::Sellmeier5 {
+            b: b(c, 0),
+            c: b(c, 1),
+        },
+        12 => DispersionFormula::Extended2 { a: a(c) },
+        13 => DispersionFormula::Extended3 { a: a(c) },
+        _ => bail!("unknown AGF formula number {number}"),
+    };
+    Ok(formula)
+}
+
+/// Parses the field at `i` as a number.
+fn number(fields: &[&str], i: usize) -> Result<Float> {
+    let field = fields
+        .get(i)
+        .ok_or_else(|| anyhow!("missing field {}", i + 1))?;
+    field
+        .parse()
+        .with_context(|| format!("cannot parse field {} '{field}' as a number", i + 1))
+}
+
+/// Parses the field at `i` as a number, or returns zero if it is missing or a
+/// dash.
+fn number_or_zero(fields: &[&str], i: usize) -> Result<Float> {
+    match fields.get(i) {
+        None | Some(&"-") => Ok(0.0),
+        Some(_) => number(fields, i),
+    }
+}
+
+/// Decodes the contents of an AGF file to text.
+pub(super) fn decode(contents: &[u8]) -> String {
+    let utf16 = |bytes: &[u8], from: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| from([pair[0], pair[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    match contents {
+        [0xff, 0xfe, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xfe, 0xff, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        [0xef, 0xbb, 0xbf, rest @ ..] => String::from_utf8_lossy(rest).into_owned(),
+        _ => match std::str::from_utf8(contents) {
+            Ok(text) => text.to_owned(),
+            // Latin-1 maps each byte to the code point of the same value.
+            Err(_) => contents.iter().map(|&b| b as char).collect(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    const CATALOG: &str = "\
+CC Test catalog
+NM N-BK7 2 517642.251 1.5168 64.17 0 0 0
+GC Borosilicate crown
+ED 7.1 8.3 2.51 -0.0009 0
+CD 1.03961212 6.00069867E-03 2.31792344E-01 2.00179144E-02 1.01046945 1.03560653E+02 0 0 0 0
+TD 1.86E-06 1.31E-08 -1.37E-11 4.34E-07 6.27E-10 1.70E-01 20.0
+OD 1 1 1 1 2.3 1
+LD 0.3 2.5
+IT 0.4 0.997 10
+IT 0.35 0.0 25
+IT 0.5 0.998 25
+NM N-BK7-SCHOTT 1.0 517642.251 1.5168 64.17
+CD 2.2718929 -1.0108077E-02 1.0592509E-02 2.0816965E-04 -7.6472538E-06 4.9240991E-07
+LD 0.32 2.5
+";
+
+    #[test]
+    fn catalog_is_parsed() {
+        let catalog = GlassCatalog::from_agf(CATALOG.as_bytes()).unwrap();
+        assert_eq!(catalog.glasses.len(), 2);
+
+        let nbk7 = catalog.get("N-BK7").unwrap();
+        assert_eq!((nbk7.nd, nbk7.vd, nbk7.delta_pgf), (1.5168, 64.17, -0.0009));
+        assert_abs_diff_eq!(nbk7.thermal_expansion, 7.1e-6, epsilon = 1e-18);
+        assert_eq!(nbk7.material.wavelength_range, [0.3, 2.5]);
+        assert_eq!(nbk7.thermal.d_0, 1.86e-6);
+        assert_eq!(nbk7.thermal.lambda_tk, 0.17);
+        assert_eq!(nbk7.thermal.reference_temperature, 20.0);
+        assert_abs_diff_eq!(nbk7.n(0.5875618).unwrap(), 1.5168, epsilon = 1e-4);
+        assert!(nbk7.n(2.6).is_err());
+
+        // The opaque point is dropped and the rest sorted.
+        let wavelengths: Vec<Float> = nbk7.transmission.iter().map(|t| t.wavelength).collect();
+        assert_eq!(wavelengths, vec![0.4, 0.5]);
+
+        let schott = catalog.get("N-BK7-SCHOTT").unwrap();
+        assert!(matches!(
+            schott.material.formula,
+            DispersionFormula::Schott { .. }
+        ));
+        assert_abs_diff_eq!(schott.n(0.5875618).unwrap(), 1.5168, epsilon = 1e-4);
+        assert_eq!(schott.thermal, ThermalCoefficients::default());
+        assert_eq!(schott.k(0.5).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn extinction_coefficient_follows_internal_transmission() {
+        let catalog = GlassCatalog::from_agf(CATALOG.as_bytes()).unwrap();
+        let nbk7 = catalog.get("N-BK7").unwrap();
+
+        // τ = exp(-4π k d / λ)
+        let k = |wavelength: Float, tau: Float, thickness: Float| {
+            -wavelength * Float::ln(tau) / (4.0 * PI * thickness * 1e3)
+        };
+        let (k_400, k_500) = (k(0.4, 0.997, 10.0), k(0.5, 0.998, 25.0));
+        assert_abs_diff_eq!(nbk7.k(0.4).unwrap(), k_400, epsilon = 1e-18);
+        assert_abs_diff_eq!(
+            nbk7.k(0.45).unwrap(),
+            0.5 * (k_400 + k_500),
+            epsilon = 1e-18
+        );
+        assert_abs_diff_eq!(nbk7.k(0.35).unwrap(), k_400, epsilon = 1e-18);
+        assert_abs_diff_eq!(nbk7.k(1.0).unwrap(), k_500, epsilon = 1e-18);
+    }
+
+    #[test]
+    fn index_in_an_environment_is_absolute() {
+        let catalog = GlassCatalog::from_agf(CATALOG.as_bytes()).unwrap();
+        let nbk7 = catalog.get("N-BK7").unwrap();
+        let wavelength = 0.5875618;
+        let n_in = |temperature| {
+            nbk7.n_in(wavelength, &Environment::new(temperature, 101_325.0))
+                .unwrap()
+        };
+
+        // At the reference temperature, the relative index times that of air.
+        let n_air = air_index(wavelength, &Environment::default());
+        assert_abs_diff_eq!(
+            n_in(20.0),
+            nbk7.n(wavelength).unwrap() * n_air,
+            epsilon = 1e-12
+        );
+
+        // dn_abs/dT of N-BK7 at the d line from 20 to 40 °C.
+        assert_abs_diff_eq!((n_in(40.0) - n_in(20.0)) / 20.0, 1.514e-6, epsilon = 1e-9);
+
+        // Without thermal coefficients, temperature has no effect.
+        let schott = catalog.get("N-BK7-SCHOTT").unwrap();
+        let env = Environment::new(60.0, 101_325.0);
+        assert_abs_diff_eq!(
+            schott.n_in(wavelength, &env).unwrap(),
+            schott.n(wavelength).unwrap() * n_air,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn formula_numbers_map_to_formulas() {
+        let coefficients: Vec<Float> = (1..=10).map(|i| i as Float * 1e-3).collect();
+        for number in 1..=13 {
+            assert!(agf_formula(number, &coefficients).is_ok(), "{number}");
+        }
+        assert!(agf_formula(0, &coefficients).is_err());
+        assert!(agf_formula(14, &coefficients).is_err());
+
+        assert_eq!(
+            agf_formula(4, &coefficients).unwrap(),
+            DispersionFormula::Sellmeier2 {
+                a: 1e-3,
+                b: [2e-3, 4e-3],
+                lambda: [3e-3, 5e-3]
+            }
+        );
+        assert_eq!(
+            agf_formula(6, &coefficients).unwrap(),
+            DispersionFormula::Sellmeier3 {
+                b: [1e-3, 3e-3, 5e-3, 7e-3],
+                c: [2e-3, 4e-3, 6e-3, 8e-3]
+            }
+        );
+        // Missing coefficients are zero.
+        assert_eq!(
+            agf_formula(5, &[1.5]).unwrap(),
+            DispersionFormula::Conrady {
+                n_0: 1.5,
+                a: 0.0,
+                b: 0.0
+            }
+        );
+    }
+
+    #[test]
+    fn nearest_glass_matches_all_three_properties() {
+        let catalog = GlassCatalog::from_agf(
+            "\
+NM N-BK7 2 0 1.5168 64.17
+CD 1.03961212 6.00069867E-03 2.31792344E-01 2.00179144E-02 1.01046945 1.03560653E+02
+LD 0.3 2.5
+NM F2 2 0 1.62004 36.37
+CD 1.34533359 9.97743871E-03 2.09073176E-01 4.70450767E-02 9.37357162E-01 1.11886764E+02
+LD 0.32 2.5
+NM NARROW 2 0 1.5168 64.17
+CD 1.03961212 6.00069867E-03 2.31792344E-01 2.00179144E-02 1.01046945 1.03560653E+02
+LD 0.5 0.7
+"
+            .as_bytes(),
+        )
+        .unwrap();
+
+        let name = |nd, vd, delta_pgf| {
+            catalog
+                .nearest_glass(nd, vd, delta_pgf)
+                .map(|g| g.name.as_str())
+        };
+        assert_eq!(name(1.52, 60.0, 0.0), Some("N-BK7"));
+        assert_eq!(name(1.60, 40.0, 0.0), Some("F2"));
+        // The Abbe number outweighs a small difference in index.
+        assert_eq!(name(1.5168, 36.37, 0.0), Some("F2"));
+
+        // NARROW is not defined at the F and g lines and is never chosen.
+        let narrow = GlassCatalog {
+            glasses: vec![catalog.get("NARROW").unwrap().clone()],
+        };
+        assert!(narrow.nearest_glass(1.5168, 64.17, 0.0).is_none());
+    }
+
+    #[test]
+    fn utf16_catalogs_are_decoded() {
+        let mut contents = vec![0xff, 0xfe];
+        contents.extend(CATALOG.encode_utf16().flat_map(u16::to_le_bytes));
+        let catalog = GlassCatalog::from_agf(&contents).unwrap();
+        assert_eq!(catalog, GlassCatalog::from_agf(CATALOG.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn malformed_catalogs_are_errors() {
+        let unknown_formula = "NM X 42 0 1.5 60\nCD 1\nLD 0.4 0.7\n";
+        let no_range = "NM X 2 0 1.5 60\nCD 1 0.01\n";
+        let bad_number = "NM X 2 0 1.5 60\nCD 1 abc\nLD 0.4 0.7\n";
+        let short_name_record = "NM X 2\n";
+        for contents in [unknown_formula, no_range, bad_number, short_name_record] {
+            assert!(
+                GlassCatalog::from_agf(contents.as_bytes()).is_err(),
+                "{contents}"
+            );
+        }
+    }
+}
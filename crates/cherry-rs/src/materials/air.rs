@@ -0,0 +1,114 @@
+//! The refractive index of air.
+use anyhow::{Result, bail};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Environment, RefractiveIndexSpec, core::Float};
+
+/// Wavelength range over which the formula is used, in µm.
+const WAVELENGTH_RANGE: [Float; 2] = [0.2, 2.5];
+
+/// Dry air whose index depends on its temperature and pressure.
+///
+/// The index follows Edlén's formula as updated by Birch and Downs,
+/// Metrologia 31, 315 (1994). The refractivity of standard air, at 15 °C and
+/// 101325 Pa with 0.04 % carbon dioxide, is
+///
+/// ```text
+/// (n - 1)_s 10⁸ = 8342.54 + 2406147 / (130 - σ²) + 15998 / (38.9 - σ²)
+/// ```
+///
+/// where `σ` is the vacuum wavenumber in 1/µm, and at temperature `t` in °C
+/// and pressure `p` in Pa
+///
+/// ```text
+/// (n - 1)_tp = p (n - 1)_s / 96095.43
+///              × (1 + 10⁻⁸ (0.601 - 0.00972 t) p) / (1 + 0.0036610 t)
+/// ```
+///
+/// [`n`](RefractiveIndexSpec::n) is the index at the default
+/// [`Environment`], 20 °C and 101325 Pa; [`n_in`](RefractiveIndexSpec::n_in)
+/// is the index in any other. Air is lossless and defined from 0.2 to 2.5 µm.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Air;
+
+impl Air {
+    fn check_wavelength(wavelength: Float) -> Result<()> {
+        let [min, max] = WAVELENGTH_RANGE;
+        if !(min..=max).contains(&wavelength) {
+            bail!("Wavelength {wavelength} µm is outside the range [{min}, {max}] µm of air");
+        }
+        Ok(())
+    }
+}
+
+/// Returns the index of air without checking the wavelength range.
+///
+/// The formula has no poles in the infrared, so it extrapolates well enough
+/// to convert the relative indices of infrared materials.
+pub(crate) fn air_index(wavelength: Float, environment: &Environment) -> Float {
+    let sigma_sq = 1.0 / (wavelength * wavelength);
+    let n_s = (8342.54 + 2_406_147.0 / (130.0 - sigma_sq) + 15998.0 / (38.9 - sigma_sq)) * 1e-8;
+
+    let Environment {
+        temperature: t,
+        pressure: p,
+    } = *environment;
+    let n_tp =
+        p * n_s / 96095.43 * (1.0 + 1e-8 * (0.601 - 0.00972 * t) * p) / (1.0 + 0.0036610 * t);
+    1.0 + n_tp
+}
+
+impl RefractiveIndexSpec for Air {
+    fn n(&self, wavelength: Float) -> Result<Float> {
+        self.n_in(wavelength, &Environment::default())
+    }
+
+    fn k(&self, wavelength: Float) -> Result<Float> {
+        Self::check_wavelength(wavelength)?;
+        Ok(0.0)
+    }
+
+    fn n_in(&self, wavelength: Float, environment: &Environment) -> Result<Float> {
+        Self::check_wavelength(wavelength)?;
+        Ok(air_index(wavelength, environment))
+    }
+
+    fn wavelength_range(&self) -> Option<[Float; 2]> {
+        Some(WAVELENGTH_RANGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn index_of_standard_air() {
+        // At the HeNe line, at 20 °C and at 15 °C.
+        assert_abs_diff_eq!(Air.n(0.633).unwrap(), 1.00027180, epsilon = 1e-8);
+        assert_abs_diff_eq!(
+            Air.n_in(0.633, &Environment::new(15.0, 101_325.0)).unwrap(),
+            1.00027653,
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn index_falls_with_temperature_and_pressure() {
+        let n = |t, p| Air.n_in(0.55, &Environment::new(t, p)).unwrap();
+        assert_eq!(n(20.0, 0.0), 1.0);
+        assert!(n(40.0, 101_325.0) < n(20.0, 101_325.0));
+        assert!(n(20.0, 80_000.0) < n(20.0, 101_325.0));
+        // About -0.93e-6 per kelvin near room temperature.
+        assert_abs_diff_eq!(
+            n(21.0, 101_325.0) - n(20.0, 101_325.0),
+            -0.93e-6,
+            epsilon = 0.02e-6
+        );
+        assert!(Air.n(0.1).is_err());
+        assert!(Air.k(3.0).is_err());
+    }
+}
@@ -0,0 +1,222 @@
+//! Glass catalogs in the CODE V private catalog format.
+//!
+//! A private catalog is a block of commands between `PRV` and `END`, usually
+//! kept in a sequence (`.seq`) file. `PWL` sets the wavelengths, in
+//! nanometers, at which the glasses that follow it are tabulated; each glass
+//! is its name followed by one refractive index per wavelength:
+//!
+//! ```text
+//! PRV
+//! PWL 656.3 587.6 486.1
+//! 'N-BK7' 1.51432 1.51680 1.52238
+//! END
+//! ```
+//!
+//! Commands are case-insensitive and may be separated by newlines or `;`.
+//! Text after `!` is a comment. Commands outside a `PRV` block, such as those
+//! that define a lens in the same sequence file, are ignored.
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use super::{
+    agf::{ThermalCoefficients, decode},
+    model_glass::{PGF_0, PGF_SLOPE},
+};
+use crate::{
+    CatalogGlass, FormulaMaterial, GlassCatalog, RefractiveIndexSpec,
+    core::Float,
+    materials::properties::{C_LINE, D_LINE, F_LINE, G_LINE},
+};
+
+impl GlassCatalog {
+    /// Parses a catalog from the private glasses of a CODE V sequence file.
+    ///
+    /// The index of each glass is interpolated linearly between its
+    /// tabulated wavelengths, and `nd`, `vd` and `delta_pgf` are computed from
+    /// it; they are NaN if the table does not span the Fraunhofer lines that
+    /// define them. CODE V catalogs carry no thermal or transmission data.
+    ///
+    /// # Errors
+    /// If a glass comes before any `PWL` command, if its number of indices
+    /// differs from the number of wavelengths, or if a number is malformed.
+    pub fn from_codev(contents: &[u8]) -> Result<Self> {
+        let mut glasses = Vec::new();
+        let mut in_catalog = false;
+        let mut wavelengths: Option<Vec<Float>> = None;
+        for (i, line) in decode(contents).lines().enumerate() {
+            let line = line.split('!').next().unwrap_or_default();
+            for command in line.split(';') {
+                let fields = tokens(command);
+                let Some(code) = fields.first() else {
+                    continue;
+                };
+                let context = || format!("line {}: {code}", i + 1);
+                match code.to_ascii_uppercase().as_str() {
+                    "PRV" => in_catalog = true,
+                    "END" => {
+                        in_catalog = false;
+                        wavelengths = None;
+                    }
+                    _ if !in_catalog => {}
+                    "PWL" => {
+                        let nanometers = numbers(&fields[1..]).with_context(context)?;
+                        wavelengths = Some(nanometers.iter().map(|w| w / 1e3).collect());
+                    }
+                    _ => {
+                        let glass = private_glass(code, &fields[1..], wavelengths.as_deref())
+                            .with_context(context)?;
+                        glasses.push(glass);
+                    }
+                }
+            }
+        }
+        Ok(Self { glasses })
+    }
+
+    /// Reads a catalog from the private glasses of a CODE V sequence file.
+    pub fn read_codev(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents =
+            std::fs::read(path).with_context(|| format!("cannot read {}", path.display()))?;
+        Self::from_codev(&contents).with_context(|| format!("cannot parse {}", path.display()))
+    }
+}
+
+/// Returns the glass named `name` with the given indices at `wavelengths`.
+fn private_glass(
+    name: &str,
+    indices: &[&str],
+    wavelengths: Option<&[Float]>,
+) -> Result<CatalogGlass> {
+    let name = name.trim_matches(['\'', '"']);
+    let wavelengths = wavelengths.ok_or_else(|| anyhow!("glass {name} comes before PWL"))?;
+    let indices = numbers(indices)?;
+    if indices.len() != wavelengths.len() {
+        bail!(
+            "glass {name} has {} indices for {} wavelengths",
+            indices.len(),
+            wavelengths.len()
+        );
+    }
+
+    let mut table: Vec<(Float, Float)> = wavelengths.iter().copied().zip(indices).collect();
+    table.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let (wavelengths, n) = table.into_iter().unzip();
+    let material =
+        FormulaMaterial::tabulated(wavelengths, n).with_context(|| format!("glass {name}"))?;
+
+    let n = |wavelength| material.n(wavelength).unwrap_or(Float::NAN);
+    let (n_d, n_f, n_c) = (n(D_LINE), n(F_LINE), n(C_LINE));
+    let vd = (n_d - 1.0) / (n_f - n_c);
+    let pgf = (n(G_LINE) - n_f) / (n_f - n_c);
+    Ok(CatalogGlass {
+        name: name.to_string(),
+        nd: n_d,
+        vd,
+        delta_pgf: pgf - (PGF_0 + PGF_SLOPE * vd),
+        thermal_expansion: 0.0,
+        material,
+        thermal: ThermalCoefficients::default(),
+        transmission: Vec::new(),
+    })
+}
+
+/// Splits a command into fields separated by whitespace or commas. A quoted
+/// field may contain either.
+fn tokens(command: &str) -> Vec<&str> {
+    let mut fields = Vec::new();
+    let mut rest = command.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    while let Some(first) = rest.chars().next() {
+        let end = match first {
+            '\'' | '"' => rest[1..].find(first).map_or(rest.len(), |i| i + 2),
+            _ => rest
+                .find(|c: char| c.is_whitespace() || c == ',')
+                .unwrap_or(rest.len()),
+        };
+        fields.push(&rest[..end]);
+        rest = rest[end..].trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+    }
+    fields
+}
+
+/// Parses every field as a number.
+fn numbers(fields: &[&str]) -> Result<Vec<Float>> {
+    fields
+        .iter()
+        .map(|field| {
+            field
+                .parse()
+                .with_context(|| format!("cannot parse '{field}' as a number"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    const CATALOG: &str = "\
+! Private glasses
+LEN \"Not a glass\"
+PRV
+PWL 656.2725 587.5618 486.1327 435.8343
+'N-BK7' 1.51432 1.51680 1.52238 1.52668
+PWL 486.1327, 656.2725
+\"LOW DISPERSION\" 1.4900, 1.4850 ! two wavelengths
+END
+RDY S1 50.0
+PRV; PWL 500 600; SILICA 1.4623 1.4580; END
+";
+
+    #[test]
+    fn catalog_is_parsed() {
+        let catalog = GlassCatalog::from_codev(CATALOG.as_bytes()).unwrap();
+        let names: Vec<&str> = catalog.glasses.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["N-BK7", "LOW DISPERSION", "SILICA"]);
+
+        let nbk7 = catalog.get("N-BK7").unwrap();
+        assert_eq!(nbk7.material.wavelength_range, [0.4358343, 0.6562725]);
+        assert_abs_diff_eq!(nbk7.nd, 1.5168, epsilon = 1e-12);
+        assert_abs_diff_eq!(nbk7.vd, 0.5168 / (1.52238 - 1.51432), epsilon = 1e-9);
+        let pgf = (1.52668 - 1.52238) / (1.52238 - 1.51432);
+        assert_abs_diff_eq!(
+            nbk7.delta_pgf,
+            pgf - (PGF_0 + PGF_SLOPE * nbk7.vd),
+            epsilon = 1e-9
+        );
+        let t = (0.5 - 0.4861327) / (0.5875618 - 0.4861327);
+        assert_abs_diff_eq!(
+            nbk7.n(0.5).unwrap(),
+            1.52238 + t * (1.51680 - 1.52238),
+            epsilon = 1e-12
+        );
+        assert_eq!(nbk7.k(0.5).unwrap(), 0.0);
+        assert_eq!(nbk7.thermal_expansion, 0.0);
+
+        // Wavelengths are sorted, and properties at lines outside the table
+        // are undefined.
+        let low = catalog.get("LOW DISPERSION").unwrap();
+        assert_eq!(low.material.wavelength_range, [0.4861327, 0.6562725]);
+        assert_abs_diff_eq!(low.vd, (low.nd - 1.0) / 0.005, epsilon = 1e-9);
+        assert!(low.delta_pgf.is_nan());
+
+        let silica = catalog.get("SILICA").unwrap();
+        assert!(silica.nd.is_finite() && silica.vd.is_nan());
+    }
+
+    #[test]
+    fn malformed_catalogs_are_errors() {
+        let no_wavelengths = "PRV\n'X' 1.5 1.6\nEND\n";
+        let wrong_count = "PRV\nPWL 500 600\n'X' 1.5\nEND\n";
+        let bad_number = "PRV\nPWL 500 600\n'X' 1.5 abc\nEND\n";
+        let repeated_wavelength = "PRV\nPWL 500 500\n'X' 1.5 1.6\nEND\n";
+        for contents in [no_wavelengths, wrong_count, bad_number, repeated_wavelength] {
+            assert!(
+                GlassCatalog::from_codev(contents.as_bytes()).is_err(),
+                "{contents}"
+            );
+        }
+    }
+}
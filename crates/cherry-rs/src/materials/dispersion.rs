@@ -0,0 +1,605 @@
+//! Materials described by standard dispersion formulas.
+use anyhow::{Result, bail};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{RefractiveIndexSpec, core::Float};
+
+/// The constant subtracted from λ² in the Herzberger formula, in µm².
+const HERZBERGER_LAMBDA_SQ: Float = 0.028;
+
+/// A dispersion formula for the real part of a material's refractive index.
+///
+/// Wavelengths λ are vacuum wavelengths in micrometers, and the coefficients
+/// follow the conventions of glass catalogs that use the same names.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DispersionFormula {
+    /// `n² - 1 = Σ b_i λ² / (λ² - c_i)` with three terms.
+    Sellmeier1 { b: [Float; 3], c: [Float; 3] },
+    /// `n² - 1 = a + b_1 λ² / (λ² - λ_1²) + b_2 / (λ² - λ_2²)`.
+    Sellmeier2 {
+        a: Float,
+        b: [Float; 2],
+        lambda: [Float; 2],
+    },
+    /// `n² - 1 = Σ b_i λ² / (λ² - c_i)` with four terms.
+    Sellmeier3 { b: [Float; 4], c: [Float; 4] },
+    /// `n² = a_0 + a_1 λ² + a_2 λ⁻² + a_3 λ⁻⁴ + a_4 λ⁻⁶ + a_5 λ⁻⁸`.
+    Schott { a: [Float; 6] },
+    /// `n = Σ a_i λ^(-2i)`, i.e. `a_0 + a_1 / λ² + a_2 / λ⁴ + ...`.
+    Cauchy { a: Vec<Float> },
+    /// `n = a_0 + a_1 L + a_2 L² + a_3 λ² + a_4 λ⁴ + a_5 λ⁶` with
+    /// `L = 1 / (λ² - 0.028)`.
+    Herzberger { a: [Float; 6] },
+    /// `n = n_0 + a / λ + b / λ^3.5`.
+    Conrady { n_0: Float, a: Float, b: Float },
+    /// `n² = a_0 + a_1 / (λ² - a_2) - a_3 λ²`.
+    HandbookOfOptics1 { a: [Float; 4] },
+    /// `n² = a_0 + a_1 λ² / (λ² - a_2) - a_3 λ²`.
+    HandbookOfOptics2 { a: [Float; 4] },
+    /// `n² = a + Σ b_i λ² / (λ² - c_i)` with two terms.
+    Sellmeier4 {
+        a: Float,
+        b: [Float; 2],
+        c: [Float; 2],
+    },
+    /// `n² - 1 = Σ b_i λ² / (λ² - c_i)` with five terms.
+    Sellmeier5 { b: [Float; 5], c: [Float; 5] },
+    /// The Schott formula with two more terms, `a_6 λ⁻¹⁰ + a_7 λ⁻¹²`.
+    Extended1 { a: [Float; 8] },
+    /// The Schott formula with two more terms, `a_6 λ⁴ + a_7 λ⁶`.
+    Extended2 { a: [Float; 8] },
+    /// `n² = a_0 + a_1 λ² + a_2 λ⁴ + a_3 λ⁻² + a_4 λ⁻⁴ + ... + a_8 λ⁻¹²`.
+    Extended3 { a: [Float; 9] },
+    /// Refractive indices `n` at increasing `wavelengths`, linearly
+    /// interpolated in between.
+    Tabulated {
+        wavelengths: Vec<Float>,
+        n: Vec<Float>,
+    },
+}
+
+impl DispersionFormula {
+    /// Returns the refractive index at a wavelength in micrometers, without
+    /// checking that the formula is valid there.
+    pub fn evaluate(&self, wavelength: Float) -> Float {
+        let l_sq = wavelength * wavelength;
+        let inv = 1.0 / l_sq;
+        let sellmeier_terms = |b: &[Float], c: &[Float]| {
+            b.iter()
+                .zip(c)
+                .map(|(b, c)| b * l_sq / (l_sq - c))
+                .sum::<Float>()
+        };
+        let sellmeier = |b: &[Float], c: &[Float]| (1.0 + sellmeier_terms(b, c)).sqrt();
+        // a_0 λ⁻² + a_1 λ⁻⁴ + ...
+        let inverse_powers = |a: &[Float]| a.iter().rev().fold(0.0, |acc, a_i| (acc + a_i) * inv);
+        match self {
+            DispersionFormula::Sellmeier1 { b, c } => sellmeier(b, c),
+            DispersionFormula::Sellmeier3 { b, c } => sellmeier(b, c),
+            DispersionFormula::Sellmeier2 { a, b, lambda } => (1.0
+                + a
+                + b[0] * l_sq / (l_sq - lambda[0] * lambda[0])
+                + b[1] / (l_sq - lambda[1] * lambda[1]))
+                .sqrt(),
+            DispersionFormula::Schott { a } => {
+                (a[0] + a[1] * l_sq + inverse_powers(&a[2..])).sqrt()
+            }
+            DispersionFormula::Cauchy { a } => a.iter().rev().fold(0.0, |acc, a_i| acc * inv + a_i),
+            DispersionFormula::Herzberger { a } => {
+                let l = 1.0 / (l_sq - HERZBERGER_LAMBDA_SQ);
+                a[0] + a[1] * l + a[2] * l * l + l_sq * (a[3] + l_sq * (a[4] + l_sq * a[5]))
+            }
+            DispersionFormula::Conrady { n_0, a, b } => {
+                n_0 + a / wavelength + b / wavelength.powf(3.5)
+            }
+            DispersionFormula::HandbookOfOptics1 { a } => {
+                (a[0] + a[1] / (l_sq - a[2]) - a[3] * l_sq).sqrt()
+            }
+            DispersionFormula::HandbookOfOptics2 { a } => {
+                (a[0] + a[1] * l_sq / (l_sq - a[2]) - a[3] * l_sq).sqrt()
+            }
+            DispersionFormula::Sellmeier4 { a, b, c } => (a + sellmeier_terms(b, c)).sqrt(),
+            DispersionFormula::Sellmeier5 { b, c } => sellmeier(b, c),
+            DispersionFormula::Extended1 { a } => {
+                (a[0] + a[1] * l_sq + inverse_powers(&a[2..])).sqrt()
+            }
+            DispersionFormula::Extended2 { a } => {
+                (a[0] + a[1] * l_sq + inverse_powers(&a[2..6]) + l_sq * l_sq * (a[6] + a[7] * l_sq))
+                    .sqrt()
+            }
+            DispersionFormula::Extended3 { a } => {
+                (a[0] + l_sq * (a[1] + a[2] * l_sq) + inverse_powers(&a[3..])).sqrt()
+            }
+            DispersionFormula::Tabulated { wavelengths, n } => {
+                if wavelengths.len() < 2 || wavelengths.len() != n.len() {
+                    return Float::NAN;
+                }
+                let i = wavelengths
+                    .partition_point(|&w| w < wavelength)
+                    .clamp(1, wavelengths.len() - 1);
+                let t = (wavelength - wavelengths[i - 1]) / (wavelengths[i] - wavelengths[i - 1]);
+                n[i - 1] + t * (n[i] - n[i - 1])
+            }
+        }
+    }
+
+    /// Checks that the coefficients are finite and that a table is well
+    /// formed.
+    pub fn validate(&self) -> Result<()> {
+        let coefficients: Vec<Float> = match self {
+            DispersionFormula::Sellmeier1 { b, c } => b.iter().chain(c).copied().collect(),
+            DispersionFormula::Sellmeier2 { a, b, lambda } => {
+                [*a].iter().chain(b).chain(lambda).copied().collect()
+            }
+            DispersionFormula::Sellmeier3 { b, c } => b.iter().chain(c).copied().collect(),
+            DispersionFormula::Schott { a } | DispersionFormula::Herzberger { a } => a.to_vec(),
+            DispersionFormula::Cauchy { a } => {
+                if a.is_empty() {
+                    bail!("Cauchy formula needs at least one coefficient");
+                }
+                a.clone()
+            }
+            DispersionFormula::Conrady { n_0, a, b } => vec![*n_0, *a, *b],
+            DispersionFormula::HandbookOfOptics1 { a }
+            | DispersionFormula::HandbookOfOptics2 { a } => a.to_vec(),
+            DispersionFormula::Sellmeier4 { a, b, c } => {
+                [*a].iter().chain(b).chain(c).copied().collect()
+            }
+            DispersionFormula::Sellmeier5 { b, c } => b.iter().chain(c).copied().collect(),
+            DispersionFormula::Extended1 { a } | DispersionFormula::Extended2 { a } => a.to_vec(),
+            DispersionFormula::Extended3 { a } => a.to_vec(),
+            DispersionFormula::Tabulated { wavelengths, n } => {
+                if wavelengths.len() != n.len() {
+                    bail!(
+                        "Tabulated formula has {} wavelengths but {} indices",
+                        wavelengths.len(),
+                        n.len()
+                    );
+                }
+                if wavelengths.len() < 2 {
+                    bail!("Tabulated formula needs at least two points");
+                }
+                if !wavelengths.windows(2).all(|w| w[0] < w[1]) {
+                    bail!("Tabulated wavelengths must be strictly increasing");
+                }
+                wavelengths.iter().chain(n).copied().collect()
+            }
+        };
+        if !coefficients.iter().all(|c| c.is_finite()) {
+            bail!("Dispersion formula coefficients must be finite");
+        }
+        Ok(())
+    }
+}
+
+/// A material whose refractive index is given by a dispersion formula.
+///
+/// The formula is only trusted inside its wavelength range; outside it,
+/// [`n`](RefractiveIndexSpec::n) returns an error rather than extrapolate.
+/// The material is lossless.
+///
+/// # Example
+/// ```rust
+/// use cherry_rs::{DispersionFormula, FormulaMaterial, RefractiveIndexSpec};
+///
+/// // N-BK7 from the Schott catalog
+/// let nbk7 = FormulaMaterial::new(
+///     DispersionFormula::Sellmeier1 {
+///         b: [1.03961212, 0.231792344, 1.01046945],
+///         c: [0.00600069867, 0.0200179144, 103.560653],
+///     },
+///     [0.3, 2.5],
+/// )
+/// .unwrap();
+/// assert!((nbk7.n(0.5875618).unwrap() - 1.5168).abs() < 1e-4);
+/// assert!(nbk7.n(3.0).is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "FormulaMaterialParams", try_from = "FormulaMaterialParams")
+)]
+pub struct FormulaMaterial {
+    pub formula: DispersionFormula,
+    /// The shortest and longest wavelengths at which the formula holds, in
+    /// micrometers.
+    pub wavelength_range: [Float; 2],
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct FormulaMaterialParams {
+    formula: DispersionFormula,
+    wavelength_range: [Float; 2],
+}
+
+#[cfg(feature = "serde")]
+impl From<FormulaMaterial> for FormulaMaterialParams {
+    fn from(material: FormulaMaterial) -> Self {
+        Self {
+            formula: material.formula,
+            wavelength_range: material.wavelength_range,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<FormulaMaterialParams> for FormulaMaterial {
+    type Error = anyhow::Error;
+
+    fn try_from(params: FormulaMaterialParams) -> Result<Self> {
+        Self::new(params.formula, params.wavelength_range)
+    }
+}
+
+impl FormulaMaterial {
+    /// Creates a new material from a dispersion formula.
+    ///
+    /// Returns an error if the formula or the wavelength range is invalid.
+    pub fn new(formula: DispersionFormula, wavelength_range: [Float; 2]) -> Result<Self> {
+        let material = Self {
+            formula,
+            wavelength_range,
+        };
+        material.validate()?;
+        Ok(material)
+    }
+
+    /// Creates a new material from a table of refractive indices, valid
+    /// across the whole table.
+    pub fn tabulated(wavelengths: Vec<Float>, n: Vec<Float>) -> Result<Self> {
+        let range = [
+            wavelengths.first().copied().unwrap_or(Float::NAN),
+            wavelengths.last().copied().unwrap_or(Float::NAN),
+        ];
+        Self::new(DispersionFormula::Tabulated { wavelengths, n }, range)
+    }
+
+    /// Checks the formula and that the wavelength range is positive,
+    /// non-empty and, for a table, covered by the table.
+    pub fn validate(&self) -> Result<()> {
+        self.formula.validate()?;
+        let [min, max] = self.wavelength_range;
+        if !(min.is_finite() && max.is_finite() && 0.0 < min && min < max) {
+            bail!(
+                "Wavelength range must be positive and finite with min < max, got [{min}, {max}]"
+            );
+        }
+        if let DispersionFormula::Tabulated { wavelengths, .. } = &self.formula
+            && (min < wavelengths[0] || max > wavelengths[wavelengths.len() - 1])
+        {
+            bail!("Wavelength range [{min}, {max}] extends beyond the table");
+        }
+        Ok(())
+    }
+}
+
+impl RefractiveIndexSpec for FormulaMaterial {
+    fn n(&self, wavelength: Float) -> Result<Float> {
+        let [min, max] = self.wavelength_range;
+        if !(min..=max).contains(&wavelength) {
+            bail!(
+                "Wavelength {wavelength} µm is outside the range [{min}, {max}] µm of the material"
+            );
+        }
+        let n = self.formula.evaluate(wavelength);
+        if !n.is_finite() {
+            bail!("Dispersion formula has no real refractive index at {wavelength} µm");
+        }
+        Ok(n)
+    }
+
+    fn k(&self, wavelength: Float) -> Result<Float> {
+        self.n(wavelength)?;
+        Ok(0.0)
+    }
+
+    fn wavelength_range(&self) -> Option<[Float; 2]> {
+        Some(self.wavelength_range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    const D_LINE: Float = 0.5875618;
+
+    fn nbk7_sellmeier() -> DispersionFormula {
+        DispersionFormula::Sellmeier1 {
+            b: [1.03961212, 0.231792344, 1.01046945],
+            c: [0.00600069867, 0.0200179144, 103.560653],
+        }
+    }
+
+    #[test]
+    fn sellmeier_and_schott_agree_for_nbk7() {
+        let schott = DispersionFormula::Schott {
+            a: [
+                2.2718929,
+                -1.0108077e-2,
+                1.0592509e-2,
+                2.0816965e-4,
+                -7.6472538e-6,
+                4.9240991e-7,
+            ],
+        };
+        for wavelength in [0.4, D_LINE, 0.7, 1.0] {
+            assert_abs_diff_eq!(
+                nbk7_sellmeier().evaluate(wavelength),
+                schott.evaluate(wavelength),
+                epsilon = 5e-5
+            );
+        }
+        assert_abs_diff_eq!(nbk7_sellmeier().evaluate(D_LINE), 1.5168, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn sellmeier_variants_reduce_to_each_other() {
+        let DispersionFormula::Sellmeier1 { b, c } = nbk7_sellmeier() else {
+            unreachable!()
+        };
+        let four_terms = DispersionFormula::Sellmeier3 {
+            b: [b[0], b[1], b[2], 0.0],
+            c: [c[0], c[1], c[2], 1.0],
+        };
+        // With a = b_2 = 0, Sellmeier 2 is a single Sellmeier 1 term.
+        let one_term = DispersionFormula::Sellmeier2 {
+            a: 0.0,
+            b: [b[0], 0.0],
+            lambda: [c[0].sqrt(), 0.0],
+        };
+        let single = DispersionFormula::Sellmeier1 {
+            b: [b[0], 0.0, 0.0],
+            c: [c[0], 0.0, 0.0],
+        };
+        for wavelength in [0.45, 0.8] {
+            assert_abs_diff_eq!(
+                four_terms.evaluate(wavelength),
+                nbk7_sellmeier().evaluate(wavelength),
+                epsilon = 1e-15
+            );
+            assert_abs_diff_eq!(
+                one_term.evaluate(wavelength),
+                single.evaluate(wavelength),
+                epsilon = 1e-15
+            );
+        }
+    }
+
+    #[test]
+    fn polynomial_formulas() {
+        let wavelength: Float = 0.5;
+        let cauchy = DispersionFormula::Cauchy {
+            a: vec![1.5, 4e-3, 1e-4],
+        };
+        assert_abs_diff_eq!(
+            cauchy.evaluate(wavelength),
+            1.5 + 4e-3 / 0.25 + 1e-4 / 0.0625,
+            epsilon = 1e-15
+        );
+
+        let conrady = DispersionFormula::Conrady {
+            n_0: 1.5,
+            a: 1e-2,
+            b: 1e-4,
+        };
+        assert_abs_diff_eq!(
+            conrady.evaluate(wavelength),
+            1.5 + 1e-2 / 0.5 + 1e-4 / wavelength.powf(3.5),
+            epsilon = 1e-15
+        );
+
+        let herzberger = DispersionFormula::Herzberger {
+            a: [1.5, 1e-2, 1e-3, -1e-3, 1e-5, -1e-7],
+        };
+        let l = 1.0 / (0.25 - 0.028);
+        assert_abs_diff_eq!(
+            herzberger.evaluate(wavelength),
+            1.5 + 1e-2 * l + 1e-3 * l * l - 1e-3 * 0.25 + 1e-5 * 0.0625 - 1e-7 * 0.015625,
+            epsilon = 1e-15
+        );
+    }
+
+    #[test]
+    fn extended_formulas_reduce_to_schott() {
+        let a = [
+            2.2718929,
+            -1.0108077e-2,
+            1.0592509e-2,
+            2.0816965e-4,
+            -7.6472538e-6,
+            4.9240991e-7,
+        ];
+        let schott = DispersionFormula::Schott { a };
+        let extended_1 = DispersionFormula::Extended1 {
+            a: [a[0], a[1], a[2], a[3], a[4], a[5], 0.0, 0.0],
+        };
+        let extended_2 = DispersionFormula::Extended2 {
+            a: [a[0], a[1], a[2], a[3], a[4], a[5], 0.0, 0.0],
+        };
+        let extended_3 = DispersionFormula::Extended3 {
+            a: [a[0], a[1], 0.0, a[2], a[3], a[4], a[5], 0.0, 0.0],
+        };
+        for wavelength in [0.4, D_LINE, 1.0] {
+            let n = schott.evaluate(wavelength);
+            assert_abs_diff_eq!(extended_1.evaluate(wavelength), n, epsilon = 1e-15);
+            assert_abs_diff_eq!(extended_2.evaluate(wavelength), n, epsilon = 1e-15);
+            assert_abs_diff_eq!(extended_3.evaluate(wavelength), n, epsilon = 1e-15);
+        }
+
+        // The extra terms
+        let wavelength: Float = 0.5;
+        let n_sq = schott.evaluate(wavelength).powi(2);
+        let extended_1 = DispersionFormula::Extended1 {
+            a: [a[0], a[1], a[2], a[3], a[4], a[5], 1e-8, -1e-9],
+        };
+        let extended_2 = DispersionFormula::Extended2 {
+            a: [a[0], a[1], a[2], a[3], a[4], a[5], 1e-3, -1e-4],
+        };
+        let extended_3 = DispersionFormula::Extended3 {
+            a: [a[0], a[1], 1e-3, a[2], a[3], a[4], a[5], 1e-8, -1e-9],
+        };
+        assert_abs_diff_eq!(
+            extended_1.evaluate(wavelength).powi(2),
+            n_sq + 1e-8 * 1024.0 - 1e-9 * 4096.0,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            extended_2.evaluate(wavelength).powi(2),
+            n_sq + 1e-3 * 0.0625 - 1e-4 * 0.015625,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            extended_3.evaluate(wavelength).powi(2),
+            n_sq + 1e-3 * 0.0625 + 1e-8 * 1024.0 - 1e-9 * 4096.0,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn other_sellmeier_forms() {
+        let DispersionFormula::Sellmeier1 { b, c } = nbk7_sellmeier() else {
+            unreachable!()
+        };
+        let five_terms = DispersionFormula::Sellmeier5 {
+            b: [b[0], b[1], b[2], 0.0, 0.0],
+            c: [c[0], c[1], c[2], 1.0, 2.0],
+        };
+        // Sellmeier 4 with a = 1 and Handbook of Optics 2 with a_3 = 0 are
+        // one or two Sellmeier 1 terms.
+        let two_terms = DispersionFormula::Sellmeier4 {
+            a: 1.0,
+            b: [b[0], b[1]],
+            c: [c[0], c[1]],
+        };
+        let handbook_2 = DispersionFormula::HandbookOfOptics2 {
+            a: [1.0, b[0], c[0], 0.0],
+        };
+        let one_term = DispersionFormula::Sellmeier1 {
+            b: [b[0], 0.0, 0.0],
+            c: [c[0], 0.0, 0.0],
+        };
+        let wavelength: Float = 0.6;
+        assert_abs_diff_eq!(
+            five_terms.evaluate(wavelength),
+            nbk7_sellmeier().evaluate(wavelength),
+            epsilon = 1e-15
+        );
+        assert_abs_diff_eq!(
+            two_terms.evaluate(wavelength),
+            DispersionFormula::Sellmeier1 {
+                b: [b[0], b[1], 0.0],
+                c: [c[0], c[1], 0.0],
+            }
+            .evaluate(wavelength),
+            epsilon = 1e-15
+        );
+        assert_abs_diff_eq!(
+            handbook_2.evaluate(wavelength),
+            one_term.evaluate(wavelength),
+            epsilon = 1e-15
+        );
+
+        let handbook_1 = DispersionFormula::HandbookOfOptics1 {
+            a: [2.0, 0.01, 0.02, 0.01],
+        };
+        assert_abs_diff_eq!(
+            handbook_1.evaluate(wavelength).powi(2),
+            2.0 + 0.01 / (0.36 - 0.02) - 0.01 * 0.36,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn table_is_interpolated_linearly() {
+        let table =
+            FormulaMaterial::tabulated(vec![0.4, 0.5, 0.7], vec![1.53, 1.52, 1.51]).unwrap();
+        assert_eq!(table.wavelength_range, [0.4, 0.7]);
+        assert_abs_diff_eq!(table.n(0.4).unwrap(), 1.53, epsilon = 1e-15);
+        assert_abs_diff_eq!(table.n(0.45).unwrap(), 1.525, epsilon = 1e-12);
+        assert_abs_diff_eq!(table.n(0.6).unwrap(), 1.515, epsilon = 1e-12);
+        assert_abs_diff_eq!(table.n(0.7).unwrap(), 1.51, epsilon = 1e-15);
+        assert!(table.n(0.35).is_err());
+    }
+
+    #[test]
+    fn wavelength_range_is_enforced() {
+        let nbk7 = FormulaMaterial::new(nbk7_sellmeier(), [0.3, 2.5]).unwrap();
+        assert!(nbk7.n(0.3).is_ok());
+        assert!(nbk7.n(0.29).is_err());
+        assert!(nbk7.k(2.6).is_err());
+        assert_eq!(nbk7.k(1.0).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn invalid_materials_are_errors() {
+        assert!(FormulaMaterial::new(nbk7_sellmeier(), [0.5, 0.4]).is_err());
+        assert!(FormulaMaterial::new(nbk7_sellmeier(), [0.0, 0.4]).is_err());
+        assert!(FormulaMaterial::new(nbk7_sellmeier(), [0.3, Float::INFINITY]).is_err());
+        assert!(
+            FormulaMaterial::new(
+                DispersionFormula::Conrady {
+                    n_0: Float::NAN,
+                    a: 0.0,
+                    b: 0.0
+                },
+                [0.4, 0.7]
+            )
+            .is_err()
+        );
+        assert!(FormulaMaterial::new(DispersionFormula::Cauchy { a: vec![] }, [0.4, 0.7]).is_err());
+        assert!(FormulaMaterial::tabulated(vec![0.5, 0.4], vec![1.5, 1.5]).is_err());
+        assert!(FormulaMaterial::tabulated(vec![0.4, 0.5], vec![1.5]).is_err());
+        assert!(FormulaMaterial::tabulated(vec![], vec![]).is_err());
+        assert!(
+            FormulaMaterial::new(
+                DispersionFormula::Tabulated {
+                    wavelengths: vec![0.4, 0.5],
+                    n: vec![1.5, 1.5]
+                },
+                [0.4, 0.6]
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn pole_inside_the_range_is_an_error() {
+        // A Sellmeier pole at 0.5 µm.
+        let material = FormulaMaterial::new(
+            DispersionFormula::Sellmeier1 {
+                b: [1.0, 0.0, 0.0],
+                c: [0.25, 0.0, 0.0],
+            },
+            [0.4, 0.7],
+        )
+        .unwrap();
+        assert!(material.n(0.45).is_err());
+        assert!(material.n(0.6).is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn material_round_trips() {
+        let material = FormulaMaterial::new(nbk7_sellmeier(), [0.3, 2.5]).unwrap();
+        let json = serde_json::to_string(&material).expect("serialize");
+        let back: FormulaMaterial = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(back, material);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn invalid_material_is_rejected_on_load() {
+        let json = r#"{
+            "formula": {"Tabulated": {"wavelengths": [0.6, 0.4, 0.8], "n": [1.5, 1.6, 1.4]}},
+            "wavelength_range": [0.4, 0.8]
+        }"#;
+        let err = serde_json::from_str::<FormulaMaterial>(json).unwrap_err();
+        assert!(err.to_string().contains("strictly increasing"));
+    }
+}
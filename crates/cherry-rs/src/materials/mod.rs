@@ -1,2 +1,8 @@
+pub(crate) mod agf;
+pub(crate) mod air;
+pub(crate) mod codev;
+pub(crate) mod dispersion;
+pub(crate) mod model_glass;
+pub(crate) mod properties;
 #[cfg(feature = "ri-info")]
-mod ri_info;
+pub(crate) mod ri_info;
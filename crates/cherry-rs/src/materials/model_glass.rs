@@ -0,0 +1,242 @@
+//! Model glasses defined by their index, Abbe number and partial dispersion.
+use anyhow::{Result, bail};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::properties::{C_LINE, D_LINE, F_LINE, G_LINE};
+use crate::{RefractiveIndexSpec, core::Float};
+
+/// The normal line of the Schott glass catalog, on which the partial
+/// dispersion `P_g,F` of a normal glass is `PGF_0 + PGF_SLOPE * V_d`.
+pub(crate) const PGF_0: Float = 0.6438;
+pub(crate) const PGF_SLOPE: Float = -0.001682;
+
+/// Coefficient of λ² in n² typical of optical glasses, which models the
+/// decrease of the index towards the infrared absorption bands.
+const INFRARED_N_SQ: Float = -0.0101;
+
+/// Wavelength range over which a model glass is defined, in µm.
+const WAVELENGTH_RANGE: [Float; 2] = [0.35, 2.5];
+
+/// A fictitious glass with a given refractive index, Abbe number and
+/// deviation of the partial dispersion from the normal line.
+///
+/// The index follows
+///
+/// ```text
+/// n(λ) = a_0 + a_1 / λ² + a_2 / λ⁴ + a_3 λ²
+/// ```
+///
+/// where `a_3` is fixed at a value typical of optical glasses and `a_0`,
+/// `a_1` and `a_2` are chosen so that the glass has exactly the requested
+/// `n_d`, `V_d = (n_d - 1) / (n_F - n_C)` and
+/// `P_g,F = (n_g - n_F) / (n_F - n_C) = 0.6438 - 0.001682 V_d + ΔP_g,F`.
+///
+/// Use it in early design, before a catalog glass is chosen. Its `n_d`,
+/// `V_d` and `ΔP_g,F` place it on the glass map, so that the nearest real
+/// glass can later be found. The glass is defined from 0.35 to 2.5 µm and is
+/// lossless.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "ModelGlassParams", try_from = "ModelGlassParams")
+)]
+pub struct ModelGlass {
+    nd: Float,
+    vd: Float,
+    delta_pgf: Float,
+    coefficients: [Float; 4],
+}
+
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct ModelGlassParams {
+    nd: Float,
+    vd: Float,
+    #[serde(default)]
+    delta_pgf: Float,
+}
+
+#[cfg(feature = "serde")]
+impl From<ModelGlass> for ModelGlassParams {
+    fn from(glass: ModelGlass) -> Self {
+        Self {
+            nd: glass.nd,
+            vd: glass.vd,
+            delta_pgf: glass.delta_pgf,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ModelGlassParams> for ModelGlass {
+    type Error = anyhow::Error;
+
+    fn try_from(params: ModelGlassParams) -> Result<Self> {
+        Self::new(params.nd, params.vd, params.delta_pgf)
+    }
+}
+
+impl ModelGlass {
+    /// Creates a new model glass.
+    ///
+    /// # Arguments
+    /// * `nd` - Refractive index at the d line (587.6 nm).
+    /// * `vd` - Abbe number at the d line.
+    /// * `delta_pgf` - Deviation of the partial dispersion `P_g,F` from the
+    ///   normal line. Zero for a normal glass.
+    ///
+    /// # Errors
+    /// If `nd` is not greater than one, if `vd` is not positive, or if any
+    /// argument is not finite.
+    pub fn new(nd: Float, vd: Float, delta_pgf: Float) -> Result<Self> {
+        if !(nd.is_finite() && nd > 1.0) {
+            bail!("Model glass nd must be finite and greater than 1, got {nd}");
+        }
+        if !(vd.is_finite() && vd > 0.0) {
+            bail!("Model glass Vd must be finite and positive, got {vd}");
+        }
+        if !delta_pgf.is_finite() {
+            bail!("Model glass ΔPgF must be finite, got {delta_pgf}");
+        }
+
+        let a_3 = INFRARED_N_SQ / (2.0 * nd);
+        let pgf = PGF_0 + PGF_SLOPE * vd + delta_pgf;
+        let d_fc = (nd - 1.0) / vd;
+
+        // Solve for a_1 and a_2 from the two dispersions, less the part due
+        // to the infrared term.
+        let x = |lambda: Float| 1.0 / (lambda * lambda);
+        let (x_d, x_f, x_c, x_g) = (x(D_LINE), x(F_LINE), x(C_LINE), x(G_LINE));
+        let r_1 = d_fc - a_3 * (1.0 / x_f - 1.0 / x_c);
+        let r_2 = pgf * d_fc - a_3 * (1.0 / x_g - 1.0 / x_f);
+        let (m_11, m_12) = (x_f - x_c, x_f * x_f - x_c * x_c);
+        let (m_21, m_22) = (x_g - x_f, x_g * x_g - x_f * x_f);
+        let det = m_11 * m_22 - m_12 * m_21;
+        let a_1 = (r_1 * m_22 - m_12 * r_2) / det;
+        let a_2 = (m_11 * r_2 - r_1 * m_21) / det;
+        let a_0 = nd - a_1 * x_d - a_2 * x_d * x_d - a_3 / x_d;
+
+        Ok(Self {
+            nd,
+            vd,
+            delta_pgf,
+            coefficients: [a_0, a_1, a_2, a_3],
+        })
+    }
+
+    /// Returns the refractive index at the d line.
+    pub fn nd(&self) -> Float {
+        self.nd
+    }
+
+    /// Returns the Abbe number at the d line.
+    pub fn vd(&self) -> Float {
+        self.vd
+    }
+
+    /// Returns the deviation of the partial dispersion `P_g,F` from the
+    /// normal line.
+    pub fn delta_pgf(&self) -> Float {
+        self.delta_pgf
+    }
+}
+
+impl RefractiveIndexSpec for ModelGlass {
+    fn n(&self, wavelength: Float) -> Result<Float> {
+        let [min, max] = WAVELENGTH_RANGE;
+        if !(min..=max).contains(&wavelength) {
+            bail!(
+                "Wavelength {wavelength} µm is outside the range [{min}, {max}] µm of model glasses"
+            );
+        }
+        let [a_0, a_1, a_2, a_3] = self.coefficients;
+        let x = 1.0 / (wavelength * wavelength);
+        Ok(a_0 + x * (a_1 + x * a_2) + a_3 / x)
+    }
+
+    fn k(&self, wavelength: Float) -> Result<Float> {
+        self.n(wavelength)?;
+        Ok(0.0)
+    }
+
+    fn wavelength_range(&self) -> Option<[Float; 2]> {
+        Some(WAVELENGTH_RANGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    fn n(glass: &ModelGlass, wavelength: Float) -> Float {
+        glass.n(wavelength).unwrap()
+    }
+
+    #[test]
+    fn glass_has_the_requested_constants() {
+        for (nd, vd, delta_pgf) in [
+            (1.5168, 64.17, 0.0),
+            (1.8052, 25.36, 0.016),
+            (1.4338, 95.1, 0.05),
+        ] {
+            let glass = ModelGlass::new(nd, vd, delta_pgf).unwrap();
+            let d_fc = n(&glass, F_LINE) - n(&glass, C_LINE);
+            assert_abs_diff_eq!(n(&glass, D_LINE), nd, epsilon = 1e-12);
+            assert_abs_diff_eq!((nd - 1.0) / d_fc, vd, epsilon = 1e-9);
+            assert_abs_diff_eq!(
+                (n(&glass, G_LINE) - n(&glass, F_LINE)) / d_fc,
+                PGF_0 + PGF_SLOPE * vd + delta_pgf,
+                epsilon = 1e-9
+            );
+        }
+    }
+
+    #[test]
+    fn model_of_nbk7_is_close_to_nbk7() {
+        // Indices of N-BK7 from its Sellmeier formula.
+        let glass = ModelGlass::new(1.5168, 64.17, 0.0).unwrap();
+        for (wavelength, expected) in [
+            (0.4, 1.53084),
+            (0.5, 1.52141),
+            (0.8, 1.51078),
+            (1.5, 1.50091),
+        ] {
+            assert_abs_diff_eq!(n(&glass, wavelength), expected, epsilon = 2e-3);
+        }
+    }
+
+    #[test]
+    fn index_falls_with_wavelength() {
+        let glass = ModelGlass::new(1.6, 40.0, 0.0).unwrap();
+        let indices: Vec<Float> = (0..=43)
+            .map(|i| n(&glass, 0.35 + 0.05 * i as Float))
+            .collect();
+        assert!(indices.windows(2).all(|w| w[1] < w[0]));
+    }
+
+    #[test]
+    fn invalid_glasses_are_errors() {
+        assert!(ModelGlass::new(1.0, 50.0, 0.0).is_err());
+        assert!(ModelGlass::new(Float::NAN, 50.0, 0.0).is_err());
+        assert!(ModelGlass::new(1.5, 0.0, 0.0).is_err());
+        assert!(ModelGlass::new(1.5, 50.0, Float::INFINITY).is_err());
+
+        let glass = ModelGlass::new(1.5, 50.0, 0.0).unwrap();
+        assert!(glass.n(0.3).is_err());
+        assert!(glass.k(3.0).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn glass_serializes_as_its_constants() {
+        let glass = ModelGlass::new(1.7, 30.0, 0.01).unwrap();
+        let json = serde_json::to_string(&glass).unwrap();
+        assert_eq!(json, r#"{"nd":1.7,"vd":30.0,"delta_pgf":0.01}"#);
+        let back: ModelGlass = serde_json::from_str(r#"{"nd":1.7,"vd":30.0}"#).unwrap();
+        assert_eq!(back, ModelGlass::new(1.7, 30.0, 0.0).unwrap());
+        assert!(serde_json::from_str::<ModelGlass>(r#"{"nd":0.5,"vd":30.0}"#).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Glass properties derived from the refractive index at the Fraunhofer lines.
+use anyhow::{Result, bail};
+
+use crate::{RefractiveIndexSpec, core::Float};
+
+/// Wavelengths of the Fraunhofer lines used to characterize glasses, in µm.
+pub const D_LINE: Float = 0.5875618;
+pub const F_LINE: Float = 0.4861327;
+pub const C_LINE: Float = 0.6562725;
+pub const G_LINE: Float = 0.4358343;
+
+/// Wavelength step of the central differences in `dn_dwavelength`, in µm.
+const STEP: Float = 1e-4;
+
+/// Returns the refractive index `n_d` of a material at the d line.
+pub fn nd(material: &(impl RefractiveIndexSpec + ?Sized)) -> Result<Float> {
+    material.n(D_LINE)
+}
+
+/// Returns the Abbe number `V_d = (n_d - 1) / (n_F - n_C)` of a material.
+///
+/// Returns an error if the material is not defined at the d, F and C lines
+/// or does not disperse between F and C.
+pub fn abbe_number(material: &(impl RefractiveIndexSpec + ?Sized)) -> Result<Float> {
+    let nd = material.n(D_LINE)?;
+    let d_fc = principal_dispersion(material)?;
+    Ok((nd - 1.0) / d_fc)
+}
+
+/// Returns the relative partial dispersion `P_x,y = (n_x - n_y) / (n_F - n_C)`
+/// of a material between the wavelengths `x` and `y`, in µm.
+///
+/// For example, `P_g,F` is `partial_dispersion(material, G_LINE, F_LINE)`.
+pub fn partial_dispersion(
+    material: &(impl RefractiveIndexSpec + ?Sized),
+    x: Float,
+    y: Float,
+) -> Result<Float> {
+    let d_xy = material.n(x)? - material.n(y)?;
+    Ok(d_xy / principal_dispersion(material)?)
+}
+
+/// Returns the derivative of the refractive index with respect to the
+/// wavelength, in 1/µm.
+///
+/// The derivative is a central difference, or a one-sided difference at the
+/// ends of the range over which the material is defined.
+pub fn dn_dwavelength(
+    material: &(impl RefractiveIndexSpec + ?Sized),
+    wavelength: Float,
+) -> Result<Float> {
+    let n = material.n(wavelength)?;
+    match (material.n(wavelength - STEP), material.n(wavelength + STEP)) {
+        (Ok(below), Ok(above)) => Ok((above - below) / (2.0 * STEP)),
+        (Ok(below), Err(_)) => Ok((n - below) / STEP),
+        (Err(_), Ok(above)) => Ok((above - n) / STEP),
+        (Err(e), Err(_)) => Err(e),
+    }
+}
+
+/// Returns the principal dispersion `n_F - n_C`, which must not vanish.
+fn principal_dispersion(material: &(impl RefractiveIndexSpec + ?Sized)) -> Result<Float> {
+    let d_fc = material.n(F_LINE)? - material.n(C_LINE)?;
+    if d_fc == 0.0 {
+        bail!("Material has no dispersion between the F and C lines");
+    }
+    Ok(d_fc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    use crate::{ConstantRefractiveIndex, DispersionFormula, FormulaMaterial, ModelGlass};
+
+    #[test]
+    fn model_glass_properties_are_recovered() {
+        let glass = ModelGlass::new(1.8052, 25.36, 0.016).unwrap();
+
+        assert_abs_diff_eq!(nd(&glass).unwrap(), 1.8052, epsilon = 1e-12);
+        assert_abs_diff_eq!(abbe_number(&glass).unwrap(), 25.36, epsilon = 1e-9);
+        assert_abs_diff_eq!(
+            partial_dispersion(&glass, G_LINE, F_LINE).unwrap(),
+            0.6438 - 0.001682 * 25.36 + 0.016,
+            epsilon = 1e-9
+        );
+
+        let dyn_glass: &dyn RefractiveIndexSpec = &glass;
+        assert_eq!(nd(dyn_glass).unwrap(), nd(&glass).unwrap());
+    }
+
+    #[test]
+    fn constant_index_has_no_abbe_number() {
+        let constant = ConstantRefractiveIndex::new(1.5, 0.0);
+        assert_eq!(nd(&constant).unwrap(), 1.5);
+        assert!(abbe_number(&constant).is_err());
+        assert_eq!(dn_dwavelength(&constant, 0.5).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn derivative_matches_the_formula() {
+        // n = a_0 + a_1 / λ², so dn/dλ = -2 a_1 / λ³
+        let (a_0, a_1) = (1.5, 0.005);
+        let material =
+            FormulaMaterial::new(DispersionFormula::Cauchy { a: vec![a_0, a_1] }, [0.4, 0.8])
+                .unwrap();
+
+        for wavelength in [0.4, 0.55, 0.8] {
+            assert_abs_diff_eq!(
+                dn_dwavelength(&material, wavelength).unwrap(),
+                -2.0 * a_1 / wavelength.powi(3),
+                epsilon = 1e-3
+            );
+        }
+        assert!(dn_dwavelength(&material, 1.0).is_err());
+    }
+}
@@ -1,23 +1,197 @@
 //! Interface to RefractiveIndex.info materials data.
 
-use anyhow::Result;
-use lib_ria::Material;
+use anyhow::{Result, bail};
+use lib_ria::{DispersionData, Material};
 
 use crate::{RefractiveIndexSpec, core::Float};
 
+mod yaml;
+
+pub use yaml::material_from_yaml;
+
+/// Tables are interpolated linearly in wavelength; formulas are evaluated by
+/// `lib_ria`. The real part comes from the first formula, `tabulated n` or
+/// `tabulated nk` block, and the imaginary part from the first `tabulated k`
+/// or `tabulated nk` block.
 impl RefractiveIndexSpec for Material {
     fn n(&self, wavelength: Float) -> Result<Float> {
-        let n = self.n(wavelength)?;
-        Ok(n)
-    }
-
-    fn k(&self, _wavelength: Float) -> Result<Float> {
-        // TODO: Implement this.
-        //let k = self.k(wavelength)?;
-        //match k {
-        //    Some(k) => Ok(k),
-        //    None => Ok(0.0),
-        //}
-        Ok(0.0)
+        let Some(data) = real_data(self) else {
+            bail!("No real refractive index data found for material");
+        };
+        match data {
+            DispersionData::TabulatedN { data } => interpolate(data, wavelength, 1),
+            DispersionData::TabulatedNK { data } => interpolate(data, wavelength, 1),
+            formula => Ok(formula.interpolate(wavelength)?.0),
+        }
+    }
+
+    /// Materials without extinction data are lossless. The extinction data
+    /// often covers a narrower range than the index data; outside it, the
+    /// material is also treated as lossless.
+    fn k(&self, wavelength: Float) -> Result<Float> {
+        RefractiveIndexSpec::n(self, wavelength)?;
+        let k = self.data.iter().find_map(|d| match d {
+            DispersionData::TabulatedK { data } => interpolate(data, wavelength, 1).ok(),
+            DispersionData::TabulatedNK { data } => interpolate(data, wavelength, 2).ok(),
+            _ => None,
+        });
+        Ok(k.unwrap_or(0.0))
+    }
+
+    /// The range of the real refractive index data.
+    fn wavelength_range(&self) -> Option<[Float; 2]> {
+        match real_data(self)? {
+            DispersionData::TabulatedN { data } => Some([data.first()?[0], data.last()?[0]]),
+            DispersionData::TabulatedNK { data } => Some([data.first()?[0], data.last()?[0]]),
+            DispersionData::TabulatedK { .. } => None,
+            DispersionData::Formula1 {
+                wavelength_range, ..
+            }
+            | DispersionData::Formula2 {
+                wavelength_range, ..
+            }
+            | DispersionData::Formula3 {
+                wavelength_range, ..
+            }
+            | DispersionData::Formula4 {
+                wavelength_range, ..
+            }
+            | DispersionData::Formula5 {
+                wavelength_range, ..
+            }
+            | DispersionData::Formula6 {
+                wavelength_range, ..
+            }
+            | DispersionData::Formula7 {
+                wavelength_range, ..
+            }
+            | DispersionData::Formula8 {
+                wavelength_range, ..
+            }
+            | DispersionData::Formula9 {
+                wavelength_range, ..
+            } => Some(*wavelength_range),
+        }
+    }
+}
+
+/// Returns the first block of real refractive index data.
+fn real_data(material: &Material) -> Option<&DispersionData> {
+    material
+        .data
+        .iter()
+        .find(|d| !matches!(d, DispersionData::TabulatedK { .. }))
+}
+
+/// Linearly interpolates column `column` of a table whose first column is the
+/// wavelength, in ascending order.
+fn interpolate<const N: usize>(
+    table: &[[Float; N]],
+    wavelength: Float,
+    column: usize,
+) -> Result<Float> {
+    let (Some(first), Some(last)) = (table.first(), table.last()) else {
+        bail!("Tabulated data is empty");
+    };
+    if !(first[0]..=last[0]).contains(&wavelength) {
+        bail!(
+            "Wavelength {wavelength} µm is outside the range [{}, {}] µm of the tabulated data",
+            first[0],
+            last[0]
+        );
+    }
+    if table.len() == 1 {
+        return Ok(first[column]);
+    }
+
+    let i = table
+        .partition_point(|row| row[0] < wavelength)
+        .clamp(1, table.len() - 1);
+    let (a, b) = (table[i - 1], table[i]);
+    let t = (wavelength - a[0]) / (b[0] - a[0]);
+    Ok(a[column] + t * (b[column] - a[column]))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::{
+        ApertureSpec, FieldSpec, ParaxialView, SamplingConfig, core::PI,
+        examples::convexplano_lens::sequential_model, n, ray_trace_3d_view,
+    };
+
+    fn material(data: Vec<DispersionData>) -> Material {
+        Material {
+            shelf: "test".to_string(),
+            book: "test".to_string(),
+            page: "test".to_string(),
+            comments: String::new(),
+            references: String::new(),
+            data,
+            shelf_divider: None,
+            book_divider: None,
+        }
+    }
+
+    #[test]
+    fn tabulated_nk_material_reports_its_extinction() {
+        let material = material(vec![DispersionData::TabulatedNK {
+            data: vec![[0.5, 1.5, 1e-5], [0.7, 1.6, 3e-5]],
+        }]);
+
+        assert_abs_diff_eq!(RefractiveIndexSpec::n(&material, 0.6).unwrap(), 1.55);
+        assert_abs_diff_eq!(RefractiveIndexSpec::k(&material, 0.6).unwrap(), 2e-5);
+        assert!(RefractiveIndexSpec::k(&material, 0.8).is_err());
+    }
+
+    #[test]
+    fn tabulated_k_absorbs_rays() {
+        let wavelength = 0.5876;
+        let glass = material(vec![
+            DispersionData::TabulatedN {
+                data: vec![[0.4, 1.52], [0.8, 1.51]],
+            },
+            DispersionData::TabulatedK {
+                data: vec![[0.5, 1e-5], [0.7, 3e-5]],
+            },
+        ]);
+        let k = RefractiveIndexSpec::k(&glass, wavelength).unwrap();
+        assert_abs_diff_eq!(k, 1.876e-5, epsilon = 1e-12);
+
+        // Outside the extinction data, the material is lossless.
+        assert_eq!(RefractiveIndexSpec::k(&glass, 0.45).unwrap(), 0.0);
+
+        let sequential_model = sequential_model(n!(1.0), Rc::new(glass), &[wavelength]);
+        let field_specs = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let paraxial_view = ParaxialView::new(&sequential_model, &field_specs, false).unwrap();
+        let results = ray_trace_3d_view(
+            &ApertureSpec::EntrancePupil { semi_diameter: 5.0 },
+            &field_specs,
+            &sequential_model,
+            &paraxial_view,
+            SamplingConfig {
+                n_fan_rays: 3,
+                full_pupil_spacing: 0.5,
+            },
+        )
+        .unwrap();
+
+        // The chief ray crosses the 5.3 mm of glass on the axis.
+        let chief = results
+            .get(0, 0)
+            .unwrap()
+            .chief_ray()
+            .rays()
+            .last()
+            .unwrap();
+        let expected = (-4.0 * PI * k * 5.3e3 / wavelength).exp();
+        assert_abs_diff_eq!(chief.intensity(), expected, epsilon = 1e-12);
     }
 }
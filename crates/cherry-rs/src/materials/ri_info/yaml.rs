@@ -0,0 +1,428 @@
+//! Material pages in the RefractiveIndex.info YAML format.
+//!
+//! A page has the references and comments of the data, and a list of data
+//! blocks, each either a dispersion formula or a table:
+//!
+//! ```text
+//! REFERENCES: "..."
+//! COMMENTS: "..."
+//! DATA:
+//!   - type: formula 2
+//!     wavelength_range: 0.3 2.5
+//!     coefficients: 0 1.03961212 0.00600069867 ...
+//!   - type: tabulated k
+//!     data: |
+//!         0.300 2.8607E-06
+//!         0.310 1.3679E-06
+//! ```
+//!
+//! Only the subset of YAML used by the database is understood: block mappings
+//! and sequences, plain and quoted scalars, and literal or folded block
+//! scalars. Other top-level keys, such as `SPECS` and `CONDITIONS`, are
+//! ignored.
+use anyhow::{Context, Result, anyhow, bail};
+use lib_ria::{DispersionData, Material};
+
+/// A line of the document with its indentation.
+#[derive(Debug, Clone, Copy)]
+struct Line<'a> {
+    indent: usize,
+    text: &'a str,
+}
+
+/// Parse a RefractiveIndex.info material page.
+///
+/// # Arguments
+/// * `contents` - The YAML text of the page.
+/// * `shelf`, `book`, `page` - Where the material is filed. They form its
+///   `shelf:book:page` key.
+pub fn material_from_yaml(contents: &str, shelf: &str, book: &str, page: &str) -> Result<Material> {
+    let lines = significant_lines(contents.trim_start_matches('\u{feff}'))?;
+
+    let mut references = String::new();
+    let mut comments = String::new();
+    let mut data = None;
+    for (key, value) in mapping(&lines)? {
+        match key {
+            "REFERENCES" => references = scalar(&value),
+            "COMMENTS" => comments = scalar(&value),
+            "DATA" => {
+                let blocks = sequence(&value)?
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| {
+                        dispersion_data(item).with_context(|| format!("DATA item {}", i + 1))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                data = Some(blocks);
+            }
+            _ => {}
+        }
+    }
+
+    let data = data.ok_or_else(|| anyhow!("Material has no DATA"))?;
+    if data.is_empty() {
+        bail!("Material DATA is empty");
+    }
+
+    Ok(Material {
+        shelf: shelf.to_string(),
+        book: book.to_string(),
+        page: page.to_string(),
+        comments,
+        references,
+        data,
+        shelf_divider: None,
+        book_divider: None,
+    })
+}
+
+/// Returns the lines that are neither blank nor comments, with their
+/// indentation.
+fn significant_lines(contents: &str) -> Result<Vec<Line<'_>>> {
+    let mut lines = Vec::new();
+    for (i, raw) in contents.lines().enumerate() {
+        let text = raw.trim_start_matches(' ');
+        if text.starts_with('\t') {
+            bail!("Line {}: tabs are not allowed for indentation", i + 1);
+        }
+        let text = text.trim_end();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        lines.push(Line {
+            indent: raw.len() - raw.trim_start_matches(' ').len(),
+            text,
+        });
+    }
+    Ok(lines)
+}
+
+/// Splits a block mapping into its keys and the lines of their values.
+///
+/// The value of a key on the same line is its first value line; the lines
+/// below it that are more indented than the key follow it, as do the items of
+/// a sequence at the key's own indentation.
+fn mapping<'a>(lines: &[Line<'a>]) -> Result<Vec<(&'a str, Vec<Line<'a>>)>> {
+    let Some(indent) = lines.first().map(|l| l.indent) else {
+        return Ok(Vec::new());
+    };
+
+    let mut entries: Vec<(&str, Vec<Line>)> = Vec::new();
+    for line in lines {
+        let is_item = line.text == "-" || line.text.starts_with("- ");
+        if (line.indent > indent || line.indent == indent && is_item)
+            && let Some((_, value)) = entries.last_mut()
+        {
+            value.push(*line);
+            continue;
+        }
+        if line.indent < indent {
+            bail!("Unexpected indentation of '{}'", line.text);
+        }
+        let (key, rest) = line
+            .text
+            .split_once(':')
+            .filter(|(_, rest)| rest.is_empty() || rest.starts_with(' '))
+            .ok_or_else(|| anyhow!("Expected 'key: value', got '{}'", line.text))?;
+        let mut value = Vec::new();
+        let rest = rest.trim();
+        if !rest.is_empty() {
+            value.push(Line {
+                indent: line.indent + key.len() + 2,
+                text: rest,
+            });
+        }
+        entries.push((key.trim(), value));
+    }
+    Ok(entries)
+}
+
+/// Splits a block sequence into the lines of its items.
+fn sequence<'a>(lines: &[Line<'a>]) -> Result<Vec<Vec<Line<'a>>>> {
+    let Some(indent) = lines.first().map(|l| l.indent) else {
+        return Ok(Vec::new());
+    };
+
+    let mut items: Vec<Vec<Line>> = Vec::new();
+    for line in lines {
+        if line.indent == indent {
+            let Some(rest) = line.text.strip_prefix('-') else {
+                bail!("Expected a list item, got '{}'", line.text);
+            };
+            let rest = rest.trim_start();
+            let mut item = Vec::new();
+            if !rest.is_empty() {
+                item.push(Line {
+                    indent: line.indent + line.text.len() - rest.len(),
+                    text: rest,
+                });
+            }
+            items.push(item);
+        } else if line.indent > indent
+            && let Some(item) = items.last_mut()
+        {
+            item.push(*line);
+        } else {
+            bail!("Unexpected indentation of '{}'", line.text);
+        }
+    }
+    Ok(items)
+}
+
+/// Returns the string value of a scalar.
+///
+/// Literal block scalars (`|`) keep their line breaks. Folded block scalars
+/// (`>`) and plain or quoted scalars that span several lines are joined with
+/// spaces.
+fn scalar(lines: &[Line]) -> String {
+    let Some((first, rest)) = lines.split_first() else {
+        return String::new();
+    };
+
+    if first.text.starts_with('|') || first.text.starts_with('>') {
+        let separator = if first.text.starts_with('|') {
+            "\n"
+        } else {
+            " "
+        };
+        return rest
+            .iter()
+            .map(|l| l.text)
+            .collect::<Vec<_>>()
+            .join(separator);
+    }
+
+    let joined = lines.iter().map(|l| l.text).collect::<Vec<_>>().join(" ");
+    unquote(&joined)
+}
+
+/// Removes the quotes of a quoted scalar, or a trailing comment from a plain
+/// one.
+fn unquote(text: &str) -> String {
+    if text.len() >= 2 && text.starts_with('"') && text.ends_with('"') {
+        let inner = &text[1..text.len() - 1];
+        let mut out = String::with_capacity(inner.len());
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        }
+        return out;
+    }
+    if text.len() >= 2 && text.starts_with('\'') && text.ends_with('\'') {
+        return text[1..text.len() - 1].replace("''", "'");
+    }
+    match text.find(" #") {
+        Some(i) => text[..i].trim_end().to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Converts one item of the DATA list.
+fn dispersion_data(item: &[Line]) -> Result<DispersionData> {
+    let entries = mapping(item)?;
+    let get = |name: &str| {
+        entries
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| scalar(value))
+            .ok_or_else(|| anyhow!("Missing '{name}'"))
+    };
+
+    let kind = get("type")?;
+    if let Some(table) = kind.strip_prefix("tabulated ") {
+        let rows = get("data")?;
+        return match table.trim() {
+            "n" => Ok(DispersionData::TabulatedN {
+                data: table_rows(&rows)?,
+            }),
+            "k" => Ok(DispersionData::TabulatedK {
+                data: table_rows(&rows)?,
+            }),
+            "nk" => Ok(DispersionData::TabulatedNK {
+                data: table_rows(&rows)?,
+            }),
+            other => bail!("Unknown table type 'tabulated {other}'"),
+        };
+    }
+
+    let Some(number) = kind.strip_prefix("formula ") else {
+        bail!("Unknown data type '{kind}'");
+    };
+    let wavelength_range = {
+        let range = numbers(&get("wavelength_range")?).context("wavelength_range")?;
+        let [min, max] = range[..] else {
+            bail!("wavelength_range must have two values");
+        };
+        [min, max]
+    };
+    let c = numbers(&get("coefficients")?).context("coefficients")?;
+    match number.trim() {
+        "1" => Ok(DispersionData::Formula1 {
+            wavelength_range,
+            c,
+        }),
+        "2" => Ok(DispersionData::Formula2 {
+            wavelength_range,
+            c,
+        }),
+        "3" => Ok(DispersionData::Formula3 {
+            wavelength_range,
+            c,
+        }),
+        "4" => Ok(DispersionData::Formula4 {
+            wavelength_range,
+            c,
+        }),
+        "5" => Ok(DispersionData::Formula5 {
+            wavelength_range,
+            c,
+        }),
+        "6" => Ok(DispersionData::Formula6 {
+            wavelength_range,
+            c,
+        }),
+        "7" => Ok(DispersionData::Formula7 {
+            wavelength_range,
+            c,
+        }),
+        "8" => Ok(DispersionData::Formula8 {
+            wavelength_range,
+            c,
+        }),
+        "9" => Ok(DispersionData::Formula9 {
+            wavelength_range,
+            c,
+        }),
+        other => bail!("Unknown formula {other}"),
+    }
+}
+
+fn numbers(text: &str) -> Result<Vec<f64>> {
+    text.split_whitespace()
+        .map(|s| s.parse().with_context(|| format!("'{s}' is not a number")))
+        .collect()
+}
+
+/// Parses the rows of a table, each with `N` numbers.
+fn table_rows<const N: usize>(text: &str) -> Result<Vec<[f64; N]>> {
+    let rows: Vec<[f64; N]> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let values = numbers(line)?;
+            values
+                .try_into()
+                .map_err(|_| anyhow!("Expected {N} values per row, got '{}'", line.trim()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if rows.is_empty() {
+        bail!("Table has no rows");
+    }
+    if rows.windows(2).any(|w| w[1][0] <= w[0][0]) {
+        bail!("Table wavelengths must increase from row to row");
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::RefractiveIndexSpec;
+
+    const N_BK7: &str = r#"
+# this file is part of refractiveindex.info database
+REFERENCES: "<a href=\"https://www.schott.com\">SCHOTT</a> Zemax catalog"
+COMMENTS: 'lead-free, "N" glass'
+DATA:
+  - type: formula 2
+    wavelength_range: 0.3 2.5
+    coefficients: 0 1.03961212 0.00600069867 0.231792344 0.0200179144 1.01046945 103.560653
+  - type: tabulated k
+    data: |
+        0.300 2.8607E-06
+        0.400 4.5e-09
+
+        0.500 1.1e-09
+SPECS:
+  n_absolute: false
+  thermal_expansion:
+    - temperature_range: -30 70 °C
+      coefficient: 7.1e-06 K<sup>-1</sup>
+"#;
+
+    #[test]
+    fn formula_and_table_are_read() {
+        let mat = material_from_yaml(N_BK7, "user", "lab", "N-BK7").unwrap();
+
+        assert_eq!(mat.page, "N-BK7");
+        assert_eq!(
+            mat.references,
+            r#"<a href="https://www.schott.com">SCHOTT</a> Zemax catalog"#
+        );
+        assert_eq!(mat.comments, r#"lead-free, "N" glass"#);
+        assert_eq!(mat.data.len(), 2);
+        assert_eq!(mat.wavelength_range(), Some([0.3, 2.5]));
+        assert_abs_diff_eq!(
+            RefractiveIndexSpec::n(&mat, 0.5875618).unwrap(),
+            1.5168,
+            epsilon = 1e-4
+        );
+        assert_abs_diff_eq!(
+            RefractiveIndexSpec::k(&mat, 0.45).unwrap(),
+            2.8e-9,
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn tabulated_nk_is_read() {
+        let yaml = "DATA:\n- type: tabulated nk\n  data: |\n    0.5 1.5 0.01\n    0.6 1.4 0.02\n";
+        let mat = material_from_yaml(yaml, "user", "lab", "oil").unwrap();
+
+        assert_eq!(mat.references, "");
+        assert_eq!(mat.wavelength_range(), Some([0.5, 0.6]));
+        assert_abs_diff_eq!(
+            RefractiveIndexSpec::n(&mat, 0.55).unwrap(),
+            1.45,
+            epsilon = 1e-12
+        );
+        assert_abs_diff_eq!(
+            RefractiveIndexSpec::k(&mat, 0.55).unwrap(),
+            0.015,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn malformed_pages_are_errors() {
+        let cases = [
+            "COMMENTS: no data",
+            "DATA:\n",
+            "DATA:\n  - type: formula 12\n    wavelength_range: 0.3 2.5\n    coefficients: 1\n",
+            "DATA:\n  - type: formula 1\n    wavelength_range: 0.3\n    coefficients: 1\n",
+            "DATA:\n  - type: tabulated n\n    data: |\n      0.5 1.5 0.1\n",
+            "DATA:\n  - type: tabulated n\n    data: |\n      0.5 abc\n",
+            "DATA:\n  - type: tabulated n\n    data: |\n      0.6 1.5\n      0.5 1.6\n",
+            "DATA:\n  - data: |\n      0.5 1.5\n",
+            "DATA:\n\t- type: tabulated n\n",
+        ];
+        for yaml in cases {
+            assert!(
+                material_from_yaml(yaml, "user", "lab", "bad").is_err(),
+                "{yaml:?}"
+            );
+        }
+    }
+}
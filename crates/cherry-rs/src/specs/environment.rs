@@ -0,0 +1,67 @@
+//! The environment in which an optical system is used.
+use anyhow::{Result, bail};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::core::Float;
+
+/// Absolute zero, in °C.
+const ABSOLUTE_ZERO: Float = -273.15;
+
+/// The temperature and pressure of the air around an optical system.
+///
+/// Materials that model their dependence on the environment, such as
+/// [`Air`](crate::Air) and catalog glasses with thermal coefficients, report
+/// their absolute refractive index in it through
+/// [`RefractiveIndexSpec::n_in`](crate::RefractiveIndexSpec::n_in). Other
+/// materials are unaffected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Environment {
+    /// Temperature in °C.
+    pub temperature: Float,
+    /// Air pressure in Pa.
+    pub pressure: Float,
+}
+
+impl Default for Environment {
+    /// 20 °C and one standard atmosphere, the conditions at which glass
+    /// catalogs are usually measured.
+    fn default() -> Self {
+        Self {
+            temperature: 20.0,
+            pressure: 101_325.0,
+        }
+    }
+}
+
+impl Environment {
+    /// Creates a new environment.
+    ///
+    /// # Arguments
+    /// * `temperature` - Temperature in °C.
+    /// * `pressure` - Air pressure in Pa. Zero is vacuum.
+    pub fn new(temperature: Float, pressure: Float) -> Self {
+        Self {
+            temperature,
+            pressure,
+        }
+    }
+
+    /// Validate the environment.
+    pub fn validate(&self) -> Result<()> {
+        if !(self.temperature.is_finite() && self.temperature > ABSOLUTE_ZERO) {
+            bail!(
+                "Temperature must be finite and above absolute zero, got {} °C",
+                self.temperature
+            );
+        }
+        if !(self.pressure.is_finite() && self.pressure >= 0.0) {
+            bail!(
+                "Pressure must be finite and non-negative, got {} Pa",
+                self.pressure
+            );
+        }
+        Ok(())
+    }
+}
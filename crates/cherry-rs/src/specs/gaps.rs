@@ -4,7 +4,7 @@ use anyhow::Result;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::core::Float;
+use crate::{core::Float, specs::environment::Environment};
 
 /// Creates a real refractive index spec.
 #[macro_export]
@@ -49,6 +49,38 @@ pub trait RefractiveIndexSpec: std::fmt::Debug {
     /// # Errors
     /// If the wavelength is not within the valid range for the material.
     fn k(&self, wavelength: Float) -> Result<Float>;
+
+    /// The real part of a material's absolute refractive index in an
+    /// environment.
+    ///
+    /// Materials whose index does not depend on temperature and pressure
+    /// return [`n`](Self::n).
+    ///
+    /// # Arguments
+    /// * `wavelength` - The wavelength of the light in micrometers.
+    /// * `environment` - The temperature and pressure around the material.
+    ///
+    /// # Errors
+    /// If the wavelength is not within the valid range for the material.
+    fn n_in(&self, wavelength: Float, _environment: &Environment) -> Result<Float> {
+        self.n(wavelength)
+    }
+
+    /// The coefficient of thermal expansion of a solid material, in 1/K.
+    ///
+    /// Returns `None` for fluids and for materials without expansion data.
+    fn thermal_expansion(&self) -> Option<Float> {
+        None
+    }
+
+    /// The shortest and longest wavelengths at which the refractive index is
+    /// defined, in micrometers.
+    ///
+    /// Returns `None` if the material is defined at all wavelengths or does
+    /// not report its range.
+    fn wavelength_range(&self) -> Option<[Float; 2]> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
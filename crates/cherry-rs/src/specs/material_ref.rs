@@ -0,0 +1,205 @@
+//! Serializable references to materials.
+use std::{collections::HashMap, rc::Rc};
+
+use anyhow::{Result, anyhow};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Air, ConstantRefractiveIndex, FormulaMaterial, GapSpec, GlassCatalog, ModelGlass,
+    RefractiveIndexSpec, core::Float,
+};
+
+/// A material of a gap, described by value or by the key of a material in a
+/// database.
+///
+/// Unlike the `Rc<dyn RefractiveIndexSpec>` of a [`GapSpec`], a reference can
+/// be serialized, so that a design can be saved and shared. A
+/// [`MaterialResolver`] turns it into a material.
+///
+/// [`GapSpec`]: crate::GapSpec
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum MaterialRef {
+    /// A refractive index that does not depend on the wavelength.
+    Constant {
+        n: Float,
+        #[cfg_attr(feature = "serde", serde(default))]
+        k: Float,
+    },
+    /// The key of a material in a database or glass catalog, such as
+    /// `SCHOTT:N-BK7` or `main:Ag:Johnson`.
+    Catalog(String),
+    /// A material described by a dispersion formula.
+    Formula(FormulaMaterial),
+    /// A model glass defined by its index, Abbe number and partial
+    /// dispersion.
+    ModelGlass(ModelGlass),
+    /// Dry air, whose index depends on the environment.
+    Air,
+}
+
+/// Specifies a gap by a reference to its material.
+///
+/// This is the serializable form of a [`GapSpec`]; resolve it to build a
+/// model.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GapRefSpec {
+    pub thickness: Float,
+    pub material: MaterialRef,
+}
+
+impl GapRefSpec {
+    /// Returns the gap spec with the referenced material.
+    ///
+    /// # Errors
+    /// If the resolver does not know the material.
+    pub fn resolve(&self, resolver: &(impl MaterialResolver + ?Sized)) -> Result<GapSpec> {
+        Ok(GapSpec {
+            thickness: self.thickness,
+            refractive_index: resolver.resolve(&self.material)?,
+        })
+    }
+}
+
+/// Turns material references into materials.
+///
+/// Implementors look up the keys of [`MaterialRef::Catalog`]; the other
+/// references describe their material by value and are built by
+/// [`resolve`](Self::resolve) itself.
+pub trait MaterialResolver {
+    /// Returns the material stored under a key, if any.
+    fn material(&self, key: &str) -> Option<Rc<dyn RefractiveIndexSpec>>;
+
+    /// Returns the material of a reference.
+    ///
+    /// # Errors
+    /// If the key of a [`MaterialRef::Catalog`] is unknown to the resolver.
+    fn resolve(&self, material: &MaterialRef) -> Result<Rc<dyn RefractiveIndexSpec>> {
+        Ok(match material {
+            MaterialRef::Constant { n, k } => Rc::new(ConstantRefractiveIndex::new(*n, *k)),
+            MaterialRef::Catalog(key) => self
+                .material(key)
+                .ok_or_else(|| anyhow!("Material '{key}' not found"))?,
+            MaterialRef::Formula(formula) => Rc::new(formula.clone()),
+            MaterialRef::ModelGlass(glass) => Rc::new(glass.clone()),
+            MaterialRef::Air => Rc::new(Air),
+        })
+    }
+}
+
+impl<R: MaterialResolver + ?Sized> MaterialResolver for &R {
+    fn material(&self, key: &str) -> Option<Rc<dyn RefractiveIndexSpec>> {
+        (**self).material(key)
+    }
+}
+
+/// Resolves keys to the materials of a map, such as the glasses of several
+/// catalogs keyed `catalog:glass`.
+impl MaterialResolver for HashMap<String, Rc<dyn RefractiveIndexSpec>> {
+    fn material(&self, key: &str) -> Option<Rc<dyn RefractiveIndexSpec>> {
+        self.get(key).cloned()
+    }
+}
+
+/// Resolves keys to the glasses of a catalog by their name, e.g. `N-BK7`.
+impl MaterialResolver for GlassCatalog {
+    fn material(&self, key: &str) -> Option<Rc<dyn RefractiveIndexSpec>> {
+        let glass = self.get(key)?.clone();
+        Some(Rc::new(glass))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    use crate::{DispersionFormula, Environment};
+
+    #[test]
+    fn references_resolve_to_their_materials() {
+        let resolver: HashMap<String, Rc<dyn RefractiveIndexSpec>> = HashMap::from([(
+            "glass:fixed".to_string(),
+            Rc::new(ConstantRefractiveIndex::new(1.6, 0.0)) as Rc<dyn RefractiveIndexSpec>,
+        )]);
+        let n = |material: MaterialRef| resolver.resolve(&material).unwrap().n(0.5).unwrap();
+
+        assert_eq!(n(MaterialRef::Constant { n: 1.5, k: 0.0 }), 1.5);
+        assert_eq!(n(MaterialRef::Catalog("glass:fixed".into())), 1.6);
+        let formula = FormulaMaterial::new(
+            DispersionFormula::Cauchy {
+                a: vec![1.45, 0.004],
+            },
+            [0.4, 0.8],
+        )
+        .unwrap();
+        assert_eq!(
+            n(MaterialRef::Formula(formula.clone())),
+            formula.n(0.5).unwrap()
+        );
+        let glass = ModelGlass::new(1.7, 30.0, 0.0).unwrap();
+        assert_eq!(
+            n(MaterialRef::ModelGlass(glass.clone())),
+            glass.n(0.5).unwrap()
+        );
+        assert_eq!(
+            n(MaterialRef::Air),
+            Air.n_in(0.5, &Environment::default()).unwrap()
+        );
+
+        let missing = resolver.resolve(&MaterialRef::Catalog("glass:missing".into()));
+        assert!(missing.unwrap_err().to_string().contains("glass:missing"));
+    }
+
+    #[test]
+    fn gap_references_resolve_to_gap_specs() {
+        let resolver: HashMap<String, Rc<dyn RefractiveIndexSpec>> = HashMap::new();
+        let gap = GapRefSpec {
+            thickness: 5.3,
+            material: MaterialRef::ModelGlass(ModelGlass::new(1.7, 30.0, 0.0).unwrap()),
+        };
+        let spec = gap.resolve(&resolver).unwrap();
+        assert_eq!(spec.thickness, 5.3);
+        assert_abs_diff_eq!(crate::nd(spec.refractive_index.as_ref()).unwrap(), 1.7);
+
+        let missing = GapRefSpec {
+            thickness: 1.0,
+            material: MaterialRef::Catalog("glass:missing".into()),
+        };
+        assert!(missing.resolve(&resolver).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn references_round_trip() {
+        let references = vec![
+            MaterialRef::Constant { n: 1.5, k: 0.001 },
+            MaterialRef::Catalog("SCHOTT:N-BK7".into()),
+            MaterialRef::Formula(
+                FormulaMaterial::new(
+                    DispersionFormula::Sellmeier1 {
+                        b: [1.03961212, 0.231792344, 1.01046945],
+                        c: [0.00600069867, 0.0200179144, 103.560653],
+                    },
+                    [0.3, 2.5],
+                )
+                .unwrap(),
+            ),
+            MaterialRef::ModelGlass(ModelGlass::new(1.7, 30.0, 0.01).unwrap()),
+            MaterialRef::Air,
+        ];
+        let json = serde_json::to_string(&references).expect("serialize");
+        let back: Vec<MaterialRef> = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(back, references);
+
+        let constant: MaterialRef = serde_json::from_str(r#"{"Constant":{"n":1.33}}"#).unwrap();
+        assert_eq!(constant, MaterialRef::Constant { n: 1.33, k: 0.0 });
+
+        let gap: GapRefSpec =
+            serde_json::from_str(r#"{"thickness":5.3,"material":{"Catalog":"SCHOTT:N-BK7"}}"#)
+                .unwrap();
+        assert_eq!(gap.material, MaterialRef::Catalog("SCHOTT:N-BK7".into()));
+    }
+}
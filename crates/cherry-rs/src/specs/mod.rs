@@ -1,4 +1,6 @@
 pub mod aperture;
+pub mod environment;
 pub mod fields;
 pub mod gaps;
+pub mod material_ref;
 pub mod surfaces;
@@ -1,7 +1,12 @@
+use anyhow::Result;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-use crate::core::{Float, math::linalg::rotations::Rotation3D, math::vec3::Vec3};
+use crate::core::{
+    Float, PI,
+    math::{linalg::rotations::Rotation3D, vec3::Vec3},
+    surfaces::SagGrid,
+};
 
 /// Specifies the kind of interaction of light with a sequential model surface.
 #[derive(Debug, Clone, Copy)]
@@ -12,6 +17,40 @@ pub enum BoundaryKind {
     NoOp,
 }
 
+/// Term ordering and normalization of a Zernike expansion.
+///
+/// `Fringe` is the 37-term University of Arizona set with unit-amplitude
+/// polynomials. `Standard` follows Noll's ordering with polynomials normalized
+/// to unit RMS over the unit disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ZernikeOrdering {
+    Fringe,
+    Standard,
+}
+
+/// Family of Forbes Q-polynomials used by a
+/// [`ForbesAsphere`](crate::ForbesAsphere).
+///
+/// `Qbfs` describes a mild asphere as a departure from its best-fit sphere,
+/// with coefficients that give the slope of the departure directly. `Qcon`
+/// describes a strong asphere as a departure from a conic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ForbesQType {
+    Qbfs,
+    Qcon,
+}
+
+/// Lattice on which the lenslets of a
+/// [`LensletArray`](crate::LensletArray) are arranged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LensletPacking {
+    Square,
+    Hexagonal,
+}
+
 /// Specifies the clear aperture of a surface.
 ///
 /// This is referred to as a "mask" to avoid confusion with
@@ -19,17 +58,64 @@ pub enum BoundaryKind {
 /// the system.
 ///
 /// `Unbounded` surfaces (Object, Image, Probe) pass all rays unconditionally.
-#[derive(Debug, Clone, Copy)]
+///
+/// All coordinates are in the surface's local xy-plane. Shapes are centered on
+/// the vertex; wrap them in `Decentered` to move them, and in `Obscured` to
+/// block parts of them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Mask {
-    Circular { semi_diameter: Float },
+    Circular {
+        semi_diameter: Float,
+    },
+    Rectangular {
+        half_width_x: Float,
+        half_width_y: Float,
+    },
+    Elliptical {
+        semi_axis_x: Float,
+        semi_axis_y: Float,
+    },
+    /// A simple polygon with vertices `[x, y]` in order around its boundary.
+    Polygon {
+        vertices: Vec<[Float; 2]>,
+    },
+    /// Another mask shifted away from the vertex.
+    Decentered {
+        offset_x: Float,
+        offset_y: Float,
+        mask: Box<Mask>,
+    },
+    /// An aperture with an optional central obscuration and spider.
+    ///
+    /// Rays that fall inside `obscuration` or on a spider vane are blocked.
+    Obscured {
+        aperture: Box<Mask>,
+        obscuration: Option<Box<Mask>>,
+        spider: Option<Spider>,
+    },
     Unbounded,
 }
 
+/// Straight vanes of equal width that radiate from the center of an
+/// [`Mask::Obscured`] aperture, such as those that hold a telescope's
+/// secondary mirror.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Spider {
+    /// Number of vanes, evenly spaced in azimuth.
+    pub vanes: usize,
+    /// Full width of each vane.
+    pub width: Float,
+    /// Angle of the first vane from the local x-axis, in radians.
+    pub rotation: Float,
+}
+
 /// Specifies a surface in a sequential optical system.
 ///
 /// Rotations specify transformations from the cursor reference frame to the
 /// surface local reference frame.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SurfaceSpec {
     Conic {
@@ -43,6 +129,173 @@ pub enum SurfaceSpec {
         #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
         rotation_offset: Rotation3D,
     },
+    /// A conic base plus even-order polynomial terms.
+    ///
+    /// `aspheric_coefficients` holds A4, A6, ... A20 in order; at most nine
+    /// terms are allowed. Trailing terms may be omitted.
+    EvenAsphere {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        aspheric_coefficients: Vec<Float>,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A conic base plus a Forbes Q-polynomial departure.
+    ///
+    /// `q_coefficients[m]` multiplies the polynomial of order `m` of the chosen
+    /// `q_type`, evaluated over `normalization_radius`. Qbfs surfaces require
+    /// a conic constant of zero.
+    ForbesAsphere {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        normalization_radius: Float,
+        q_type: ForbesQType,
+        q_coefficients: Vec<Float>,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A flat carrier whose facets follow a conic or even-asphere profile.
+    ///
+    /// The base profile is given as for [`SurfaceSpec::EvenAsphere`]. The
+    /// `groove_pitch` sets the radial width of the grooves drawn in
+    /// cross-section views and does not affect ray tracing; see
+    /// [`Fresnel`](crate::Fresnel).
+    Fresnel {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        #[cfg_attr(feature = "serde", serde(default))]
+        aspheric_coefficients: Vec<Float>,
+        groove_pitch: Float,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A surface with independent radii and conic constants in the local x
+    /// and y meridians.
+    Biconic {
+        semi_diameter: Float,
+        radius_of_curvature_x: Float,
+        radius_of_curvature_y: Float,
+        conic_constant_x: Float,
+        conic_constant_y: Float,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A cone with its apex on the axis.
+    ///
+    /// `cone_angle` is the angle between the cone and the plane perpendicular
+    /// to the axis, in radians; see [`Axicon`](crate::Axicon).
+    Axicon {
+        semi_diameter: Float,
+        cone_angle: Float,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A square or hexagonal array of identical conic lenslets, one of which
+    /// is centered on the axis.
+    ///
+    /// `pitch` is the distance between neighbouring lenslet centers; see
+    /// [`LensletArray`](crate::LensletArray).
+    LensletArray {
+        semi_diameter: Float,
+        pitch: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        packing: LensletPacking,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A ruled diffraction grating on a spherical base.
+    ///
+    /// `line_density` is in lines per mm and `line_orientation` is the angle of
+    /// the rulings from the local x-axis in radians. A refracting grating
+    /// transmits `diffraction_order`; a reflecting grating reflects it.
+    Grating {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        line_density: Float,
+        line_orientation: Float,
+        diffraction_order: i32,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A rotationally symmetric diffractive phase on a conic base.
+    ///
+    /// `phase_coefficients[i]` multiplies ρ^(2i + 2), where ρ is the radial
+    /// distance divided by `normalization_radius`; see
+    /// [`Diffractive`](crate::Diffractive).
+    Diffractive {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        normalization_radius: Float,
+        phase_coefficients: Vec<Float>,
+        diffraction_order: i32,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// An ideal lens described by its focal length and principal planes.
+    ///
+    /// The surface lies in the front principal plane and the following gap is
+    /// measured from the back principal plane, `principal_plane_separation`
+    /// further along the axis; see [`CardinalLens`](crate::CardinalLens).
+    CardinalLens {
+        semi_diameter: Float,
+        focal_length: Float,
+        principal_plane_separation: Float,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// An ideal thin lens that focuses every ray perfectly.
+    ///
+    /// `focal_length` is the image-space focal length. The lens is flat and
+    /// has no thickness; see [`ParaxialLens`](crate::ParaxialLens).
+    ParaxialLens {
+        semi_diameter: Float,
+        focal_length: Float,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
     Sphere {
         semi_diameter: Float,
         radius_of_curvature: Float,
@@ -53,6 +306,116 @@ pub enum SurfaceSpec {
         #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
         rotation_offset: Rotation3D,
     },
+    /// A surface with different radii of curvature in the local x and y
+    /// meridians.
+    ///
+    /// The conic constant applies to the y profile. An infinite radius in
+    /// either meridian gives a cylinder with power in the other.
+    Toroid {
+        semi_diameter: Float,
+        radius_of_curvature_x: Float,
+        radius_of_curvature_y: Float,
+        conic_constant: Float,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A cylinder with power in the local y meridian.
+    ///
+    /// The cylinder axis lies along local x. Use `rotation_offset` to clock it
+    /// about the optical axis.
+    Cylinder {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A conic base plus a polynomial in x and y.
+    ///
+    /// `xy_coefficients[k]` multiplies the k-th term of the sequence `x, y,
+    /// x², xy, y², x³, ...` in coordinates normalized by
+    /// `normalization_radius`. Terms up to total order `order` are allowed;
+    /// trailing terms may be omitted.
+    XYPolynomial {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        normalization_radius: Float,
+        order: usize,
+        xy_coefficients: Vec<Float>,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A conic base plus a tabulated grid of sag departures.
+    ///
+    /// The grid is stored in the spec so that designs are self-contained; see
+    /// [`GridSag`](crate::GridSag).
+    GridSag {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        grid: SagGrid,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// A conic base plus a Zernike expansion of the sag.
+    ///
+    /// `zernike_coefficients[j]` multiplies term `j + 1` of the chosen
+    /// `ordering`, evaluated over `normalization_radius`.
+    ZernikeSag {
+        semi_diameter: Float,
+        radius_of_curvature: Float,
+        conic_constant: Float,
+        normalization_radius: Float,
+        ordering: ZernikeOrdering,
+        zernike_coefficients: Vec<Float>,
+        surf_kind: BoundaryKind,
+        rotation: Rotation3D,
+        #[cfg_attr(feature = "serde", serde(default = "default_zero_vec3"))]
+        decenter: Vec3,
+        #[cfg_attr(feature = "serde", serde(default = "default_rotation3d_none"))]
+        rotation_offset: Rotation3D,
+    },
+    /// Another surface whose clear aperture is given by `mask` instead of its
+    /// own semi-diameter.
+    ///
+    /// Use this for rectangular, elliptical, polygonal, decentered or
+    /// obscured apertures. The semi-diameter of `surface` should still enclose
+    /// the mask, since some surfaces use it to size their features. Rotation
+    /// and decenter are those of `surface`.
+    Masked {
+        mask: Mask,
+        surface: Box<SurfaceSpec>,
+    },
+    /// A refracting or reflecting surface whose mechanical edge lies outside
+    /// its clear aperture.
+    ///
+    /// Rays are clipped by the clear aperture of `surface`, but cross-section
+    /// views draw the surface out to `mechanical_semi_diameter`. The outer
+    /// `flat_width` of the mechanical aperture is drawn as a flat normal to
+    /// the axis, such as one ground for mounting. Rotation and decenter are
+    /// those of `surface`.
+    Mounted {
+        mechanical_semi_diameter: Float,
+        #[cfg_attr(feature = "serde", serde(default))]
+        flat_width: Float,
+        surface: Box<SurfaceSpec>,
+    },
     /// A user-defined surface type registered with a [`SurfaceRegistry`].
     ///
     /// `type_id` must match a key registered via
@@ -107,10 +470,28 @@ impl SurfaceSpec {
     pub fn rotation(&self) -> Rotation3D {
         match self {
             SurfaceSpec::Conic { rotation, .. }
+            | SurfaceSpec::Biconic { rotation, .. }
+            | SurfaceSpec::EvenAsphere { rotation, .. }
+            | SurfaceSpec::ForbesAsphere { rotation, .. }
+            | SurfaceSpec::Fresnel { rotation, .. }
+            | SurfaceSpec::Axicon { rotation, .. }
+            | SurfaceSpec::LensletArray { rotation, .. }
+            | SurfaceSpec::Grating { rotation, .. }
+            | SurfaceSpec::Diffractive { rotation, .. }
+            | SurfaceSpec::ParaxialLens { rotation, .. }
+            | SurfaceSpec::CardinalLens { rotation, .. }
             | SurfaceSpec::Sphere { rotation, .. }
+            | SurfaceSpec::Toroid { rotation, .. }
+            | SurfaceSpec::Cylinder { rotation, .. }
+            | SurfaceSpec::XYPolynomial { rotation, .. }
+            | SurfaceSpec::GridSag { rotation, .. }
+            | SurfaceSpec::ZernikeSag { rotation, .. }
             | SurfaceSpec::Image { rotation, .. }
             | SurfaceSpec::Probe { rotation, .. }
             | SurfaceSpec::Iris { rotation, .. } => rotation.clone(),
+            SurfaceSpec::Masked { surface, .. } | SurfaceSpec::Mounted { surface, .. } => {
+                surface.rotation()
+            }
             SurfaceSpec::Object => Rotation3D::None,
             #[cfg(feature = "serde")]
             SurfaceSpec::Custom { rotation, .. } => rotation.clone(),
@@ -123,9 +504,54 @@ impl SurfaceSpec {
             SurfaceSpec::Conic {
                 rotation_offset, ..
             }
+            | SurfaceSpec::Biconic {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::EvenAsphere {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::ForbesAsphere {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::Fresnel {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::Axicon {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::LensletArray {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::Grating {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::Diffractive {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::ParaxialLens {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::CardinalLens {
+                rotation_offset, ..
+            }
             | SurfaceSpec::Sphere {
                 rotation_offset, ..
             }
+            | SurfaceSpec::Toroid {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::Cylinder {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::XYPolynomial {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::GridSag {
+                rotation_offset, ..
+            }
+            | SurfaceSpec::ZernikeSag {
+                rotation_offset, ..
+            }
             | SurfaceSpec::Image {
                 rotation_offset, ..
             }
@@ -135,6 +561,9 @@ impl SurfaceSpec {
             | SurfaceSpec::Iris {
                 rotation_offset, ..
             } => rotation_offset.clone(),
+            SurfaceSpec::Masked { surface, .. } | SurfaceSpec::Mounted { surface, .. } => {
+                surface.rotation_offset()
+            }
             SurfaceSpec::Object => Rotation3D::None,
             #[cfg(feature = "serde")]
             SurfaceSpec::Custom { .. } => Rotation3D::None,
@@ -146,40 +575,475 @@ impl SurfaceSpec {
     pub fn decenter(&self) -> Vec3 {
         match self {
             SurfaceSpec::Conic { decenter, .. }
+            | SurfaceSpec::Biconic { decenter, .. }
+            | SurfaceSpec::EvenAsphere { decenter, .. }
+            | SurfaceSpec::ForbesAsphere { decenter, .. }
+            | SurfaceSpec::Fresnel { decenter, .. }
+            | SurfaceSpec::Axicon { decenter, .. }
+            | SurfaceSpec::LensletArray { decenter, .. }
+            | SurfaceSpec::Grating { decenter, .. }
+            | SurfaceSpec::Diffractive { decenter, .. }
+            | SurfaceSpec::ParaxialLens { decenter, .. }
+            | SurfaceSpec::CardinalLens { decenter, .. }
             | SurfaceSpec::Sphere { decenter, .. }
+            | SurfaceSpec::Toroid { decenter, .. }
+            | SurfaceSpec::Cylinder { decenter, .. }
+            | SurfaceSpec::XYPolynomial { decenter, .. }
+            | SurfaceSpec::GridSag { decenter, .. }
+            | SurfaceSpec::ZernikeSag { decenter, .. }
             | SurfaceSpec::Image { decenter, .. }
             | SurfaceSpec::Probe { decenter, .. }
             | SurfaceSpec::Iris { decenter, .. } => *decenter,
+            SurfaceSpec::Masked { surface, .. } | SurfaceSpec::Mounted { surface, .. } => {
+                surface.decenter()
+            }
             SurfaceSpec::Object => Vec3::new(0.0, 0.0, 0.0),
             #[cfg(feature = "serde")]
             SurfaceSpec::Custom { .. } => Vec3::new(0.0, 0.0, 0.0),
         }
     }
+
+    /// Returns the spec of the surface itself, looking through any
+    /// [`SurfaceSpec::Masked`] and [`SurfaceSpec::Mounted`] wrappers.
+    pub fn unwrapped(&self) -> &SurfaceSpec {
+        match self {
+            SurfaceSpec::Masked { surface, .. } | SurfaceSpec::Mounted { surface, .. } => {
+                surface.unwrapped()
+            }
+            spec => spec,
+        }
+    }
+
+    /// Mutable version of [`unwrapped`](SurfaceSpec::unwrapped).
+    pub fn unwrapped_mut(&mut self) -> &mut SurfaceSpec {
+        match self {
+            SurfaceSpec::Masked { surface, .. } | SurfaceSpec::Mounted { surface, .. } => {
+                surface.unwrapped_mut()
+            }
+            spec => spec,
+        }
+    }
 }
 
 impl Mask {
     /// Returns `true` if `pos` lies outside the clear aperture. The axial
     /// z-component of `pos` is ignored.
     pub fn outside_clear_aperture(&self, pos: Vec3) -> bool {
+        self.blocks(pos.x(), pos.y(), true)
+    }
+
+    /// Returns `true` if `pos` lies outside the aperture or inside an
+    /// obscuration, ignoring spider vanes.
+    pub(crate) fn outside_aperture_or_obscured(&self, pos: Vec3) -> bool {
+        self.blocks(pos.x(), pos.y(), false)
+    }
+
+    fn blocks(&self, x: Float, y: Float, include_spider: bool) -> bool {
         match self {
             Mask::Circular { semi_diameter } => {
-                let r_transv = pos.x() * pos.x() + pos.y() * pos.y();
+                let r_transv = x * x + y * y;
                 let r_max = *semi_diameter;
                 r_transv > r_max * r_max
             }
+            Mask::Rectangular {
+                half_width_x,
+                half_width_y,
+            } => x.abs() > *half_width_x || y.abs() > *half_width_y,
+            Mask::Elliptical {
+                semi_axis_x,
+                semi_axis_y,
+            } => (x / semi_axis_x).powi(2) + (y / semi_axis_y).powi(2) > 1.0,
+            Mask::Polygon { vertices } => !polygon_contains(vertices, x, y),
+            Mask::Decentered {
+                offset_x,
+                offset_y,
+                mask,
+            } => mask.blocks(x - offset_x, y - offset_y, include_spider),
+            Mask::Obscured {
+                aperture,
+                obscuration,
+                spider,
+            } => {
+                aperture.blocks(x, y, include_spider)
+                    || obscuration
+                        .as_ref()
+                        .is_some_and(|o| !o.blocks(x, y, include_spider))
+                    || (include_spider && spider.is_some_and(|s| s.blocks(x, y)))
+            }
             Mask::Unbounded => false,
         }
     }
 
-    /// Returns the semi-diameter of the mask.
+    /// Returns the radius of the smallest circle about the vertex that
+    /// encloses the clear aperture.
     ///
-    /// Returns [`Float::INFINITY`] for [`Mask::Unbounded`].
+    /// This is the semi-diameter of a `Circular` mask. Paraxial analysis and
+    /// drawing use it as the size of the surface. Returns [`Float::INFINITY`]
+    /// for [`Mask::Unbounded`].
     pub fn semi_diameter(&self) -> Float {
         match self {
             Mask::Circular { semi_diameter } => *semi_diameter,
+            Mask::Rectangular {
+                half_width_x,
+                half_width_y,
+            } => half_width_x.hypot(*half_width_y),
+            Mask::Elliptical {
+                semi_axis_x,
+                semi_axis_y,
+            } => semi_axis_x.max(*semi_axis_y),
+            Mask::Polygon { vertices } => vertices
+                .iter()
+                .map(|[x, y]| x.hypot(*y))
+                .fold(0.0, Float::max),
+            Mask::Decentered {
+                offset_x,
+                offset_y,
+                mask,
+            } => mask.semi_diameter() + offset_x.hypot(*offset_y),
+            Mask::Obscured { aperture, .. } => aperture.semi_diameter(),
+            Mask::Unbounded => Float::INFINITY,
+        }
+    }
+
+    /// Returns the half-length of the chord of the clear aperture through the
+    /// vertex in the direction `(dx, dy)`, i.e. the largest height at which
+    /// rays on both sides of the vertex along that line pass the aperture.
+    ///
+    /// This is `half_width_y` of a `Rectangular` mask in the y direction, and
+    /// `semi_diameter - offset` of a `Circular` mask decentered along it.
+    /// Paraxial analysis uses it to select the aperture stop and size the
+    /// marginal ray in each meridian. Returns zero if the aperture does not
+    /// contain the vertex, and [`semi_diameter`](Self::semi_diameter) if the
+    /// direction vanishes. Obscurations and spider vanes are ignored.
+    pub fn semi_extent(&self, dx: Float, dy: Float) -> Float {
+        let norm = dx.hypot(dy);
+        if norm == 0.0 {
+            return self.semi_diameter();
+        }
+        let (dx, dy) = (dx / norm, dy / norm);
+        self.reach(0.0, 0.0, dx, dy)
+            .min(self.reach(0.0, 0.0, -dx, -dy))
+    }
+
+    /// Returns the distance from `(x, y)` along the unit vector `(dx, dy)` to
+    /// the edge of the aperture, or zero if `(x, y)` lies outside it.
+    fn reach(&self, x: Float, y: Float, dx: Float, dy: Float) -> Float {
+        match self {
+            Mask::Circular { semi_diameter: r } => ellipse_reach(x / r, y / r, dx / r, dy / r),
+            Mask::Rectangular {
+                half_width_x,
+                half_width_y,
+            } => {
+                if x.abs() > *half_width_x || y.abs() > *half_width_y {
+                    return 0.0;
+                }
+                slab_reach(x, dx, *half_width_x).min(slab_reach(y, dy, *half_width_y))
+            }
+            Mask::Elliptical {
+                semi_axis_x: a,
+                semi_axis_y: b,
+            } => ellipse_reach(x / a, y / b, dx / a, dy / b),
+            Mask::Polygon { vertices } => polygon_reach(vertices, x, y, dx, dy),
+            Mask::Decentered {
+                offset_x,
+                offset_y,
+                mask,
+            } => mask.reach(x - offset_x, y - offset_y, dx, dy),
+            Mask::Obscured { aperture, .. } => aperture.reach(x, y, dx, dy),
             Mask::Unbounded => Float::INFINITY,
         }
     }
+
+    /// Validate the mask.
+    pub fn validate(&self) -> Result<()> {
+        let positive = |name: &str, value: Float| {
+            if !(value.is_finite() && value > 0.0) {
+                anyhow::bail!("Mask {name} must be positive and finite, got {value}");
+            }
+            Ok(())
+        };
+        match self {
+            Mask::Circular { semi_diameter } => positive("semi-diameter", *semi_diameter)?,
+            Mask::Rectangular {
+                half_width_x,
+                half_width_y,
+            } => {
+                positive("half-width", *half_width_x)?;
+                positive("half-width", *half_width_y)?;
+            }
+            Mask::Elliptical {
+                semi_axis_x,
+                semi_axis_y,
+            } => {
+                positive("semi-axis", *semi_axis_x)?;
+                positive("semi-axis", *semi_axis_y)?;
+            }
+            Mask::Polygon { vertices } => {
+                if vertices.len() < 3 {
+                    anyhow::bail!("Polygon masks need at least 3 vertices");
+                }
+                if vertices.iter().flatten().any(|v| !v.is_finite()) {
+                    anyhow::bail!("Polygon mask vertices must be finite");
+                }
+            }
+            Mask::Decentered {
+                offset_x,
+                offset_y,
+                mask,
+            } => {
+                if !(offset_x.is_finite() && offset_y.is_finite()) {
+                    anyhow::bail!("Mask offsets must be finite");
+                }
+                mask.validate()?;
+            }
+            Mask::Obscured {
+                aperture,
+                obscuration,
+                spider,
+            } => {
+                aperture.validate()?;
+                if let Some(obscuration) = obscuration {
+                    obscuration.validate()?;
+                }
+                if let Some(spider) = spider {
+                    positive("spider vane width", spider.width)?;
+                    if !spider.rotation.is_finite() {
+                        anyhow::bail!("Spider rotation must be finite");
+                    }
+                }
+            }
+            Mask::Unbounded => {}
+        }
+        Ok(())
+    }
+}
+
+impl Spider {
+    fn blocks(&self, x: Float, y: Float) -> bool {
+        (0..self.vanes).any(|k| {
+            let angle = self.rotation + 2.0 * PI * k as Float / self.vanes as Float;
+            let (sin, cos) = angle.sin_cos();
+            let along = x * cos + y * sin;
+            let across = -x * sin + y * cos;
+            along >= 0.0 && across.abs() <= 0.5 * self.width
+        })
+    }
+}
+
+/// Even-odd test for whether a point lies inside a polygon.
+fn polygon_contains(vertices: &[[Float; 2]], x: Float, y: Float) -> bool {
+    let mut inside = false;
+    let mut j = vertices.len().wrapping_sub(1);
+    for (i, &[x_i, y_i]) in vertices.iter().enumerate() {
+        let [x_j, y_j] = vertices[j];
+        if (y_i > y) != (y_j > y) && x < (x_j - x_i) * (y - y_i) / (y_j - y_i) + x_i {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Distance from `(x, y)` along `(dx, dy)` to the unit circle, with the
+/// coordinates of an ellipse scaled to it; zero if `(x, y)` lies outside.
+fn ellipse_reach(x: Float, y: Float, dx: Float, dy: Float) -> Float {
+    // Larger root of |(x, y) + t (dx, dy)|² = 1
+    let a = dx * dx + dy * dy;
+    let b = x * dx + y * dy;
+    let c = x * x + y * y - 1.0;
+    if c > 0.0 {
+        return 0.0;
+    }
+    (-b + (b * b - a * c).sqrt()) / a
+}
+
+/// Distance from `p` along `d` to the nearer of the lines `±half_width`.
+fn slab_reach(p: Float, d: Float, half_width: Float) -> Float {
+    if d > 0.0 {
+        (half_width - p) / d
+    } else if d < 0.0 {
+        (-half_width - p) / d
+    } else {
+        Float::INFINITY
+    }
+}
+
+/// Distance from `(x, y)` along `(dx, dy)` to the first edge of a polygon;
+/// zero if `(x, y)` lies outside.
+fn polygon_reach(vertices: &[[Float; 2]], x: Float, y: Float, dx: Float, dy: Float) -> Float {
+    if !polygon_contains(vertices, x, y) {
+        return 0.0;
+    }
+    let cross = |ax: Float, ay: Float, bx: Float, by: Float| ax * by - ay * bx;
+    let mut j = vertices.len() - 1;
+    let mut nearest = Float::INFINITY;
+    for (i, &[x_i, y_i]) in vertices.iter().enumerate() {
+        let [x_j, y_j] = vertices[j];
+        j = i;
+        // Solve (x, y) + t (dx, dy) = (x_j, y_j) + s (e_x, e_y) for t and s.
+        let (e_x, e_y) = (x_i - x_j, y_i - y_j);
+        let denom = cross(dx, dy, e_x, e_y);
+        if denom == 0.0 {
+            continue;
+        }
+        let (w_x, w_y) = (x_j - x, y_j - y);
+        let t = cross(w_x, w_y, e_x, e_y) / denom;
+        let s = cross(w_x, w_y, dx, dy) / denom;
+        if t >= 0.0 && (0.0..=1.0).contains(&s) {
+            nearest = nearest.min(t);
+        }
+    }
+    nearest
+}
+
+#[cfg(test)]
+mod mask_tests {
+    use super::*;
+
+    fn at(x: Float, y: Float) -> Vec3 {
+        Vec3::new(x, y, 0.0)
+    }
+
+    #[test]
+    fn rectangular_and_elliptical_apertures() {
+        let rect = Mask::Rectangular {
+            half_width_x: 3.0,
+            half_width_y: 1.0,
+        };
+        assert!(!rect.outside_clear_aperture(at(2.9, -0.9)));
+        assert!(!rect.outside_clear_aperture(at(3.0, 1.0)));
+        assert!(rect.outside_clear_aperture(at(0.0, 1.1)));
+        assert_eq!(rect.semi_diameter(), Float::hypot(3.0, 1.0));
+        assert_eq!(rect.semi_extent(0.0, 1.0), 1.0);
+        assert_eq!(rect.semi_extent(-2.0, 0.0), 3.0);
+
+        let ellipse = Mask::Elliptical {
+            semi_axis_x: 3.0,
+            semi_axis_y: 1.0,
+        };
+        assert!(!ellipse.outside_clear_aperture(at(2.9, 0.0)));
+        assert!(ellipse.outside_clear_aperture(at(2.9, 0.9)));
+        assert_eq!(ellipse.semi_diameter(), 3.0);
+        assert_eq!(ellipse.semi_extent(0.0, 1.0), 1.0);
+        assert_eq!(ellipse.semi_extent(1.0, 0.0), 3.0);
+    }
+
+    #[test]
+    fn polygon_aperture_may_be_concave() {
+        // An L-shape missing its upper right quadrant.
+        let l_shape = Mask::Polygon {
+            vertices: vec![
+                [-1.0, -1.0],
+                [1.0, -1.0],
+                [1.0, 0.0],
+                [0.0, 0.0],
+                [0.0, 1.0],
+                [-1.0, 1.0],
+            ],
+        };
+        assert!(!l_shape.outside_clear_aperture(at(0.5, -0.5)));
+        assert!(!l_shape.outside_clear_aperture(at(-0.5, 0.5)));
+        assert!(l_shape.outside_clear_aperture(at(0.5, 0.5)));
+        assert!(l_shape.outside_clear_aperture(at(1.5, -0.5)));
+        assert_eq!(l_shape.semi_diameter(), Float::sqrt(2.0));
+    }
+
+    #[test]
+    fn decentered_aperture_moves_with_its_offset() {
+        let mask = Mask::Decentered {
+            offset_x: 0.0,
+            offset_y: 10.0,
+            mask: Box::new(Mask::Circular { semi_diameter: 2.0 }),
+        };
+        assert!(mask.outside_clear_aperture(at(0.0, 0.0)));
+        assert!(!mask.outside_clear_aperture(at(1.0, 11.0)));
+        assert_eq!(mask.semi_diameter(), 12.0);
+        assert_eq!(mask.semi_extent(0.0, 1.0), 0.0);
+
+        // The chord through the vertex is shortened on the side of the offset.
+        let mask = Mask::Decentered {
+            offset_x: 0.0,
+            offset_y: 1.0,
+            mask: Box::new(Mask::Circular { semi_diameter: 2.0 }),
+        };
+        assert_eq!(mask.semi_extent(0.0, 1.0), 1.0);
+        assert_eq!(mask.semi_extent(1.0, 0.0), Float::sqrt(3.0));
+    }
+
+    #[test]
+    fn polygon_chord_through_the_vertex() {
+        let square = Mask::Polygon {
+            vertices: vec![[-1.0, -2.0], [2.0, -2.0], [2.0, 2.0], [-1.0, 2.0]],
+        };
+        assert_eq!(square.semi_extent(1.0, 0.0), 1.0);
+        assert_eq!(square.semi_extent(0.0, 1.0), 2.0);
+        assert_eq!(square.semi_extent(1.0, 1.0), Float::sqrt(2.0));
+    }
+
+    #[test]
+    fn obscuration_and_spider_block_rays() {
+        let mask = Mask::Obscured {
+            aperture: Box::new(Mask::Circular {
+                semi_diameter: 10.0,
+            }),
+            obscuration: Some(Box::new(Mask::Circular { semi_diameter: 3.0 })),
+            spider: Some(Spider {
+                vanes: 3,
+                width: 0.5,
+                rotation: PI / 2.0,
+            }),
+        };
+        assert_eq!(mask.semi_diameter(), 10.0);
+
+        // Central obscuration
+        assert!(mask.outside_clear_aperture(at(1.0, 1.0)));
+        assert!(mask.outside_aperture_or_obscured(at(1.0, 1.0)));
+
+        // On the vane along +y, but not on the -y side where there is none.
+        assert!(mask.outside_clear_aperture(at(0.2, 6.0)));
+        assert!(!mask.outside_aperture_or_obscured(at(0.2, 6.0)));
+        assert!(!mask.outside_clear_aperture(at(0.2, -6.0)));
+
+        // On the vane at 210 degrees.
+        let (sin, cos) = (7.0 * PI / 6.0).sin_cos();
+        assert!(mask.outside_clear_aperture(at(6.0 * cos, 6.0 * sin)));
+
+        assert!(!mask.outside_clear_aperture(at(5.0, 0.0)));
+        assert!(mask.outside_clear_aperture(at(11.0, 0.0)));
+    }
+
+    #[test]
+    fn invalid_masks_are_errors() {
+        assert!(Mask::Circular { semi_diameter: 1.0 }.validate().is_ok());
+        assert!(
+            Mask::Rectangular {
+                half_width_x: 1.0,
+                half_width_y: 0.0,
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            Mask::Polygon {
+                vertices: vec![[0.0, 0.0], [1.0, 0.0]],
+            }
+            .validate()
+            .is_err()
+        );
+        assert!(
+            Mask::Obscured {
+                aperture: Box::new(Mask::Circular { semi_diameter: 1.0 }),
+                obscuration: None,
+                spider: Some(Spider {
+                    vanes: 4,
+                    width: Float::NAN,
+                    rotation: 0.0,
+                }),
+            }
+            .validate()
+            .is_err()
+        );
+    }
 }
 
 #[cfg(all(test, feature = "serde"))]
@@ -247,4 +1111,172 @@ mod tests {
             "default rotation_offset should be None"
         );
     }
+
+    #[test]
+    fn even_asphere_serde_round_trip_preserves_coefficients() {
+        let spec = SurfaceSpec::EvenAsphere {
+            semi_diameter: 8.0,
+            radius_of_curvature: 30.0,
+            conic_constant: -0.9,
+            aspheric_coefficients: vec![1.2e-5, -3.4e-8, 5.6e-11],
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+
+        let json = serde_json::to_string(&spec).expect("serialize");
+        let back: SurfaceSpec = serde_json::from_str(&json).expect("deserialize");
+
+        match back {
+            SurfaceSpec::EvenAsphere {
+                radius_of_curvature,
+                conic_constant,
+                aspheric_coefficients,
+                ..
+            } => {
+                assert_eq!(radius_of_curvature, 30.0);
+                assert_eq!(conic_constant, -0.9);
+                assert_eq!(aspheric_coefficients, vec![1.2e-5, -3.4e-8, 5.6e-11]);
+            }
+            other => panic!("unexpected variant: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn xy_polynomial_serde_round_trip_preserves_coefficients() {
+        let spec = SurfaceSpec::XYPolynomial {
+            semi_diameter: 8.0,
+            radius_of_curvature: 30.0,
+            conic_constant: -0.9,
+            normalization_radius: 8.0,
+            order: 2,
+            xy_coefficients: vec![0.0, 0.0, 1.5e-3, -2.0e-4, 3.0e-3],
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+
+        let json = serde_json::to_string(&spec).expect("serialize");
+        let back: SurfaceSpec = serde_json::from_str(&json).expect("deserialize");
+
+        match back {
+            SurfaceSpec::XYPolynomial {
+                normalization_radius,
+                order,
+                xy_coefficients,
+                ..
+            } => {
+                assert_eq!(normalization_radius, 8.0);
+                assert_eq!(order, 2);
+                assert_eq!(xy_coefficients, vec![0.0, 0.0, 1.5e-3, -2.0e-4, 3.0e-3]);
+            }
+            other => panic!("unexpected variant: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn grid_sag_serde_round_trip_embeds_grid() {
+        let grid = SagGrid::new(
+            3,
+            2,
+            5.0,
+            10.0,
+            vec![0.0, 1e-4, 2e-4, 3e-4, -1e-4, Float::NAN],
+        )
+        .unwrap();
+        let spec = SurfaceSpec::GridSag {
+            semi_diameter: 4.0,
+            radius_of_curvature: 30.0,
+            conic_constant: 0.0,
+            grid: grid.clone(),
+            surf_kind: BoundaryKind::Reflecting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+
+        let json = serde_json::to_string(&spec).expect("serialize");
+        let back: SurfaceSpec = serde_json::from_str(&json).expect("deserialize");
+
+        match back {
+            SurfaceSpec::GridSag { grid: back, .. } => {
+                assert_eq!((back.nx, back.ny, back.dx, back.dy), (3, 2, 5.0, 10.0));
+                assert_eq!(back.values[..5], grid.values[..5]);
+                assert!(back.values[5].is_nan());
+            }
+            other => panic!("unexpected variant: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn masked_surface_round_trips() {
+        let mask = Mask::Decentered {
+            offset_x: 1.0,
+            offset_y: -2.0,
+            mask: Box::new(Mask::Obscured {
+                aperture: Box::new(Mask::Polygon {
+                    vertices: vec![[0.0, 0.0], [4.0, 0.0], [0.0, 3.0]],
+                }),
+                obscuration: Some(Box::new(Mask::Elliptical {
+                    semi_axis_x: 0.5,
+                    semi_axis_y: 0.25,
+                })),
+                spider: Some(Spider {
+                    vanes: 4,
+                    width: 0.1,
+                    rotation: 0.3,
+                }),
+            }),
+        };
+        let spec = SurfaceSpec::Masked {
+            mask: mask.clone(),
+            surface: Box::new(SurfaceSpec::Sphere {
+                semi_diameter: 6.0,
+                radius_of_curvature: 25.0,
+                surf_kind: BoundaryKind::Reflecting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.5, 0.0),
+                rotation_offset: Rotation3D::None,
+            }),
+        };
+
+        let json = serde_json::to_string(&spec).expect("serialize");
+        let back: SurfaceSpec = serde_json::from_str(&json).expect("deserialize");
+
+        let SurfaceSpec::Masked {
+            mask: back_mask, ..
+        } = &back
+        else {
+            panic!("unexpected variant: {back:?}");
+        };
+        assert_eq!(back_mask, &mask);
+        assert_eq!(back.decenter(), Vec3::new(0.0, 0.5, 0.0));
+        assert!(matches!(back.unwrapped(), SurfaceSpec::Sphere { .. }));
+    }
+
+    #[test]
+    fn mounted_surface_flat_width_defaults_to_zero() {
+        let json = r#"{"Mounted": {
+            "mechanical_semi_diameter": 15.0,
+            "surface": {"Sphere": {
+                "semi_diameter": 12.5,
+                "radius_of_curvature": 50.0,
+                "surf_kind": "Refracting",
+                "rotation": "None"
+            }}
+        }}"#;
+        let spec: SurfaceSpec = serde_json::from_str(json).expect("deserialize");
+        let SurfaceSpec::Mounted {
+            mechanical_semi_diameter,
+            flat_width,
+            ..
+        } = &spec
+        else {
+            panic!("unexpected variant: {spec:?}");
+        };
+        assert_eq!((*mechanical_semi_diameter, *flat_width), (15.0, 0.0));
+        assert!(matches!(spec.unwrapped(), SurfaceSpec::Sphere { .. }));
+    }
 }
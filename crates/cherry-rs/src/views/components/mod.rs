@@ -35,11 +35,42 @@ pub enum Component {
     Mirror {
         surf_idx: usize,
     },
+    /// An ideal lens represented by a single surface, such as a paraxial or
+    /// cardinal lens.
+    ParaxialLens {
+        surf_idx: usize,
+    },
     UnpairedSurface {
         surf_idx: usize,
     },
 }
 
+impl Component {
+    /// Returns the indices of the surfaces that make up the component.
+    pub fn surface_indices(&self) -> &[usize] {
+        match self {
+            Component::Element { surf_idxs } => surf_idxs,
+            Component::Iris { stop_idx } => std::slice::from_ref(stop_idx),
+            Component::Mirror { surf_idx }
+            | Component::ParaxialLens { surf_idx }
+            | Component::UnpairedSurface { surf_idx } => std::slice::from_ref(surf_idx),
+        }
+    }
+
+    /// Returns the largest mechanical semi-diameter of the component's
+    /// surfaces, or `None` if none of them has a mechanical edge.
+    ///
+    /// The edge of a lens is a cylinder, so every surface of an element is
+    /// drawn out to this semi-diameter.
+    pub fn mechanical_semi_diameter(&self, sequential_model: &SequentialModel) -> Option<Float> {
+        let surfaces = sequential_model.surfaces();
+        self.surface_indices()
+            .iter()
+            .filter_map(|&i| surfaces.get(i)?.mechanical_semi_diameter())
+            .reduce(Float::max)
+    }
+}
+
 /// Determine the components of an optical system.
 ///
 /// Components are the basic building blocks of an optical system. They are
@@ -64,7 +95,11 @@ pub fn components_view(
         .copied()
         .unwrap_or(0.5876);
 
-    let background_ri = RefractiveIndex::try_from_spec(background.as_ref(), wavelength)?;
+    let background_ri = RefractiveIndex::try_from_spec(
+        background.as_ref(),
+        wavelength,
+        sequential_model.environment(),
+    )?;
 
     let sequential_sub_model = sequential_model
         .submodel(0)
@@ -76,8 +111,9 @@ pub fn components_view(
         return Ok(vec![]);
     }
 
-    // Collect non-element components (mirrors, irises) and track which surfaces
-    // are already claimed so we can detect unpaired surfaces later.
+    // Collect non-element components (mirrors, irises, paraxial lenses) and track
+    // which surfaces are already claimed so we can detect unpaired surfaces
+    // later.
     let mut non_elements: Vec<Component> = Vec::new();
     let mut claimed: HashSet<usize> = HashSet::new();
 
@@ -89,6 +125,9 @@ pub fn components_view(
         } else if kind == SurfaceKind::Iris {
             non_elements.push(Component::Iris { stop_idx: i });
             claimed.insert(i);
+        } else if surface.paraxial_focal_length().is_some() {
+            non_elements.push(Component::ParaxialLens { surf_idx: i });
+            claimed.insert(i);
         }
     }
 
@@ -203,6 +242,7 @@ pub fn components_view(
         Component::Element { surf_idxs } => *surf_idxs.first().unwrap_or(&usize::MAX),
         Component::Iris { stop_idx } => *stop_idx,
         Component::Mirror { surf_idx } => *surf_idx,
+        Component::ParaxialLens { surf_idx } => *surf_idx,
         Component::UnpairedSurface { surf_idx } => *surf_idx,
     });
     Ok(result)
@@ -715,6 +755,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_element_mechanical_semi_diameter_is_largest_of_its_surfaces() {
+        let air = n!(1.0);
+        let conic = |roc: Float| SurfaceSpec::Conic {
+            semi_diameter: 12.5,
+            radius_of_curvature: roc,
+            conic_constant: 0.0,
+            surf_kind: crate::BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+        let mounted =
+            |mechanical_semi_diameter: Float, surface: SurfaceSpec| SurfaceSpec::Mounted {
+                mechanical_semi_diameter,
+                flat_width: 0.0,
+                surface: Box::new(surface),
+            };
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            mounted(14.0, conic(50.0)),
+            conic(-30.0),
+            mounted(13.5, conic(-100.0)),
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 6.0,
+                refractive_index: n!(1.515),
+            },
+            GapSpec {
+                thickness: 3.0,
+                refractive_index: n!(1.648),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air.clone(),
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let components = components_view(&model, air.clone()).unwrap();
+
+        assert_eq!(components[0].surface_indices(), &[1, 2, 3]);
+        assert_eq!(components[0].mechanical_semi_diameter(&model), Some(14.0));
+
+        let doublet = cemented_doublet();
+        let components = components_view(&doublet, air).unwrap();
+        assert_eq!(components[0].mechanical_semi_diameter(&doublet), None);
+    }
+
     pub fn singlet_with_probe() -> SequentialModel {
         // Singlet with a probe surface inside the glass: Object[0], Conic[1],
         // Probe[2] (inside glass), Conic[3], Image[4]. The probe must not split
@@ -857,4 +955,62 @@ mod tests {
         assert!(components.contains(&Component::UnpairedSurface { surf_idx: 1 }));
         assert!(components.contains(&Component::UnpairedSurface { surf_idx: 3 }));
     }
+
+    pub fn biconic_lens_and_mirror() -> SequentialModel {
+        let air = n!(1.0);
+        let glass = n!(1.515);
+        let biconic = |r_x: Float, r_y: Float, surf_kind| SurfaceSpec::Biconic {
+            semi_diameter: 12.5,
+            radius_of_curvature_x: r_x,
+            radius_of_curvature_y: r_y,
+            conic_constant_x: -0.5,
+            conic_constant_y: 0.0,
+            surf_kind,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            biconic(40.0, 60.0, crate::BoundaryKind::Refracting),
+            biconic(-80.0, Float::INFINITY, crate::BoundaryKind::Refracting),
+            biconic(-150.0, -200.0, crate::BoundaryKind::Reflecting),
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 5.0,
+                refractive_index: glass,
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air,
+            },
+        ];
+        SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap()
+    }
+
+    #[test]
+    fn test_biconic_lens_and_mirror() {
+        let model = biconic_lens_and_mirror();
+        let components = components_view(&model, n!(1.0)).unwrap();
+
+        assert_eq!(components.len(), 2);
+        assert!(components.contains(&Component::Element {
+            surf_idxs: vec![1, 2]
+        }));
+        assert!(components.contains(&Component::Mirror { surf_idx: 3 }));
+    }
 }
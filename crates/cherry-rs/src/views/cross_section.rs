@@ -3,6 +3,7 @@
 use crate::{
     SequentialModel, SurfaceKind,
     core::{Float, math::vec3::Vec3, sequential_model::placement::Placement, surfaces::Surface},
+    specs::surfaces::Mask,
     views::{components::Component, ray_trace_3d::RayBundle},
 };
 
@@ -14,6 +15,14 @@ pub enum GlobalAxis {
 }
 
 const N_PTS: usize = 64;
+/// Distance either side of a profile breakpoint at which it is sampled, as a
+/// fraction of the length of the surface's cut line.
+const BREAKPOINT_OFFSET: Float = 1e-9;
+/// Number of intervals in which a non-circular mask is tested when finding
+/// where a cut line crosses its edges.
+const N_MASK_SCAN: usize = 1024;
+/// Number of bisections that refine each crossing of a mask edge.
+const MASK_BISECTIONS: usize = 50;
 const EPS: f64 = 1e-6;
 
 /// The complete 2D cross-section view of a sequential optical system.
@@ -66,6 +75,16 @@ pub enum DrawElement {
         half_gap: f64,
         extent: f64,
     },
+    /// An ideal thin lens, drawn as a line across its clear aperture with
+    /// arrowheads that point outwards for a converging lens and inwards for a
+    /// diverging one.
+    ThinLens {
+        /// First endpoint in (z, transverse) plot space.
+        p1: [f64; 2],
+        /// Second endpoint in (z, transverse) plot space.
+        p2: [f64; 2],
+        converging: bool,
+    },
     FlatPlane {
         /// First endpoint in (z, transverse) plot space.
         p1: [f64; 2],
@@ -148,8 +167,30 @@ fn build_plane_geometry(
             Component::Element { surf_idxs } => {
                 let i = surf_idxs.first().copied().unwrap_or(0);
                 let j = surf_idxs.last().copied().unwrap_or(0);
-                let front_pts = sample_surface(surfaces[i].as_ref(), &placements[i], axis, N_PTS);
-                let back_pts = sample_surface(surfaces[j].as_ref(), &placements[j], axis, N_PTS);
+                let (front, back) = (surfaces[i].as_ref(), surfaces[j].as_ref());
+                let (Some(front_cut), Some(back_cut)) = (
+                    cut_line(front, &placements[i], axis),
+                    cut_line(back, &placements[j], axis),
+                ) else {
+                    continue;
+                };
+                // A lens with a mechanical edge is drawn out to it as a
+                // cylinder, whatever the clear apertures of its surfaces.
+                let (front_cut, back_cut) = match comp.mechanical_semi_diameter(model) {
+                    Some(mechanical) => {
+                        let edge = mechanical
+                            .max(front_cut.clear_extent())
+                            .max(back_cut.clear_extent());
+                        (
+                            front_cut.extended_to(front, edge),
+                            back_cut.extended_to(back, edge),
+                        )
+                    }
+                    None => (front_cut, back_cut),
+                };
+                let params = sample_params(&[(front, &front_cut), (back, &back_cut)]);
+                let front_pts = sample_surface_at(front, &placements[i], axis, &front_cut, &params);
+                let back_pts = sample_surface_at(back, &placements[j], axis, &back_cut, &params);
                 if !front_pts.is_empty() && !back_pts.is_empty() {
                     elements.push(DrawElement::LensGroup {
                         front_pts,
@@ -158,11 +199,24 @@ fn build_plane_geometry(
                 }
             }
             Component::Iris { stop_idx } => {
+                let surf = surfaces[*stop_idx].as_ref();
                 let placement = &placements[*stop_idx];
-                let center_z = placement.position.z();
+                // The opening spans the cut line, which need not be centered
+                // on the vertex.
+                let (center, half_gap) = match cut_line(surf, placement, axis) {
+                    Some(cut) => {
+                        let mid = cut.local_point(0.5 * (cut.start + cut.end));
+                        (
+                            placement.inv_rotation_matrix * mid + placement.position,
+                            0.5 * (cut.end - cut.start),
+                        )
+                    }
+                    None => (placement.position, surf.mask().semi_diameter()),
+                };
+                let center_z = center.z();
                 let center_t = match axis {
-                    GlobalAxis::Y => placement.position.y(),
-                    GlobalAxis::X => placement.position.x(),
+                    GlobalAxis::Y => center.y(),
+                    GlobalAxis::X => center.x(),
                 };
                 let fwd = placement.inv_rotation_matrix * Vec3::new(0.0, 0.0, 1.0);
                 let fwd_z = fwd.z();
@@ -170,36 +224,52 @@ fn build_plane_geometry(
                     GlobalAxis::Y => fwd.y(),
                     GlobalAxis::X => fwd.x(),
                 };
-                let sd = surfaces[*stop_idx].mask().semi_diameter();
                 elements.push(DrawElement::Iris {
                     center_z,
                     center_t,
                     fwd_z,
                     fwd_t,
-                    half_gap: sd,
+                    half_gap,
                     extent: largest_sd * 1.5,
                 });
             }
-            Component::Mirror { surf_idx } => {
-                let pts = sample_surface(
-                    surfaces[*surf_idx].as_ref(),
-                    &placements[*surf_idx],
-                    axis,
-                    N_PTS,
-                );
-                if !pts.is_empty() {
-                    elements.push(DrawElement::SurfaceProfile { points: pts });
+            Component::Mirror { surf_idx } | Component::UnpairedSurface { surf_idx } => {
+                let surf = surfaces[*surf_idx].as_ref();
+                let placement = &placements[*surf_idx];
+                let Some(cut) = cut_line(surf, placement, axis) else {
+                    continue;
+                };
+                let cut = match comp.mechanical_semi_diameter(model) {
+                    Some(mechanical) => {
+                        let edge = mechanical.max(cut.clear_extent());
+                        cut.extended_to(surf, edge)
+                    }
+                    None => cut,
+                };
+                let params = sample_params(&[(surf, &cut)]);
+                for points in sample_clear_runs(surf, placement, axis, &cut, &params) {
+                    elements.push(DrawElement::SurfaceProfile { points });
                 }
             }
-            Component::UnpairedSurface { surf_idx } => {
-                let pts = sample_surface(
-                    surfaces[*surf_idx].as_ref(),
-                    &placements[*surf_idx],
-                    axis,
-                    N_PTS,
-                );
-                if !pts.is_empty() {
-                    elements.push(DrawElement::SurfaceProfile { points: pts });
+            Component::ParaxialLens { surf_idx } => {
+                let surf = surfaces[*surf_idx].as_ref();
+                let placement = &placements[*surf_idx];
+                let converging = surf.paraxial_focal_length().is_some_and(|f| f > 0.0);
+                let pts = sample_surface(surf, placement, axis, 2);
+                if let [p1, p2] = pts[..] {
+                    elements.push(DrawElement::ThinLens { p1, p2, converging });
+
+                    // A cardinal lens also gets a marker at its back principal
+                    // plane.
+                    let d = surf.axial_displacement();
+                    if d != 0.0 {
+                        let [dz, dt] = axial_offset(placement, axis, d);
+                        elements.push(DrawElement::ThinLens {
+                            p1: [p1[0] + dz, p1[1] + dt],
+                            p2: [p2[0] + dz, p2[1] + dt],
+                            converging,
+                        });
+                    }
                 }
             }
         }
@@ -283,6 +353,15 @@ fn build_plane_geometry(
                             GlobalAxis::Y => ray.y(),
                             GlobalAxis::X => ray.x(),
                         };
+
+                        // Rays are recorded after they leave a surface. For a
+                        // surface that displaces rays, also record where they
+                        // arrived.
+                        let d = surfaces[surf_idx].axial_displacement();
+                        if d != 0.0 {
+                            let [dz, dt] = axial_offset(&placements[surf_idx], axis, d);
+                            path.push([ray.z() - dz, transverse - dt]);
+                        }
                         path.push([ray.z(), transverse]);
                     }
                 }
@@ -302,44 +381,315 @@ fn build_plane_geometry(
     }
 }
 
-/// Sample points on a surface in the cutting plane.
+/// Sample points on a surface in the cutting plane at `n_pts` evenly spaced
+/// positions.
 ///
-/// For axis = Y: samples at (0, y, 0) for y in [-sd, sd], returns global (z, y)
-/// pairs. For axis = X: samples at (x, 0, 0) for x in [-sd, sd], returns global
-/// (z, x) pairs.
+/// See [`sample_surface_at`].
 fn sample_surface(
     surf: &dyn Surface,
     placement: &Placement,
     axis: GlobalAxis,
     n_pts: usize,
 ) -> Vec<[f64; 2]> {
-    let sd = surf.mask().semi_diameter();
-    if !sd.is_finite() || sd <= 0.0 || n_pts < 2 {
+    let Some(cut) = cut_line(surf, placement, axis) else {
+        return Vec::new();
+    };
+    if n_pts < 2 {
         return Vec::new();
     }
+    let params: Vec<Float> = (0..n_pts)
+        .map(|i| i as Float / (n_pts - 1) as Float)
+        .collect();
+    sample_surface_at(surf, placement, axis, &cut, &params)
+}
 
-    let mut pts = Vec::with_capacity(n_pts);
-    for i in 0..n_pts {
-        let t = i as Float / (n_pts - 1) as Float; // 0.0 to 1.0
-        let transverse = sd * (2.0 * t - 1.0); // -sd to +sd
-        let local_pt = match axis {
-            GlobalAxis::Y => Vec3::new(0.0, transverse, 0.0),
-            GlobalAxis::X => Vec3::new(transverse, 0.0, 0.0),
-        };
-        let sag = surf.sag(local_pt);
-        let local_surface_pt = match axis {
-            GlobalAxis::Y => Vec3::new(0.0, transverse, sag),
-            GlobalAxis::X => Vec3::new(transverse, 0.0, sag),
-        };
-        // Transform to global coordinates.
-        let global_pt = placement.inv_rotation_matrix * local_surface_pt + placement.position;
-        let transverse_global = match axis {
-            GlobalAxis::Y => global_pt.y(),
-            GlobalAxis::X => global_pt.x(),
-        };
-        pts.push([global_pt.z(), transverse_global]);
+/// The line along which the cutting plane meets a surface, in the surface's
+/// local xy-plane.
+///
+/// The line passes through the vertex along the local direction whose global
+/// image lies in the cutting plane, so that surfaces without rotational
+/// symmetry (e.g. Zernike sag) show their true profile in each plane. For an
+/// untilted surface this is the local y-axis for axis = Y and the local x-axis
+/// for axis = X.
+struct CutLine {
+    dir_x: Float,
+    dir_y: Float,
+    /// Signed distances from the vertex of the first and last points drawn.
+    start: Float,
+    end: Float,
+    /// Signed distances from the vertex of the first and last points of the
+    /// line inside the clear aperture.
+    clear_start: Float,
+    clear_end: Float,
+    /// Distances at which the line enters or leaves an obscuration.
+    edges: Vec<Float>,
+    /// Distance from the vertex beyond which the surface is drawn flat.
+    flat_from: Option<Float>,
+}
+
+impl CutLine {
+    /// Maps a parameter from 0.0 at `start` to 1.0 at `end` to a distance
+    /// along the line.
+    fn position(&self, t: Float) -> Float {
+        self.start + t * (self.end - self.start)
+    }
+
+    fn param(&self, s: Float) -> Float {
+        (s - self.start) / (self.end - self.start)
+    }
+
+    fn local_point(&self, s: Float) -> Vec3 {
+        Vec3::new(s * self.dir_x, s * self.dir_y, 0.0)
+    }
+
+    /// Returns the point at which the drawn relief is evaluated for a
+    /// distance along the line, which lies on the flat at the edge of the
+    /// surface if there is one.
+    fn relief_point(&self, s: Float) -> Vec3 {
+        match self.flat_from {
+            Some(r) if s.abs() > r => self.local_point(r.copysign(s)),
+            _ => self.local_point(s),
+        }
+    }
+
+    /// Returns the largest distance from the vertex of a point in the clear
+    /// aperture.
+    fn clear_extent(&self) -> Float {
+        self.clear_start.abs().max(self.clear_end.abs())
+    }
+
+    /// Extends the line to a mechanical edge `edge` from the vertex on both
+    /// sides, starting the surface's flat if it has one.
+    fn extended_to(self, surf: &dyn Surface, edge: Float) -> Self {
+        let flat_from = surf
+            .mechanical_semi_diameter()
+            .filter(|_| surf.flat_width() > 0.0)
+            .map(|r| r - surf.flat_width());
+        Self {
+            start: -edge,
+            end: edge,
+            flat_from,
+            ..self
+        }
+    }
+}
+
+/// Returns the line along which a surface is drawn, or `None` if the surface
+/// is unbounded or the line misses its clear aperture.
+///
+/// The ends of the line are exact for circular masks. For other masks they,
+/// and the edges of any obscuration, are found by scanning the mask and
+/// bisecting between samples. Spider vanes are ignored, because a vane along
+/// the line would otherwise hide the whole surface.
+fn cut_line(surf: &dyn Surface, placement: &Placement, axis: GlobalAxis) -> Option<CutLine> {
+    let mask = surf.mask();
+    let sd = mask.semi_diameter();
+    if !sd.is_finite() || sd <= 0.0 {
+        return None;
+    }
+
+    // The global transverse axis expressed in the surface's local frame,
+    // projected onto the local xy-plane.
+    let global_axis = match axis {
+        GlobalAxis::Y => Vec3::new(0.0, 1.0, 0.0),
+        GlobalAxis::X => Vec3::new(1.0, 0.0, 0.0),
+    };
+    let local_axis = placement.rotation_matrix * global_axis;
+    let len = (local_axis.x().powi(2) + local_axis.y().powi(2)).sqrt();
+    let (dir_x, dir_y) = if len > EPS {
+        (local_axis.x() / len, local_axis.y() / len)
+    } else {
+        match axis {
+            GlobalAxis::Y => (0.0, 1.0),
+            GlobalAxis::X => (1.0, 0.0),
+        }
+    };
+    if matches!(mask, Mask::Circular { .. }) {
+        return Some(CutLine {
+            dir_x,
+            dir_y,
+            start: -sd,
+            end: sd,
+            clear_start: -sd,
+            clear_end: sd,
+            edges: Vec::new(),
+            flat_from: None,
+        });
+    }
+
+    let blocked =
+        |s: Float| mask.outside_aperture_or_obscured(Vec3::new(s * dir_x, s * dir_y, 0.0));
+    let samples: Vec<(Float, bool)> = (0..=N_MASK_SCAN)
+        .map(|k| {
+            let s = sd * (2.0 * k as Float / N_MASK_SCAN as Float - 1.0);
+            (s, blocked(s))
+        })
+        .collect();
+
+    if samples.iter().all(|&(_, blocked)| blocked) {
+        return None;
+    }
+    let mut crossings = Vec::new();
+    for pair in samples.windows(2) {
+        let [(s_0, blocked_0), (s_1, blocked_1)] = [pair[0], pair[1]];
+        if blocked_0 == blocked_1 {
+            continue;
+        }
+        // Bisect towards the edge, keeping the clear side.
+        let (mut clear, mut dark) = if blocked_0 { (s_1, s_0) } else { (s_0, s_1) };
+        for _ in 0..MASK_BISECTIONS {
+            let mid = 0.5 * (clear + dark);
+            if blocked(mid) {
+                dark = mid;
+            } else {
+                clear = mid;
+            }
+        }
+        crossings.push(clear);
+    }
+
+    let start = if samples[0].1 {
+        crossings.remove(0)
+    } else {
+        -sd
+    };
+    let end = if samples[N_MASK_SCAN].1 {
+        crossings.pop()?
+    } else {
+        sd
+    };
+    (end > start).then_some(CutLine {
+        dir_x,
+        dir_y,
+        start,
+        end,
+        clear_start: start,
+        clear_end: end,
+        edges: crossings,
+        flat_from: None,
+    })
+}
+
+/// Sample points on a surface along its cut line.
+///
+/// Each of `params` from 0.0 to 1.0 maps to a point from the start to the end
+/// of `cut`. Obscurations are ignored. Returns global (z, transverse) pairs.
+fn sample_surface_at(
+    surf: &dyn Surface,
+    placement: &Placement,
+    axis: GlobalAxis,
+    cut: &CutLine,
+    params: &[Float],
+) -> Vec<[f64; 2]> {
+    if params.len() < 2 {
+        return Vec::new();
+    }
+    params
+        .iter()
+        .filter_map(|&t| profile_point(surf, placement, axis, cut, cut.position(t)))
+        .collect()
+}
+
+/// Sample points on a surface along its cut line, split into the runs that
+/// lie between obscurations.
+///
+/// Points outside the clear aperture, between it and the mechanical edge, are
+/// never obscured.
+///
+/// See [`sample_surface_at`].
+fn sample_clear_runs(
+    surf: &dyn Surface,
+    placement: &Placement,
+    axis: GlobalAxis,
+    cut: &CutLine,
+    params: &[Float],
+) -> Vec<Vec<[f64; 2]>> {
+    let mut runs = Vec::new();
+    let mut run = Vec::new();
+    for &t in params {
+        let s = cut.position(t);
+        if (cut.clear_start..=cut.clear_end).contains(&s)
+            && surf.mask().outside_aperture_or_obscured(cut.local_point(s))
+        {
+            runs.push(std::mem::take(&mut run));
+        } else if let Some(pt) = profile_point(surf, placement, axis, cut, s) {
+            run.push(pt);
+        }
+    }
+    runs.push(run);
+    runs.retain(|run| run.len() >= 2);
+    runs
+}
+
+/// Returns the global (z, transverse) position of the drawn relief at a
+/// distance `s` along a surface's cut line, or `None` outside the domain of
+/// the surface (e.g. a steep conic base).
+fn profile_point(
+    surf: &dyn Surface,
+    placement: &Placement,
+    axis: GlobalAxis,
+    cut: &CutLine,
+    s: Float,
+) -> Option<[f64; 2]> {
+    let local_pt = cut.local_point(s);
+    let sag = surf.profile_sag(cut.relief_point(s));
+    if !sag.is_finite() {
+        return None;
+    }
+    let local_surface_pt = Vec3::new(local_pt.x(), local_pt.y(), sag);
+    // Transform to global coordinates.
+    let global_pt = placement.inv_rotation_matrix * local_surface_pt + placement.position;
+    let transverse_global = match axis {
+        GlobalAxis::Y => global_pt.y(),
+        GlobalAxis::X => global_pt.x(),
+    };
+    Some([global_pt.z(), transverse_global])
+}
+
+/// Returns the positions, from 0.0 at the start to 1.0 at the end of each
+/// surface's cut line, at which to sample a group of surfaces that are drawn
+/// together.
+///
+/// The positions are [`N_PTS`] evenly spaced samples plus samples just either
+/// side of every profile breakpoint, obscuration edge and start of a flat of
+/// every surface, so that kinks, steps and holes are drawn sharply. All
+/// surfaces in the group share the positions so that their points pair up.
+fn sample_params(cuts: &[(&dyn Surface, &CutLine)]) -> Vec<Float> {
+    let mut params: Vec<Float> = (0..N_PTS)
+        .map(|i| i as Float / (N_PTS - 1) as Float)
+        .collect();
+    for (surf, cut) in cuts {
+        let breakpoints = surf
+            .profile_breakpoints()
+            .into_iter()
+            .flat_map(|r| [-r.abs(), r.abs()]);
+        let flat = cut.flat_from.into_iter().flat_map(|r| [-r, r]);
+        for s in breakpoints.chain(cut.edges.iter().copied()).chain(flat) {
+            let t = cut.param(s);
+            if s == 0.0 {
+                if (0.0..=1.0).contains(&t) {
+                    params.push(t);
+                }
+            } else if t - BREAKPOINT_OFFSET > 0.0 && t + BREAKPOINT_OFFSET < 1.0 {
+                params.push(t - BREAKPOINT_OFFSET);
+                params.push(t + BREAKPOINT_OFFSET);
+            }
+        }
     }
-    pts
+    params.sort_by(|a, b| a.total_cmp(b));
+    params.dedup();
+    params
+}
+
+/// Returns the (z, transverse) offset of a distance `d` along a surface's
+/// local z-axis.
+fn axial_offset(placement: &Placement, axis: GlobalAxis, d: Float) -> [f64; 2] {
+    let fwd = placement.inv_rotation_matrix * Vec3::new(0.0, 0.0, d);
+    let transverse = match axis {
+        GlobalAxis::Y => fwd.y(),
+        GlobalAxis::X => fwd.x(),
+    };
+    [fwd.z(), transverse]
 }
 
 /// Compute the bounding box over all elements and ray paths.
@@ -404,7 +754,7 @@ fn compute_bounds(elements: &[DrawElement], ray_paths: &[Vec<Vec<[f64; 2]>>]) ->
                     &mut t_max,
                 );
             }
-            DrawElement::FlatPlane { p1, p2, .. } => {
+            DrawElement::ThinLens { p1, p2, .. } | DrawElement::FlatPlane { p1, p2, .. } => {
                 update(p1[0], p1[1], &mut z_min, &mut z_max, &mut t_min, &mut t_max);
                 update(p2[0], p2[1], &mut z_min, &mut z_max, &mut t_min, &mut t_max);
             }
@@ -439,7 +789,7 @@ fn compute_bounds(elements: &[DrawElement], ray_paths: &[Vec<Vec<[f64; 2]>>]) ->
 mod tests {
     use super::*;
     use crate::{
-        BoundaryKind, EulerAngles, GapSpec, Rotation3D, SequentialModel, SurfaceSpec, Vec3,
+        BoundaryKind, EulerAngles, GapSpec, Rotation3D, SequentialModel, Spider, SurfaceSpec, Vec3,
         core::Float, examples::convexplano_lens, n, views::components::components_view,
     };
 
@@ -649,67 +999,196 @@ mod tests {
     }
 
     #[test]
-    fn test_f_theta_three_lens_groups() {
-        use crate::examples::f_theta_scan_lens;
-        let air = n!(1.00029); // simulate Ciddor air
-        let glass = n!(1.847); // simulate N-SF57
-        let model = f_theta_scan_lens::sequential_model(air.clone(), glass, &[0.5876]);
+    fn grating_disperses_ray_paths_by_wavelength() {
+        use crate::{
+            ApertureSpec, FieldSpec, ParaxialView, specs::fields::PupilSampling,
+            views::ray_trace_3d::trace_ray_bundle,
+        };
+        // Object → 300 lines/mm transmission grating → image 100 mm behind it.
+        let air = n!(1.0);
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 100.0,
+                refractive_index: air.clone(),
+            },
+        ];
+        let surfs = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Grating {
+                semi_diameter: 10.0,
+                radius_of_curvature: Float::INFINITY,
+                line_density: 300.0,
+                line_orientation: 0.0,
+                diffraction_order: 1,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let wavelengths: [Float; 3] = [0.45, 0.55, 0.65];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfs, &wavelengths, None)
+            .expect("build model");
+        let fields = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let aperture = ApertureSpec::EntrancePupil { semi_diameter: 5.0 };
+        let pv = ParaxialView::new(&model, &fields, false).unwrap();
+        let rays = trace_ray_bundle(
+            &aperture,
+            &fields,
+            &model,
+            &pv,
+            PupilSampling::TangentialRayFan { n: 3 },
+        )
+        .unwrap();
         let components = components_view(&model, air).unwrap();
-        let cs = cross_section_view(&model, None, &components);
-        let n_groups = cs
-            .yz
-            .elements
-            .iter()
-            .filter(|e| matches!(e, DrawElement::LensGroup { .. }))
-            .count();
-        assert_eq!(n_groups, 3, "expected 3 separate lens groups");
+        let cs = cross_section_view(&model, Some(&rays), &components);
+
+        // Each wavelength leaves the grating at sin θ = λ ν and is displaced
+        // along y at the image by 100 tan θ.
+        for (wl_idx, paths) in cs.yz.ray_paths.iter().enumerate() {
+            let sin_theta = wavelengths[wl_idx] * 300.0 * 1e-3;
+            let shift = 100.0 * sin_theta / (1.0 - sin_theta * sin_theta).sqrt();
+            assert_eq!(paths.len(), 3);
+            for path in paths {
+                let start = path[path.len() - 2];
+                let end = path[path.len() - 1];
+                assert!(
+                    (end[1] - start[1] - shift).abs() < 1e-9,
+                    "wavelength {wl_idx}: expected shift {shift}, got {}",
+                    end[1] - start[1]
+                );
+            }
+        }
     }
 
     #[test]
-    fn iris_in_folded_system_has_correct_forward_direction() {
-        // Regression: iris after a 45° fold mirror was drawn with wrong orientation.
-        // Build: Object → Sphere(refracting) → Mirror(theta=45°) → Iris → Image
+    fn paraxial_lens_is_drawn_as_thin_lens_marker() {
+        use crate::{
+            ApertureSpec, FieldSpec, ParaxialView, specs::fields::PupilSampling,
+            views::ray_trace_3d::trace_ray_bundle,
+        };
+        // Object at infinity → f = 50 mm paraxial lens → image at its focus.
         let air = n!(1.0);
         let gaps = vec![
             GapSpec {
-                thickness: 100.0,
+                thickness: Float::INFINITY,
                 refractive_index: air.clone(),
             },
             GapSpec {
                 thickness: 50.0,
                 refractive_index: air.clone(),
             },
+        ];
+        let surfs = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::ParaxialLens {
+                semi_diameter: 10.0,
+                focal_length: 50.0,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfs, &[0.5876], None)
+            .expect("build model");
+        let fields = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let aperture = ApertureSpec::EntrancePupil { semi_diameter: 8.0 };
+        let pv = ParaxialView::new(&model, &fields, false).unwrap();
+        let rays = trace_ray_bundle(
+            &aperture,
+            &fields,
+            &model,
+            &pv,
+            PupilSampling::TangentialRayFan { n: 5 },
+        )
+        .unwrap();
+        let components = components_view(&model, air).unwrap();
+        assert_eq!(components, vec![Component::ParaxialLens { surf_idx: 1 }]);
+
+        let cs = cross_section_view(&model, Some(&rays), &components);
+        let markers: Vec<_> = cs
+            .yz
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                DrawElement::ThinLens {
+                    p1, p2, converging, ..
+                } => Some((*p1, *p2, *converging)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(markers.len(), 1);
+        let (p1, p2, converging) = markers[0];
+        assert!(converging);
+        assert!((p1[0]).abs() < EPS && (p2[0]).abs() < EPS);
+        assert!((p1[1] + 10.0).abs() < EPS && (p2[1] - 10.0).abs() < EPS);
+
+        // Every ray of the collimated fan lands on the axis at the focus.
+        let paths = &cs.yz.ray_paths[0];
+        assert_eq!(paths.len(), 5);
+        for path in paths {
+            let end = path[path.len() - 1];
+            assert!((end[1]).abs() < 1e-9, "ray lands at {}", end[1]);
+        }
+    }
+
+    #[test]
+    fn cardinal_lens_objective_and_tube_lens_image_the_object() {
+        use crate::{
+            ApertureSpec, FieldSpec, ParaxialView, specs::fields::PupilSampling,
+            views::ray_trace_3d::trace_ray_bundle,
+        };
+        // A 20x infinity-corrected objective (f = 9 mm, H' 15 mm before H)
+        // with the object at its front focal plane, followed by a 180 mm tube
+        // lens 50 mm after H'.
+        let air = n!(1.0);
+        let gaps = vec![
             GapSpec {
-                thickness: 25.0,
+                thickness: 9.0,
                 refractive_index: air.clone(),
             },
             GapSpec {
                 thickness: 50.0,
                 refractive_index: air.clone(),
             },
+            GapSpec {
+                thickness: 180.0,
+                refractive_index: air.clone(),
+            },
         ];
-        let mirror_rotation =
-            Rotation3D::IntrinsicPassiveRUF(EulerAngles(45.0_f64.to_radians(), 0.0, 0.0));
         let surfs = vec![
             SurfaceSpec::Object,
-            SurfaceSpec::Sphere {
-                semi_diameter: 12.7,
-                radius_of_curvature: 102.4,
-                surf_kind: BoundaryKind::Refracting,
+            SurfaceSpec::CardinalLens {
+                semi_diameter: 5.0,
+                focal_length: 9.0,
+                principal_plane_separation: -15.0,
                 rotation: Rotation3D::None,
                 decenter: Vec3::new(0.0, 0.0, 0.0),
                 rotation_offset: Rotation3D::None,
             },
-            SurfaceSpec::Sphere {
-                semi_diameter: 10.0,
-                radius_of_curvature: Float::INFINITY,
-                surf_kind: BoundaryKind::Reflecting,
-                rotation: mirror_rotation,
-                decenter: Vec3::new(0.0, 0.0, 0.0),
-                rotation_offset: Rotation3D::None,
-            },
-            SurfaceSpec::Iris {
-                semi_diameter: 7.1,
+            SurfaceSpec::ParaxialLens {
+                semi_diameter: 15.0,
+                focal_length: 180.0,
                 rotation: Rotation3D::None,
                 decenter: Vec3::new(0.0, 0.0, 0.0),
                 rotation_offset: Rotation3D::None,
@@ -721,24 +1200,712 @@ mod tests {
             },
         ];
         let model = SequentialModel::from_surface_specs(&gaps, &surfs, &[0.5876], None)
-            .expect("build folded model");
+            .expect("build model");
+        let fields = vec![FieldSpec::PointSource { x: 0.0, y: 0.1 }];
+        let aperture = ApertureSpec::EntrancePupil { semi_diameter: 2.0 };
+        let pv = ParaxialView::new(&model, &fields, false).unwrap();
+        let rays = trace_ray_bundle(
+            &aperture,
+            &fields,
+            &model,
+            &pv,
+            PupilSampling::TangentialRayFan { n: 5 },
+        )
+        .unwrap();
         let components = components_view(&model, air).unwrap();
-        let cs = cross_section_view(&model, None, &components);
-
-        // After the 45° fold the optical axis points along Y (transverse), not Z.
-        // The iris DrawElement must therefore have fwd_z ≈ 0 and fwd_t ≈ ±1.
-        let iris = cs.yz.elements.iter().find_map(|e| match e {
-            DrawElement::Iris { fwd_z, fwd_t, .. } => Some((*fwd_z, *fwd_t)),
-            _ => None,
-        });
-        let (fwd_z, fwd_t) = iris.expect("iris DrawElement not found in YZ plane");
-        assert!(
-            fwd_z.abs() < 0.01,
-            "after fold, iris fwd_z should be ~0, got {fwd_z}"
+        assert_eq!(
+            components,
+            vec![
+                Component::ParaxialLens { surf_idx: 1 },
+                Component::ParaxialLens { surf_idx: 2 },
+            ]
         );
-        assert!(
-            fwd_t.abs() > 0.99,
-            "after fold, iris fwd_t should be ~±1, got {fwd_t}"
+
+        let cs = cross_section_view(&model, Some(&rays), &components);
+
+        // Markers at H, H' and the tube lens.
+        let marker_z: Vec<f64> = cs
+            .yz
+            .elements
+            .iter()
+            .filter_map(|e| match e {
+                DrawElement::ThinLens { p1, .. } => Some(p1[0]),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(marker_z.len(), 3);
+        assert!((marker_z[0]).abs() < EPS);
+        assert!((marker_z[1] + 15.0).abs() < EPS);
+        assert!((marker_z[2] - 35.0).abs() < EPS);
+
+        // Every ray jumps from H to H' and lands on the 20x magnified image.
+        let paths = &cs.yz.ray_paths[0];
+        assert_eq!(paths.len(), 5);
+        for path in paths {
+            // object, H, H', tube lens, image
+            assert_eq!(path.len(), 5);
+            assert!((path[1][0]).abs() < EPS && (path[2][0] + 15.0).abs() < EPS);
+            assert!((path[1][1] - path[2][1]).abs() < EPS);
+            let end = path[path.len() - 1];
+            assert!((end[0] - 215.0).abs() < EPS);
+            assert!((end[1] + 2.0).abs() < 1e-9, "ray lands at {}", end[1]);
+        }
+    }
+
+    #[test]
+    fn axicon_is_drawn_with_its_tip() {
+        use crate::{
+            ApertureSpec, FieldSpec, ParaxialView, specs::fields::PupilSampling,
+            views::ray_trace_3d::trace_ray_bundle,
+        };
+        // A plano-axicon with its tip towards a collimated beam.
+        let air = n!(1.0);
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 5.0,
+                refractive_index: n!(1.5),
+            },
+            GapSpec {
+                thickness: 200.0,
+                refractive_index: air.clone(),
+            },
+        ];
+        let surfs = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Axicon {
+                semi_diameter: 10.0,
+                cone_angle: 0.1,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Conic {
+                semi_diameter: 10.0,
+                radius_of_curvature: Float::INFINITY,
+                conic_constant: 0.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfs, &[0.5876], None)
+            .expect("build model");
+        let fields = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let aperture = ApertureSpec::EntrancePupil { semi_diameter: 8.0 };
+        let pv = ParaxialView::new(&model, &fields, false).unwrap();
+        let rays = trace_ray_bundle(
+            &aperture,
+            &fields,
+            &model,
+            &pv,
+            PupilSampling::TangentialRayFan { n: 5 },
+        )
+        .unwrap();
+        let components = components_view(&model, air).unwrap();
+        let cs = cross_section_view(&model, Some(&rays), &components);
+
+        let (front_pts, back_pts) = cs
+            .yz
+            .elements
+            .iter()
+            .find_map(|e| match e {
+                DrawElement::LensGroup {
+                    front_pts,
+                    back_pts,
+                } => Some((front_pts, back_pts)),
+                _ => None,
+            })
+            .expect("axicon lens group");
+        assert_eq!(front_pts.len(), back_pts.len());
+        assert!(
+            front_pts
+                .iter()
+                .any(|[z, t]| z.abs() < EPS && t.abs() < EPS),
+            "tip missing from {front_pts:?}"
+        );
+        let edge = front_pts[0];
+        assert!((edge[0] - 10.0 * Float::tan(0.1)).abs() < EPS);
+
+        // The cone bends the beam across the axis.
+        for path in &cs.yz.ray_paths[0] {
+            let start = path[0][1];
+            let end = path[path.len() - 1][1];
+            if start.abs() > EPS {
+                assert!(start * end < 0.0, "ray from {start} lands at {end}");
+            }
+        }
+    }
+
+    #[test]
+    fn fresnel_is_drawn_as_a_sawtooth() {
+        // A plano-convex Fresnel lens, facets towards the object.
+        let air = n!(1.0);
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 2.0,
+                refractive_index: n!(1.5),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air.clone(),
+            },
+        ];
+        let surfs = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Fresnel {
+                semi_diameter: 10.0,
+                radius_of_curvature: 25.0,
+                conic_constant: 0.0,
+                aspheric_coefficients: vec![],
+                groove_pitch: 2.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Conic {
+                semi_diameter: 10.0,
+                radius_of_curvature: Float::INFINITY,
+                conic_constant: 0.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfs, &[0.5876], None)
+            .expect("build model");
+        let components = components_view(&model, air).unwrap();
+        let cs = cross_section_view(&model, None, &components);
+
+        let (front_pts, back_pts) = cs
+            .yz
+            .elements
+            .iter()
+            .find_map(|e| match e {
+                DrawElement::LensGroup {
+                    front_pts,
+                    back_pts,
+                } => Some((front_pts, back_pts)),
+                _ => None,
+            })
+            .expect("Fresnel lens group");
+        assert_eq!(front_pts.len(), back_pts.len());
+
+        // The relief never rises above the outermost groove, and drops back to
+        // the carrier at each of the four groove edges on either side.
+        let sag = |r: Float| 25.0 - (25.0 * 25.0 - r * r).sqrt();
+        let max_step = sag(10.0) - sag(8.0);
+        for [z, _] in front_pts {
+            assert!(*z > -EPS && *z < max_step + EPS, "z = {z}");
+        }
+        let drops = front_pts
+            .windows(2)
+            .filter(|w| {
+                (w[1][1] - w[0][1]).abs() < 1e-6 && (w[1][0] - w[0][0]).abs() > 0.5 * sag(2.0)
+            })
+            .count();
+        assert_eq!(drops, 8);
+    }
+
+    #[test]
+    fn lens_is_drawn_out_to_its_mechanical_edge() {
+        // A biconvex lens whose front surface has a mechanical edge with a
+        // flat; the back surface has a smaller clear aperture and no edge.
+        let air = n!(1.0);
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 4.0,
+                refractive_index: n!(1.5),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air.clone(),
+            },
+        ];
+        let conic = |semi_diameter: Float, radius_of_curvature: Float| SurfaceSpec::Conic {
+            semi_diameter,
+            radius_of_curvature,
+            conic_constant: 0.0,
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+        let surfs = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Mounted {
+                mechanical_semi_diameter: 12.0,
+                flat_width: 1.0,
+                surface: Box::new(conic(10.0, 50.0)),
+            },
+            conic(8.0, -50.0),
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfs, &[0.5876], None)
+            .expect("build model");
+        let components = components_view(&model, air).unwrap();
+        let cs = cross_section_view(&model, None, &components);
+
+        let (front_pts, back_pts) = cs
+            .yz
+            .elements
+            .iter()
+            .find_map(|e| match e {
+                DrawElement::LensGroup {
+                    front_pts,
+                    back_pts,
+                } => Some((front_pts, back_pts)),
+                _ => None,
+            })
+            .expect("lens group");
+        for pts in [front_pts, back_pts] {
+            assert!((pts.first().unwrap()[1] + 12.0).abs() < 1e-9);
+            assert!((pts.last().unwrap()[1] - 12.0).abs() < 1e-9);
+        }
+
+        // The front surface is flat beyond 11 mm; the back one keeps curving.
+        let sag = |r: Float| 50.0 - (50.0 * 50.0 - r * r).sqrt();
+        for [z, t] in front_pts {
+            let expected = sag(t.abs().min(11.0));
+            assert!((z - expected).abs() < 1e-9, "z = {z} at t = {t}");
+        }
+        let [z, _] = back_pts.last().unwrap();
+        assert!((z - (4.0 - sag(12.0))).abs() < 1e-9, "z = {z}");
+    }
+
+    #[test]
+    fn lenslet_array_is_drawn_with_its_cell_boundaries() {
+        use crate::LensletPacking;
+
+        let air = n!(1.0);
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 1.0,
+                refractive_index: n!(1.5),
+            },
+            GapSpec {
+                thickness: 5.0,
+                refractive_index: air.clone(),
+            },
+        ];
+        let surfs = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::LensletArray {
+                semi_diameter: 3.0,
+                pitch: 1.0,
+                radius_of_curvature: 2.0,
+                conic_constant: 0.0,
+                packing: LensletPacking::Square,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Conic {
+                semi_diameter: 3.0,
+                radius_of_curvature: Float::INFINITY,
+                conic_constant: 0.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfs, &[0.5876], None)
+            .expect("build model");
+        let components = components_view(&model, air).unwrap();
+        let cs = cross_section_view(&model, None, &components);
+
+        let (front_pts, back_pts) = cs
+            .yz
+            .elements
+            .iter()
+            .find_map(|e| match e {
+                DrawElement::LensGroup {
+                    front_pts,
+                    back_pts,
+                } => Some((front_pts, back_pts)),
+                _ => None,
+            })
+            .expect("lenslet array lens group");
+        assert_eq!(front_pts.len(), back_pts.len());
+
+        // Each lenslet rises from its vertex to the kinks at the edges of its
+        // cell, which are drawn exactly.
+        let kink = 2.0 - Float::sqrt(4.0 - 0.25);
+        for [z, _] in front_pts {
+            assert!(*z > -EPS && *z < kink + EPS, "z = {z}");
+        }
+        for edge in [-2.5, -1.5, -0.5, 0.5, 1.5, 2.5] {
+            let [z, _] = front_pts
+                .iter()
+                .find(|[_, t]| (t - edge).abs() < EPS)
+                .unwrap_or_else(|| panic!("no point at the cell edge {edge}"));
+            assert!((z - kink).abs() < EPS, "z = {z} at {edge}");
+        }
+    }
+
+    #[test]
+    fn shaped_apertures_are_drawn_to_size() {
+        // Object → decentered slit → mirror with a central hole → Image
+        let air = n!(1.0);
+        let gaps = vec![
+            GapSpec {
+                thickness: 100.0,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air.clone(),
+            },
+        ];
+        let surfs = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Masked {
+                mask: Mask::Decentered {
+                    offset_x: 0.0,
+                    offset_y: 1.0,
+                    mask: Box::new(Mask::Rectangular {
+                        half_width_x: 4.0,
+                        half_width_y: 2.0,
+                    }),
+                },
+                surface: Box::new(SurfaceSpec::Iris {
+                    semi_diameter: 5.0,
+                    rotation: Rotation3D::None,
+                    decenter: Vec3::new(0.0, 0.0, 0.0),
+                    rotation_offset: Rotation3D::None,
+                }),
+            },
+            SurfaceSpec::Masked {
+                mask: Mask::Obscured {
+                    aperture: Box::new(Mask::Circular {
+                        semi_diameter: 10.0,
+                    }),
+                    obscuration: Some(Box::new(Mask::Circular { semi_diameter: 3.0 })),
+                    spider: Some(Spider {
+                        vanes: 4,
+                        width: 0.5,
+                        rotation: 0.0,
+                    }),
+                },
+                surface: Box::new(SurfaceSpec::Sphere {
+                    semi_diameter: 10.0,
+                    radius_of_curvature: -200.0,
+                    surf_kind: BoundaryKind::Reflecting,
+                    rotation: Rotation3D::None,
+                    decenter: Vec3::new(0.0, 0.0, 0.0),
+                    rotation_offset: Rotation3D::None,
+                }),
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfs, &[0.5876], None)
+            .expect("build model");
+        let components = components_view(&model, air).unwrap();
+        let cs = cross_section_view(&model, None, &components);
+
+        let iris = |plane: &PlaneGeometry| {
+            plane
+                .elements
+                .iter()
+                .find_map(|e| match e {
+                    DrawElement::Iris {
+                        center_t, half_gap, ..
+                    } => Some((*center_t, *half_gap)),
+                    _ => None,
+                })
+                .expect("iris DrawElement")
+        };
+        let (center_t, half_gap) = iris(&cs.yz);
+        assert!((center_t - 1.0).abs() < 1e-9, "center_t = {center_t}");
+        assert!((half_gap - 2.0).abs() < 1e-9, "half_gap = {half_gap}");
+        let (center_t, half_gap) = iris(&cs.xz);
+        assert!(center_t.abs() < 1e-9, "center_t = {center_t}");
+        assert!((half_gap - 4.0).abs() < 1e-9, "half_gap = {half_gap}");
+
+        // The hole splits the mirror into two pieces in each plane. The vanes
+        // are not drawn even though two of them lie in each plane.
+        for plane in [&cs.yz, &cs.xz] {
+            let mut pieces: Vec<(f64, f64)> = plane
+                .elements
+                .iter()
+                .filter_map(|e| match e {
+                    DrawElement::SurfaceProfile { points } => {
+                        Some((points.first().unwrap()[1], points.last().unwrap()[1]))
+                    }
+                    _ => None,
+                })
+                .collect();
+            pieces.sort_by(|a, b| a.0.total_cmp(&b.0));
+            assert_eq!(pieces.len(), 2);
+            for (piece, (start, end)) in pieces.iter().zip([(-10.0, -3.0), (3.0, 10.0)]) {
+                assert!((piece.0 - start).abs() < 1e-6, "{piece:?}");
+                assert!((piece.1 - end).abs() < 1e-6, "{piece:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_f_theta_three_lens_groups() {
+        use crate::examples::f_theta_scan_lens;
+        let air = n!(1.00029); // simulate Ciddor air
+        let glass = n!(1.847); // simulate N-SF57
+        let model = f_theta_scan_lens::sequential_model(air.clone(), glass, &[0.5876]);
+        let components = components_view(&model, air).unwrap();
+        let cs = cross_section_view(&model, None, &components);
+        let n_groups = cs
+            .yz
+            .elements
+            .iter()
+            .filter(|e| matches!(e, DrawElement::LensGroup { .. }))
+            .count();
+        assert_eq!(n_groups, 3, "expected 3 separate lens groups");
+    }
+
+    #[test]
+    fn iris_in_folded_system_has_correct_forward_direction() {
+        // Regression: iris after a 45° fold mirror was drawn with wrong orientation.
+        // Build: Object → Sphere(refracting) → Mirror(theta=45°) → Iris → Image
+        let air = n!(1.0);
+        let gaps = vec![
+            GapSpec {
+                thickness: 100.0,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 25.0,
+                refractive_index: air.clone(),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: air.clone(),
+            },
+        ];
+        let mirror_rotation =
+            Rotation3D::IntrinsicPassiveRUF(EulerAngles(45.0_f64.to_radians(), 0.0, 0.0));
+        let surfs = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Sphere {
+                semi_diameter: 12.7,
+                radius_of_curvature: 102.4,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Sphere {
+                semi_diameter: 10.0,
+                radius_of_curvature: Float::INFINITY,
+                surf_kind: BoundaryKind::Reflecting,
+                rotation: mirror_rotation,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Iris {
+                semi_diameter: 7.1,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfs, &[0.5876], None)
+            .expect("build folded model");
+        let components = components_view(&model, air).unwrap();
+        let cs = cross_section_view(&model, None, &components);
+
+        // After the 45° fold the optical axis points along Y (transverse), not Z.
+        // The iris DrawElement must therefore have fwd_z ≈ 0 and fwd_t ≈ ±1.
+        let iris = cs.yz.elements.iter().find_map(|e| match e {
+            DrawElement::Iris { fwd_z, fwd_t, .. } => Some((*fwd_z, *fwd_t)),
+            _ => None,
+        });
+        let (fwd_z, fwd_t) = iris.expect("iris DrawElement not found in YZ plane");
+        assert!(
+            fwd_z.abs() < 0.01,
+            "after fold, iris fwd_z should be ~0, got {fwd_z}"
+        );
+        assert!(
+            fwd_t.abs() > 0.99,
+            "after fold, iris fwd_t should be ~±1, got {fwd_t}"
+        );
+    }
+
+    #[test]
+    fn sample_even_asphere_follows_sag() {
+        use crate::core::{math::linalg::mat3x3::Mat3x3, surfaces::EvenAsphere};
+
+        let surf =
+            EvenAsphere::new(10.0, 40.0, -1.0, &[1e-4, -5e-7], BoundaryKind::Refracting).unwrap();
+        let placement = Placement::new(
+            Vec3::new(0.0, 0.0, 100.0),
+            0.0,
+            Mat3x3::identity(),
+            Mat3x3::identity(),
+            Mat3x3::identity(),
         );
+
+        for axis in [GlobalAxis::Y, GlobalAxis::X] {
+            let pts = sample_surface(&surf, &placement, axis, 11);
+            assert_eq!(pts.len(), 11);
+            for &[z, t] in &pts {
+                let expected = 100.0 + surf.sag(Vec3::new(t, 0.0, 0.0));
+                assert!(
+                    (z - expected).abs() < 1e-12,
+                    "z = {z}, expected {expected} at t = {t}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn sample_zernike_sag_differs_between_planes() {
+        use crate::{
+            ZernikeOrdering,
+            core::{math::linalg::mat3x3::Mat3x3, surfaces::ZernikeSag},
+        };
+
+        // Fringe Z5 (ρ² cos 2θ) bends the x and y profiles in opposite
+        // directions.
+        let surf = ZernikeSag::new(
+            10.0,
+            Float::INFINITY,
+            0.0,
+            10.0,
+            ZernikeOrdering::Fringe,
+            &[0.0, 0.0, 0.0, 0.0, 0.1],
+            BoundaryKind::Refracting,
+        )
+        .unwrap();
+        let placement = Placement::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            0.0,
+            Mat3x3::identity(),
+            Mat3x3::identity(),
+            Mat3x3::identity(),
+        );
+
+        let yz = sample_surface(&surf, &placement, GlobalAxis::Y, 3);
+        let xz = sample_surface(&surf, &placement, GlobalAxis::X, 3);
+        assert!((yz[0][0] + 0.1).abs() < 1e-12, "y edge z = {}", yz[0][0]);
+        assert!((xz[0][0] - 0.1).abs() < 1e-12, "x edge z = {}", xz[0][0]);
+    }
+
+    #[test]
+    fn sample_surface_follows_rotation_about_axis() {
+        use crate::{
+            ZernikeOrdering,
+            core::{math::linalg::mat3x3::Mat3x3, surfaces::ZernikeSag},
+        };
+
+        // Rotating the surface 90° about the optical axis swaps which local
+        // profile lands in each cutting plane.
+        let surf = ZernikeSag::new(
+            10.0,
+            Float::INFINITY,
+            0.0,
+            10.0,
+            ZernikeOrdering::Fringe,
+            &[0.0, 0.0, 0.0, 0.0, 0.1],
+            BoundaryKind::Refracting,
+        )
+        .unwrap();
+        let rot =
+            Rotation3D::IntrinsicPassiveRUF(EulerAngles(0.0, 0.0, std::f64::consts::FRAC_PI_2))
+                .rotation_matrix();
+        let placement = Placement::new(Vec3::new(0.0, 0.0, 0.0), 0.0, rot, rot, Mat3x3::identity());
+
+        let yz = sample_surface(&surf, &placement, GlobalAxis::Y, 3);
+        let xz = sample_surface(&surf, &placement, GlobalAxis::X, 3);
+        assert!((yz[0][0] - 0.1).abs() < 1e-12, "y edge z = {}", yz[0][0]);
+        assert!((xz[0][0] + 0.1).abs() < 1e-12, "x edge z = {}", xz[0][0]);
+        assert!((yz[0][1].abs() - 10.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn sample_biconic_mirror_follows_each_meridian() {
+        use crate::core::{
+            math::linalg::mat3x3::Mat3x3,
+            surfaces::{Biconic, Conic},
+        };
+
+        let surf = Biconic::new(10.0, -60.0, -100.0, -1.0, 0.0, BoundaryKind::Reflecting);
+        let placement = Placement::new(
+            Vec3::new(0.0, 0.0, 0.0),
+            0.0,
+            Mat3x3::identity(),
+            Mat3x3::identity(),
+            Mat3x3::identity(),
+        );
+
+        let yz = sample_surface(&surf, &placement, GlobalAxis::Y, 5);
+        let xz = sample_surface(&surf, &placement, GlobalAxis::X, 5);
+        let profile_y = Conic::new(10.0, -100.0, 0.0, BoundaryKind::Reflecting);
+        let profile_x = Conic::new(10.0, -60.0, -1.0, BoundaryKind::Reflecting);
+        for ([z_y, t_y], [z_x, t_x]) in yz.iter().zip(xz.iter()) {
+            let expected_y = profile_y.sag(Vec3::new(0.0, *t_y, 0.0));
+            let expected_x = profile_x.sag(Vec3::new(*t_x, 0.0, 0.0));
+            assert!((z_y - expected_y).abs() < 1e-12, "z = {z_y}, t = {t_y}");
+            assert!((z_x - expected_x).abs() < 1e-12, "z = {z_x}, t = {t_x}");
+        }
+        assert!((yz[0][0] - xz[0][0]).abs() > 1e-3);
     }
 }
@@ -10,3 +10,4 @@ pub mod components;
 pub mod cross_section;
 pub mod paraxial;
 pub mod ray_trace_3d;
+pub mod thermal;
@@ -7,21 +7,22 @@
 /// paraxial view is used to compute the paraxial parameters of an optical
 /// system, such as the entrance and exit pupils, the back and front focal
 /// distances, and the effective focal length.
-use anyhow::{Result, anyhow};
+use anyhow::{Result, anyhow, bail};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::{
     FieldSpec,
     core::{
-        Float,
+        Float, PI,
         math::{linalg::mat2x2::Mat2x2, vec3::Vec3},
         sequential_model::{
             SequentialModel, SequentialSubModel, Step, first_physical_surface,
             last_physical_surface, placement::Placement, propagate_tangential_vec,
             reversed_surface_id,
         },
-        surfaces::Surface,
+        surfaces::{Surface, SurfaceKind},
     },
     specs::{fields::unique_tangential_vecs, surfaces::BoundaryKind},
 };
@@ -277,13 +278,38 @@ impl ParaxialView {
     ) -> Result<Self> {
         let surfaces = sequential_model.surfaces();
         let placements = sequential_model.placements();
-        let tangential_vecs: Vec<TangentialVector> =
-            if SequentialModel::is_rotationally_symmetric(placements) {
+
+        // Axicons have no focal point; they are modeled as flat surfaces.
+        for (i, surf) in surfaces.iter().enumerate() {
+            if surf.surface_kind() == SurfaceKind::Axicon {
+                warn!(
+                    surface = i,
+                    "surface {i} is an axicon, which is treated as flat; \
+                     the paraxial model of this system is not meaningful"
+                );
+            }
+        }
+
+        let meridians = principal_meridians(surfaces, placements);
+        let mut tangential_vecs: Vec<TangentialVector> =
+            if SequentialModel::is_rotationally_symmetric(placements) && meridians.is_empty() {
                 vec![Vec3::new(0.0, 1.0, 0.0)]
             } else {
                 unique_tangential_vecs(field_specs)
             };
 
+        // Anamorphic surfaces have a different focal length, pupil and image
+        // plane in each principal meridian, so each one gets its own submodels.
+        for m in meridians {
+            if !tangential_vecs
+                .iter()
+                .any(|v| (v.x() * m.y() - v.y() * m.x()).abs() < 1e-9)
+            {
+                tangential_vecs.push(m);
+            }
+        }
+        tangential_vecs.sort_by(|a, b| a.y().atan2(a.x()).total_cmp(&b.y().atan2(b.x())));
+
         let stop_surface = sequential_model.stop_surface();
         let mut subviews = Vec::new();
         for (wav_idx, submodel) in sequential_model.submodels().iter().enumerate() {
@@ -448,11 +474,20 @@ impl ParaxialSubView {
         // Propagate v through mirror surfaces to get per-surface tangential vectors.
         let per_surf_v: Vec<TangentialVector> = propagate_tangential_vec(v, surfaces, placements);
 
-        let pseudo_marginal_ray =
-            Self::calc_pseudo_marginal_ray(sequential_sub_model, surfaces, placements)?;
-        let parallel_ray = Self::calc_parallel_ray(sequential_sub_model, surfaces, placements)?;
-        let reverse_parallel_ray =
-            Self::calc_reverse_parallel_ray(sequential_sub_model, surfaces, placements)?;
+        let pseudo_marginal_ray = Self::calc_pseudo_marginal_ray(
+            sequential_sub_model,
+            surfaces,
+            placements,
+            &per_surf_v,
+        )?;
+        let parallel_ray =
+            Self::calc_parallel_ray(sequential_sub_model, surfaces, placements, &per_surf_v)?;
+        let reverse_parallel_ray = Self::calc_reverse_parallel_ray(
+            sequential_sub_model,
+            surfaces,
+            placements,
+            &per_surf_v,
+        )?;
 
         let aperture_stop = match data.stop_surface {
             Some(i) => i,
@@ -469,7 +504,7 @@ impl ParaxialSubView {
             &pseudo_marginal_ray,
             &aperture_stop,
             &per_surf_v,
-        );
+        )?;
         let entrance_pupil = Self::calc_entrance_pupil(
             sequential_sub_model,
             surfaces,
@@ -484,6 +519,7 @@ impl ParaxialSubView {
             surfaces,
             placements,
             &aperture_stop,
+            &per_surf_v,
             &marginal_ray,
         )?;
         let effective_focal_length = Self::calc_effective_focal_length(&parallel_ray);
@@ -498,6 +534,7 @@ impl ParaxialSubView {
             sequential_sub_model,
             placements,
             v,
+            &per_surf_v,
             field_specs,
             &entrance_pupil,
         )?;
@@ -646,8 +683,10 @@ impl ParaxialSubView {
             return Ok(Float::INFINITY);
         }
 
-        // Distance is always positive
-        Ok(bfd.abs())
+        // The intercept is always positive along the beam path. It is measured
+        // from the back principal plane of a cardinal lens, so shift it back to
+        // the surface itself.
+        Ok(bfd.abs() + surfaces[last_physical_surface_index].axial_displacement())
     }
 
     fn calc_back_principal_plane(
@@ -677,6 +716,7 @@ impl ParaxialSubView {
         sequential_sub_model: &dyn SequentialSubModel,
         placements: &[Placement],
         v: TangentialVector,
+        per_surf_v: &[TangentialVector],
         field_specs: &[FieldSpec],
         entrance_pupil: &Pupil,
     ) -> Result<ParaxialRayBundle> {
@@ -716,6 +756,7 @@ impl ParaxialSubView {
             sequential_sub_model,
             surfaces,
             placements,
+            per_surf_v,
             false,
         )
     }
@@ -758,8 +799,11 @@ impl ParaxialSubView {
         if *aperture_stop == 1usize {
             return Ok(Pupil {
                 location: 0.0,
-                semi_diameter: placements[1]
-                    .projected_semi_diameter(surfaces[1].mask().semi_diameter(), per_surf_v[1]),
+                semi_diameter: meridional_semi_diameter(
+                    surfaces[1].as_ref(),
+                    &placements[1],
+                    per_surf_v[1],
+                ),
             });
         }
 
@@ -774,6 +818,7 @@ impl ParaxialSubView {
             &sequential_sub_model.slice(0..*aperture_stop),
             &surfaces[0..aperture_stop + 1],
             &placements[0..aperture_stop + 1],
+            &per_surf_v[0..aperture_stop + 1],
             true,
         )?;
         let location = axis_intercepts(results.last_surface().unwrap())?[0];
@@ -803,14 +848,24 @@ impl ParaxialSubView {
         surfaces: &[Box<dyn Surface>],
         placements: &[Placement],
         aperture_stop: &usize,
+        per_surf_v: &[TangentialVector],
         marginal_ray: &ParaxialRayBundle,
     ) -> Result<Pupil> {
         let last_physical_surface_id =
             last_physical_surface(surfaces).ok_or(anyhow!("There are no physical surfaces"))?;
+        // A stop in the last surface is imaged onto its back principal plane,
+        // which is the surface itself unless it is a cardinal lens.
+        let axial_displacement = surfaces[last_physical_surface_id].axial_displacement();
         if last_physical_surface_id == *aperture_stop {
             return Ok(Pupil {
-                location: 0.0,
-                semi_diameter: surfaces[last_physical_surface_id].mask().semi_diameter(),
+                location: axial_displacement,
+                semi_diameter: {
+                    let (dx, dy) = placements[last_physical_surface_id]
+                        .local_meridian(per_surf_v[last_physical_surface_id]);
+                    surfaces[last_physical_surface_id]
+                        .mask()
+                        .semi_extent(dx, dy)
+                },
             });
         }
 
@@ -825,10 +880,12 @@ impl ParaxialSubView {
             &sequential_sub_model.slice(*aperture_stop..sequential_sub_model.len()),
             &surfaces[*aperture_stop..],
             &placements[*aperture_stop..],
+            &per_surf_v[*aperture_stop..],
             false,
         )?;
 
-        // Distance is relative to the last physical surface
+        // Distance is relative to the last physical surface, or to its back
+        // principal plane if it is a cardinal lens
         let sliced_last_physical_surface_id = last_physical_surface_id - aperture_stop;
         let distance =
             axis_intercepts(results.rays_at_surface(sliced_last_physical_surface_id))?[0];
@@ -841,7 +898,7 @@ impl ParaxialSubView {
         .height;
 
         Ok(Pupil {
-            location: distance,
+            location: distance + axial_displacement,
             semi_diameter,
         })
     }
@@ -855,6 +912,8 @@ impl ParaxialSubView {
         let index = reversed_surface_id(surfaces.len(), first_physical_surface_index);
         let intercepts = axis_intercepts(reverse_parallel_ray.rays_at_surface(index))?;
 
+        // No cardinal lens correction is needed here: a cardinal lens lies in
+        // its front principal plane, from which the preceding gap is measured.
         let ffd = intercepts[0];
 
         // Handle edge case for infinite FFD
@@ -884,7 +943,7 @@ impl ParaxialSubView {
         pseudo_marginal_ray: &ParaxialRayBundle,
         aperture_stop: &usize,
         per_surf_v: &[TangentialVector],
-    ) -> ParaxialRayBundle {
+    ) -> Result<ParaxialRayBundle> {
         calc_marginal_ray(
             surfaces,
             placements,
@@ -899,13 +958,21 @@ impl ParaxialSubView {
         sequential_sub_model: &dyn SequentialSubModel,
         surfaces: &[Box<dyn Surface>],
         placements: &[Placement],
+        per_surf_v: &[TangentialVector],
     ) -> Result<ParaxialRayBundle> {
         let ray = vec![ParaxialRay {
             height: 1.0,
             angle: 0.0,
         }];
 
-        Self::trace(ray, sequential_sub_model, surfaces, placements, false)
+        Self::trace(
+            ray,
+            sequential_sub_model,
+            surfaces,
+            placements,
+            per_surf_v,
+            false,
+        )
     }
 
     /// Compute the paraxial image plane.
@@ -923,7 +990,9 @@ impl ParaxialSubView {
             // Ensure positive infinity is returned for infinite image planes
             Float::INFINITY
         } else {
-            placements[last_physical_surface_id].track + d_axis
+            placements[last_physical_surface_id].track
+                + surfaces[last_physical_surface_id].axial_displacement()
+                + d_axis
         };
 
         // Propagate the chief ray from the last physical surface to the image plane to
@@ -942,8 +1011,9 @@ impl ParaxialSubView {
         sequential_sub_model: &dyn SequentialSubModel,
         surfaces: &[Box<dyn Surface>],
         placements: &[Placement],
+        per_surf_v: &[TangentialVector],
     ) -> Result<ParaxialRayBundle> {
-        calc_pseudo_marginal_ray(sequential_sub_model, surfaces, placements)
+        calc_pseudo_marginal_ray(sequential_sub_model, surfaces, placements, per_surf_v)
     }
 
     /// Compute the reverse parallel ray.
@@ -951,20 +1021,34 @@ impl ParaxialSubView {
         sequential_sub_model: &dyn SequentialSubModel,
         surfaces: &[Box<dyn Surface>],
         placements: &[Placement],
+        per_surf_v: &[TangentialVector],
     ) -> Result<ParaxialRayBundle> {
         let ray = vec![ParaxialRay {
             height: 1.0,
             angle: 0.0,
         }];
 
-        Self::trace(ray, sequential_sub_model, surfaces, placements, true)
+        Self::trace(
+            ray,
+            sequential_sub_model,
+            surfaces,
+            placements,
+            per_surf_v,
+            true,
+        )
     }
 
     /// Compute the ray transfer matrix for each gap/surface pair.
+    ///
+    /// `per_surf_v` holds the incident tangential vector at each surface in
+    /// `surfaces`. Its direction in each surface's local frame selects the
+    /// meridian in which the radius of curvature is evaluated, so surfaces
+    /// such as cylinders and toroids contribute the power of that meridian.
     fn rtms(
         sequential_sub_model: &dyn SequentialSubModel,
         surfaces: &[Box<dyn Surface>],
         placements: &[Placement],
+        per_surf_v: &[TangentialVector],
         reverse: bool,
     ) -> Result<Vec<RayTransferMatrix>> {
         let mut txs: Vec<RayTransferMatrix> = Vec::new();
@@ -979,12 +1063,17 @@ impl ParaxialSubView {
             forward_iter = sequential_sub_model.try_iter(surfaces, placements)?;
             &mut forward_iter
         };
-        for Step {
-            gap_before: gap_0,
-            surface,
-            gap_after: gap_1,
-            ..
-        } in steps
+        let num_steps = surfaces.len() - 1;
+        let wavelength = sequential_sub_model.wavelength();
+        for (
+            k,
+            Step {
+                gap_before: gap_0,
+                surface,
+                gap_after: gap_1,
+                placement,
+            },
+        ) in steps.enumerate()
         {
             let t = if gap_0.thickness.is_infinite() {
                 DEFAULT_THICKNESS
@@ -996,7 +1085,11 @@ impl ParaxialSubView {
                 gap_0.thickness
             };
 
-            let roc = surface.roc(0.0);
+            // Forward steps start at surface 1; reverse steps start at the last
+            // surface before the image.
+            let surf_idx = if reverse { num_steps - 1 - k } else { k + 1 };
+            let v_local = placement.rotation_matrix * per_surf_v[surf_idx];
+            let roc = surface.roc(v_local.y().atan2(v_local.x()));
 
             let n_0 = gap_0.refractive_index.n();
             let n_1 = if let Some(gap_1) = gap_1 {
@@ -1005,7 +1098,7 @@ impl ParaxialSubView {
                 gap_0.refractive_index.n()
             };
 
-            let rtm = surface_to_rtm(surface, t, roc, n_0, n_1);
+            let rtm = surface_to_rtm(surface, t, roc, n_0, n_1, wavelength);
             txs.push(rtm);
         }
 
@@ -1017,9 +1110,16 @@ impl ParaxialSubView {
         sequential_sub_model: &dyn SequentialSubModel,
         surfaces: &[Box<dyn Surface>],
         placements: &[Placement],
+        per_surf_v: &[TangentialVector],
         reverse: bool,
     ) -> Result<ParaxialRayBundle> {
-        let txs = Self::rtms(sequential_sub_model, surfaces, placements, reverse)?;
+        let txs = Self::rtms(
+            sequential_sub_model,
+            surfaces,
+            placements,
+            per_surf_v,
+            reverse,
+        )?;
         let num_surfaces = txs.len() + 1;
         let num_rays = initial_rays.len();
         let mut flat: Vec<ParaxialRay> = Vec::with_capacity(num_surfaces * num_rays);
@@ -1045,23 +1145,82 @@ impl ParaxialSubView {
     }
 }
 
+/// Returns the principal meridians of the anamorphic surfaces in a system.
+///
+/// A surface is anamorphic if its vertex radius of curvature differs between
+/// its local x and y meridians. Each such surface contributes its local x and
+/// y axes, expressed in the cursor frame and projected onto the transverse
+/// plane. The result is empty for systems of circularly symmetric surfaces.
+fn principal_meridians(
+    surfaces: &[Box<dyn Surface>],
+    placements: &[Placement],
+) -> Vec<TangentialVector> {
+    let mut meridians: Vec<TangentialVector> = Vec::new();
+    for (surface, placement) in surfaces.iter().zip(placements.iter()) {
+        let c_x = 1.0 / surface.roc(0.0);
+        let c_y = 1.0 / surface.roc(PI / 2.0);
+        if (c_x - c_y).abs() < 1e-12 {
+            continue;
+        }
+
+        // Local x-axis in the cursor frame; the y meridian is perpendicular.
+        let r_surf = placement.rotation_matrix * placement.cursor_rotation_matrix.transpose();
+        let x_axis = r_surf.transpose() * Vec3::new(1.0, 0.0, 0.0);
+        let phi_x = x_axis.y().atan2(x_axis.x());
+        for phi in [phi_x, phi_x + PI / 2.0] {
+            // A meridian and its reverse are the same plane; keep phi in (-90°, 90°].
+            let phi = if phi > PI / 2.0 + 1e-9 {
+                phi - PI
+            } else if phi <= -PI / 2.0 + 1e-9 {
+                phi + PI
+            } else {
+                phi
+            };
+            let m = Vec3::new(phi.cos(), phi.sin(), 0.0);
+            if !meridians
+                .iter()
+                .any(|v| (v.x() * m.y() - v.y() * m.x()).abs() < 1e-9)
+            {
+                meridians.push(m);
+            }
+        }
+    }
+    meridians
+}
+
 /// Compute the ray transfer matrix for propagation to and interaction with a
 /// surface.
+///
+/// `roc` is the radius of the surface's base sphere. Higher-order sag terms,
+/// such as the polynomial of an even asphere, vanish to first order at the
+/// vertex and so do not contribute to the paraxial power.
+///
+/// Surfaces with a [`paraxial_focal_length`](Surface::paraxial_focal_length)
+/// are treated as thin lenses with that image-space focal length instead.
+/// Otherwise the [`diffractive_power`](Surface::diffractive_power) at
+/// `wavelength` adds to the power of the curvature.
 fn surface_to_rtm(
     surface: &dyn Surface,
     t: Float,
     roc: Float,
     n_0: Float,
     n_1: Float,
+    wavelength: Float,
 ) -> RayTransferMatrix {
+    if let Some(f) = surface.paraxial_focal_length() {
+        return Mat2x2::new(1.0, t, -1.0 / f, -t / f + n_0 / n_1);
+    }
+
+    let phi_d = surface.diffractive_power(wavelength);
     match surface.boundary_kind() {
-        BoundaryKind::Refracting => Mat2x2::new(
-            1.0,
-            t,
-            (n_0 - n_1) / n_1 / roc,
-            t * (n_0 - n_1) / n_1 / roc + n_0 / n_1,
-        ),
-        BoundaryKind::Reflecting => Mat2x2::new(1.0, t, 2.0 / roc, 2.0 * t / roc + 1.0),
+        BoundaryKind::Refracting => {
+            let c = (n_0 - n_1) / n_1 / roc - phi_d / n_1;
+            Mat2x2::new(1.0, t, c, t * c + n_0 / n_1)
+        }
+        BoundaryKind::Reflecting => {
+            let c = 2.0 / roc - phi_d / n_0;
+            Mat2x2::new(1.0, t, c, t * c + 1.0)
+        }
         BoundaryKind::NoOp => Mat2x2::new(1.0, t, 0.0, 1.0),
     }
 }
@@ -1071,6 +1230,7 @@ pub(crate) fn calc_pseudo_marginal_ray(
     sequential_sub_model: &dyn SequentialSubModel,
     surfaces: &[Box<dyn Surface>],
     placements: &[Placement],
+    per_surf_v: &[TangentialVector],
 ) -> Result<ParaxialRayBundle> {
     let ray = if sequential_sub_model.is_obj_at_inf() {
         vec![ParaxialRay {
@@ -1083,7 +1243,28 @@ pub(crate) fn calc_pseudo_marginal_ray(
             angle: 1.0,
         }]
     };
-    ParaxialSubView::trace(ray, sequential_sub_model, surfaces, placements, false)
+    ParaxialSubView::trace(
+        ray,
+        sequential_sub_model,
+        surfaces,
+        placements,
+        per_surf_v,
+        false,
+    )
+}
+
+/// Returns the semi-diameter of a surface's clear aperture in the tangential
+/// plane `v`, as seen by a paraxial ray travelling along the cursor axis.
+///
+/// Apertures are sized by their chord through the vertex in that plane, so
+/// that a slit limits the marginal ray by its half-width across the slit.
+fn meridional_semi_diameter(
+    surface: &dyn Surface,
+    placement: &Placement,
+    v: TangentialVector,
+) -> Float {
+    let (dx, dy) = placement.local_meridian(v);
+    placement.projected_semi_diameter(surface.mask().semi_extent(dx, dy), v)
 }
 
 /// Compute the aperture stop surface index using the minimum aperture-ratio
@@ -1100,30 +1281,34 @@ pub(crate) fn calc_aperture_stop(
         .zip(pseudo_marginal_ray.iter_surfaces())
         .zip(per_surf_v.iter())
         .map(|(((s, p), rays), &v)| {
-            (p.projected_semi_diameter(s.mask().semi_diameter(), v) / rays[0].height).abs()
+            (meridional_semi_diameter(s.as_ref(), p, v) / rays[0].height).abs()
         })
         .collect();
     argmin(&ratios[1..ratios.len() - 1]) + 1
 }
 
 /// Scale the pseudo-marginal ray to match the aperture stop semi-diameter.
+///
+/// Returns an error if the aperture stop does not contain the optical axis,
+/// e.g. an off-axis aperture, since no marginal ray passes it.
 pub(crate) fn calc_marginal_ray(
     surfaces: &[Box<dyn Surface>],
     placements: &[Placement],
     pseudo_marginal_ray: &ParaxialRayBundle,
     aperture_stop: &usize,
     per_surf_v: &[TangentialVector],
-) -> ParaxialRayBundle {
+) -> Result<ParaxialRayBundle> {
     let ratios: Vec<Float> = surfaces
         .iter()
         .zip(placements.iter())
         .zip(pseudo_marginal_ray.iter_surfaces())
         .zip(per_surf_v.iter())
-        .map(|(((s, p), rays), &v)| {
-            p.projected_semi_diameter(s.mask().semi_diameter(), v) / rays[0].height
-        })
+        .map(|(((s, p), rays), &v)| meridional_semi_diameter(s.as_ref(), p, v) / rays[0].height)
         .collect();
     let scale_factor = ratios[*aperture_stop];
+    if scale_factor == 0.0 {
+        bail!("The aperture stop at surface {aperture_stop} does not contain the optical axis");
+    }
 
     let rays = pseudo_marginal_ray
         .rays
@@ -1134,10 +1319,10 @@ pub(crate) fn calc_marginal_ray(
         })
         .collect();
 
-    ParaxialRayBundle {
+    Ok(ParaxialRayBundle {
         rays,
         num_surfaces: pseudo_marginal_ray.num_surfaces,
-    }
+    })
 }
 
 /// Compute the paraxial marginal ray bundle for a given wavelength.
@@ -1156,18 +1341,12 @@ pub(crate) fn marginal_ray_bundle(
 
     let v = Vec3::new(0.0, 1.0, 0.0);
     let per_surf_v = propagate_tangential_vec(v, surfaces, placements);
-    let pseudo = calc_pseudo_marginal_ray(submodel, surfaces, placements)?;
+    let pseudo = calc_pseudo_marginal_ray(submodel, surfaces, placements, &per_surf_v)?;
     let stop = match model.stop_surface() {
         Some(i) => i,
         None => calc_aperture_stop(surfaces, placements, &pseudo, &per_surf_v),
     };
-    Ok(calc_marginal_ray(
-        surfaces,
-        placements,
-        &pseudo,
-        &stop,
-        &per_surf_v,
-    ))
+    calc_marginal_ray(surfaces, placements, &pseudo, &stop, &per_surf_v)
 }
 
 fn argmin(ratios: &[Float]) -> usize {
@@ -1366,10 +1545,16 @@ mod test {
         let wavelengths: [Float; 1] = [0.5876];
         let sequential_model = convexplano_lens::sequential_model(air, nbk7, &wavelengths);
         let seq_sub_model = sequential_model.submodel(0).expect("Submodel not found.");
+        let per_surf_v = propagate_tangential_vec(
+            Vec3::new(0.0, 1.0, 0.0),
+            sequential_model.surfaces(),
+            sequential_model.placements(),
+        );
         let pseudo_marginal_ray = calc_pseudo_marginal_ray(
             seq_sub_model,
             sequential_model.surfaces(),
             sequential_model.placements(),
+            &per_surf_v,
         )
         .unwrap();
 
@@ -1396,10 +1581,16 @@ mod test {
         let wavelengths: [Float; 1] = [0.5876];
         let sequential_model = convexplano_lens::sequential_model(air, nbk7, &wavelengths);
         let seq_sub_model = sequential_model.submodel(0).expect("Submodel not found.");
+        let per_surf_v = propagate_tangential_vec(
+            Vec3::new(0.0, 1.0, 0.0),
+            sequential_model.surfaces(),
+            sequential_model.placements(),
+        );
         let reverse_parallel_ray = ParaxialSubView::calc_reverse_parallel_ray(
             seq_sub_model,
             sequential_model.surfaces(),
             sequential_model.placements(),
+            &per_surf_v,
         )
         .unwrap();
 
@@ -1567,4 +1758,670 @@ mod test {
         let expected = *sub.effective_focal_length() / (2.0 * sub.entrance_pupil().semi_diameter);
         assert_abs_diff_eq!(sub.image_space_fno(), expected, epsilon = 1e-6);
     }
+
+    /// EFL of a singlet with the given front surface and a flat back surface.
+    fn singlet_view(front: SurfaceSpec) -> ParaxialView {
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 5.3,
+                refractive_index: n!(1.515),
+            },
+            GapSpec {
+                thickness: 46.6,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            front,
+            SurfaceSpec::Conic {
+                semi_diameter: 12.5,
+                radius_of_curvature: Float::INFINITY,
+                conic_constant: 0.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let seq = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let field = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        ParaxialView::new(&seq, &field, false).unwrap()
+    }
+
+    fn singlet_efl(front: SurfaceSpec) -> Float {
+        *singlet_view(front)
+            .get(0, 0)
+            .unwrap()
+            .effective_focal_length()
+    }
+
+    fn base_conic_efl() -> Float {
+        singlet_efl(SurfaceSpec::Conic {
+            semi_diameter: 12.5,
+            radius_of_curvature: 25.8,
+            conic_constant: -0.6,
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        })
+    }
+
+    /// The polynomial terms of an even asphere carry no paraxial power, so its
+    /// EFL must equal that of the base conic.
+    #[test]
+    fn even_asphere_efl_matches_base_conic() {
+        let asphere_efl = singlet_efl(SurfaceSpec::EvenAsphere {
+            semi_diameter: 12.5,
+            radius_of_curvature: 25.8,
+            conic_constant: -0.6,
+            aspheric_coefficients: vec![3e-5, -2e-7, 1e-10],
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        });
+
+        assert_abs_diff_eq!(asphere_efl, base_conic_efl(), epsilon = 1e-12);
+    }
+
+    /// A Zernike sag surface is treated paraxially as its base conic.
+    #[test]
+    fn zernike_sag_efl_matches_base_conic() {
+        let zernike_efl = singlet_efl(SurfaceSpec::ZernikeSag {
+            semi_diameter: 12.5,
+            radius_of_curvature: 25.8,
+            conic_constant: -0.6,
+            normalization_radius: 12.5,
+            ordering: crate::ZernikeOrdering::Fringe,
+            zernike_coefficients: vec![0.0, 0.0, 0.0, 0.0, 1e-3, 0.0, 0.0, 0.0, 2e-4],
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        });
+
+        assert_abs_diff_eq!(zernike_efl, base_conic_efl(), epsilon = 1e-12);
+    }
+
+    /// Each principal meridian of a toroid sees only that meridian's radius.
+    /// With a flat back surface, EFL = R / (n - 1) in each meridian.
+    #[test]
+    fn toroid_meridians_have_separate_focal_lengths() {
+        let pv = singlet_view(SurfaceSpec::Toroid {
+            semi_diameter: 12.5,
+            radius_of_curvature_x: 51.6,
+            radius_of_curvature_y: 25.8,
+            conic_constant: 0.0,
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        });
+        let x_id = pv.tangential_vec_id_for_phi(0.0);
+        let y_id = pv.tangential_vec_id_for_phi(PI / 2.0);
+        assert_ne!(x_id, y_id);
+
+        let x_view = pv.get(0, x_id).unwrap();
+        let y_view = pv.get(0, y_id).unwrap();
+        assert_abs_diff_eq!(
+            *x_view.effective_focal_length(),
+            51.6 / 0.515,
+            epsilon = 1e-9
+        );
+        assert_abs_diff_eq!(
+            *y_view.effective_focal_length(),
+            25.8 / 0.515,
+            epsilon = 1e-9
+        );
+        assert!(x_view.paraxial_image_plane().location > y_view.paraxial_image_plane().location);
+    }
+
+    fn masked_toroid(mask: crate::Mask) -> SurfaceSpec {
+        SurfaceSpec::Masked {
+            mask,
+            surface: Box::new(SurfaceSpec::Toroid {
+                semi_diameter: 12.5,
+                radius_of_curvature_x: 51.6,
+                radius_of_curvature_y: 25.8,
+                conic_constant: 0.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            }),
+        }
+    }
+
+    /// A slit limits the marginal ray by its half-width in each meridian, not
+    /// by the radius of the circle about its corners.
+    #[test]
+    fn slit_stop_is_sized_per_meridian() {
+        let pv = singlet_view(masked_toroid(crate::Mask::Rectangular {
+            half_width_x: 10.0,
+            half_width_y: 2.0,
+        }));
+        let x_view = pv.get(0, pv.tangential_vec_id_for_phi(0.0)).unwrap();
+        let y_view = pv.get(0, pv.tangential_vec_id_for_phi(PI / 2.0)).unwrap();
+
+        assert_eq!(*x_view.aperture_stop(), 1);
+        assert_eq!(*y_view.aperture_stop(), 1);
+        assert_abs_diff_eq!(x_view.entrance_pupil().semi_diameter, 10.0);
+        assert_abs_diff_eq!(y_view.entrance_pupil().semi_diameter, 2.0);
+        assert_abs_diff_eq!(y_view.image_space_fno(), 25.8 / 0.515 / 4.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn off_axis_stop_is_an_error() {
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 10.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Masked {
+                mask: crate::Mask::Decentered {
+                    offset_x: 0.0,
+                    offset_y: 5.0,
+                    mask: Box::new(crate::Mask::Circular { semi_diameter: 2.0 }),
+                },
+                surface: Box::new(SurfaceSpec::Iris {
+                    semi_diameter: 10.0,
+                    rotation: Rotation3D::None,
+                    decenter: Vec3::new(0.0, 0.0, 0.0),
+                    rotation_offset: Rotation3D::None,
+                }),
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let seq = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let field = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let err = ParaxialView::new(&seq, &field, false).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("does not contain the optical axis")
+        );
+    }
+
+    /// A cylinder has no power along its axis.
+    #[test]
+    fn cylinder_has_no_power_along_its_axis() {
+        let pv = singlet_view(SurfaceSpec::Cylinder {
+            semi_diameter: 12.5,
+            radius_of_curvature: 25.8,
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        });
+        assert_eq!(pv.iter().count(), 2);
+
+        let x_view = pv.get(0, pv.tangential_vec_id_for_phi(0.0)).unwrap();
+        let y_view = pv.get(0, pv.tangential_vec_id_for_phi(PI / 2.0)).unwrap();
+        assert!(x_view.effective_focal_length().is_infinite());
+        assert!(x_view.paraxial_image_plane().location.is_infinite());
+        assert_abs_diff_eq!(
+            *y_view.effective_focal_length(),
+            25.8 / 0.515,
+            epsilon = 1e-9
+        );
+    }
+
+    /// Clocking a cylinder by 90° swaps the meridian that carries the power.
+    #[test]
+    fn clocked_cylinder_swaps_meridians() {
+        let pv = singlet_view(SurfaceSpec::Cylinder {
+            semi_diameter: 12.5,
+            radius_of_curvature: 25.8,
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::IntrinsicPassiveRUF(crate::EulerAngles(
+                0.0,
+                0.0,
+                PI / 2.0,
+            )),
+        });
+
+        let x_view = pv.get(0, pv.tangential_vec_id_for_phi(0.0)).unwrap();
+        let y_view = pv.get(0, pv.tangential_vec_id_for_phi(PI / 2.0)).unwrap();
+        assert_abs_diff_eq!(
+            *x_view.effective_focal_length(),
+            25.8 / 0.515,
+            epsilon = 1e-9
+        );
+        assert!(y_view.effective_focal_length().is_infinite());
+    }
+
+    /// A biconic contributes the power of its vertex radius in each meridian.
+    #[test]
+    fn biconic_meridians_have_separate_focal_lengths() {
+        let pv = singlet_view(SurfaceSpec::Biconic {
+            semi_diameter: 12.5,
+            radius_of_curvature_x: 40.0,
+            radius_of_curvature_y: 25.8,
+            conic_constant_x: -1.0,
+            conic_constant_y: 0.5,
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        });
+
+        let x_view = pv.get(0, pv.tangential_vec_id_for_phi(0.0)).unwrap();
+        let y_view = pv.get(0, pv.tangential_vec_id_for_phi(PI / 2.0)).unwrap();
+        assert_abs_diff_eq!(
+            *x_view.effective_focal_length(),
+            40.0 / 0.515,
+            epsilon = 1e-9
+        );
+        assert_abs_diff_eq!(
+            *y_view.effective_focal_length(),
+            25.8 / 0.515,
+            epsilon = 1e-9
+        );
+    }
+    /// Two paraxial lenses in air combine by the thin-lens equations:
+    /// 1/f = 1/f1 + 1/f2 - d/(f1 f2) and BFD = f (f1 - d) / f1.
+    #[test]
+    fn paraxial_lens_pair_matches_thin_lens_equations() {
+        let paraxial_lens = |focal_length| SurfaceSpec::ParaxialLens {
+            semi_diameter: 12.5,
+            focal_length,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 25.0,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 30.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            paraxial_lens(100.0),
+            paraxial_lens(50.0),
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let seq = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let field = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let pv = ParaxialView::new(&seq, &field, false).unwrap();
+        let view = pv.get(0, 0).unwrap();
+
+        assert_abs_diff_eq!(*view.effective_focal_length(), 40.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(*view.back_focal_distance(), 30.0, epsilon = 1e-9);
+    }
+
+    /// Image-space quantities are measured from the cardinal lens itself, which
+    /// lies in its front principal plane, and not from its back principal
+    /// plane.
+    #[test]
+    fn cardinal_lens_image_space_distances_include_principal_plane_separation() {
+        let cardinal_lens = || SurfaceSpec::CardinalLens {
+            semi_diameter: 12.5,
+            focal_length: 9.0,
+            principal_plane_separation: -15.0,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+        let image = || SurfaceSpec::Image {
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+        let field = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+
+        // The lens is the aperture stop.
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 9.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![SurfaceSpec::Object, cardinal_lens(), image()];
+        let seq = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let pv = ParaxialView::new(&seq, &field, false).unwrap();
+        let view = pv.get(0, 0).unwrap();
+
+        assert_abs_diff_eq!(view.paraxial_image_plane().location, -6.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(*view.back_focal_distance(), -6.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(*view.back_principal_plane(), -15.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(view.exit_pupil().location, -15.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(*view.front_focal_distance(), 9.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(*view.front_principal_plane(), 0.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(view.entrance_pupil().location, 0.0, epsilon = 1e-9);
+
+        // A stop 10 mm before the lens is imaged 90 mm past the back principal
+        // plane.
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 10.0,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 9.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Conic {
+                semi_diameter: 5.0,
+                radius_of_curvature: Float::INFINITY,
+                conic_constant: 0.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            cardinal_lens(),
+            image(),
+        ];
+        let seq = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let pv = ParaxialView::new(&seq, &field, false).unwrap();
+        let view = pv.get(0, 0).unwrap();
+
+        assert_eq!(*view.aperture_stop(), 1);
+        assert_abs_diff_eq!(*view.back_focal_distance(), -6.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(*view.back_principal_plane(), -15.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(view.exit_pupil().location, 75.0, epsilon = 1e-9);
+    }
+
+    /// The gap after a cardinal lens is measured from its back principal
+    /// plane, so the image lies one focal length beyond it.
+    #[test]
+    fn cardinal_lens_focuses_one_focal_length_past_back_principal_plane() {
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::CardinalLens {
+                semi_diameter: 12.5,
+                focal_length: 50.0,
+                principal_plane_separation: -10.0,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let seq = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let field = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let pv = ParaxialView::new(&seq, &field, false).unwrap();
+        let view = pv.get(0, 0).unwrap();
+
+        assert_abs_diff_eq!(seq.placements()[2].track, 40.0, epsilon = 1e-12);
+        assert_abs_diff_eq!(*view.effective_focal_length(), 50.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(view.paraxial_image_plane().location, 40.0, epsilon = 1e-9);
+    }
+
+    /// An axicon has no paraxial power, so an axicon plate in front of a lens
+    /// leaves its focal length unchanged.
+    #[test]
+    fn axicon_is_treated_as_flat() {
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 3.0,
+                refractive_index: n!(1.5),
+            },
+            GapSpec {
+                thickness: 5.0,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 50.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Axicon {
+                semi_diameter: 12.5,
+                cone_angle: 0.05,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Conic {
+                semi_diameter: 12.5,
+                radius_of_curvature: Float::INFINITY,
+                conic_constant: 0.0,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::ParaxialLens {
+                semi_diameter: 12.5,
+                focal_length: 50.0,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let seq = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let field = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let pv = ParaxialView::new(&seq, &field, false).unwrap();
+        let view = pv.get(0, 0).unwrap();
+
+        assert_abs_diff_eq!(*view.effective_focal_length(), 50.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(*view.back_focal_distance(), 50.0, epsilon = 1e-9);
+    }
+
+    /// A Fresnel surface has the paraxial power of its base profile.
+    #[test]
+    fn fresnel_uses_base_curvature() {
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 3.0,
+                refractive_index: n!(1.5),
+            },
+            GapSpec {
+                thickness: 60.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let front = |fresnel: bool| {
+            if fresnel {
+                SurfaceSpec::Fresnel {
+                    semi_diameter: 12.5,
+                    radius_of_curvature: 25.0,
+                    conic_constant: -1.0,
+                    aspheric_coefficients: vec![],
+                    groove_pitch: 0.5,
+                    surf_kind: BoundaryKind::Refracting,
+                    rotation: Rotation3D::None,
+                    decenter: Vec3::new(0.0, 0.0, 0.0),
+                    rotation_offset: Rotation3D::None,
+                }
+            } else {
+                SurfaceSpec::Conic {
+                    semi_diameter: 12.5,
+                    radius_of_curvature: 25.0,
+                    conic_constant: -1.0,
+                    surf_kind: BoundaryKind::Refracting,
+                    rotation: Rotation3D::None,
+                    decenter: Vec3::new(0.0, 0.0, 0.0),
+                    rotation_offset: Rotation3D::None,
+                }
+            }
+        };
+        let field = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+
+        let efl = |fresnel: bool| {
+            let surfaces = vec![
+                SurfaceSpec::Object,
+                front(fresnel),
+                SurfaceSpec::Conic {
+                    semi_diameter: 12.5,
+                    radius_of_curvature: Float::INFINITY,
+                    conic_constant: 0.0,
+                    surf_kind: BoundaryKind::Refracting,
+                    rotation: Rotation3D::None,
+                    decenter: Vec3::new(0.0, 0.0, 0.0),
+                    rotation_offset: Rotation3D::None,
+                },
+                SurfaceSpec::Image {
+                    rotation: Rotation3D::None,
+                    decenter: Vec3::new(0.0, 0.0, 0.0),
+                    rotation_offset: Rotation3D::None,
+                },
+            ];
+            let seq =
+                SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+            let pv = ParaxialView::new(&seq, &field, false).unwrap();
+            *pv.get(0, 0).unwrap().effective_focal_length()
+        };
+
+        assert_abs_diff_eq!(efl(true), 50.0, epsilon = 1e-9);
+        assert_abs_diff_eq!(efl(true), efl(false), epsilon = 1e-12);
+    }
+
+    /// The power of a diffractive lens is proportional to wavelength, so red
+    /// light focuses closer than blue.
+    #[test]
+    fn diffractive_lens_has_negative_dispersion() {
+        let (f_d, w_d, w_f, w_c) = (100.0, 0.5876, 0.4861, 0.6563);
+        let r_norm: Float = 10.0;
+        let a_1 = -PI * r_norm * r_norm / (f_d * w_d * 1e-3);
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: f_d,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Diffractive {
+                semi_diameter: 12.5,
+                radius_of_curvature: Float::INFINITY,
+                conic_constant: 0.0,
+                normalization_radius: r_norm,
+                phase_coefficients: vec![a_1],
+                diffraction_order: 1,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let seq =
+            SequentialModel::from_surface_specs(&gaps, &surfaces, &[w_f, w_d, w_c], None).unwrap();
+        let field = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let pv = ParaxialView::new(&seq, &field, false).unwrap();
+
+        let efl = |wavelength_id| *pv.get(wavelength_id, 0).unwrap().effective_focal_length();
+        assert_abs_diff_eq!(efl(1), f_d, epsilon = 1e-9);
+        assert_abs_diff_eq!(efl(0), f_d * w_d / w_f, epsilon = 1e-9);
+        assert_abs_diff_eq!(efl(2), f_d * w_d / w_c, epsilon = 1e-9);
+        assert!(efl(2) < efl(0));
+
+        // The Abbe number of a diffractive surface, λd / (λF - λC), is about
+        // -3.45, so the axial color is about 29 % of the focal length.
+        let abbe = w_d / (w_f - w_c);
+        assert_abs_diff_eq!(abbe, -3.45, epsilon = 0.01);
+        assert_abs_diff_eq!(pv.primary_axial_color()[0], efl(0) - efl(2), epsilon = 1e-9);
+        assert!(pv.primary_axial_color()[0] > 0.25 * f_d);
+    }
 }
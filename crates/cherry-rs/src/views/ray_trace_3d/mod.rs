@@ -14,7 +14,6 @@ use crate::{
         math::vec3::Vec3,
         ray::Ray,
         sequential_model::{SequentialModel, SequentialSubModel, placement::Placement},
-        surfaces::Surface,
     },
     specs::{
         aperture::ApertureSpec,
@@ -22,7 +21,7 @@ use crate::{
     },
 };
 
-use trace::trace;
+use trace::{TraceSettings, trace};
 
 pub use trace::RayBundle;
 
@@ -131,12 +130,12 @@ pub fn trace_ray_bundle(
 
                 let bundle = ray_trace_submodel(
                     sequential_submodel,
-                    sequential_model.surfaces(),
-                    sequential_model.placements(),
+                    sequential_model,
                     aperture_spec,
                     &field_specs[field_id],
                     paraxial_subview,
                     sampling,
+                    TraceSettings::new(sequential_model.wavelengths()[wavelength_id]),
                 )?;
 
                 Ok((field_id, wavelength_id, bundle))
@@ -191,50 +190,49 @@ pub fn ray_trace_3d_view(
                 .ok_or_else(|| anyhow!("Submodel not found"))?;
 
             let field_spec = &field_specs[field_id];
-            let surfaces = sequential_model.surfaces();
-            let placements = sequential_model.placements();
+            let settings = TraceSettings::new(sequential_model.wavelengths()[wavelength_id]);
 
             let chief_ray = ray_trace_submodel(
                 sequential_submodel,
-                surfaces,
-                placements,
+                sequential_model,
                 aperture_spec,
                 field_spec,
                 paraxial_subview,
                 PupilSampling::ChiefRay,
+                settings,
             )?;
             let full_pupil = ray_trace_submodel(
                 sequential_submodel,
-                surfaces,
-                placements,
+                sequential_model,
                 aperture_spec,
                 field_spec,
                 paraxial_subview,
                 PupilSampling::SquareGrid {
                     spacing: config.full_pupil_spacing,
                 },
+                settings,
             )?;
             let tangential_fan = ray_trace_submodel(
                 sequential_submodel,
-                surfaces,
-                placements,
+                sequential_model,
                 aperture_spec,
                 field_spec,
                 paraxial_subview,
                 PupilSampling::TangentialRayFan {
                     n: config.n_fan_rays,
                 },
+                settings,
             )?;
             let sagittal_fan = ray_trace_submodel(
                 sequential_submodel,
-                surfaces,
-                placements,
+                sequential_model,
                 aperture_spec,
                 field_spec,
                 paraxial_subview,
                 PupilSampling::SagittalRayFan {
                     n: config.n_fan_rays,
                 },
+                settings,
             )?;
 
             trace!(
@@ -344,17 +342,34 @@ impl TraceResults {
     pub fn chief_ray_reached_image(&self) -> bool {
         self.chief_ray.terminated().first().copied().unwrap_or(0) == 0
     }
+
+    /// Returns the fraction of the light entering the pupil that reaches the
+    /// image, from the full-pupil bundle.
+    ///
+    /// This accounts for vignetting and for absorption in the gaps; see
+    /// [`RayBundle::throughput`]. Returns `None` if the bundle is empty.
+    pub fn throughput(&self) -> Option<Float> {
+        self.full_pupil.throughput()
+    }
+
+    /// Returns the mean internal transmittance of the full-pupil rays that
+    /// reach the image; see [`RayBundle::internal_transmittance`].
+    pub fn internal_transmittance(&self) -> Option<Float> {
+        self.full_pupil.internal_transmittance()
+    }
 }
 
 fn ray_trace_submodel(
     sequential_submodel: &impl SequentialSubModel,
-    surfaces: &[Box<dyn Surface>],
-    placements: &[Placement],
+    sequential_model: &SequentialModel,
     aperture_spec: &ApertureSpec,
     field_spec: &FieldSpec,
     paraxial_subview: &ParaxialSubView,
     pupil_sampling: PupilSampling,
+    settings: TraceSettings,
 ) -> Result<RayBundle> {
+    let surfaces = sequential_model.surfaces();
+    let placements = sequential_model.placements();
     let rays = rays(
         placements,
         aperture_spec,
@@ -364,7 +379,7 @@ fn ray_trace_submodel(
     )?;
 
     let mut sequential_sub_model_iter = sequential_submodel.try_iter(surfaces, placements)?;
-    Ok(trace(&mut sequential_sub_model_iter, rays))
+    Ok(trace(&mut sequential_sub_model_iter, rays, settings))
 }
 
 /// Returns the initial rays in a ray bundle to trace through the system.
@@ -1348,4 +1363,120 @@ mod tests {
             "On-axis chief ray should reach the image surface"
         );
     }
+
+    #[test]
+    fn slit_clears_only_rays_inside_it() {
+        use crate::{GapSpec, Mask, Rotation3D, SurfaceSpec};
+
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 10.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Masked {
+                mask: Mask::Rectangular {
+                    half_width_x: 4.0,
+                    half_width_y: 1.0,
+                },
+                surface: Box::new(SurfaceSpec::Iris {
+                    semi_diameter: 5.0,
+                    rotation: Rotation3D::None,
+                    decenter: Vec3::new(0.0, 0.0, 0.0),
+                    rotation_offset: Rotation3D::None,
+                }),
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let model = SequentialModel::from_surface_specs(&gaps, &surfaces, &[0.5876], None).unwrap();
+        let field_specs = vec![FieldSpec::Angle {
+            chi: 0.0,
+            phi: 90.0,
+        }];
+        let paraxial_view = ParaxialView::new(&model, &field_specs, false).unwrap();
+        let aperture_spec = ApertureSpec::EntrancePupil { semi_diameter: 5.0 };
+
+        let bundles = trace_ray_bundle(
+            &aperture_spec,
+            &field_specs,
+            &model,
+            &paraxial_view,
+            PupilSampling::SquareGrid { spacing: 0.1 },
+        )
+        .unwrap();
+        let bundle = &bundles[0].2;
+        let n_rays = bundle.rays().len() / bundle.num_surfaces();
+
+        let mut passed = 0;
+        for ray_id in 0..n_rays {
+            let at_slit = &bundle.rays()[n_rays + ray_id];
+            let inside = at_slit.x().abs() <= 4.0 && at_slit.y().abs() <= 1.0;
+            let terminated = bundle.terminated()[ray_id] != 0;
+            assert_eq!(
+                inside,
+                !terminated,
+                "ray at ({}, {})",
+                at_slit.x(),
+                at_slit.y()
+            );
+            passed += usize::from(inside);
+        }
+        assert!(passed > 0 && passed < n_rays);
+    }
+
+    #[test]
+    fn test_throughput_follows_absorption_in_the_glass() {
+        let wavelength = 0.5876;
+        let results = |k: Float| {
+            let glass = std::rc::Rc::new(crate::ConstantRefractiveIndex::new(1.515, k));
+            let sequential_model = sequential_model(n!(1.0), glass, &[wavelength]);
+            let aperture_spec = ApertureSpec::EntrancePupil { semi_diameter: 5.0 };
+            let field_specs = vec![FieldSpec::Angle {
+                chi: 0.0,
+                phi: 90.0,
+            }];
+            let paraxial_view = ParaxialView::new(&sequential_model, &field_specs, false).unwrap();
+            let config = SamplingConfig {
+                n_fan_rays: 3,
+                full_pupil_spacing: 0.1,
+            };
+            ray_trace_3d_view(
+                &aperture_spec,
+                &field_specs,
+                &sequential_model,
+                &paraxial_view,
+                config,
+            )
+            .unwrap()
+        };
+
+        let lossless = results(0.0);
+        let lossless = lossless.get(0, 0).unwrap();
+        assert_eq!(lossless.throughput(), Some(1.0));
+        assert_eq!(lossless.internal_transmittance(), Some(1.0));
+
+        // The chief ray crosses the 5.3 mm of glass on the axis.
+        let k = 1e-5;
+        let absorbing = results(k);
+        let absorbing = absorbing.get(0, 0).unwrap();
+        let on_axis = (-4.0 * PI * k * 5.3e3 / wavelength).exp();
+        let chief = absorbing.chief_ray().rays().last().unwrap();
+        assert_abs_diff_eq!(chief.intensity(), on_axis, epsilon = 1e-12);
+
+        // Marginal rays cross less glass in the convexplano lens, and no ray is
+        // vignetted.
+        let throughput = absorbing.throughput().unwrap();
+        assert!(on_axis < throughput && throughput < 1.0);
+        assert_eq!(Some(throughput), absorbing.internal_transmittance());
+    }
 }
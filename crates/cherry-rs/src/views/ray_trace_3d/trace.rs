@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use serde::Serialize;
 use tracing::{error, trace_span, warn};
 
-use crate::core::{ray::Ray, sequential_model::SequentialSubModelIter};
+use crate::core::{Float, ray::Ray, sequential_model::SequentialSubModelIter};
 
 const MAX_INTERSECTION_ITER: usize = 100;
 
@@ -26,9 +26,41 @@ pub struct RayBundle {
     num_surfaces: usize,
 }
 
+/// Inputs that control how rays are traced through a system.
+#[derive(Debug, Clone, Copy)]
+pub struct TraceSettings {
+    /// The vacuum wavelength of the rays in micrometers. It is passed to each
+    /// surface's `interact` method so that wavelength-dependent surfaces such
+    /// as gratings can redirect the rays, and sets the absorption in the gaps.
+    pub wavelength: Float,
+    /// The maximum number of iterations when intersecting a ray with a
+    /// surface. Rays whose intersection does not converge are terminated.
+    pub max_intersection_iter: usize,
+}
+
+impl TraceSettings {
+    /// Creates the settings for tracing rays of a wavelength with the default
+    /// iteration limit.
+    pub fn new(wavelength: Float) -> Self {
+        Self {
+            wavelength,
+            max_intersection_iter: MAX_INTERSECTION_ITER,
+        }
+    }
+}
+
 /// Trace a set of rays through a system using the technique of Spencer and
 /// Murty, JOSA (1962).
-pub fn trace(sequential_submodel: &mut SequentialSubModelIter, mut rays: Vec<Ray>) -> RayBundle {
+pub fn trace(
+    sequential_submodel: &mut SequentialSubModelIter,
+    mut rays: Vec<Ray>,
+    settings: TraceSettings,
+) -> RayBundle {
+    let TraceSettings {
+        wavelength,
+        max_intersection_iter,
+    } = settings;
+
     // Pre-allocate the results bundle. Include the initial ray positions as a
     // "surface."
     let mut terminated = vec![0; rays.len()];
@@ -56,7 +88,7 @@ pub fn trace(sequential_submodel: &mut SequentialSubModelIter, mut rays: Vec<Ray
 
             // Find the ray intersection with the surface.
             // Errors if the intersection point does not converge.
-            let (pos, norm) = match step.surface.intersect(ray, MAX_INTERSECTION_ITER) {
+            let (pos, norm) = match step.surface.intersect(ray, max_intersection_iter) {
                 Ok((pos, norm)) => (pos, norm),
                 Err(e) => {
                     if !ray_is_terminated(ray_id, &terminated) {
@@ -82,13 +114,37 @@ pub fn trace(sequential_submodel: &mut SequentialSubModelIter, mut rays: Vec<Ray
                 );
             }
 
+            // Absorb light along the path through the gap before the surface.
+            // Rays into an infinite object space start from an arbitrary plane,
+            // so absorption there is ignored.
+            if step.gap_before.thickness.is_finite() {
+                let path_length = (pos - ray.pos()).length();
+                ray.attenuate(
+                    step.gap_before.refractive_index.k(),
+                    path_length,
+                    wavelength,
+                );
+            }
+
             // Displace the ray to the intersection point
             ray.displace(pos);
 
             // Interact the ray with the surface (redirect and/or displace)
             let n_0 = step.gap_before.refractive_index.n();
             let n_1 = step.gap_after.map_or(n_0, |g| g.refractive_index.n());
-            step.surface.interact(ray, n_0, n_1, norm);
+            step.surface.interact(ray, n_0, n_1, norm, wavelength);
+
+            // Terminate ray if it cannot leave the surface, e.g. because of total
+            // internal reflection or an evanescent diffraction order
+            let propagates = ray.l().is_finite() && ray.m().is_finite() && ray.n().is_finite();
+            if !propagates && !ray_is_terminated(ray_id, &terminated) {
+                terminated[ray_id] = surface_id;
+                reason_for_termination.insert(ray_id, "Ray does not propagate".to_string());
+                warn!(
+                    ray_id,
+                    surface_id, "Ray terminated because it does not propagate past the surface"
+                );
+            }
 
             // Transform back to the global coordinate system
             ray.i_transform(step.placement);
@@ -127,6 +183,45 @@ impl RayBundle {
     pub fn num_surfaces(&self) -> usize {
         self.num_surfaces
     }
+
+    /// Returns the fraction of the light launched into the system that
+    /// reaches the last surface.
+    ///
+    /// Each ray carries an equal share of the light. Rays that terminate,
+    /// e.g. by vignetting, deliver none of theirs; the rest deliver their
+    /// intensity after absorption in the gaps. Surface reflection losses are
+    /// not included. Returns `None` for an empty bundle.
+    pub fn throughput(&self) -> Option<Float> {
+        let num_rays = self.terminated.len();
+        if num_rays == 0 {
+            return None;
+        }
+        let delivered: Float = self.delivered_intensities().sum();
+        Some(delivered / num_rays as Float)
+    }
+
+    /// Returns the mean internal transmittance of the rays that reach the last
+    /// surface, i.e. the fraction of their light not absorbed in the gaps.
+    ///
+    /// Returns `None` if no ray reaches the last surface.
+    pub fn internal_transmittance(&self) -> Option<Float> {
+        let (count, total) = self
+            .delivered_intensities()
+            .fold((0usize, 0.0), |(count, total), intensity| {
+                (count + 1, total + intensity)
+            });
+        (count > 0).then(|| total / count as Float)
+    }
+
+    /// Iterates over the intensities of the unterminated rays at the last
+    /// surface.
+    fn delivered_intensities(&self) -> impl Iterator<Item = Float> + '_ {
+        rays_at_surface(&self.rays, self.num_surfaces - 1, self.num_surfaces)
+            .iter()
+            .zip(&self.terminated)
+            .filter(|(_, terminated)| **terminated == 0)
+            .map(|(ray, _)| ray.intensity())
+    }
 }
 
 /// Initializes a ray bundle for the start of a ray trace.
@@ -149,6 +244,13 @@ fn ray_is_terminated(ray_id: usize, terminated: &[usize]) -> bool {
     terminated[ray_id] != 0
 }
 
+/// Returns the set of rays at a given surface index.
+fn rays_at_surface(bundle: &[Ray], surface_id: usize, num_surfaces: usize) -> &[Ray] {
+    let start = surface_id * bundle.len() / num_surfaces;
+    let end = (surface_id + 1) * bundle.len() / num_surfaces;
+    &bundle[start..end]
+}
+
 /// Returns the set of rays at a given surface index.
 fn rays_at_surface_mut(bundle: &mut [Ray], surface_id: usize, num_surfaces: usize) -> &mut [Ray] {
     let start = surface_id * bundle.len() / num_surfaces;
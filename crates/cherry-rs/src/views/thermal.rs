@@ -0,0 +1,737 @@
+//! Thermal defocus analysis.
+use std::rc::Rc;
+
+use anyhow::{Result, anyhow, bail};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Environment, FieldSpec, GapSpec, Mask, ParaxialView, SequentialModelBuilder, SurfaceSpec,
+    core::Float,
+};
+
+/// Specifies the conditions of a thermal analysis.
+///
+/// The system's lengths are those at `reference_temperature`. At each of
+/// `temperatures`, a gap filled with a material that reports a coefficient of
+/// thermal expansion, such as a catalog glass, grows with it; every other gap,
+/// including the air spaces set by the lens barrel and spacers, grows with
+/// `mount_expansion`. Each surface grows uniformly with the material after
+/// it, the material before it or the mount, in that order, so that its radii,
+/// apertures, normalization radii and sag all scale together. The object
+/// distance is held fixed.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ThermalSpec {
+    /// Temperatures at which to analyze the system, in °C.
+    pub temperatures: Vec<Float>,
+    /// Temperature at which the system's lengths are specified, in °C.
+    pub reference_temperature: Float,
+    /// Air pressure, in Pa.
+    pub pressure: Float,
+    /// Coefficient of thermal expansion of the mount, in 1/K.
+    pub mount_expansion: Float,
+}
+
+/// The first-order properties of a system at one temperature and wavelength.
+///
+/// Changes are relative to the system at the reference temperature. All
+/// lengths are in mm.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ThermalResult {
+    /// Temperature in °C.
+    pub temperature: Float,
+    pub wavelength_id: usize,
+    pub effective_focal_length: Float,
+    pub back_focal_distance: Float,
+    /// Location of the paraxial image plane relative to the first physical
+    /// surface.
+    pub image_plane_location: Float,
+    /// Distance from the image surface to the paraxial image plane.
+    pub defocus: Float,
+    pub efl_change: Float,
+    pub bfd_change: Float,
+    /// Change of `image_plane_location`.
+    pub image_plane_shift: Float,
+}
+
+/// The results of a thermal analysis.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ThermalView {
+    reference: Vec<ThermalResult>,
+    results: Vec<ThermalResult>,
+}
+
+impl ThermalSpec {
+    /// Validate the thermal spec.
+    pub fn validate(&self) -> Result<()> {
+        Environment::new(self.reference_temperature, self.pressure).validate()?;
+        for &temperature in &self.temperatures {
+            Environment::new(temperature, self.pressure).validate()?;
+        }
+        if !self.mount_expansion.is_finite() {
+            bail!(
+                "Mount expansion coefficient must be finite, got {}",
+                self.mount_expansion
+            );
+        }
+        Ok(())
+    }
+}
+
+impl ThermalView {
+    /// Returns the results at the reference temperature, one per wavelength.
+    pub fn reference(&self) -> &[ThermalResult] {
+        &self.reference
+    }
+
+    /// Returns the results ordered by temperature, then by wavelength.
+    pub fn results(&self) -> &[ThermalResult] {
+        &self.results
+    }
+
+    /// Returns the results at one wavelength, ordered by temperature.
+    pub fn get_by_wavelength_id(&self, wavelength_id: usize) -> Vec<&ThermalResult> {
+        self.results
+            .iter()
+            .filter(|r| r.wavelength_id == wavelength_id)
+            .collect()
+    }
+}
+
+/// Analyzes the change of focus of a system with temperature.
+///
+/// The system is rebuilt at each temperature of the spec with its gaps and
+/// radii of curvature expanded and its gap indices evaluated in an
+/// [`Environment`] at that temperature and the spec's pressure. The
+/// first-order properties are computed along the first tangential direction
+/// of a [`ParaxialView`].
+///
+/// Pass the specs after any solves have been applied, so that the solves do
+/// not compensate the thermal changes.
+///
+/// # Arguments
+/// * `gap_specs` - The gaps of the system at the reference temperature.
+/// * `surface_specs` - The surfaces of the system at the reference temperature.
+/// * `wavelengths` - The wavelengths at which to analyze the system.
+/// * `stop_surface` - Optional index of the aperture stop.
+/// * `field_specs` - The field specs of the system.
+/// * `spec` - The temperatures and materials of the analysis.
+pub fn thermal_view(
+    gap_specs: &[GapSpec],
+    surface_specs: &[SurfaceSpec],
+    wavelengths: &[Float],
+    stop_surface: Option<usize>,
+    field_specs: &[FieldSpec],
+    spec: &ThermalSpec,
+) -> Result<ThermalView> {
+    spec.validate()?;
+
+    let analyze = |temperature: Float| -> Result<Vec<ThermalResult>> {
+        let (gaps, surfaces) = expand(gap_specs, surface_specs, spec, temperature);
+        let mut builder = SequentialModelBuilder::new()
+            .gap_specs(gaps)
+            .surface_specs(surfaces)
+            .wavelengths(wavelengths.to_vec())
+            .environment(Environment::new(temperature, spec.pressure));
+        if let Some(stop) = stop_surface {
+            builder = builder.stop_surface(stop);
+        }
+        let model = builder.build()?.model;
+        let paraxial_view = ParaxialView::new(&model, field_specs, false)?;
+        let image_surface = model
+            .placements()
+            .last()
+            .ok_or_else(|| anyhow!("The system has no surfaces"))?
+            .track;
+
+        (0..wavelengths.len())
+            .map(|wavelength_id| {
+                let subview = paraxial_view
+                    .get(wavelength_id, 0)
+                    .ok_or_else(|| anyhow!("No paraxial subview for wavelength {wavelength_id}"))?;
+                let image_plane_location = subview.paraxial_image_plane().location;
+                Ok(ThermalResult {
+                    temperature,
+                    wavelength_id,
+                    effective_focal_length: *subview.effective_focal_length(),
+                    back_focal_distance: *subview.back_focal_distance(),
+                    image_plane_location,
+                    defocus: image_plane_location - image_surface,
+                    efl_change: 0.0,
+                    bfd_change: 0.0,
+                    image_plane_shift: 0.0,
+                })
+            })
+            .collect()
+    };
+
+    let reference = analyze(spec.reference_temperature)?;
+    let mut results = Vec::with_capacity(spec.temperatures.len() * wavelengths.len());
+    for &temperature in &spec.temperatures {
+        for (mut result, nominal) in analyze(temperature)?.into_iter().zip(&reference) {
+            result.efl_change = result.effective_focal_length - nominal.effective_focal_length;
+            result.bfd_change = result.back_focal_distance - nominal.back_focal_distance;
+            result.image_plane_shift = result.image_plane_location - nominal.image_plane_location;
+            results.push(result);
+        }
+    }
+
+    Ok(ThermalView { reference, results })
+}
+
+/// Returns the specs of a system expanded from the reference temperature to
+/// `temperature`.
+fn expand(
+    gap_specs: &[GapSpec],
+    surface_specs: &[SurfaceSpec],
+    spec: &ThermalSpec,
+    temperature: Float,
+) -> (Vec<GapSpec>, Vec<SurfaceSpec>) {
+    let dt = temperature - spec.reference_temperature;
+    let material_expansion =
+        |gap: Option<&GapSpec>| gap.and_then(|g| g.refractive_index.thermal_expansion());
+
+    let gaps = gap_specs
+        .iter()
+        .enumerate()
+        .map(|(i, gap)| {
+            let expansion = match i {
+                0 => 0.0,
+                _ => material_expansion(Some(gap)).unwrap_or(spec.mount_expansion),
+            };
+            GapSpec {
+                thickness: gap.thickness * (1.0 + expansion * dt),
+                refractive_index: Rc::clone(&gap.refractive_index),
+            }
+        })
+        .collect();
+
+    // A surface belongs to the solid after it or, failing that, before it.
+    let surfaces = surface_specs
+        .iter()
+        .enumerate()
+        .map(|(i, surface)| {
+            let expansion = material_expansion(gap_specs.get(i))
+                .or_else(|| material_expansion(i.checked_sub(1).and_then(|i| gap_specs.get(i))))
+                .unwrap_or(spec.mount_expansion);
+            let mut surface = surface.clone();
+            scale_lengths(&mut surface, 1.0 + expansion * dt);
+            surface
+        })
+        .collect();
+
+    (gaps, surfaces)
+}
+
+/// Scales every length of a surface by `factor`, as for a uniform expansion of
+/// the solid that it belongs to.
+///
+/// Sag polynomials are scaled so that the sag grows with the surface, and
+/// gratings and diffractive phases are stretched across it. Dimensionless
+/// parameters such as conic constants are unchanged, as are the parameters of
+/// custom surfaces, which are opaque to this analysis.
+fn scale_lengths(surface: &mut SurfaceSpec, factor: Float) {
+    match surface {
+        SurfaceSpec::Conic {
+            semi_diameter,
+            radius_of_curvature,
+            decenter,
+            ..
+        }
+        | SurfaceSpec::Sphere {
+            semi_diameter,
+            radius_of_curvature,
+            decenter,
+            ..
+        }
+        | SurfaceSpec::Cylinder {
+            semi_diameter,
+            radius_of_curvature,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *radius_of_curvature *= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::EvenAsphere {
+            semi_diameter,
+            radius_of_curvature,
+            aspheric_coefficients,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *radius_of_curvature *= factor;
+            scale_even_asphere_coefficients(aspheric_coefficients, factor);
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::Fresnel {
+            semi_diameter,
+            radius_of_curvature,
+            aspheric_coefficients,
+            groove_pitch,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *radius_of_curvature *= factor;
+            scale_even_asphere_coefficients(aspheric_coefficients, factor);
+            *groove_pitch *= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::ForbesAsphere {
+            semi_diameter,
+            radius_of_curvature,
+            normalization_radius,
+            q_coefficients: coefficients,
+            decenter,
+            ..
+        }
+        | SurfaceSpec::XYPolynomial {
+            semi_diameter,
+            radius_of_curvature,
+            normalization_radius,
+            xy_coefficients: coefficients,
+            decenter,
+            ..
+        }
+        | SurfaceSpec::ZernikeSag {
+            semi_diameter,
+            radius_of_curvature,
+            normalization_radius,
+            zernike_coefficients: coefficients,
+            decenter,
+            ..
+        } => {
+            // The polynomials are evaluated in normalized coordinates, so only
+            // their sag scales.
+            *semi_diameter *= factor;
+            *radius_of_curvature *= factor;
+            *normalization_radius *= factor;
+            coefficients.iter_mut().for_each(|c| *c *= factor);
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::Diffractive {
+            semi_diameter,
+            radius_of_curvature,
+            normalization_radius,
+            decenter,
+            ..
+        } => {
+            // The phase is in radians, so stretching it across the surface
+            // only changes the normalization radius.
+            *semi_diameter *= factor;
+            *radius_of_curvature *= factor;
+            *normalization_radius *= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::GridSag {
+            semi_diameter,
+            radius_of_curvature,
+            grid,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *radius_of_curvature *= factor;
+            grid.dx *= factor;
+            grid.dy *= factor;
+            grid.values.iter_mut().for_each(|v| *v *= factor);
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::LensletArray {
+            semi_diameter,
+            pitch,
+            radius_of_curvature,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *pitch *= factor;
+            *radius_of_curvature *= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::Grating {
+            semi_diameter,
+            radius_of_curvature,
+            line_density,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *radius_of_curvature *= factor;
+            *line_density /= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::Biconic {
+            semi_diameter,
+            radius_of_curvature_x,
+            radius_of_curvature_y,
+            decenter,
+            ..
+        }
+        | SurfaceSpec::Toroid {
+            semi_diameter,
+            radius_of_curvature_x,
+            radius_of_curvature_y,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *radius_of_curvature_x *= factor;
+            *radius_of_curvature_y *= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::CardinalLens {
+            semi_diameter,
+            focal_length,
+            principal_plane_separation,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *focal_length *= factor;
+            *principal_plane_separation *= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::ParaxialLens {
+            semi_diameter,
+            focal_length,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *focal_length *= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::Axicon {
+            semi_diameter,
+            decenter,
+            ..
+        }
+        | SurfaceSpec::Iris {
+            semi_diameter,
+            decenter,
+            ..
+        } => {
+            *semi_diameter *= factor;
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::Image { decenter, .. } | SurfaceSpec::Probe { decenter, .. } => {
+            *decenter = *decenter * factor;
+        }
+        SurfaceSpec::Masked { mask, surface } => {
+            scale_mask(mask, factor);
+            scale_lengths(surface, factor);
+        }
+        SurfaceSpec::Mounted {
+            mechanical_semi_diameter,
+            flat_width,
+            surface,
+        } => {
+            *mechanical_semi_diameter *= factor;
+            *flat_width *= factor;
+            scale_lengths(surface, factor);
+        }
+        SurfaceSpec::Object => {}
+        #[cfg(feature = "serde")]
+        SurfaceSpec::Custom { .. } => {}
+    }
+}
+
+/// Scales the coefficients A4, A6, ... of an even asphere so that its
+/// polynomial sag scales with the surface.
+fn scale_even_asphere_coefficients(coefficients: &mut [Float], factor: Float) {
+    for (i, c) in coefficients.iter_mut().enumerate() {
+        // A_(2i + 4) multiplies r^(2i + 4).
+        *c *= factor.powi(-(2 * i as i32 + 3));
+    }
+}
+
+/// Scales every length of a mask by `factor`.
+fn scale_mask(mask: &mut Mask, factor: Float) {
+    match mask {
+        Mask::Circular { semi_diameter } => *semi_diameter *= factor,
+        Mask::Rectangular {
+            half_width_x,
+            half_width_y,
+        } => {
+            *half_width_x *= factor;
+            *half_width_y *= factor;
+        }
+        Mask::Elliptical {
+            semi_axis_x,
+            semi_axis_y,
+        } => {
+            *semi_axis_x *= factor;
+            *semi_axis_y *= factor;
+        }
+        Mask::Polygon { vertices } => vertices.iter_mut().flatten().for_each(|v| *v *= factor),
+        Mask::Decentered {
+            offset_x,
+            offset_y,
+            mask,
+        } => {
+            *offset_x *= factor;
+            *offset_y *= factor;
+            scale_mask(mask, factor);
+        }
+        Mask::Obscured {
+            aperture,
+            obscuration,
+            spider,
+        } => {
+            scale_mask(aperture, factor);
+            if let Some(obscuration) = obscuration {
+                scale_mask(obscuration, factor);
+            }
+            if let Some(spider) = spider {
+                spider.width *= factor;
+            }
+        }
+        Mask::Unbounded => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_abs_diff_eq;
+
+    use super::*;
+    use crate::{
+        BoundaryKind, CatalogGlass, DispersionFormula, FormulaMaterial, RefractiveIndexSpec,
+        Rotation3D, ThermalCoefficients, Vec3, n,
+    };
+
+    const WAVELENGTH: Float = 0.5876;
+
+    fn gaps(glass: Rc<dyn RefractiveIndexSpec>) -> Vec<GapSpec> {
+        vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 5.3,
+                refractive_index: glass,
+            },
+            GapSpec {
+                thickness: 46.6,
+                refractive_index: n!(1.0),
+            },
+        ]
+    }
+
+    fn surfaces() -> Vec<SurfaceSpec> {
+        let sphere = |radius_of_curvature| SurfaceSpec::Sphere {
+            semi_diameter: 12.5,
+            radius_of_curvature,
+            surf_kind: BoundaryKind::Refracting,
+            rotation: Rotation3D::None,
+            decenter: Vec3::new(0.0, 0.0, 0.0),
+            rotation_offset: Rotation3D::None,
+        };
+        vec![
+            SurfaceSpec::Object,
+            sphere(25.8),
+            sphere(Float::INFINITY),
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ]
+    }
+
+    fn nbk7() -> CatalogGlass {
+        CatalogGlass {
+            name: "N-BK7".into(),
+            nd: 1.5168,
+            vd: 64.17,
+            delta_pgf: -0.0009,
+            thermal_expansion: 7.1e-6,
+            material: FormulaMaterial::new(
+                DispersionFormula::Sellmeier1 {
+                    b: [1.03961212, 0.231792344, 1.01046945],
+                    c: [6.00069867e-3, 2.00179144e-2, 1.03560653e2],
+                },
+                [0.3, 2.5],
+            )
+            .unwrap(),
+            thermal: ThermalCoefficients {
+                d_0: 1.86e-6,
+                d_1: 1.31e-8,
+                d_2: -1.37e-11,
+                e_0: 4.34e-7,
+                e_1: 6.27e-10,
+                lambda_tk: 0.17,
+                reference_temperature: 20.0,
+            },
+            transmission: Vec::new(),
+        }
+    }
+
+    fn spec(temperatures: Vec<Float>, mount_expansion: Float) -> ThermalSpec {
+        ThermalSpec {
+            temperatures,
+            reference_temperature: 20.0,
+            pressure: 101_325.0,
+            mount_expansion,
+        }
+    }
+
+    #[test]
+    fn uniform_expansion_scales_the_system() {
+        // Without index changes or a glass expansion coefficient, the whole
+        // system grows with the mount, and so does its focus.
+        let alpha = 23e-6;
+        let view = thermal_view(
+            &gaps(n!(1.515)),
+            &surfaces(),
+            &[WAVELENGTH],
+            None,
+            &[FieldSpec::Angle {
+                chi: 0.0,
+                phi: 90.0,
+            }],
+            &spec(vec![-20.0, 20.0, 70.0], alpha),
+        )
+        .unwrap();
+
+        let nominal = &view.reference()[0];
+        assert_eq!(view.results().len(), 3);
+        for result in view.results() {
+            let scale = alpha * (result.temperature - 20.0);
+            assert_abs_diff_eq!(
+                result.efl_change,
+                nominal.effective_focal_length * scale,
+                epsilon = 1e-9
+            );
+            assert_abs_diff_eq!(
+                result.bfd_change,
+                nominal.back_focal_distance * scale,
+                epsilon = 1e-9
+            );
+            assert_abs_diff_eq!(
+                result.image_plane_shift,
+                nominal.image_plane_location * scale,
+                epsilon = 1e-9
+            );
+            assert_abs_diff_eq!(
+                result.defocus,
+                nominal.defocus * (1.0 + scale),
+                epsilon = 1e-9
+            );
+        }
+        assert_eq!(view.results()[1].efl_change, 0.0);
+    }
+
+    #[test]
+    fn diffractive_power_scales_with_the_normalization_radius() {
+        // The phase is stretched across the grown surface, so its power falls
+        // as the inverse square of the normalization radius.
+        let alpha = 23e-6;
+        let gaps = vec![
+            GapSpec {
+                thickness: Float::INFINITY,
+                refractive_index: n!(1.0),
+            },
+            GapSpec {
+                thickness: 100.0,
+                refractive_index: n!(1.0),
+            },
+        ];
+        let surfaces = vec![
+            SurfaceSpec::Object,
+            SurfaceSpec::Diffractive {
+                semi_diameter: 12.5,
+                radius_of_curvature: Float::INFINITY,
+                conic_constant: 0.0,
+                normalization_radius: 10.0,
+                phase_coefficients: vec![-100.0],
+                diffraction_order: 1,
+                surf_kind: BoundaryKind::Refracting,
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+            SurfaceSpec::Image {
+                rotation: Rotation3D::None,
+                decenter: Vec3::new(0.0, 0.0, 0.0),
+                rotation_offset: Rotation3D::None,
+            },
+        ];
+        let view = thermal_view(
+            &gaps,
+            &surfaces,
+            &[WAVELENGTH],
+            None,
+            &[FieldSpec::Angle {
+                chi: 0.0,
+                phi: 90.0,
+            }],
+            &spec(vec![70.0], alpha),
+        )
+        .unwrap();
+
+        let nominal = &view.reference()[0];
+        let result = &view.results()[0];
+        let power_change = nominal.effective_focal_length
+            / (nominal.effective_focal_length + result.efl_change)
+            - 1.0;
+        assert_abs_diff_eq!(power_change, -2.0 * alpha * 50.0, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn glass_expansion_and_dn_dt_change_the_focal_length() {
+        let glass = nbk7();
+        let view = thermal_view(
+            &gaps(Rc::new(glass.clone())),
+            &surfaces(),
+            &[WAVELENGTH],
+            None,
+            &[FieldSpec::Angle {
+                chi: 0.0,
+                phi: 90.0,
+            }],
+            &spec(vec![40.0], 0.0),
+        )
+        .unwrap();
+
+        // Thin lens: df / f dT = α - (dn/dT) / (n - 1)
+        let env = |t| Environment::new(t, 101_325.0);
+        let n_20 = glass.n_in(WAVELENGTH, &env(20.0)).unwrap();
+        let n_40 = glass.n_in(WAVELENGTH, &env(40.0)).unwrap();
+        let expected = 7.1e-6 * 20.0 - (n_40 - n_20) / (n_20 - 1.0);
+
+        let nominal = &view.reference()[0];
+        let result = &view.get_by_wavelength_id(0)[0];
+        let relative = result.efl_change / nominal.effective_focal_length;
+        assert_abs_diff_eq!(relative, expected, epsilon = 0.02 * expected);
+
+        // The air spaces are fixed, so the image surface moves only by the
+        // growth of the lens.
+        assert_abs_diff_eq!(
+            result.defocus - nominal.defocus,
+            result.image_plane_shift - 5.3 * 7.1e-6 * 20.0,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn invalid_specs_are_errors() {
+        let mut invalid = spec(vec![20.0, -300.0], 0.0);
+        assert!(invalid.validate().is_err());
+        invalid.temperatures.pop();
+        assert!(invalid.validate().is_ok());
+        invalid.mount_expansion = Float::NAN;
+        assert!(invalid.validate().is_err());
+        invalid.mount_expansion = 0.0;
+        invalid.pressure = -1.0;
+        assert!(invalid.validate().is_err());
+    }
+}
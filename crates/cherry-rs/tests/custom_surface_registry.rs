@@ -1,8 +1,8 @@
 #![cfg(feature = "serde")]
 
 use cherry_rs::{
-    BoundaryKind, GapSpec, Mask, Rotation3D, SequentialModel, SequentialModelBuilder, Surface,
-    SurfaceRegistry, SurfaceSpec, Vec3, n,
+    BoundaryKind, GapSpec, GridSag, Mask, Rotation3D, SagGrid, SequentialModel,
+    SequentialModelBuilder, Surface, SurfaceRegistry, SurfaceSpec, Vec3, n,
 };
 use serde_json::json;
 
@@ -146,3 +146,23 @@ fn params_are_forwarded_to_constructor() {
     // The custom surface is at index 1.
     assert_eq!(model.surfaces()[1].mask().semi_diameter(), 7.5);
 }
+
+#[test]
+fn grid_sag_constructor_can_be_registered() {
+    let mut registry = SurfaceRegistry::new();
+    registry.register("grid_sag", GridSag::from_params);
+
+    let grid = SagGrid::from_csv("0, 0, 0\n0, 0.001, 0\n0, 0, 0\n", 5.0, 5.0).unwrap();
+    let params = json!({
+        "semi_diameter": 5.0,
+        "radius_of_curvature": 100.0,
+        "grid": grid,
+        "surf_kind": "Refracting",
+    });
+    let surface = registry
+        .build("grid_sag", &params)
+        .expect("grid sag should build");
+
+    // The bump at the vertex rides on the base sphere.
+    assert!((surface.sag(Vec3::new(0.0, 0.0, 0.0)) - 0.001).abs() < 1e-15);
+}